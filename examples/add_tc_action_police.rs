@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MIT
+
+use std::env;
+
+#[cfg(not(target_os = "freebsd"))]
+use futures_util::stream::TryStreamExt;
+#[cfg(not(target_os = "freebsd"))]
+use rtnetlink::{
+    new_connection, packet_route::tc::TcActionType, Error, Handle, TcActionExt,
+    TrafficActionPolice, TrafficFilterMatchall,
+};
+
+#[cfg(target_os = "freebsd")]
+fn main() -> () {}
+
+#[cfg(not(target_os = "freebsd"))]
+#[tokio::main]
+async fn main() -> Result<(), ()> {
+    env_logger::init();
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        usage();
+        return Ok(());
+    }
+
+    let indexes: Vec<i32> = args[1..]
+        .iter()
+        .map(|arg| {
+            arg.parse().unwrap_or_else(|_| {
+                eprintln!("invalid index");
+                std::process::exit(1);
+            })
+        })
+        .collect();
+
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+
+    if let Err(e) = share_policer(handle, &indexes).await {
+        eprintln!("{e}");
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "freebsd"))]
+const ETH_P_ALL: u16 = 0x0003;
+
+// Equivalent to:
+//   tc actions add action police rate 1mbit burst 10k drop index 1
+//   tc qdisc add dev DEV1 clsact
+//   tc filter add dev DEV1 ingress protocol all matchall \
+//       action police index 1
+//   tc qdisc add dev DEV2 clsact
+//   tc filter add dev DEV2 ingress protocol all matchall \
+//       action police index 1
+//   tc -s actions ls action police
+#[cfg(not(target_os = "freebsd"))]
+async fn share_policer(handle: Handle, indexes: &[i32]) -> Result<(), Error> {
+    let police = TrafficActionPolice::new(125_000, 10 * 1024)
+        .conform_exceed(TcActionType::Shot, TcActionType::Ok)
+        .index(1);
+    handle
+        .traffic_action()
+        .add()
+        .action(police)
+        .execute()
+        .await?;
+
+    for index in indexes {
+        handle.qdisc().add(*index).clsact().execute().await?;
+        handle
+            .traffic_filter(*index)
+            .add()
+            .ingress()
+            .protocol(ETH_P_ALL.to_be())
+            .matchall(TrafficFilterMatchall::new().action(police))?
+            .execute()
+            .await?;
+    }
+
+    let mut actions = handle.traffic_action().get("police").execute();
+    while let Some(action) = actions.try_next().await? {
+        println!("{:?}", action.action_options());
+    }
+    Ok(())
+}
+
+fn usage() {
+    eprintln!(
+        "usage:
+    cargo run --example add_tc_action_police -- <index> <index>
+
+Note that you need to run this program as root. Instead of running cargo as root,
+build the example normally:
+
+    cd rtnetlink ; cargo build --example add_tc_action_police
+
+Then find the binary in the target directory:
+
+    cd ../target/debug/example ; sudo ./add_tc_action_police <index> <index>"
+    );
+}
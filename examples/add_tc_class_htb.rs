@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT
+
+use std::env;
+
+#[cfg(not(target_os = "freebsd"))]
+use rtnetlink::{new_connection, Error, Handle, QDiscHtb, TrafficClassHtb};
+
+#[cfg(target_os = "freebsd")]
+fn main() -> () {}
+
+#[cfg(not(target_os = "freebsd"))]
+#[tokio::main]
+async fn main() -> Result<(), ()> {
+    env_logger::init();
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        usage();
+        return Ok(());
+    }
+
+    let index: i32 = args[1].parse().unwrap_or_else(|_| {
+        eprintln!("invalid index");
+        std::process::exit(1);
+    });
+
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+
+    if let Err(e) = add_htb_tree(handle, index).await {
+        eprintln!("{e}");
+    }
+
+    Ok(())
+}
+
+// Equivalent to:
+//   tc qdisc add dev DEV root handle 1: htb default 20
+//   tc class add dev DEV parent 1: classid 1:1 htb rate 100mbit
+//   tc class add dev DEV parent 1:1 classid 1:10 htb rate 60mbit \
+//       ceil 100mbit prio 0
+//   tc class add dev DEV parent 1:1 classid 1:20 htb rate 40mbit \
+//       ceil 100mbit prio 1
+#[cfg(not(target_os = "freebsd"))]
+async fn add_htb_tree(handle: Handle, index: i32) -> Result<(), Error> {
+    const MBIT: u64 = 1_000_000 / 8;
+
+    handle
+        .qdisc()
+        .add(index)
+        .root()
+        .handle(1, 0)
+        .htb(QDiscHtb::new().default_class(20))
+        .execute()
+        .await?;
+
+    let mut classes = handle.traffic_class(index);
+    classes
+        .add()
+        .parent(0x10000)
+        .classid(1, 1)
+        .htb(TrafficClassHtb::new(100 * MBIT))
+        .execute()
+        .await?;
+    classes
+        .add()
+        .parent(0x10001)
+        .classid(1, 10)
+        .htb(TrafficClassHtb::new(60 * MBIT).ceil(100 * MBIT).prio(0))
+        .execute()
+        .await?;
+    classes
+        .add()
+        .parent(0x10001)
+        .classid(1, 20)
+        .htb(TrafficClassHtb::new(40 * MBIT).ceil(100 * MBIT).prio(1))
+        .execute()
+        .await
+}
+
+fn usage() {
+    eprintln!(
+        "usage:
+    cargo run --example add_tc_class_htb -- <index>
+
+Note that you need to run this program as root. Instead of running cargo as root,
+build the example normally:
+
+    cd rtnetlink ; cargo build --example add_tc_class_htb
+
+Then find the binary in the target directory:
+
+    cd ../target/debug/example ; sudo ./add_tc_class_htb <index>"
+    );
+}
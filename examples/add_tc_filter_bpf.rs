@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT
+
+use std::env;
+
+#[cfg(not(target_os = "freebsd"))]
+use rtnetlink::{new_connection, Error, Handle, TrafficFilterBpf};
+
+#[cfg(target_os = "freebsd")]
+fn main() -> () {}
+
+#[cfg(not(target_os = "freebsd"))]
+#[tokio::main]
+async fn main() -> Result<(), ()> {
+    env_logger::init();
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        usage();
+        return Ok(());
+    }
+
+    let index: i32 = args[1].parse().unwrap_or_else(|_| {
+        eprintln!("invalid index");
+        std::process::exit(1);
+    });
+
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+
+    if let Err(e) = attach_bpf(handle, index, &args[2]).await {
+        eprintln!("{e}");
+    }
+
+    Ok(())
+}
+
+// Equivalent to:
+//   tc qdisc add dev DEV clsact
+//   tc filter add dev DEV ingress bpf direct-action pinned PATH
+#[cfg(not(target_os = "freebsd"))]
+async fn attach_bpf(
+    handle: Handle,
+    index: i32,
+    path: &str,
+) -> Result<(), Error> {
+    handle.qdisc().add(index).clsact().execute().await?;
+    handle
+        .traffic_filter(index)
+        .add()
+        .ingress()
+        .bpf(TrafficFilterBpf::new().pinned(path).direct_action())?
+        .execute()
+        .await
+}
+
+fn usage() {
+    eprintln!(
+        "usage:
+    cargo run --example add_tc_filter_bpf -- <index> <pinned program path>
+
+Note that you need to run this program as root. Instead of running cargo as root,
+build the example normally:
+
+    cd rtnetlink ; cargo build --example add_tc_filter_bpf
+
+Then find the binary in the target directory:
+
+    cd ../target/debug/example ; sudo ./add_tc_filter_bpf <index> <path>"
+    );
+}
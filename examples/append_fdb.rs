@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MIT
+
+use std::{env, net::IpAddr};
+
+use futures_util::stream::TryStreamExt;
+use rtnetlink::{new_connection, Error, Handle, NeighbourMessageExt};
+
+#[tokio::main]
+async fn main() -> Result<(), ()> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        usage();
+        return Ok(());
+    }
+
+    let link_name = &args[1];
+    let remote: IpAddr = args[2].parse().unwrap_or_else(|_| {
+        eprintln!("invalid IP address");
+        std::process::exit(1);
+    });
+
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+
+    if let Err(e) = append_fdb(link_name, remote, handle).await {
+        eprintln!("{e}");
+    }
+    Ok(())
+}
+
+async fn append_fdb(
+    link_name: &str,
+    remote: IpAddr,
+    handle: Handle,
+) -> Result<(), Error> {
+    let mut links = handle
+        .link()
+        .get()
+        .match_name(link_name.to_string())
+        .execute();
+    if let Some(link) = links.try_next().await? {
+        // Equivalent to
+        // `bridge fdb append 00:00:00:00:00:00 dev <link> dst <remote>`
+        handle
+            .fdb()
+            .append(link.header.index, &[0u8; 6])
+            .destination(remote)
+            .execute()
+            .await?;
+
+        let mut entries = handle.fdb().get().port(link.header.index).execute();
+        while let Some(entry) = entries.try_next().await? {
+            println!(
+                "{:02x?} dst {:?}",
+                entry.link_layer_address().unwrap_or_default(),
+                entry.destination()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn usage() {
+    eprintln!(
+        "usage:
+    cargo run --example append_fdb -- <vxlan_link_name> <remote_ip>
+
+Note that you need to run this program as root. Instead of running cargo as root,
+build the example normally:
+
+    cd rtnetlink ; cargo build --example append_fdb
+
+Then find the binary in the target directory:
+
+    cd ../target/debug/example ;
+    sudo ./append_fdb <vxlan_link_name> <remote_ip>"
+    );
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MIT
+
+use std::env;
+
+use rtnetlink::new_connection;
+
+#[cfg(target_os = "freebsd")]
+fn main() -> () {}
+
+#[cfg(not(target_os = "freebsd"))]
+#[tokio::main]
+async fn main() -> Result<(), ()> {
+    env_logger::init();
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        usage();
+        return Ok(());
+    }
+
+    let index: u32 = args[1].parse().unwrap_or_else(|_| {
+        eprintln!("invalid index");
+        std::process::exit(1);
+    });
+
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+
+    match handle.tc_tree(index as i32).execute().await {
+        Ok(tree) => print!("{tree}"),
+        Err(e) => eprintln!("{e}"),
+    }
+
+    Ok(())
+}
+
+fn usage() {
+    eprintln!(
+        "usage:
+    cargo run --example get_tc_tree -- <index>"
+    );
+}
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MIT
+
+use std::env;
+
+use futures_util::stream::TryStreamExt;
+use rtnetlink::new_connection;
+
+#[tokio::main]
+async fn main() -> Result<(), ()> {
+    env_logger::init();
+    let names: Vec<String> = env::args().skip(1).collect();
+    if names.is_empty() {
+        usage();
+        return Ok(());
+    }
+
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+
+    let mut monitor = handle.link().rate_monitor();
+    for name in names {
+        monitor = monitor.name(name);
+    }
+    let mut rates = Box::pin(monitor.execute());
+    loop {
+        match rates.try_next().await {
+            Ok(Some(rates)) => {
+                for rate in rates {
+                    let Some(delta) = rate.delta else {
+                        continue;
+                    };
+                    println!(
+                        "{}: rx {:.0} bit/s {:.0} pkt/s, tx {:.0} bit/s \
+                         {:.0} pkt/s",
+                        rate.name,
+                        delta.rx_bps,
+                        delta.rx_pps,
+                        delta.tx_bps,
+                        delta.tx_pps
+                    );
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("{e}");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn usage() {
+    eprintln!(
+        "usage:
+    cargo run --example monitor_link_rates -- <link name>..."
+    );
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MIT
+
+use std::{env, net::IpAddr, time::Duration};
+
+use futures_channel::mpsc::UnboundedReceiver;
+use futures_util::stream::TryStreamExt;
+use rtnetlink::{
+    new_multicast_connection, packet_core::NetlinkMessage,
+    packet_route::RouteNetlinkMessage, sys::SocketAddr, Error, Handle,
+    MulticastGroup,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), ()> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        usage();
+        return Ok(());
+    }
+
+    let link_name = &args[1];
+    let ip: IpAddr = args[2].parse().unwrap_or_else(|_| {
+        eprintln!("invalid IP address");
+        std::process::exit(1);
+    });
+
+    // Subscribe to neighbour events before triggering the resolution so that
+    // the state change cannot be missed.
+    let (connection, handle, messages) =
+        new_multicast_connection(&[MulticastGroup::Neigh]).unwrap();
+    tokio::spawn(connection);
+
+    if let Err(e) = resolve_neighbour(link_name, ip, handle, messages).await {
+        eprintln!("{e}");
+    }
+    Ok(())
+}
+
+async fn resolve_neighbour(
+    link_name: &str,
+    ip: IpAddr,
+    handle: Handle,
+    messages: UnboundedReceiver<(
+        NetlinkMessage<RouteNetlinkMessage>,
+        SocketAddr,
+    )>,
+) -> Result<(), Error> {
+    let mut links = handle
+        .link()
+        .get()
+        .match_name(link_name.to_string())
+        .execute();
+    if let Some(link) = links.try_next().await? {
+        let neighbour = handle
+            .neighbours()
+            .resolve(link.header.index, ip)
+            .timeout(Duration::from_secs(5))
+            .execute(messages)
+            .await?;
+        println!("{neighbour:?}");
+    }
+
+    Ok(())
+}
+
+fn usage() {
+    eprintln!(
+        "usage:
+    cargo run --example resolve_neighbour -- <link_name> <ip_address>
+
+Note that you need to run this program as root. Instead of running cargo as root,
+build the example normally:
+
+    cd rtnetlink ; cargo build --example resolve_neighbour
+
+Then find the binary in the target directory:
+
+    cd ../target/debug/example ;
+    sudo ./resolve_neighbour <link_name> <ip_address>"
+    );
+}
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::link::{
+    BridgeMulticastRouterType, BridgePortState, BridgeVlanInfoFlags,
+};
+
+use super::message::{BridgeVlanSetEntry, RTM_NEWVLAN};
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle,
+};
+
+/// Add VLANs to a bridge port, or a bridge, or change their options.
+/// Equivalent to `bridge vlan add` and `bridge vlan set`.
+pub struct BridgeVlanAddRequest {
+    handle: Handle,
+    entry: BridgeVlanSetEntry,
+}
+
+impl BridgeVlanAddRequest {
+    pub(crate) fn new(
+        handle: Handle,
+        index: u32,
+        vid: u16,
+        flags: BridgeVlanInfoFlags,
+    ) -> Self {
+        BridgeVlanAddRequest {
+            handle,
+            entry: BridgeVlanSetEntry::new(index, vid, flags),
+        }
+    }
+
+    /// Apply the request to all the VLANs from `vid` to `vid_end`.
+    /// Equivalent to `vid VID-VID_END`.
+    pub fn range(mut self, vid_end: u16) -> Self {
+        self.entry.vid_end = Some(vid_end);
+        self
+    }
+
+    /// Use the VLAN for the untagged ingress traffic.
+    /// Equivalent to `pvid`, ignored by
+    /// [crate::BridgeVlanHandle::set].
+    pub fn pvid(mut self) -> Self {
+        self.entry.flags |= BridgeVlanInfoFlags::Pvid;
+        self
+    }
+
+    /// Send the VLAN traffic untagged on egress.
+    /// Equivalent to `untagged`, ignored by
+    /// [crate::BridgeVlanHandle::set].
+    pub fn untagged(mut self) -> Self {
+        self.entry.flags |= BridgeVlanInfoFlags::Untagged;
+        self
+    }
+
+    /// STP state of the VLAN on the port, refused when the bridge runs
+    /// multiple spanning trees.
+    /// Equivalent to `state STATE`.
+    pub fn state(mut self, state: BridgePortState) -> Self {
+        self.entry.state = Some(state);
+        self
+    }
+
+    /// Map the VLAN to the tunnel id `tunnel_id`, the VNI for VXLAN ports
+    /// with `vlan_tunnel` enabled, see [crate::LinkBridgePort].
+    /// Equivalent to `tunnel_info id TUNNEL_ID`.
+    pub fn tunnel_id(mut self, tunnel_id: u32) -> Self {
+        self.entry.tunnel_id = Some(Some(tunnel_id));
+        self
+    }
+
+    /// Remove the tunnel id mapped to the VLAN.
+    pub fn remove_tunnel_id(mut self) -> Self {
+        self.entry.tunnel_id = Some(None);
+        self
+    }
+
+    /// Multicast router mode of the VLAN on the port, requires
+    /// `mcast_vlan_snooping` on the [crate::LinkBridge].
+    /// Equivalent to `mcast_router MCAST_ROUTER`.
+    pub fn mcast_router(mut self, router: BridgeMulticastRouterType) -> Self {
+        self.entry.mcast_router = Some(router);
+        self
+    }
+
+    /// Maximum number of multicast groups the VLAN can join on the port, 0
+    /// for no limit.
+    /// Equivalent to `mcast_max_groups MAX_GROUPS`.
+    pub fn mcast_max_groups(mut self, max_groups: u32) -> Self {
+        self.entry.mcast_max_groups = Some(max_groups);
+        self
+    }
+
+    /// Equivalent to `neigh_suppress { on | off }`.
+    pub fn neigh_suppress(mut self, suppress: bool) -> Self {
+        self.entry.neigh_suppress = Some(suppress);
+        self
+    }
+
+    pub(crate) fn raw_message(&self) -> RawRouteMessage {
+        self.entry.build(RTM_NEWVLAN)
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message());
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
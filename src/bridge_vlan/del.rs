@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::link::BridgeVlanInfoFlags;
+
+use super::message::{BridgeVlanSetEntry, RTM_DELVLAN};
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle,
+};
+
+/// Delete VLANs of a bridge port, or a bridge.
+/// Equivalent to `bridge vlan del`.
+pub struct BridgeVlanDelRequest {
+    handle: Handle,
+    entry: BridgeVlanSetEntry,
+}
+
+impl BridgeVlanDelRequest {
+    pub(crate) fn new(handle: Handle, index: u32, vid: u16) -> Self {
+        BridgeVlanDelRequest {
+            handle,
+            entry: BridgeVlanSetEntry::new(
+                index,
+                vid,
+                BridgeVlanInfoFlags::empty(),
+            ),
+        }
+    }
+
+    /// Delete all the VLANs from `vid` to `vid_end`.
+    /// Equivalent to `vid VID-VID_END`.
+    pub fn range(mut self, vid_end: u16) -> Self {
+        self.entry.vid_end = Some(vid_end);
+        self
+    }
+
+    pub(crate) fn raw_message(&self) -> RawRouteMessage {
+        self.entry.build(RTM_DELVLAN)
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message());
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::{self, Stream, TryStreamExt};
+use netlink_packet_core::{NetlinkMessage, NLM_F_DUMP, NLM_F_REQUEST};
+
+use super::message::{
+    dump_request, parse_vlans, BRIDGE_VLANDB_DUMPF_GLOBAL,
+    BRIDGE_VLANDB_DUMPF_STATS, RTM_NEWVLAN,
+};
+use crate::{raw, BridgeVlanMessage, Error, Handle};
+
+/// List the VLANs of the bridges and bridge ports.
+/// Equivalent to `bridge vlan show`.
+pub struct BridgeVlanGetRequest {
+    handle: Handle,
+    index: u32,
+    dump_flags: u32,
+}
+
+impl BridgeVlanGetRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        BridgeVlanGetRequest {
+            handle,
+            index: 0,
+            dump_flags: 0,
+        }
+    }
+
+    /// Only list the VLANs of this bridge or bridge port (equivalent to
+    /// `dev DEVICE`).
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Include the traffic counters of the VLANs (equivalent to
+    /// `bridge -s vlan show`).
+    pub fn stats(mut self) -> Self {
+        self.dump_flags |= BRIDGE_VLANDB_DUMPF_STATS;
+        self
+    }
+
+    /// List the bridge-wide options of the VLANs of the bridges instead,
+    /// as [BridgeVlanMessage::GlobalOptions] (equivalent to
+    /// `bridge vlan global show`).
+    pub fn global(mut self) -> Self {
+        self.dump_flags |= BRIDGE_VLANDB_DUMPF_GLOBAL;
+        self
+    }
+
+    /// Execute the request.
+    pub fn execute(
+        self,
+    ) -> impl Stream<Item = Result<BridgeVlanMessage, Error>> {
+        let BridgeVlanGetRequest {
+            handle,
+            index,
+            dump_flags,
+        } = self;
+
+        let mut req = NetlinkMessage::from(dump_request(index, dump_flags));
+        req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+
+        stream::once(async move {
+            let mut vlans = Vec::new();
+            for message in raw::request(&handle, req).await? {
+                if message.message_type == RTM_NEWVLAN {
+                    vlans.extend(
+                        parse_vlans(&message.payload).map_err(|e| {
+                            Error::InvalidMessage(e.to_string())
+                        })?,
+                    );
+                }
+            }
+            Ok(stream::iter(vlans.into_iter().map(Ok)))
+        })
+        .try_flatten()
+    }
+}
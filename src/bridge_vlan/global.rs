@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+
+use super::message::BridgeVlanGlobalSetEntry;
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle,
+};
+
+/// Change the bridge-wide options of VLANs of a bridge.
+/// Equivalent to `bridge vlan global set`.
+pub struct BridgeVlanGlobalSetRequest {
+    handle: Handle,
+    entry: BridgeVlanGlobalSetEntry,
+}
+
+impl BridgeVlanGlobalSetRequest {
+    pub(crate) fn new(handle: Handle, index: u32, vid: u16) -> Self {
+        BridgeVlanGlobalSetRequest {
+            handle,
+            entry: BridgeVlanGlobalSetEntry::new(index, vid),
+        }
+    }
+
+    /// Apply the request to all the VLANs from `vid` to `vid_end`.
+    /// Equivalent to `vid VID-VID_END`.
+    pub fn range(mut self, vid_end: u16) -> Self {
+        self.entry.vid_end = Some(vid_end);
+        self
+    }
+
+    /// Multicast snooping of the VLAN, requires
+    /// `mcast_vlan_snooping` on the [crate::LinkBridge].
+    /// Equivalent to `mcast_snooping { 0 | 1 }`.
+    pub fn mcast_snooping(mut self, enabled: bool) -> Self {
+        self.entry.mcast_snooping = Some(enabled);
+        self
+    }
+
+    /// Equivalent to `mcast_igmp_version VERSION`.
+    pub fn mcast_igmp_version(mut self, version: u8) -> Self {
+        self.entry.mcast_igmp_version = Some(version);
+        self
+    }
+
+    /// Equivalent to `mcast_mld_version VERSION`.
+    pub fn mcast_mld_version(mut self, version: u8) -> Self {
+        self.entry.mcast_mld_version = Some(version);
+        self
+    }
+
+    /// Equivalent to `mcast_querier { 0 | 1 }`.
+    pub fn mcast_querier(mut self, enabled: bool) -> Self {
+        self.entry.mcast_querier = Some(enabled);
+        self
+    }
+
+    /// Assign the VLAN to the multiple spanning tree instance `msti`,
+    /// requires `mst_enabled` on the [crate::LinkBridge].
+    /// Equivalent to `msti MSTI`.
+    pub fn msti(mut self, msti: u16) -> Self {
+        self.entry.msti = Some(msti);
+        self
+    }
+
+    pub(crate) fn raw_message(&self) -> RawRouteMessage {
+        self.entry.build()
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message());
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_route::link::BridgeVlanInfoFlags;
+
+use crate::{
+    BridgeVlanAddRequest, BridgeVlanDelRequest, BridgeVlanGetRequest,
+    BridgeVlanGlobalSetRequest, BridgeVlanMonitor, Handle,
+};
+
+/// Handle of the per-VLAN options of bridges and bridge ports.
+///
+/// The `RTM_NEWVLAN` messages have no variant in
+/// [RouteNetlinkMessage](netlink_packet_route::RouteNetlinkMessage), so the
+/// requests are sent on their own netlink connection, opened in the network
+/// namespace of the [Handle].
+#[derive(Debug, Clone)]
+pub struct BridgeVlanHandle(Handle);
+
+impl BridgeVlanHandle {
+    pub fn new(handle: Handle) -> Self {
+        BridgeVlanHandle(handle)
+    }
+
+    /// List the VLANs of the bridges and bridge ports (equivalent to
+    /// `bridge vlan show`)
+    pub fn get(&self) -> BridgeVlanGetRequest {
+        BridgeVlanGetRequest::new(self.0.clone())
+    }
+
+    /// Add the VLAN `vid` to the bridge port, or the bridge itself, of index
+    /// `index` (equivalent to `bridge vlan add`)
+    pub fn add(&self, index: u32, vid: u16) -> BridgeVlanAddRequest {
+        BridgeVlanAddRequest::new(
+            self.0.clone(),
+            index,
+            vid,
+            BridgeVlanInfoFlags::empty(),
+        )
+    }
+
+    /// Change the options of the existing VLAN `vid` of the bridge port, or
+    /// the bridge itself, of index `index` (equivalent to `bridge vlan set`)
+    pub fn set(&self, index: u32, vid: u16) -> BridgeVlanAddRequest {
+        BridgeVlanAddRequest::new(
+            self.0.clone(),
+            index,
+            vid,
+            BridgeVlanInfoFlags::OnlyOpts,
+        )
+    }
+
+    /// Change the bridge-wide options of the VLAN `vid` of the bridge of
+    /// index `index` (equivalent to `bridge vlan global set`)
+    pub fn set_global(
+        &self,
+        index: u32,
+        vid: u16,
+    ) -> BridgeVlanGlobalSetRequest {
+        BridgeVlanGlobalSetRequest::new(self.0.clone(), index, vid)
+    }
+
+    /// Delete the VLAN `vid` of the bridge port, or the bridge itself, of
+    /// index `index` (equivalent to `bridge vlan del`)
+    pub fn del(&self, index: u32, vid: u16) -> BridgeVlanDelRequest {
+        BridgeVlanDelRequest::new(self.0.clone(), index, vid)
+    }
+
+    /// Receive the changes of the VLANs (equivalent to
+    /// `bridge monitor vlan`)
+    pub fn monitor(&self) -> BridgeVlanMonitor {
+        BridgeVlanMonitor::new(self.0.clone())
+    }
+}
@@ -0,0 +1,439 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{
+    parse_u16, parse_u32, parse_u64, parse_u8, DecodeError, DefaultNla,
+};
+use netlink_packet_route::{
+    link::{BridgeMulticastRouterType, BridgePortState, BridgeVlanInfoFlags},
+    AddressFamily,
+};
+
+use crate::{
+    nla::{
+        emit_nlas, nla_nested, nla_u16, nla_u32, nla_u8, parse_nlas, read_u16,
+        read_u32,
+    },
+    raw::RawRouteMessage,
+};
+
+pub(crate) const RTM_NEWVLAN: u16 = 112;
+pub(crate) const RTM_DELVLAN: u16 = 113;
+pub(crate) const RTM_GETVLAN: u16 = 114;
+
+const BRIDGE_VLANDB_ENTRY: u16 = 1;
+const BRIDGE_VLANDB_DUMP_FLAGS: u16 = 2;
+const BRIDGE_VLANDB_GLOBAL_OPTIONS: u16 = 3;
+
+pub(crate) const BRIDGE_VLANDB_DUMPF_STATS: u32 = 1 << 0;
+pub(crate) const BRIDGE_VLANDB_DUMPF_GLOBAL: u32 = 1 << 1;
+
+const BRIDGE_VLANDB_ENTRY_INFO: u16 = 1;
+const BRIDGE_VLANDB_ENTRY_RANGE: u16 = 2;
+const BRIDGE_VLANDB_ENTRY_STATE: u16 = 3;
+const BRIDGE_VLANDB_ENTRY_TUNNEL_INFO: u16 = 4;
+const BRIDGE_VLANDB_ENTRY_STATS: u16 = 5;
+const BRIDGE_VLANDB_ENTRY_MCAST_ROUTER: u16 = 6;
+const BRIDGE_VLANDB_ENTRY_MCAST_N_GROUPS: u16 = 7;
+const BRIDGE_VLANDB_ENTRY_MCAST_MAX_GROUPS: u16 = 8;
+const BRIDGE_VLANDB_ENTRY_NEIGH_SUPPRESS: u16 = 9;
+
+const BRIDGE_VLANDB_TINFO_ID: u16 = 1;
+const BRIDGE_VLANDB_TINFO_CMD: u16 = 2;
+
+const BRIDGE_VLANDB_STATS_RX_BYTES: u16 = 1;
+const BRIDGE_VLANDB_STATS_RX_PACKETS: u16 = 2;
+const BRIDGE_VLANDB_STATS_TX_BYTES: u16 = 3;
+const BRIDGE_VLANDB_STATS_TX_PACKETS: u16 = 4;
+
+const BRIDGE_VLANDB_GOPTS_ID: u16 = 1;
+const BRIDGE_VLANDB_GOPTS_RANGE: u16 = 2;
+const BRIDGE_VLANDB_GOPTS_MCAST_SNOOPING: u16 = 3;
+const BRIDGE_VLANDB_GOPTS_MCAST_IGMP_VERSION: u16 = 4;
+const BRIDGE_VLANDB_GOPTS_MCAST_MLD_VERSION: u16 = 5;
+const BRIDGE_VLANDB_GOPTS_MCAST_QUERIER: u16 = 15;
+const BRIDGE_VLANDB_GOPTS_MSTI: u16 = 18;
+
+const RTM_DELLINK: u32 = 17;
+const RTM_SETLINK: u32 = 19;
+
+// struct br_vlan_msg
+const BR_VLAN_MSG_LEN: usize = 8;
+
+/// Traffic counters of a VLAN on a port.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct BridgeVlanStats {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+}
+
+/// VLAN, or range of VLANs sharing the same options, of a bridge or a
+/// bridge port.
+/// Equivalent to a line of `bridge vlan show`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct BridgeVlanEntry {
+    /// Interface index of the bridge or the bridge port.
+    pub index: u32,
+    pub vid: u16,
+    /// Last VLAN of the range starting at [Self::vid], if any.
+    pub vid_end: Option<u16>,
+    pub flags: BridgeVlanInfoFlags,
+    /// STP state of the VLAN on the port.
+    pub state: Option<BridgePortState>,
+    /// Tunnel id mapped to the VLAN, the VNI for VXLAN ports.
+    pub tunnel_id: Option<u32>,
+    /// Only dumped with [crate::BridgeVlanGetRequest::stats].
+    pub stats: Option<BridgeVlanStats>,
+    pub mcast_router: Option<BridgeMulticastRouterType>,
+    pub mcast_n_groups: Option<u32>,
+    pub mcast_max_groups: Option<u32>,
+    pub neigh_suppress: Option<bool>,
+}
+
+/// Bridge-wide options of a VLAN, or range of VLANs.
+/// Equivalent to a line of `bridge vlan global show`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct BridgeVlanGlobalOptions {
+    /// Interface index of the bridge.
+    pub index: u32,
+    pub vid: u16,
+    /// Last VLAN of the range starting at [Self::vid], if any.
+    pub vid_end: Option<u16>,
+    pub mcast_snooping: Option<bool>,
+    pub mcast_igmp_version: Option<u8>,
+    pub mcast_mld_version: Option<u8>,
+    pub mcast_querier: Option<bool>,
+    /// Multiple spanning tree instance of the VLAN.
+    pub msti: Option<u16>,
+}
+
+/// Content of a `RTM_NEWVLAN` or `RTM_DELVLAN` message.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BridgeVlanMessage {
+    Entry(BridgeVlanEntry),
+    GlobalOptions(BridgeVlanGlobalOptions),
+}
+
+/// Change of the bridge VLANs, received from
+/// [crate::BridgeVlanHandle::monitor].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BridgeVlanEvent {
+    New(BridgeVlanMessage),
+    Del(BridgeVlanMessage),
+}
+
+impl BridgeVlanEvent {
+    pub(crate) fn parse(
+        message: &RawRouteMessage,
+    ) -> Result<Vec<Self>, DecodeError> {
+        let event = match message.message_type {
+            RTM_NEWVLAN => Self::New,
+            RTM_DELVLAN => Self::Del,
+            _ => return Ok(Vec::new()),
+        };
+        Ok(parse_vlans(&message.payload)?
+            .into_iter()
+            .map(event)
+            .collect())
+    }
+}
+
+/// Parse the VLANs of a `RTM_NEWVLAN` or `RTM_DELVLAN` message.
+pub(crate) fn parse_vlans(
+    payload: &[u8],
+) -> Result<Vec<BridgeVlanMessage>, DecodeError> {
+    let index = read_u32(payload, 4)?;
+    let nlas = payload.get(BR_VLAN_MSG_LEN..).unwrap_or_default();
+    let mut messages = Vec::new();
+    for (kind, value) in parse_nlas(nlas)? {
+        match kind {
+            BRIDGE_VLANDB_ENTRY => messages
+                .push(BridgeVlanMessage::Entry(parse_entry(index, value)?)),
+            BRIDGE_VLANDB_GLOBAL_OPTIONS => {
+                messages.push(BridgeVlanMessage::GlobalOptions(
+                    parse_global_options(index, value)?,
+                ))
+            }
+            _ => (),
+        }
+    }
+    Ok(messages)
+}
+
+fn parse_entry(
+    index: u32,
+    payload: &[u8],
+) -> Result<BridgeVlanEntry, DecodeError> {
+    let mut entry = BridgeVlanEntry {
+        index,
+        vid: 0,
+        vid_end: None,
+        flags: BridgeVlanInfoFlags::empty(),
+        state: None,
+        tunnel_id: None,
+        stats: None,
+        mcast_router: None,
+        mcast_n_groups: None,
+        mcast_max_groups: None,
+        neigh_suppress: None,
+    };
+    for (kind, value) in parse_nlas(payload)? {
+        match kind {
+            BRIDGE_VLANDB_ENTRY_INFO => {
+                // struct bridge_vlan_info
+                entry.flags =
+                    BridgeVlanInfoFlags::from_bits_retain(read_u16(value, 0)?);
+                entry.vid = read_u16(value, 2)?;
+            }
+            BRIDGE_VLANDB_ENTRY_RANGE => {
+                entry.vid_end = Some(parse_u16(value)?)
+            }
+            BRIDGE_VLANDB_ENTRY_STATE => {
+                entry.state = Some(parse_u8(value)?.into())
+            }
+            BRIDGE_VLANDB_ENTRY_TUNNEL_INFO => {
+                for (kind, value) in parse_nlas(value)? {
+                    if kind == BRIDGE_VLANDB_TINFO_ID {
+                        entry.tunnel_id = Some(parse_u32(value)?);
+                    }
+                }
+            }
+            BRIDGE_VLANDB_ENTRY_STATS => {
+                let mut stats = BridgeVlanStats::default();
+                for (kind, value) in parse_nlas(value)? {
+                    match kind {
+                        BRIDGE_VLANDB_STATS_RX_BYTES => {
+                            stats.rx_bytes = parse_u64(value)?
+                        }
+                        BRIDGE_VLANDB_STATS_RX_PACKETS => {
+                            stats.rx_packets = parse_u64(value)?
+                        }
+                        BRIDGE_VLANDB_STATS_TX_BYTES => {
+                            stats.tx_bytes = parse_u64(value)?
+                        }
+                        BRIDGE_VLANDB_STATS_TX_PACKETS => {
+                            stats.tx_packets = parse_u64(value)?
+                        }
+                        _ => (),
+                    }
+                }
+                entry.stats = Some(stats);
+            }
+            BRIDGE_VLANDB_ENTRY_MCAST_ROUTER => {
+                entry.mcast_router = Some(parse_u8(value)?.into())
+            }
+            BRIDGE_VLANDB_ENTRY_MCAST_N_GROUPS => {
+                entry.mcast_n_groups = Some(parse_u32(value)?)
+            }
+            BRIDGE_VLANDB_ENTRY_MCAST_MAX_GROUPS => {
+                entry.mcast_max_groups = Some(parse_u32(value)?)
+            }
+            BRIDGE_VLANDB_ENTRY_NEIGH_SUPPRESS => {
+                entry.neigh_suppress = Some(parse_u8(value)? != 0)
+            }
+            _ => (),
+        }
+    }
+    Ok(entry)
+}
+
+fn parse_global_options(
+    index: u32,
+    payload: &[u8],
+) -> Result<BridgeVlanGlobalOptions, DecodeError> {
+    let mut options = BridgeVlanGlobalOptions {
+        index,
+        vid: 0,
+        vid_end: None,
+        mcast_snooping: None,
+        mcast_igmp_version: None,
+        mcast_mld_version: None,
+        mcast_querier: None,
+        msti: None,
+    };
+    for (kind, value) in parse_nlas(payload)? {
+        match kind {
+            BRIDGE_VLANDB_GOPTS_ID => options.vid = parse_u16(value)?,
+            BRIDGE_VLANDB_GOPTS_RANGE => {
+                options.vid_end = Some(parse_u16(value)?)
+            }
+            BRIDGE_VLANDB_GOPTS_MCAST_SNOOPING => {
+                options.mcast_snooping = Some(parse_u8(value)? != 0)
+            }
+            BRIDGE_VLANDB_GOPTS_MCAST_IGMP_VERSION => {
+                options.mcast_igmp_version = Some(parse_u8(value)?)
+            }
+            BRIDGE_VLANDB_GOPTS_MCAST_MLD_VERSION => {
+                options.mcast_mld_version = Some(parse_u8(value)?)
+            }
+            BRIDGE_VLANDB_GOPTS_MCAST_QUERIER => {
+                options.mcast_querier = Some(parse_u8(value)? != 0)
+            }
+            BRIDGE_VLANDB_GOPTS_MSTI => options.msti = Some(parse_u16(value)?),
+            _ => (),
+        }
+    }
+    Ok(options)
+}
+
+/// `struct br_vlan_msg` of the device `index`.
+fn br_vlan_msg(index: u32) -> Vec<u8> {
+    let mut header = vec![0u8; BR_VLAN_MSG_LEN];
+    header[0] = AddressFamily::Bridge.into();
+    header[4..].copy_from_slice(&index.to_ne_bytes());
+    header
+}
+
+/// `RTM_GETVLAN` dump request of the device `index`, all of them for 0.
+pub(crate) fn dump_request(index: u32, dump_flags: u32) -> RawRouteMessage {
+    let mut payload = br_vlan_msg(index);
+    if dump_flags != 0 {
+        payload.extend(emit_nlas(&[nla_u32(
+            BRIDGE_VLANDB_DUMP_FLAGS,
+            dump_flags,
+        )]));
+    }
+    RawRouteMessage::new(RTM_GETVLAN, payload)
+}
+
+/// Per-port options of a VLAN, or range of VLANs, in a `RTM_NEWVLAN` or
+/// `RTM_DELVLAN` request.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct BridgeVlanSetEntry {
+    pub(crate) index: u32,
+    pub(crate) vid: u16,
+    pub(crate) vid_end: Option<u16>,
+    pub(crate) flags: BridgeVlanInfoFlags,
+    pub(crate) state: Option<BridgePortState>,
+    pub(crate) tunnel_id: Option<Option<u32>>,
+    pub(crate) mcast_router: Option<BridgeMulticastRouterType>,
+    pub(crate) mcast_max_groups: Option<u32>,
+    pub(crate) neigh_suppress: Option<bool>,
+}
+
+impl BridgeVlanSetEntry {
+    pub(crate) fn new(
+        index: u32,
+        vid: u16,
+        flags: BridgeVlanInfoFlags,
+    ) -> Self {
+        BridgeVlanSetEntry {
+            index,
+            vid,
+            vid_end: None,
+            flags,
+            state: None,
+            tunnel_id: None,
+            mcast_router: None,
+            mcast_max_groups: None,
+            neigh_suppress: None,
+        }
+    }
+
+    pub(crate) fn build(&self, message_type: u16) -> RawRouteMessage {
+        let mut info = self.flags.bits().to_ne_bytes().to_vec();
+        info.extend(self.vid.to_ne_bytes());
+        let mut nlas = vec![DefaultNla::new(BRIDGE_VLANDB_ENTRY_INFO, info)];
+        if let Some(vid_end) = self.vid_end {
+            nlas.push(nla_u16(BRIDGE_VLANDB_ENTRY_RANGE, vid_end));
+        }
+        if let Some(state) = self.state {
+            nlas.push(nla_u8(BRIDGE_VLANDB_ENTRY_STATE, state.into()));
+        }
+        if let Some(tunnel_id) = self.tunnel_id {
+            let tinfo = match tunnel_id {
+                Some(id) => vec![
+                    nla_u32(BRIDGE_VLANDB_TINFO_ID, id),
+                    nla_u32(BRIDGE_VLANDB_TINFO_CMD, RTM_SETLINK),
+                ],
+                None => vec![nla_u32(BRIDGE_VLANDB_TINFO_CMD, RTM_DELLINK)],
+            };
+            nlas.push(nla_nested(BRIDGE_VLANDB_ENTRY_TUNNEL_INFO, &tinfo));
+        }
+        if let Some(router) = self.mcast_router {
+            nlas.push(nla_u8(BRIDGE_VLANDB_ENTRY_MCAST_ROUTER, router.into()));
+        }
+        if let Some(max_groups) = self.mcast_max_groups {
+            nlas.push(nla_u32(
+                BRIDGE_VLANDB_ENTRY_MCAST_MAX_GROUPS,
+                max_groups,
+            ));
+        }
+        if let Some(suppress) = self.neigh_suppress {
+            nlas.push(nla_u8(
+                BRIDGE_VLANDB_ENTRY_NEIGH_SUPPRESS,
+                suppress.into(),
+            ));
+        }
+        let mut payload = br_vlan_msg(self.index);
+        payload.extend(emit_nlas(&[nla_nested(BRIDGE_VLANDB_ENTRY, &nlas)]));
+        RawRouteMessage::new(message_type, payload)
+    }
+}
+
+/// Bridge-wide options of a VLAN, or range of VLANs, in a `RTM_NEWVLAN`
+/// request.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct BridgeVlanGlobalSetEntry {
+    pub(crate) index: u32,
+    pub(crate) vid: u16,
+    pub(crate) vid_end: Option<u16>,
+    pub(crate) mcast_snooping: Option<bool>,
+    pub(crate) mcast_igmp_version: Option<u8>,
+    pub(crate) mcast_mld_version: Option<u8>,
+    pub(crate) mcast_querier: Option<bool>,
+    pub(crate) msti: Option<u16>,
+}
+
+impl BridgeVlanGlobalSetEntry {
+    pub(crate) fn new(index: u32, vid: u16) -> Self {
+        BridgeVlanGlobalSetEntry {
+            index,
+            vid,
+            vid_end: None,
+            mcast_snooping: None,
+            mcast_igmp_version: None,
+            mcast_mld_version: None,
+            mcast_querier: None,
+            msti: None,
+        }
+    }
+
+    pub(crate) fn build(&self) -> RawRouteMessage {
+        let mut nlas = vec![nla_u16(BRIDGE_VLANDB_GOPTS_ID, self.vid)];
+        if let Some(vid_end) = self.vid_end {
+            nlas.push(nla_u16(BRIDGE_VLANDB_GOPTS_RANGE, vid_end));
+        }
+        if let Some(snooping) = self.mcast_snooping {
+            nlas.push(nla_u8(
+                BRIDGE_VLANDB_GOPTS_MCAST_SNOOPING,
+                snooping.into(),
+            ));
+        }
+        if let Some(version) = self.mcast_igmp_version {
+            nlas.push(nla_u8(BRIDGE_VLANDB_GOPTS_MCAST_IGMP_VERSION, version));
+        }
+        if let Some(version) = self.mcast_mld_version {
+            nlas.push(nla_u8(BRIDGE_VLANDB_GOPTS_MCAST_MLD_VERSION, version));
+        }
+        if let Some(querier) = self.mcast_querier {
+            nlas.push(nla_u8(
+                BRIDGE_VLANDB_GOPTS_MCAST_QUERIER,
+                querier.into(),
+            ));
+        }
+        if let Some(msti) = self.msti {
+            nlas.push(nla_u16(BRIDGE_VLANDB_GOPTS_MSTI, msti));
+        }
+        let mut payload = br_vlan_msg(self.index);
+        payload.extend(emit_nlas(&[nla_nested(
+            BRIDGE_VLANDB_GLOBAL_OPTIONS,
+            &nlas,
+        )]));
+        RawRouteMessage::new(RTM_NEWVLAN, payload)
+    }
+}
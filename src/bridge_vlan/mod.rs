@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: MIT
+
+mod add;
+mod del;
+mod get;
+mod global;
+mod handle;
+mod message;
+mod monitor;
+
+#[cfg(test)]
+mod test;
+
+pub use self::{
+    add::BridgeVlanAddRequest,
+    del::BridgeVlanDelRequest,
+    get::BridgeVlanGetRequest,
+    global::BridgeVlanGlobalSetRequest,
+    handle::BridgeVlanHandle,
+    message::{
+        BridgeVlanEntry, BridgeVlanEvent, BridgeVlanGlobalOptions,
+        BridgeVlanMessage, BridgeVlanStats,
+    },
+    monitor::BridgeVlanMonitor,
+};
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::{self, Stream, StreamExt};
+
+use crate::{raw, BridgeVlanEvent, Error, Handle, MulticastGroup};
+
+/// Receive the changes of the bridge VLANs, which the connections of this
+/// crate cannot decode.
+/// Equivalent to `bridge monitor vlan`.
+pub struct BridgeVlanMonitor {
+    handle: Handle,
+}
+
+impl BridgeVlanMonitor {
+    pub(crate) fn new(handle: Handle) -> Self {
+        BridgeVlanMonitor { handle }
+    }
+
+    /// Execute the request, subscribing to [MulticastGroup::Brvlan] on a
+    /// dedicated socket: the changes made after this call are received.
+    ///
+    /// Must be called from the context of the async runtime.
+    pub fn execute(
+        self,
+    ) -> Result<impl Stream<Item = Result<BridgeVlanEvent, Error>>, Error> {
+        let messages = raw::subscribe(&self.handle, &[MulticastGroup::Brvlan])?;
+        Ok(messages.flat_map(|message| {
+            stream::iter(match BridgeVlanEvent::parse(&message) {
+                Ok(events) => events.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(Error::InvalidMessage(e.to_string()))],
+            })
+        }))
+    }
+}
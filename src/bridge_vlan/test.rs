@@ -0,0 +1,350 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use futures_util::stream::{StreamExt, TryStreamExt};
+use netlink_packet_route::link::{
+    BridgeMulticastRouterType, BridgePortState, BridgeVlanInfoFlags,
+};
+use tokio::runtime::Runtime;
+
+use super::message::{dump_request, parse_vlans};
+use crate::{
+    new_connection,
+    test_netns::{setup_bridge, TEST_BRIDGE},
+    BridgeVlanEntry, BridgeVlanEvent, BridgeVlanGlobalOptions,
+    BridgeVlanMessage, BridgeVlanStats, Error, Handle, LinkBridge,
+};
+
+const TEST_NS: &str = "netlink_test_bridge_vlan_ns";
+
+#[test]
+fn bridge_vlan_set_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge vlan set dev eth0 vid 10-20 state blocking mcast_router 2 \
+        //     neigh_suppress on
+        let message = handle
+            .bridge_vlan()
+            .set(4, 10)
+            .range(20)
+            .state(BridgePortState::Blocking)
+            .mcast_router(BridgeMulticastRouterType::Permanent)
+            .neigh_suppress(true)
+            .raw_message();
+        assert_eq!(message.message_type, 112);
+        assert_eq!(
+            message.payload,
+            vec![
+                7, 0, 0, 0, 4, 0, 0, 0, // br_vlan_msg
+                44, 0, 1, 0x80, // BRIDGE_VLANDB_ENTRY
+                8, 0, 1, 0, 0x40, 0, 10, 0, // BRIDGE_VLANDB_ENTRY_INFO
+                6, 0, 2, 0, 20, 0, 0, 0, // BRIDGE_VLANDB_ENTRY_RANGE
+                5, 0, 3, 0, 4, 0, 0, 0, // BRIDGE_VLANDB_ENTRY_STATE
+                5, 0, 6, 0, 2, 0, 0, 0, // MCAST_ROUTER
+                5, 0, 9, 0, 1, 0, 0, 0, // NEIGH_SUPPRESS
+            ]
+        );
+    });
+}
+
+#[test]
+fn bridge_vlan_add_del_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge vlan add dev vxlan0 vid 10 pvid untagged tunnel_info id 1010
+        let message = handle
+            .bridge_vlan()
+            .add(4, 10)
+            .pvid()
+            .untagged()
+            .tunnel_id(1010)
+            .raw_message();
+        assert_eq!(message.message_type, 112);
+        assert_eq!(
+            message.payload[8..],
+            [
+                32, 0, 1, 0x80, // BRIDGE_VLANDB_ENTRY
+                8, 0, 1, 0, 6, 0, 10, 0, // BRIDGE_VLANDB_ENTRY_INFO
+                20, 0, 4, 0x80, // BRIDGE_VLANDB_ENTRY_TUNNEL_INFO
+                8, 0, 1, 0, 0xf2, 0x03, 0, 0, // BRIDGE_VLANDB_TINFO_ID
+                8, 0, 2, 0, 19, 0, 0, 0, // BRIDGE_VLANDB_TINFO_CMD
+            ]
+        );
+
+        // Remove the tunnel id mapped to vid 10
+        let message = handle
+            .bridge_vlan()
+            .set(4, 10)
+            .remove_tunnel_id()
+            .raw_message();
+        assert_eq!(
+            message.payload[20..],
+            [
+                12, 0, 4, 0x80, // BRIDGE_VLANDB_ENTRY_TUNNEL_INFO
+                8, 0, 2, 0, 17, 0, 0, 0, // BRIDGE_VLANDB_TINFO_CMD
+            ]
+        );
+
+        // bridge vlan del dev eth0 vid 10-20
+        let message = handle.bridge_vlan().del(4, 10).range(20).raw_message();
+        assert_eq!(message.message_type, 113);
+        assert_eq!(
+            message.payload[8..],
+            [
+                20, 0, 1, 0x80, // BRIDGE_VLANDB_ENTRY
+                8, 0, 1, 0, 0, 0, 10, 0, // BRIDGE_VLANDB_ENTRY_INFO
+                6, 0, 2, 0, 20, 0, 0, 0, // BRIDGE_VLANDB_ENTRY_RANGE
+            ]
+        );
+    });
+}
+
+#[test]
+fn bridge_vlan_global_set_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge vlan global set dev br0 vid 10-20 mcast_snooping 1 msti 5
+        let message = handle
+            .bridge_vlan()
+            .set_global(3, 10)
+            .range(20)
+            .mcast_snooping(true)
+            .msti(5)
+            .raw_message();
+        assert_eq!(message.message_type, 112);
+        assert_eq!(
+            message.payload,
+            vec![
+                7, 0, 0, 0, 3, 0, 0, 0, // br_vlan_msg
+                36, 0, 3, 0x80, // BRIDGE_VLANDB_GLOBAL_OPTIONS
+                6, 0, 1, 0, 10, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_ID
+                6, 0, 2, 0, 20, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_RANGE
+                5, 0, 3, 0, 1, 0, 0, 0, // MCAST_SNOOPING
+                6, 0, 18, 0, 5, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_MSTI
+            ]
+        );
+    });
+}
+
+#[test]
+fn bridge_vlan_dump_request() {
+    // bridge -s vlan show dev eth0
+    let message = dump_request(4, 1);
+    assert_eq!(message.message_type, 114);
+    assert_eq!(
+        message.payload,
+        vec![
+            7, 0, 0, 0, 4, 0, 0, 0, // br_vlan_msg
+            8, 0, 2, 0, 1, 0, 0, 0, // BRIDGE_VLANDB_DUMP_FLAGS
+        ]
+    );
+}
+
+#[test]
+fn bridge_vlan_parse() {
+    #[rustfmt::skip]
+    let payload = vec![
+        7, 0, 0, 0, 4, 0, 0, 0, // br_vlan_msg
+        72, 0, 1, 0x80, // BRIDGE_VLANDB_ENTRY
+        8, 0, 1, 0, 6, 0, 1, 0, // BRIDGE_VLANDB_ENTRY_INFO
+        5, 0, 3, 0, 3, 0, 0, 0, // BRIDGE_VLANDB_ENTRY_STATE
+        12, 0, 4, 0x80, // BRIDGE_VLANDB_ENTRY_TUNNEL_INFO
+        8, 0, 1, 0, 0xf2, 0x03, 0, 0, // BRIDGE_VLANDB_TINFO_ID
+        40, 0, 5, 0x80, // BRIDGE_VLANDB_ENTRY_STATS
+        12, 0, 1, 0, 100, 0, 0, 0, 0, 0, 0, 0, // RX_BYTES
+        12, 0, 2, 0, 1, 0, 0, 0, 0, 0, 0, 0, // RX_PACKETS
+        12, 0, 3, 0, 0, 1, 0, 0, 0, 0, 0, 0, // TX_BYTES
+        20, 0, 3, 0x80, // BRIDGE_VLANDB_GLOBAL_OPTIONS
+        6, 0, 1, 0, 1, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_ID
+        5, 0, 4, 0, 3, 0, 0, 0, // MCAST_IGMP_VERSION
+    ];
+    let entry = BridgeVlanEntry {
+        index: 4,
+        vid: 1,
+        vid_end: None,
+        flags: BridgeVlanInfoFlags::Pvid | BridgeVlanInfoFlags::Untagged,
+        state: Some(BridgePortState::Forwarding),
+        tunnel_id: Some(1010),
+        stats: Some(BridgeVlanStats {
+            rx_bytes: 100,
+            rx_packets: 1,
+            tx_bytes: 256,
+            tx_packets: 0,
+        }),
+        mcast_router: None,
+        mcast_n_groups: None,
+        mcast_max_groups: None,
+        neigh_suppress: None,
+    };
+    let options = BridgeVlanGlobalOptions {
+        index: 4,
+        vid: 1,
+        vid_end: None,
+        mcast_snooping: None,
+        mcast_igmp_version: Some(3),
+        mcast_mld_version: None,
+        mcast_querier: None,
+        msti: None,
+    };
+    assert_eq!(
+        parse_vlans(&payload).unwrap(),
+        vec![
+            BridgeVlanMessage::Entry(entry),
+            BridgeVlanMessage::GlobalOptions(options)
+        ]
+    );
+}
+
+async fn vlan_entries(handle: &Handle, index: u32) -> Vec<BridgeVlanEntry> {
+    handle
+        .bridge_vlan()
+        .get()
+        .index(index)
+        .stats()
+        .execute()
+        .try_filter_map(|message| async move {
+            Ok(match message {
+                BridgeVlanMessage::Entry(entry) => Some(entry),
+                BridgeVlanMessage::GlobalOptions(_) => None,
+            })
+        })
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+// The VLAN messages are only handled by kernels built with
+// CONFIG_BRIDGE_VLAN_FILTERING.
+async fn vlan_filtering_supported() -> bool {
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+    let result: Result<Vec<_>, _> =
+        handle.bridge_vlan().get().execute().try_collect().await;
+    match result {
+        Err(Error::NetlinkError(e))
+            if e.raw_code() == -nix::libc::EOPNOTSUPP =>
+        {
+            false
+        }
+        result => {
+            result.unwrap();
+            true
+        }
+    }
+}
+
+async fn test_async_bridge_vlan() {
+    if !vlan_filtering_supported().await {
+        eprintln!("skipped: the kernel has no bridge VLAN filtering");
+        return;
+    }
+    let (handle, bridge, port, _netns) = setup_bridge(
+        TEST_NS,
+        LinkBridge::new(TEST_BRIDGE)
+            .vlan_filtering(true)
+            .mcast_vlan_snooping(true)
+            .build(),
+    )
+    .await;
+    let (bridge, port) = (bridge.header.index, port.header.index);
+    let mut events = handle.bridge_vlan().monitor().execute().unwrap();
+
+    handle
+        .bridge_vlan()
+        .add(port, 10)
+        .range(12)
+        .untagged()
+        .execute()
+        .await
+        .unwrap();
+    handle
+        .bridge_vlan()
+        .set(port, 11)
+        .state(BridgePortState::Blocking)
+        .mcast_router(BridgeMulticastRouterType::Disabled)
+        .neigh_suppress(true)
+        .execute()
+        .await
+        .unwrap();
+    handle
+        .bridge_vlan()
+        .set_global(bridge, 1)
+        .mcast_igmp_version(3)
+        .execute()
+        .await
+        .unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(1), events.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        event,
+        BridgeVlanEvent::New(BridgeVlanMessage::Entry(BridgeVlanEntry {
+            index,
+            vid: 10,
+            ..
+        })) if index == port
+    ));
+
+    let entries = vlan_entries(&handle, port).await;
+    let find = |vid: u16| {
+        entries
+            .iter()
+            .find(|entry| {
+                entry.vid <= vid && vid <= entry.vid_end.unwrap_or(entry.vid)
+            })
+            .unwrap_or_else(|| panic!("VLAN {vid} not in {entries:?}"))
+    };
+    let entry = find(10);
+    assert!(entry.flags.contains(BridgeVlanInfoFlags::Untagged));
+    assert_eq!(entry.state, Some(BridgePortState::Forwarding));
+    assert!(entry.stats.is_some());
+    let entry = find(11);
+    assert_eq!(entry.state, Some(BridgePortState::Blocking));
+    assert_eq!(
+        entry.mcast_router,
+        Some(BridgeMulticastRouterType::Disabled)
+    );
+    assert_eq!(entry.neigh_suppress, Some(true));
+
+    let options: Vec<_> = handle
+        .bridge_vlan()
+        .get()
+        .index(bridge)
+        .global()
+        .execute()
+        .try_collect()
+        .await
+        .unwrap();
+    assert!(options.iter().any(|message| matches!(
+        message,
+        BridgeVlanMessage::GlobalOptions(BridgeVlanGlobalOptions {
+            vid: 1,
+            mcast_igmp_version: Some(3),
+            ..
+        })
+    )));
+
+    handle
+        .bridge_vlan()
+        .del(port, 10)
+        .range(12)
+        .execute()
+        .await
+        .unwrap();
+    assert!(vlan_entries(&handle, port)
+        .await
+        .iter()
+        .all(|entry| entry.vid_end.unwrap_or(entry.vid) < 10));
+}
+
+#[test]
+fn test_bridge_vlan() {
+    Runtime::new().unwrap().block_on(test_async_bridge_vlan());
+}
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MIT
 
+use std::net::IpAddr;
+
 use netlink_packet_core::{ErrorMessage, NetlinkMessage};
 use netlink_packet_route::RouteNetlinkMessage;
 use thiserror::Error;
@@ -31,4 +33,19 @@ pub enum Error {
 
     #[error("Attempting to set and Invalid NLA: {0}")]
     InvalidNla(String),
+
+    #[error("Timed out waiting for the kernel")]
+    Timeout,
+
+    #[error("Neighbour {0} could not be resolved")]
+    NeighbourUnreachable(IpAddr),
+
+    #[error("BPF error {0}")]
+    BpfError(String),
+
+    #[error("Invalid tc notation {0:?}")]
+    InvalidTcNotation(String),
+
+    #[error("Received an invalid netlink message: {0}")]
+    InvalidMessage(String),
 }
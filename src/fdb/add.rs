@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use futures_util::stream::StreamExt;
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    neighbour::{
+        NeighbourAttribute, NeighbourFlags, NeighbourMessage, NeighbourState,
+    },
+    route::RouteType,
+    AddressFamily, RouteNetlinkMessage,
+};
+
+use super::default_to_own;
+use crate::{
+    neighbour::{set_attribute, set_destination, set_nhid},
+    try_nl, Error, Handle,
+};
+
+const NUD_REACHABLE: u16 = 0x02;
+const NUD_NOARP: u16 = 0x40;
+const NUD_PERMANENT: u16 = 0x80;
+
+pub struct FdbAddRequest {
+    handle: Handle,
+    message: NeighbourMessage,
+    flags: u16,
+}
+
+impl FdbAddRequest {
+    pub(crate) fn new(
+        handle: Handle,
+        index: u32,
+        lla: &[u8],
+        flags: u16,
+    ) -> Self {
+        let mut message = NeighbourMessage::default();
+
+        message.header.family = AddressFamily::Bridge;
+        message.header.ifindex = index;
+        message.header.state = NeighbourState::from(NUD_NOARP | NUD_PERMANENT);
+        message.header.kind = RouteType::Unspec;
+
+        message
+            .attributes
+            .push(NeighbourAttribute::LinkLayerAddress(lla.to_vec()));
+
+        FdbAddRequest {
+            handle,
+            message,
+            flags: NLM_F_REQUEST | flags,
+        }
+    }
+
+    /// Add the entry to the fdb of the bridge this device is a port of
+    /// (equivalent to `master`).
+    pub fn master(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::Controller;
+        self
+    }
+
+    /// Add the entry to the fdb of the device itself, like a VXLAN device
+    /// (equivalent to `self`). This is the default unless
+    /// [FdbAddRequest::master()] is used.
+    pub fn own(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::Own;
+        self
+    }
+
+    /// Mark the entry as permanent, which makes it a local address of a
+    /// bridge (equivalent to `permanent` or `local`). This is the default.
+    pub fn permanent(mut self) -> Self {
+        self.message.header.state =
+            NeighbourState::from(NUD_NOARP | NUD_PERMANENT);
+        self
+    }
+
+    /// Mark the entry as static: it is never aged out (equivalent to
+    /// `static`).
+    pub fn static_entry(mut self) -> Self {
+        self.message.header.state =
+            NeighbourState::from(NUD_NOARP | NUD_REACHABLE);
+        self
+    }
+
+    /// Mark the entry as dynamic: it is aged out like a learned entry
+    /// (equivalent to `dynamic`).
+    pub fn dynamic(mut self) -> Self {
+        self.message.header.state = NeighbourState::from(NUD_REACHABLE);
+        self
+    }
+
+    /// Prevent the entry from roaming to another port (equivalent to
+    /// `sticky`).
+    pub fn sticky(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::Sticky;
+        self
+    }
+
+    /// Mark the entry as learned by an external control plane (equivalent
+    /// to `extern_learn`).
+    pub fn extern_learn(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::ExtLearned;
+        self
+    }
+
+    /// Set the VLAN of the entry (equivalent to `vlan VID`).
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vlan(vlan));
+        self
+    }
+
+    /// Set the IP address of the remote VXLAN tunnel endpoint (equivalent to
+    /// `dst IPADDR`).
+    pub fn destination(mut self, addr: IpAddr) -> Self {
+        set_destination(&mut self.message, addr);
+        self
+    }
+
+    /// Set the VNI used to reach the remote (equivalent to `vni VNI`).
+    pub fn vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vni(vni));
+        self
+    }
+
+    /// Set the source VNI of the entry on a collect metadata VXLAN device
+    /// (equivalent to `src_vni VNI`).
+    pub fn src_vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::SourceVni(vni));
+        self
+    }
+
+    /// Set the UDP destination port of the remote (equivalent to
+    /// `port PORT`).
+    pub fn port(mut self, port: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Port(port));
+        self
+    }
+
+    /// Set the outgoing interface used to reach the remote (equivalent to
+    /// `via DEVICE`).
+    pub fn via(mut self, index: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::IfIndex(index));
+        self
+    }
+
+    /// Use a nexthop group to reach the remote (equivalent to `nhid ID`).
+    pub fn nhid(mut self, id: u32) -> Self {
+        set_nhid(&mut self.message, id);
+        self
+    }
+
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let FdbAddRequest {
+            mut handle,
+            mut message,
+            flags,
+        } = self;
+
+        default_to_own(&mut message);
+
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::NewNeighbour(message));
+        req.header.flags = NLM_F_ACK | flags;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request message.
+    pub fn message_mut(&mut self) -> &mut NeighbourMessage {
+        &mut self.message
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use futures_util::stream::StreamExt;
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    neighbour::{NeighbourAttribute, NeighbourFlags, NeighbourMessage},
+    AddressFamily, RouteNetlinkMessage,
+};
+
+use super::default_to_own;
+use crate::{
+    neighbour::{set_attribute, set_destination},
+    try_nl, Error, Handle,
+};
+
+pub struct FdbDelRequest {
+    handle: Handle,
+    message: NeighbourMessage,
+}
+
+impl FdbDelRequest {
+    pub(crate) fn new(handle: Handle, index: u32, lla: &[u8]) -> Self {
+        let mut message = NeighbourMessage::default();
+
+        message.header.family = AddressFamily::Bridge;
+        message.header.ifindex = index;
+
+        message
+            .attributes
+            .push(NeighbourAttribute::LinkLayerAddress(lla.to_vec()));
+
+        FdbDelRequest { handle, message }
+    }
+
+    /// Delete the entry from the fdb of the bridge this device is a port of
+    /// (equivalent to `master`).
+    pub fn master(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::Controller;
+        self
+    }
+
+    /// Delete the entry from the fdb of the device itself (equivalent to
+    /// `self`). This is the default unless [FdbDelRequest::master()] is used.
+    pub fn own(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::Own;
+        self
+    }
+
+    /// Set the VLAN of the entry (equivalent to `vlan VID`).
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vlan(vlan));
+        self
+    }
+
+    /// Set the IP address of the remote VXLAN tunnel endpoint, required to
+    /// delete one of several entries appended for the same address
+    /// (equivalent to `dst IPADDR`).
+    pub fn destination(mut self, addr: IpAddr) -> Self {
+        set_destination(&mut self.message, addr);
+        self
+    }
+
+    /// Set the VNI used to reach the remote (equivalent to `vni VNI`).
+    pub fn vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vni(vni));
+        self
+    }
+
+    /// Set the source VNI of the entry (equivalent to `src_vni VNI`).
+    pub fn src_vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::SourceVni(vni));
+        self
+    }
+
+    /// Set the UDP destination port of the remote (equivalent to
+    /// `port PORT`).
+    pub fn port(mut self, port: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Port(port));
+        self
+    }
+
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let FdbDelRequest {
+            mut handle,
+            mut message,
+        } = self;
+
+        default_to_own(&mut message);
+
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::DelNeighbour(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request message.
+    pub fn message_mut(&mut self) -> &mut NeighbourMessage {
+        &mut self.message
+    }
+}
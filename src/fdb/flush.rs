@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use futures_util::stream::StreamExt;
+use netlink_packet_core::{
+    DefaultNla, NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST,
+};
+use netlink_packet_route::{
+    neighbour::{
+        NeighbourAttribute, NeighbourFlags, NeighbourMessage, NeighbourState,
+    },
+    AddressFamily, RouteNetlinkMessage,
+};
+
+use super::default_to_own;
+use crate::{
+    neighbour::{set_attribute, set_destination, set_nhid},
+    try_nl, Error, Handle,
+};
+
+const NLM_F_BULK: u16 = 0x200;
+const NDA_NDM_STATE_MASK: u16 = 16;
+const NDA_NDM_FLAGS_MASK: u16 = 17;
+
+pub struct FdbFlushRequest {
+    handle: Handle,
+    message: NeighbourMessage,
+}
+
+impl FdbFlushRequest {
+    pub(crate) fn new(handle: Handle, index: u32) -> Self {
+        let mut message = NeighbourMessage::default();
+
+        message.header.family = AddressFamily::Bridge;
+        message.header.ifindex = index;
+
+        FdbFlushRequest { handle, message }
+    }
+
+    /// Flush the fdb of the bridge this device is a port of (equivalent to
+    /// `master`).
+    pub fn master(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::Controller;
+        self
+    }
+
+    /// Flush the fdb of the device itself (equivalent to `self`). This is
+    /// the default unless [FdbFlushRequest::master()] is used.
+    pub fn own(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::Own;
+        self
+    }
+
+    /// Only flush entries pointing to this bridge port (equivalent to
+    /// `brport DEVICE`).
+    pub fn brport(mut self, index: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::IfIndex(index));
+        self
+    }
+
+    /// Only flush entries of this VLAN (equivalent to `vlan VID`).
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vlan(vlan));
+        self
+    }
+
+    /// Only flush entries whose state bits selected by `mask` are equal to
+    /// `state`. For example, `state(Permanent, Permanent)` is equivalent to
+    /// `permanent` while `state(None, Permanent)` is equivalent to
+    /// `nopermanent`.
+    pub fn state(
+        mut self,
+        state: NeighbourState,
+        mask: NeighbourState,
+    ) -> Self {
+        self.message.header.state = state;
+        set_attribute(
+            &mut self.message,
+            NeighbourAttribute::Other(DefaultNla::new(
+                NDA_NDM_STATE_MASK,
+                u16::from(mask).to_ne_bytes().to_vec(),
+            )),
+        );
+        self
+    }
+
+    /// Only flush entries whose flags selected by `mask` are equal to
+    /// `flags`. For example, `flags(Sticky, Sticky)` is equivalent to
+    /// `sticky` while `flags(empty(), ExtLearned)` is equivalent to
+    /// `noextern_learn`.
+    pub fn flags(
+        mut self,
+        flags: NeighbourFlags,
+        mask: NeighbourFlags,
+    ) -> Self {
+        let own = NeighbourFlags::Own | NeighbourFlags::Controller;
+        self.message.header.flags =
+            (self.message.header.flags & own) | (flags - own);
+        set_attribute(
+            &mut self.message,
+            NeighbourAttribute::Other(DefaultNla::new(
+                NDA_NDM_FLAGS_MASK,
+                vec![(mask - own).bits()],
+            )),
+        );
+        self
+    }
+
+    /// Only flush entries pointing to this remote VXLAN tunnel endpoint
+    /// (equivalent to `dst IPADDR`).
+    pub fn destination(mut self, addr: IpAddr) -> Self {
+        set_destination(&mut self.message, addr);
+        self
+    }
+
+    /// Only flush entries with this VNI (equivalent to `vni VNI`).
+    pub fn vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vni(vni));
+        self
+    }
+
+    /// Only flush entries with this source VNI (equivalent to
+    /// `src_vni VNI`).
+    pub fn src_vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::SourceVni(vni));
+        self
+    }
+
+    /// Only flush entries with this UDP destination port (equivalent to
+    /// `port PORT`).
+    pub fn port(mut self, port: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Port(port));
+        self
+    }
+
+    /// Only flush entries using this nexthop group (equivalent to
+    /// `nhid ID`).
+    pub fn nhid(mut self, id: u32) -> Self {
+        set_nhid(&mut self.message, id);
+        self
+    }
+
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let FdbFlushRequest {
+            mut handle,
+            mut message,
+        } = self;
+
+        default_to_own(&mut message);
+
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::DelNeighbour(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_BULK;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request message.
+    pub fn message_mut(&mut self) -> &mut NeighbourMessage {
+        &mut self.message
+    }
+}
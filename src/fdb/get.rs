@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::{
+    future,
+    stream::{Stream, TryStreamExt},
+};
+use netlink_packet_route::{neighbour::NeighbourMessage, AddressFamily};
+
+use crate::{Error, Handle, NeighbourGetRequest, NeighbourMessageExt};
+
+pub struct FdbGetRequest {
+    handle: Handle,
+    bridge: Option<u32>,
+    port: Option<u32>,
+    vlan: Option<u16>,
+}
+
+impl FdbGetRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        FdbGetRequest {
+            handle,
+            bridge: None,
+            port: None,
+            vlan: None,
+        }
+    }
+
+    /// Only list the entries of this bridge, including the ones of its ports
+    /// (equivalent to `br DEVICE`).
+    pub fn bridge(mut self, index: u32) -> Self {
+        self.bridge = Some(index);
+        self
+    }
+
+    /// Only list the entries of this device (equivalent to `brport DEVICE`
+    /// or `dev DEVICE`).
+    pub fn port(mut self, index: u32) -> Self {
+        self.port = Some(index);
+        self
+    }
+
+    /// Only list the entries of this VLAN (equivalent to `vlan VID`).
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        self.vlan = Some(vlan);
+        self
+    }
+
+    /// Execute the request.
+    ///
+    /// The filters are applied on the dumped entries, as the kernel only
+    /// filters fdb dumps on sockets with strict checking enabled.
+    pub fn execute(
+        self,
+    ) -> impl Stream<Item = Result<NeighbourMessage, Error>> {
+        let FdbGetRequest {
+            handle,
+            bridge,
+            port,
+            vlan,
+        } = self;
+
+        NeighbourGetRequest::new(handle)
+            .set_address_family(AddressFamily::Bridge)
+            .execute()
+            .try_filter(move |msg| {
+                let matched = bridge.map_or(true, |bridge| {
+                    msg.header.ifindex == bridge || msg.master() == Some(bridge)
+                }) && port
+                    .map_or(true, |port| msg.header.ifindex == port)
+                    && vlan.map_or(true, |vlan| msg.vlan() == Some(vlan));
+                future::ready(matched)
+            })
+    }
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{
+    NLM_F_APPEND, NLM_F_CREATE, NLM_F_EXCL, NLM_F_REPLACE,
+};
+
+use crate::{
+    FdbAddRequest, FdbDelRequest, FdbFlushRequest, FdbGetRequest, Handle,
+};
+
+#[derive(Debug, Clone)]
+pub struct FdbHandle(Handle);
+
+impl FdbHandle {
+    pub fn new(handle: Handle) -> Self {
+        FdbHandle(handle)
+    }
+
+    /// List fdb entries (equivalent to `bridge fdb show`)
+    pub fn get(&self) -> FdbGetRequest {
+        FdbGetRequest::new(self.0.clone())
+    }
+
+    /// Add a new fdb entry, fail if it already exists (equivalent to
+    /// `bridge fdb add`)
+    pub fn add(&self, index: u32, lla: &[u8]) -> FdbAddRequest {
+        FdbAddRequest::new(
+            self.0.clone(),
+            index,
+            lla,
+            NLM_F_CREATE | NLM_F_EXCL,
+        )
+    }
+
+    /// Append a fdb entry, allowing several remote destinations for the same
+    /// link layer address on VXLAN devices (equivalent to
+    /// `bridge fdb append`)
+    pub fn append(&self, index: u32, lla: &[u8]) -> FdbAddRequest {
+        FdbAddRequest::new(
+            self.0.clone(),
+            index,
+            lla,
+            NLM_F_CREATE | NLM_F_APPEND,
+        )
+    }
+
+    /// Add a fdb entry or replace the existing one (equivalent to
+    /// `bridge fdb replace`)
+    pub fn replace(&self, index: u32, lla: &[u8]) -> FdbAddRequest {
+        FdbAddRequest::new(
+            self.0.clone(),
+            index,
+            lla,
+            NLM_F_CREATE | NLM_F_REPLACE,
+        )
+    }
+
+    /// Delete a fdb entry (equivalent to `bridge fdb del`)
+    pub fn del(&self, index: u32, lla: &[u8]) -> FdbDelRequest {
+        FdbDelRequest::new(self.0.clone(), index, lla)
+    }
+
+    /// Delete all the fdb entries of a device matching the filters of the
+    /// request (equivalent to `bridge fdb flush`)
+    pub fn flush(&self, index: u32) -> FdbFlushRequest {
+        FdbFlushRequest::new(self.0.clone(), index)
+    }
+}
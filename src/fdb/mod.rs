@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MIT
+
+mod add;
+mod del;
+mod flush;
+mod get;
+mod handle;
+
+#[cfg(test)]
+mod test;
+
+use netlink_packet_route::neighbour::{NeighbourFlags, NeighbourMessage};
+
+pub use self::{
+    add::FdbAddRequest, del::FdbDelRequest, flush::FdbFlushRequest,
+    get::FdbGetRequest, handle::FdbHandle,
+};
+
+// Target the fdb of the device itself unless `master` or `self` was asked,
+// like iproute2.
+fn default_to_own(message: &mut NeighbourMessage) {
+    if !message
+        .header
+        .flags
+        .intersects(NeighbourFlags::Own | NeighbourFlags::Controller)
+    {
+        message.header.flags |= NeighbourFlags::Own;
+    }
+}
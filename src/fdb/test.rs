@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use futures_util::{future, stream::TryStreamExt};
+use netlink_packet_core::Emitable;
+use netlink_packet_route::neighbour::{
+    NeighbourFlags, NeighbourMessage, NeighbourState,
+};
+use tokio::runtime::Runtime;
+
+use crate::{
+    new_connection,
+    test_netns::{get_link, setup_netns},
+    Handle, LinkVxlan, NeighbourMessageExt,
+};
+
+const TEST_NS: &str = "netlink_test_fdb_ns";
+const TEST_VXLAN: &str = "test_vxlan";
+const MAC: [u8; 6] = [2, 0, 0, 0, 0, 1];
+const ZERO_MAC: [u8; 6] = [0; 6];
+
+fn emit(message: &NeighbourMessage) -> Vec<u8> {
+    let mut buffer = vec![0u8; message.buffer_len()];
+    message.emit(&mut buffer);
+    buffer
+}
+
+fn remote(last: u8) -> IpAddr {
+    Ipv4Addr::new(192, 0, 2, last).into()
+}
+
+#[test]
+fn fdb_add_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge fdb add 02:00:00:00:00:01 dev vxlan0 dst 192.0.2.2 \
+        //     port 4789 vni 100 src_vni 200 via eth0 nhid 7 self
+        let mut request = handle
+            .fdb()
+            .add(4, &MAC)
+            .destination(remote(2))
+            .port(4789)
+            .vni(100)
+            .src_vni(200)
+            .via(2)
+            .nhid(7)
+            .own();
+        #[rustfmt::skip]
+        assert_eq!(
+            emit(request.message_mut()),
+            vec![
+                7, 0, 0, 0, 4, 0, 0, 0, // family, ifindex
+                0xc0, 0, 0x02, 0, // NUD_NOARP | NUD_PERMANENT, NTF_SELF
+                10, 0, 2, 0, 2, 0, 0, 0, 0, 1, 0, 0, // NDA_LLADDR
+                8, 0, 1, 0, 192, 0, 2, 2, // NDA_DST
+                6, 0, 6, 0, 0x12, 0xb5, 0, 0, // NDA_PORT
+                8, 0, 7, 0, 100, 0, 0, 0, // NDA_VNI
+                8, 0, 11, 0, 200, 0, 0, 0, // NDA_SRC_VNI
+                8, 0, 8, 0, 2, 0, 0, 0, // NDA_IFINDEX
+                8, 0, 13, 0, 7, 0, 0, 0, // NDA_NH_ID
+            ]
+        );
+
+        // bridge fdb add 02:00:00:00:00:01 dev eth0 master static vlan 10 \
+        //     sticky extern_learn
+        let mut request = handle
+            .fdb()
+            .add(4, &MAC)
+            .master()
+            .static_entry()
+            .vlan(10)
+            .sticky()
+            .extern_learn();
+        #[rustfmt::skip]
+        assert_eq!(
+            emit(request.message_mut())[8..],
+            [
+                // NUD_NOARP | NUD_REACHABLE,
+                // NTF_MASTER | NTF_EXT_LEARNED | NTF_STICKY
+                0x42, 0, 0x54, 0,
+                10, 0, 2, 0, 2, 0, 0, 0, 0, 1, 0, 0, // NDA_LLADDR
+                6, 0, 5, 0, 10, 0, 0, 0, // NDA_VLAN
+            ]
+        );
+    });
+}
+
+#[test]
+fn fdb_del_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge fdb del 00:00:00:00:00:00 dev vxlan0 dst 192.0.2.2 vni 100 \
+        //     self
+        // iproute2 also sets NUD_NOARP | NUD_PERMANENT, which the kernel
+        // ignores on deletion.
+        let mut request = handle
+            .fdb()
+            .del(4, &ZERO_MAC)
+            .destination(remote(2))
+            .vni(100)
+            .own();
+        #[rustfmt::skip]
+        assert_eq!(
+            emit(request.message_mut()),
+            vec![
+                7, 0, 0, 0, 4, 0, 0, 0, // family, ifindex
+                0, 0, 0x02, 0, // NTF_SELF
+                10, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, // NDA_LLADDR
+                8, 0, 1, 0, 192, 0, 2, 2, // NDA_DST
+                8, 0, 7, 0, 100, 0, 0, 0, // NDA_VNI
+            ]
+        );
+    });
+}
+
+#[test]
+fn fdb_flush_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge fdb flush dev br0 master vlan 10 permanent nosticky
+        let mut request = handle
+            .fdb()
+            .flush(3)
+            .master()
+            .vlan(10)
+            .state(NeighbourState::Permanent, NeighbourState::Permanent)
+            .flags(NeighbourFlags::empty(), NeighbourFlags::Sticky);
+        #[rustfmt::skip]
+        assert_eq!(
+            emit(request.message_mut()),
+            vec![
+                7, 0, 0, 0, 3, 0, 0, 0, // family, ifindex
+                0x80, 0, 0x04, 0, // NUD_PERMANENT, NTF_MASTER
+                6, 0, 5, 0, 10, 0, 0, 0, // NDA_VLAN
+                6, 0, 16, 0, 0x80, 0, 0, 0, // NDA_NDM_STATE_MASK
+                5, 0, 17, 0, 0x40, 0, 0, 0, // NDA_NDM_FLAGS_MASK
+            ]
+        );
+    });
+}
+
+async fn remotes(handle: &Handle, index: u32, mac: &[u8]) -> Vec<IpAddr> {
+    handle
+        .fdb()
+        .get()
+        .port(index)
+        .execute()
+        .try_filter_map(|msg| async move {
+            Ok((msg.link_layer_address() == Some(mac))
+                .then(|| msg.destination())
+                .flatten())
+        })
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+async fn test_async_fdb_vxlan() {
+    let (handle, _netns) = setup_netns(TEST_NS).await;
+    handle
+        .link()
+        .add(
+            LinkVxlan::new(TEST_VXLAN, 100)
+                .port(4789)
+                .learning(false)
+                .build(),
+        )
+        .execute()
+        .await
+        .unwrap();
+    let vxlan = get_link(&handle, TEST_VXLAN).await.header.index;
+
+    // Head end replication to two remotes.
+    for last in [2, 3] {
+        handle
+            .fdb()
+            .append(vxlan, &ZERO_MAC)
+            .destination(remote(last))
+            .execute()
+            .await
+            .unwrap();
+    }
+    handle
+        .fdb()
+        .add(vxlan, &MAC)
+        .destination(remote(4))
+        .vni(200)
+        .port(4790)
+        .execute()
+        .await
+        .unwrap();
+
+    let mut flood = remotes(&handle, vxlan, &ZERO_MAC).await;
+    flood.sort();
+    assert_eq!(flood, vec![remote(2), remote(3)]);
+    let entry = handle
+        .fdb()
+        .get()
+        .port(vxlan)
+        .execute()
+        .try_filter(|msg| {
+            future::ready(msg.link_layer_address() == Some(&MAC[..]))
+        })
+        .try_next()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.destination(), Some(remote(4)));
+    assert_eq!(entry.vni(), Some(200));
+    assert_eq!(entry.port(), Some(4790));
+
+    handle
+        .fdb()
+        .del(vxlan, &ZERO_MAC)
+        .destination(remote(2))
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(remotes(&handle, vxlan, &ZERO_MAC).await, vec![remote(3)]);
+
+    handle
+        .fdb()
+        .flush(vxlan)
+        .destination(remote(4))
+        .state(NeighbourState::Permanent, NeighbourState::Permanent)
+        .execute()
+        .await
+        .unwrap();
+    assert!(remotes(&handle, vxlan, &MAC).await.is_empty());
+    assert_eq!(remotes(&handle, vxlan, &ZERO_MAC).await, vec![remote(3)]);
+}
+
+#[test]
+fn test_fdb_vxlan() {
+    Runtime::new().unwrap().block_on(test_async_fdb_vxlan());
+}
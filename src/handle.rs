@@ -1,5 +1,11 @@
 // SPDX-License-Identifier: MIT
 
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+use std::{os::fd::OwnedFd, sync::Arc};
+
 use futures_util::stream::Stream;
 use netlink_packet_core::NetlinkMessage;
 use netlink_packet_route::RouteNetlinkMessage;
@@ -8,17 +14,47 @@ use netlink_proto::{sys::SocketAddr, ConnectionHandle};
 use crate::{
     AddressHandle, Error, LinkHandle, NeighbourHandle, RouteHandle, RuleHandle,
 };
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+use crate::{BridgeVlanHandle, MdbHandle, VniHandle};
 #[cfg(not(target_os = "freebsd"))]
 use crate::{
-    QDiscHandle, TrafficChainHandle, TrafficClassHandle, TrafficFilterHandle,
+    FdbHandle, QDiscHandle, TrafficActionHandle, TrafficChainHandle,
+    TrafficClassHandle, TrafficFilterHandle, TrafficTreeGetRequest,
 };
 
 #[derive(Clone, Debug)]
-pub struct Handle(ConnectionHandle<RouteNetlinkMessage>);
+pub struct Handle(
+    ConnectionHandle<RouteNetlinkMessage>,
+    // The network namespace of the thread creating the connection, for the
+    // requests which cannot go through it, see `raw.rs`.
+    #[cfg(all(
+        not(target_os = "freebsd"),
+        any(feature = "tokio_socket", feature = "smol_socket")
+    ))]
+    Option<Arc<OwnedFd>>,
+);
 
 impl Handle {
     pub(crate) fn new(conn: ConnectionHandle<RouteNetlinkMessage>) -> Self {
-        Handle(conn)
+        Handle(
+            conn,
+            #[cfg(all(
+                not(target_os = "freebsd"),
+                any(feature = "tokio_socket", feature = "smol_socket")
+            ))]
+            crate::raw::current_netns().map(Arc::new),
+        )
+    }
+
+    #[cfg(all(
+        not(target_os = "freebsd"),
+        any(feature = "tokio_socket", feature = "smol_socket")
+    ))]
+    pub(crate) fn netns(&self) -> Option<&OwnedFd> {
+        self.1.as_deref()
     }
 
     pub fn request(
@@ -71,6 +107,43 @@ impl Handle {
         NeighbourHandle::new(self.clone())
     }
 
+    /// Create a new handle, specifically for bridge forwarding database
+    /// requests (equivalent to `bridge fdb` commands)
+    #[cfg(not(target_os = "freebsd"))]
+    pub fn fdb(&self) -> FdbHandle {
+        FdbHandle::new(self.clone())
+    }
+
+    /// Create a new handle, specifically for bridge multicast database
+    /// requests (equivalent to `bridge mdb` commands)
+    #[cfg(all(
+        not(target_os = "freebsd"),
+        any(feature = "tokio_socket", feature = "smol_socket")
+    ))]
+    pub fn mdb(&self) -> MdbHandle {
+        MdbHandle::new(self.clone())
+    }
+
+    /// Create a new handle, specifically for bridge per-VLAN requests
+    /// (equivalent to `bridge vlan` commands)
+    #[cfg(all(
+        not(target_os = "freebsd"),
+        any(feature = "tokio_socket", feature = "smol_socket")
+    ))]
+    pub fn bridge_vlan(&self) -> BridgeVlanHandle {
+        BridgeVlanHandle::new(self.clone())
+    }
+
+    /// Create a new handle, specifically for the VNI filter of VXLAN
+    /// devices (equivalent to `bridge vni` commands)
+    #[cfg(all(
+        not(target_os = "freebsd"),
+        any(feature = "tokio_socket", feature = "smol_socket")
+    ))]
+    pub fn vni(&self) -> VniHandle {
+        VniHandle::new(self.clone())
+    }
+
     /// Create a new handle, specifically for traffic control qdisc requests
     /// (equivalent to `tc qdisc show` commands)
     #[cfg(not(target_os = "freebsd"))]
@@ -92,10 +165,25 @@ impl Handle {
         TrafficFilterHandle::new(self.clone(), ifindex)
     }
 
+    /// Create a new handle, specifically for traffic control action requests
+    /// (equivalent to `tc actions` commands)
+    #[cfg(not(target_os = "freebsd"))]
+    pub fn traffic_action(&self) -> TrafficActionHandle {
+        TrafficActionHandle::new(self.clone())
+    }
+
     /// Create a new handle, specifically for traffic control chain requests
     /// (equivalent to `tc chain show dev <interface_name>` commands)
     #[cfg(not(target_os = "freebsd"))]
     pub fn traffic_chain(&self, ifindex: i32) -> TrafficChainHandle {
         TrafficChainHandle::new(self.clone(), ifindex)
     }
+
+    /// Retrieve the qdiscs, classes, filters and chains of a device as a
+    /// tree (equivalent to `tc -s qdisc/class/filter show dev
+    /// <interface_name>` combined)
+    #[cfg(not(target_os = "freebsd"))]
+    pub fn tc_tree(&self, ifindex: i32) -> TrafficTreeGetRequest {
+        TrafficTreeGetRequest::new(self.clone(), ifindex)
+    }
 }
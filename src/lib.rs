@@ -11,31 +11,84 @@ pub use netlink_proto as proto;
 pub use netlink_sys as sys;
 
 mod addr;
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+mod bridge_vlan;
 mod connection;
 pub mod constants;
 mod errors;
+#[cfg(not(target_os = "freebsd"))]
+mod fdb;
 mod handle;
 mod link;
 mod macros;
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+mod mdb;
 mod multicast;
 mod neighbour;
 #[cfg(not(target_os = "freebsd"))]
+mod nla;
+#[cfg(not(target_os = "freebsd"))]
 mod ns;
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+mod raw;
 mod route;
 mod rule;
+#[cfg(all(test, not(target_os = "freebsd")))]
+mod test_netns;
+mod time;
 #[cfg(not(target_os = "freebsd"))]
 mod traffic_control;
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+mod vni;
 
 #[cfg(feature = "tokio_socket")]
 pub use crate::connection::{new_connection, new_multicast_connection};
 #[cfg(not(target_os = "freebsd"))]
+pub use crate::fdb::{
+    FdbAddRequest, FdbDelRequest, FdbFlushRequest, FdbGetRequest, FdbHandle,
+};
+#[cfg(not(target_os = "freebsd"))]
 pub use crate::ns::{NetworkNamespace, NETNS_PATH, NONE_FS, SELF_NS_PATH};
 #[cfg(not(target_os = "freebsd"))]
 pub use crate::traffic_control::{
-    QDiscDelRequest, QDiscGetRequest, QDiscHandle, QDiscNewRequest,
-    TrafficChainGetRequest, TrafficChainHandle, TrafficClassGetRequest,
-    TrafficClassHandle, TrafficFilterGetRequest, TrafficFilterHandle,
-    TrafficFilterNewRequest,
+    CakeAckFilter, CakeAtm, CakeDiffserv, CakeFlowMode, CakeTinStats,
+    CakeXstats, Ematch, EmatchCmp, EmatchCmpAlign, EmatchLayer, EmatchMeta,
+    EmatchMetaId, EmatchOperand, EmatchU32, FqXstats, HfscServiceCurve,
+    HtbXstats, MqprioMode, MqprioShaper, NetemLoss, NetemRate, NetemSlot,
+    PeditCmd, PeditHeader, PeditKey, QDiscBfifo, QDiscCake, QDiscDelRequest,
+    QDiscDrr, QDiscEts, QDiscFq, QDiscFqCodel, QDiscGetRequest, QDiscHandle,
+    QDiscHfsc, QDiscHtb, QDiscMq, QDiscMqprio, QDiscNetem, QDiscNewRequest,
+    QDiscOptions, QDiscPfifo, QDiscPrio, QDiscSfq, QDiscStatsPollRequest,
+    QDiscTaprio, QDiscTbf, TaprioFramePreemption, TaprioGateCommand,
+    TaprioSchedEntry, TcActionExt, TcClassId, TcMessageExt, TcRate, TcSize,
+    TcTime, TrafficActionCsum, TrafficActionCt, TrafficActionDelRequest,
+    TrafficActionGact, TrafficActionGetRequest, TrafficActionHandle,
+    TrafficActionMirred, TrafficActionNat, TrafficActionNewRequest,
+    TrafficActionOptions, TrafficActionPedit, TrafficActionPolice,
+    TrafficActionSkbedit, TrafficActionTunnelKey, TrafficActionVlan,
+    TrafficBlock, TrafficBlockGetRequest, TrafficChainDelRequest,
+    TrafficChainGetRequest, TrafficChainHandle, TrafficChainNewRequest,
+    TrafficClassDelRequest, TrafficClassDrr, TrafficClassEts,
+    TrafficClassGetRequest, TrafficClassHandle, TrafficClassHfsc,
+    TrafficClassHtb, TrafficClassNewRequest, TrafficClassOptions,
+    TrafficFilterBasic, TrafficFilterBpf, TrafficFilterFlower,
+    TrafficFilterFlushRequest, TrafficFilterFw, TrafficFilterGetRequest,
+    TrafficFilterHandle, TrafficFilterMatchall, TrafficFilterNewRequest,
+    TrafficFilterOptions, TrafficFilterRoute, TrafficFilterU32, TrafficStats,
+    TrafficStatsDelta, TrafficStatsSample, TrafficTree, TrafficTreeGetRequest,
+    TrafficTreeNode, TrafficXstats, U32Match, VlanOp,
 };
 pub use crate::{
     addr::{
@@ -56,15 +109,16 @@ pub use crate::{
         LinkGetRequest, LinkGre, LinkGre6, LinkGtp, LinkHandle, LinkHsr,
         LinkIfb, LinkIp6Tnl, LinkIpIp, LinkIpVlan, LinkIpVtap, LinkIpoib,
         LinkMacSec, LinkMacVlan, LinkMacVtap, LinkMessageBuilder,
-        LinkNetdevsim, LinkNetkit, LinkNlmon, LinkPfcp, LinkRmNet,
-        LinkSetRequest, LinkSit, LinkTeam, LinkTun, LinkUnspec, LinkVcan,
-        LinkVeth, LinkVirtWifi, LinkVlan, LinkVrf, LinkVti, LinkVti6,
-        LinkVxcan, LinkVxlan, LinkWireguard, LinkWwan, LinkXfrm, QosMapping,
+        LinkNetdevsim, LinkNetkit, LinkNlmon, LinkPfcp, LinkRate,
+        LinkRateDelta, LinkRateMonitor, LinkRmNet, LinkSetRequest, LinkSit,
+        LinkStats, LinkTeam, LinkTun, LinkUnspec, LinkVcan, LinkVeth,
+        LinkVirtWifi, LinkVlan, LinkVrf, LinkVti, LinkVti6, LinkVxcan,
+        LinkVxlan, LinkWireguard, LinkWwan, LinkXfrm, QosMapping, StatsRequest,
     },
     multicast::MulticastGroup,
     neighbour::{
         NeighbourAddRequest, NeighbourDelRequest, NeighbourGetRequest,
-        NeighbourHandle,
+        NeighbourHandle, NeighbourMessageExt, NeighbourResolveRequest,
     },
     route::{
         IpVersion, RouteAddRequest, RouteDelRequest, RouteGetRequest,
@@ -72,3 +126,25 @@ pub use crate::{
     },
     rule::{RuleAddRequest, RuleDelRequest, RuleGetRequest, RuleHandle},
 };
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+pub use crate::{
+    bridge_vlan::{
+        BridgeVlanAddRequest, BridgeVlanDelRequest, BridgeVlanEntry,
+        BridgeVlanEvent, BridgeVlanGetRequest, BridgeVlanGlobalOptions,
+        BridgeVlanGlobalSetRequest, BridgeVlanHandle, BridgeVlanMessage,
+        BridgeVlanMonitor, BridgeVlanStats,
+    },
+    link::StatsSetRequest,
+    mdb::{
+        MdbAddRequest, MdbDelRequest, MdbEntry, MdbEvent, MdbFilterMode,
+        MdbGetRequest, MdbGroup, MdbHandle, MdbMessage, MdbMonitor,
+        MdbRouterPort, MdbSource,
+    },
+    vni::{
+        VniAddRequest, VniDelRequest, VniEntry, VniEvent, VniGetRequest,
+        VniHandle, VniMonitor, VniStats,
+    },
+};
@@ -4,7 +4,7 @@ use crate::{
     packet_route::{
         link::{
             AfSpecBridge, BridgeFlag, BridgeVlanInfo, BridgeVlanInfoFlags,
-            LinkAttribute,
+            BridgeVlanTunnelInfo, LinkAttribute,
         },
         AddressFamily,
     },
@@ -65,4 +65,54 @@ impl LinkMessageBuilder<LinkBridgeVlan> {
     pub fn bridge_self(self) -> Self {
         self.append_af_spec(AfSpecBridge::Flags(BridgeFlag::LowerDev))
     }
+
+    /// Map VLAN `vid` to tunnel id `tunnel_id` (the VNI for VXLAN ports).
+    /// Equal to `bridge vlan add dev vxlan0 vid 10 tunnel_info id 1010`.
+    ///
+    /// Requires `vlan_tunnel` enabled on the port, see [crate::LinkBridgePort].
+    /// The mappings are reported in [AfSpecBridge::VlanTunnelInfo] when
+    /// dumping links of [AddressFamily::Bridge] with
+    /// [crate::packet_route::link::LinkExtentMask::Brvlan].
+    pub fn vlan_tunnel(self, vid: u16, tunnel_id: u32) -> Self {
+        self.vlan_tunnel_with_flags(
+            vid,
+            tunnel_id,
+            BridgeVlanInfoFlags::empty(),
+        )
+    }
+
+    /// Start of a range of VLAN to tunnel id mappings, to be followed by
+    /// [Self::vlan_tunnel_range_end]. The VLAN range and the tunnel id range
+    /// must have the same size.
+    /// Equal to `bridge vlan add dev vxlan0 vid 10-20 tunnel_info id 1010-1020`.
+    pub fn vlan_tunnel_range_start(self, vid: u16, tunnel_id: u32) -> Self {
+        self.vlan_tunnel_with_flags(
+            vid,
+            tunnel_id,
+            BridgeVlanInfoFlags::RangeBegin,
+        )
+    }
+
+    /// End of a range of VLAN to tunnel id mappings started with
+    /// [Self::vlan_tunnel_range_start].
+    pub fn vlan_tunnel_range_end(self, vid: u16, tunnel_id: u32) -> Self {
+        self.vlan_tunnel_with_flags(
+            vid,
+            tunnel_id,
+            BridgeVlanInfoFlags::RangeEnd,
+        )
+    }
+
+    fn vlan_tunnel_with_flags(
+        self,
+        vid: u16,
+        tunnel_id: u32,
+        flags: BridgeVlanInfoFlags,
+    ) -> Self {
+        self.append_af_spec(AfSpecBridge::VlanTunnelInfo(vec![
+            BridgeVlanTunnelInfo::Id(tunnel_id),
+            BridgeVlanTunnelInfo::Vid(vid),
+            BridgeVlanTunnelInfo::Flags(flags),
+        ]))
+    }
 }
@@ -1,9 +1,14 @@
 // SPDX-License-Identifier: MIT
 
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+use super::StatsSetRequest;
 use super::{
     AfstatsRequest, LinkAddRequest, LinkAssignNsidHandle, LinkDelPropRequest,
     LinkDelRequest, LinkGetNsidHandle, LinkGetRequest, LinkNewPropRequest,
-    LinkSetRequest,
+    LinkRateMonitor, LinkSetRequest, StatsRequest,
 };
 use crate::{
     packet_core::{NLM_F_ACK, NLM_F_REQUEST},
@@ -83,6 +88,27 @@ impl LinkHandle {
         AfstatsRequest::new(self.0.clone())
     }
 
+    /// Retrieve link statistics by group (equivalent to `ip stats show`)
+    pub fn stats(&self) -> StatsRequest {
+        StatsRequest::new(self.0.clone())
+    }
+
+    /// Change the statistics collected for a link (equivalent to
+    /// `ip stats set dev DEV`)
+    #[cfg(all(
+        not(target_os = "freebsd"),
+        any(feature = "tokio_socket", feature = "smol_socket")
+    ))]
+    pub fn set_stats(&self, index: u32) -> StatsSetRequest {
+        StatsSetRequest::new(self.0.clone(), index)
+    }
+
+    /// Periodically sample the rates of links (similar to `ip -s link show`
+    /// in a loop)
+    pub fn rate_monitor(&self) -> LinkRateMonitor {
+        LinkRateMonitor::new(self.0.clone())
+    }
+
     /// The `LinkHandle::set()` cannot be used for setting bond or bridge port
     /// configuration, `RTM_NEWLINK` and `NLM_F_REQUEST|NLM_F_ACK` are required,
     /// Equal to `LinkAddRequest::new().set_flags(NLM_F_REQUEST | NLM_F_ACK)`
@@ -38,9 +38,16 @@ mod nlmon;
 mod pfcp;
 mod property_add;
 mod property_del;
+mod rate;
 mod rmnet;
 mod set;
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+mod set_stats;
 mod sit;
+mod stats;
 mod team;
 mod tun;
 mod vcan;
@@ -56,6 +63,11 @@ mod wireguard;
 mod wwan;
 mod xfrm;
 
+#[cfg(all(
+    not(target_os = "freebsd"),
+    any(feature = "tokio_socket", feature = "smol_socket")
+))]
+pub use self::set_stats::StatsSetRequest;
 pub use self::{
     add::LinkAddRequest,
     afstats::AfstatsRequest,
@@ -93,9 +105,11 @@ pub use self::{
     pfcp::LinkPfcp,
     property_add::LinkNewPropRequest,
     property_del::LinkDelPropRequest,
+    rate::{LinkRate, LinkRateDelta, LinkRateMonitor},
     rmnet::LinkRmNet,
     set::LinkSetRequest,
     sit::LinkSit,
+    stats::{LinkStats, StatsRequest},
     team::LinkTeam,
     tun::LinkTun,
     vcan::LinkVcan,
@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use futures_util::stream::{self, Stream, TryStreamExt};
+use netlink_packet_route::link::{LinkAttribute, Stats64};
+
+use super::LinkGetRequest;
+use crate::{time::sleep, Error, Handle};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Statistics of a link sampled by [LinkRateMonitor].
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct LinkRate {
+    /// Interface index of the link when sampled.
+    pub index: u32,
+    pub name: String,
+    /// `IFLA_STATS64` counters read from the kernel.
+    pub stats: Stats64,
+    /// Rates since the previous sample, `None` for the first sample of a
+    /// link, or when its counters were reset or the link was re-created.
+    pub delta: Option<LinkRateDelta>,
+}
+
+/// Rates between two [LinkRate], per second.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct LinkRateDelta {
+    /// Time elapsed since the previous sample.
+    pub interval: Duration,
+    pub rx_bps: f64,
+    pub tx_bps: f64,
+    pub rx_pps: f64,
+    pub tx_pps: f64,
+    pub rx_errors: f64,
+    pub tx_errors: f64,
+    pub rx_dropped: f64,
+    pub tx_dropped: f64,
+}
+
+impl LinkRateDelta {
+    pub(crate) fn new(
+        previous: &Stats64,
+        current: &Stats64,
+        interval: Duration,
+    ) -> Option<Self> {
+        let pairs = [
+            (previous.rx_bytes, current.rx_bytes),
+            (previous.tx_bytes, current.tx_bytes),
+            (previous.rx_packets, current.rx_packets),
+            (previous.tx_packets, current.tx_packets),
+            (previous.rx_errors, current.rx_errors),
+            (previous.tx_errors, current.tx_errors),
+            (previous.rx_dropped, current.rx_dropped),
+            (previous.tx_dropped, current.tx_dropped),
+        ];
+        // 64 bits counters only go backward when they were reset.
+        if pairs.iter().any(|(previous, current)| current < previous) {
+            return None;
+        }
+        let secs = interval.as_secs_f64();
+        let per_sec = |previous: u64, current: u64| {
+            if secs > 0.0 {
+                (current - previous) as f64 / secs
+            } else {
+                0.0
+            }
+        };
+        Some(Self {
+            interval,
+            rx_bps: per_sec(previous.rx_bytes, current.rx_bytes) * 8.0,
+            tx_bps: per_sec(previous.tx_bytes, current.tx_bytes) * 8.0,
+            rx_pps: per_sec(previous.rx_packets, current.rx_packets),
+            tx_pps: per_sec(previous.tx_packets, current.tx_packets),
+            rx_errors: per_sec(previous.rx_errors, current.rx_errors),
+            tx_errors: per_sec(previous.tx_errors, current.tx_errors),
+            rx_dropped: per_sec(previous.rx_dropped, current.rx_dropped),
+            tx_dropped: per_sec(previous.tx_dropped, current.tx_dropped),
+        })
+    }
+}
+
+/// Periodically sample the `IFLA_STATS64` counters of links and compute
+/// their rates. Links are tracked by name, so that a re-created link
+/// restarts its rates instead of reporting bogus ones.
+/// Similar to `ip -s link show` in a loop.
+#[derive(Debug, Clone)]
+pub struct LinkRateMonitor {
+    handle: Handle,
+    names: Vec<String>,
+    interval: Duration,
+}
+
+impl LinkRateMonitor {
+    pub(crate) fn new(handle: Handle) -> Self {
+        LinkRateMonitor {
+            handle,
+            names: Vec::new(),
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// Only sample the link `name`, can be called several times. By
+    /// default, all the links are sampled.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.names.push(name.into());
+        self
+    }
+
+    /// Set the time between two samples. Default: 1 second.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Execute the request, returning the rates of the links at each
+    /// interval. The first item is returned immediately, without any
+    /// [LinkRate::delta]. Selected links which do not exist are skipped.
+    ///
+    /// The stream ends after the first error.
+    pub fn execute(self) -> impl Stream<Item = Result<Vec<LinkRate>, Error>> {
+        let state = (self, HashMap::<String, (u32, Stats64)>::new(), None);
+        stream::try_unfold(state, |(monitor, previous, last)| async move {
+            if last.is_some() {
+                sleep(monitor.interval).await;
+            }
+            let links = monitor.dump().await?;
+            let now = Instant::now();
+            let interval = last.map(|last: Instant| now - last);
+
+            let mut current = HashMap::new();
+            let rates = links
+                .into_iter()
+                .map(|(index, name, stats)| {
+                    let delta = match (previous.get(&name), interval) {
+                        (Some((previous_index, previous)), Some(interval))
+                            if *previous_index == index =>
+                        {
+                            LinkRateDelta::new(previous, &stats, interval)
+                        }
+                        _ => None,
+                    };
+                    current.insert(name.clone(), (index, stats));
+                    LinkRate {
+                        index,
+                        name,
+                        stats,
+                        delta,
+                    }
+                })
+                .collect();
+            Ok(Some((rates, (monitor, current, Some(now)))))
+        })
+    }
+
+    async fn dump(&self) -> Result<Vec<(u32, String, Stats64)>, Error> {
+        let links: Vec<_> = LinkGetRequest::new(self.handle.clone())
+            .execute()
+            .try_collect()
+            .await?;
+        Ok(links
+            .into_iter()
+            .filter_map(|link| {
+                let mut name = None;
+                let mut stats = None;
+                for attr in link.attributes {
+                    match attr {
+                        LinkAttribute::IfName(n) => name = Some(n),
+                        LinkAttribute::Stats64(s) => stats = Some(s),
+                        _ => (),
+                    }
+                }
+                let name = name?;
+                if !self.names.is_empty() && !self.names.contains(&name) {
+                    return None;
+                }
+                Some((link.header.index, name, stats?))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{
+    DefaultNla, Emitable, NetlinkMessage, Nla, NLM_F_ACK, NLM_F_REQUEST,
+};
+use netlink_packet_route::stats::{StatsAttribute, StatsMessage};
+
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle,
+};
+
+const RTM_SETSTATS: u16 = 95;
+
+const IFLA_STATS_SET_OFFLOAD_XSTATS_L3_STATS: u16 = 2;
+
+/// Change the statistics collected for a link with `RTM_SETSTATS`.
+/// Equivalent to `ip stats set dev DEV`.
+///
+/// `RTM_SETSTATS` has no variant in
+/// [RouteNetlinkMessage](netlink_packet_route::RouteNetlinkMessage), so the
+/// request is sent on its own netlink connection, opened in the network
+/// namespace of the [Handle].
+///
+/// Read the result back with [crate::StatsRequest::hw_stats_info] and
+/// [crate::StatsRequest::l3_stats].
+pub struct StatsSetRequest {
+    handle: Handle,
+    message: StatsMessage,
+}
+
+impl StatsSetRequest {
+    pub(crate) fn new(handle: Handle, index: u32) -> Self {
+        let mut message = StatsMessage::default();
+        message.header.ifindex = index;
+        StatsSetRequest { handle, message }
+    }
+    /// Request the device to count the L3 statistics in hardware. Whether
+    /// the device actually does is reported by
+    /// [crate::LinkStats::hw_stats_info].
+    /// Equivalent to `ip stats set dev DEV l3_stats { on | off }`.
+    pub fn l3_stats(mut self, enable: bool) -> Self {
+        self.message.attributes.retain(|attr| {
+            attr.kind() != IFLA_STATS_SET_OFFLOAD_XSTATS_L3_STATS
+        });
+        self.message
+            .attributes
+            .push(StatsAttribute::Other(DefaultNla::new(
+                IFLA_STATS_SET_OFFLOAD_XSTATS_L3_STATS,
+                vec![enable.into()],
+            )));
+        self
+    }
+
+    /// Return a mutable reference to the request message.
+    pub fn message_mut(&mut self) -> &mut StatsMessage {
+        &mut self.message
+    }
+
+    pub(crate) fn raw_message(&self) -> RawRouteMessage {
+        let mut payload = vec![0u8; self.message.buffer_len()];
+        self.message.emit(&mut payload);
+        RawRouteMessage::new(RTM_SETSTATS, payload)
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message());
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
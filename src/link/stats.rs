@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::{
+    future::{self, Either},
+    stream::{Stream, StreamExt},
+    FutureExt,
+};
+use netlink_packet_core::{
+    DefaultNla, Emitable, NetlinkMessage, NLA_F_NESTED, NLM_F_DUMP,
+    NLM_F_REQUEST,
+};
+use netlink_packet_route::{
+    link::Stats64,
+    stats::{
+        AfSpecStats, HwStats64, HwStatsInfo, LinkXstatGroup, OffloadXstat,
+        StatsAttribute, StatsFilterMask, StatsMessage,
+    },
+    RouteNetlinkMessage,
+};
+
+use crate::{try_rtnl, Error, Handle};
+
+const IFLA_STATS_GET_FILTERS: u16 = 1;
+const IFLA_STATS_LINK_OFFLOAD_XSTATS: u16 = 4;
+
+const IFLA_OFFLOAD_XSTATS_CPU_HIT: u16 = 1;
+const IFLA_OFFLOAD_XSTATS_HW_S_INFO: u16 = 2;
+const IFLA_OFFLOAD_XSTATS_L3_STATS: u16 = 3;
+
+/// Statistics of a link returned by [StatsRequest], one field per group
+/// of `ip stats show`. Groups not selected by the request, or not supported
+/// by the link, are left empty.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct LinkStats {
+    /// Interface index of the link.
+    pub index: u32,
+    /// Equivalent to `ip stats show group link`.
+    pub link_64: Option<Stats64>,
+    /// Statistics of bridge and bond masters, including the per-VLAN
+    /// statistics of a bridge.
+    /// Equivalent to `ip stats show group xstats`.
+    pub xstats: Vec<LinkXstatGroup>,
+    /// Statistics of bridge and bond ports.
+    /// Equivalent to `ip stats show group xstats_slave`.
+    pub xstats_port: Vec<LinkXstatGroup>,
+    /// Packets handled by the CPU of an offloading device.
+    /// Equivalent to `ip stats show group offload subgroup cpu_hit`.
+    pub cpu_hit: Option<Stats64>,
+    /// Whether the L3 hardware statistics are requested and used.
+    /// Equivalent to `ip stats show group offload subgroup hw_stats_info`.
+    pub hw_stats_info: Option<HwStatsInfo>,
+    /// L3 statistics counted by hardware, once enabled.
+    /// Equivalent to `ip stats show group offload subgroup l3_stats`.
+    pub l3_stats: Option<HwStats64>,
+    /// Equivalent to `ip stats show group afstats`.
+    pub af_spec: Option<AfSpecStats>,
+}
+
+impl From<StatsMessage> for LinkStats {
+    fn from(message: StatsMessage) -> Self {
+        let mut stats = LinkStats {
+            index: message.header.ifindex,
+            ..Default::default()
+        };
+        for attr in message.attributes {
+            match attr {
+                StatsAttribute::Link64(link_64) => {
+                    stats.link_64 = Some(link_64)
+                }
+                StatsAttribute::LinkXstats(xstats) => stats.xstats = xstats,
+                StatsAttribute::LinkXstatsPort(xstats) => {
+                    stats.xstats_port = xstats
+                }
+                StatsAttribute::LinkOffloadXstats(xstats) => {
+                    for xstat in xstats {
+                        match xstat {
+                            OffloadXstat::CpuHit(cpu_hit) => {
+                                stats.cpu_hit = Some(cpu_hit)
+                            }
+                            OffloadXstat::HwStatsInfo(info) => {
+                                stats.hw_stats_info = Some(info)
+                            }
+                            OffloadXstat::L3Stats(l3_stats) => {
+                                stats.l3_stats = Some(l3_stats)
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                StatsAttribute::AfSpec(af_spec) => {
+                    stats.af_spec = Some(af_spec)
+                }
+                _ => (),
+            }
+        }
+        stats
+    }
+}
+
+/// Retrieve the statistics of one or all links with `RTM_GETSTATS`.
+/// Equivalent to `ip stats show`.
+///
+/// When no group is selected, all of them are requested.
+pub struct StatsRequest {
+    handle: Handle,
+    message: StatsMessage,
+    offload_filter: u32,
+}
+
+impl StatsRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        StatsRequest {
+            handle,
+            message: StatsMessage::default(),
+            offload_filter: 0,
+        }
+    }
+
+    /// Only return the statistics of the link `index`, instead of dumping
+    /// all the links.
+    /// Equivalent to `ip stats show dev DEV`.
+    pub fn match_index(mut self, index: u32) -> Self {
+        self.message.header.ifindex = index;
+        self
+    }
+
+    /// Equivalent to `ip stats show group link`.
+    pub fn link_64(mut self) -> Self {
+        self.message.header.filter_mask |= StatsFilterMask::Link64;
+        self
+    }
+
+    /// Equivalent to `ip stats show group xstats`.
+    pub fn xstats(mut self) -> Self {
+        self.message.header.filter_mask |= StatsFilterMask::LinkXstats;
+        self
+    }
+
+    /// Equivalent to `ip stats show group xstats_slave`.
+    pub fn xstats_port(mut self) -> Self {
+        self.message.header.filter_mask |= StatsFilterMask::LinkXstatsPort;
+        self
+    }
+
+    /// Select all the offload subgroups.
+    /// Equivalent to `ip stats show group offload`.
+    pub fn offload_xstats(mut self) -> Self {
+        self.message.header.filter_mask |= StatsFilterMask::LinkOffloadXstats;
+        self
+    }
+
+    /// Equivalent to `ip stats show group offload subgroup cpu_hit`.
+    pub fn cpu_hit(self) -> Self {
+        self.offload_subgroup(IFLA_OFFLOAD_XSTATS_CPU_HIT)
+    }
+
+    /// Equivalent to `ip stats show group offload subgroup hw_stats_info`.
+    pub fn hw_stats_info(self) -> Self {
+        self.offload_subgroup(IFLA_OFFLOAD_XSTATS_HW_S_INFO)
+    }
+
+    /// Equivalent to `ip stats show group offload subgroup l3_stats`.
+    pub fn l3_stats(self) -> Self {
+        self.offload_subgroup(IFLA_OFFLOAD_XSTATS_L3_STATS)
+    }
+
+    fn offload_subgroup(mut self, subgroup: u16) -> Self {
+        self.offload_filter |= 1 << (subgroup - 1);
+        self.offload_xstats()
+    }
+
+    /// Equivalent to `ip stats show group afstats`.
+    pub fn af_spec(mut self) -> Self {
+        self.message.header.filter_mask |= StatsFilterMask::AfSpec;
+        self
+    }
+
+    /// Return a mutable reference to the request message.
+    pub fn message_mut(&mut self) -> &mut StatsMessage {
+        self.finalize();
+        &mut self.message
+    }
+
+    fn finalize(&mut self) {
+        if self.message.header.filter_mask.is_empty() {
+            self.message.header.filter_mask = StatsFilterMask::Link64
+                | StatsFilterMask::LinkXstats
+                | StatsFilterMask::LinkXstatsPort
+                | StatsFilterMask::LinkOffloadXstats
+                | StatsFilterMask::AfSpec;
+        }
+        self.message
+            .attributes
+            .retain(|attr| !matches!(attr, StatsAttribute::Other(_)));
+        if self.offload_filter != 0 {
+            let filter = DefaultNla::new(
+                IFLA_STATS_LINK_OFFLOAD_XSTATS,
+                self.offload_filter.to_ne_bytes().to_vec(),
+            );
+            let mut filters = vec![0u8; filter.buffer_len()];
+            filter.emit(&mut filters);
+            self.message.attributes.push(StatsAttribute::Other(
+                DefaultNla::new(IFLA_STATS_GET_FILTERS | NLA_F_NESTED, filters),
+            ));
+        }
+    }
+
+    /// Execute the request and return a stream of link statistics
+    pub fn execute(mut self) -> impl Stream<Item = Result<LinkStats, Error>> {
+        self.finalize();
+        let StatsRequest {
+            mut handle,
+            message,
+            ..
+        } = self;
+
+        let dump = message.header.ifindex == 0;
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::GetStats(message));
+        req.header.flags = if dump {
+            NLM_F_REQUEST | NLM_F_DUMP
+        } else {
+            NLM_F_REQUEST
+        };
+
+        match handle.request(req) {
+            Ok(response) => Either::Left(response.map(move |msg| {
+                Ok(try_rtnl!(msg, RouteNetlinkMessage::NewStats).into())
+            })),
+            Err(e) => {
+                Either::Right(future::err::<LinkStats, Error>(e).into_stream())
+            }
+        }
+    }
+}
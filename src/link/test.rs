@@ -1,18 +1,27 @@
 // SPDX-License-Identifier: MIT
 
-use futures_util::stream::TryStreamExt;
+use std::time::Duration;
+
+use futures_util::stream::{StreamExt, TryStreamExt};
+use netlink_packet_core::{DefaultNla, Emitable, NLA_F_NESTED};
 use tokio::runtime::Runtime;
 
 use crate::{
     new_connection,
-    packet_route::link::{
-        InfoData, InfoKind, InfoMacVlan, InfoVrf, LinkAttribute, LinkInfo,
-        LinkMessage, MacVlanFlags, MacVlanMode, NetkitMode,
+    packet_route::{
+        link::{
+            InfoData, InfoKind, InfoMacVlan, InfoVrf, LinkAttribute, LinkInfo,
+            LinkMessage, MacVlanFlags, MacVlanMode, NetkitMode, Stats64,
+        },
+        stats::{StatsAttribute, StatsFilterMask},
     },
-    Error, LinkHandle, LinkMacVlan, LinkNetkit, LinkVrf, LinkWireguard,
+    test_netns::setup_netns,
+    Error, LinkBridgeVlan, LinkHandle, LinkMacVlan, LinkNetkit, LinkRate,
+    LinkRateDelta, LinkStats, LinkVrf, LinkWireguard,
 };
 
 const IFACE_NAME: &str = "wg142"; // rand?
+const TEST_STATS_NS: &str = "netlink_test_stats_ns";
 
 #[test]
 fn create_get_delete_wg() {
@@ -219,3 +228,239 @@ async fn _create_netkit(
     req.execute().await?;
     Ok(link_handle)
 }
+
+#[test]
+fn stats_request_filters() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+
+        // ip stats show: all the groups
+        let mut request = handle.link().stats();
+        assert_eq!(request.message_mut().header.filter_mask.bits(), 0x1f);
+        assert!(request.message_mut().attributes.is_empty());
+
+        // ip stats show dev lo group offload subgroup l3_stats
+        let mut request = handle.link().stats().match_index(1).l3_stats();
+        let message = request.message_mut();
+        assert_eq!(message.header.ifindex, 1);
+        assert_eq!(
+            message.header.filter_mask,
+            StatsFilterMask::LinkOffloadXstats
+        );
+        let mut nested = vec![0u8; 8];
+        DefaultNla::new(4, 4u32.to_ne_bytes().to_vec()).emit(&mut nested);
+        assert_eq!(
+            message.attributes,
+            vec![StatsAttribute::Other(DefaultNla::new(
+                NLA_F_NESTED | 1,
+                nested
+            ))]
+        );
+    });
+}
+
+#[test]
+fn get_stats_lo() {
+    let rt = Runtime::new().unwrap();
+    let stats: Vec<LinkStats> = rt
+        .block_on(async {
+            let (conn, handle, _) = new_connection().unwrap();
+            tokio::spawn(conn);
+            handle
+                .link()
+                .stats()
+                .match_index(1)
+                .link_64()
+                .af_spec()
+                .execute()
+                .try_collect()
+                .await
+        })
+        .unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].index, 1);
+    assert!(stats[0].link_64.is_some());
+    assert!(stats[0].cpu_hit.is_none());
+
+    // The offload subgroups are filtered with IFLA_STATS_GET_FILTERS
+    let stats: Vec<LinkStats> = rt
+        .block_on(async {
+            let (conn, handle, _) = new_connection().unwrap();
+            tokio::spawn(conn);
+            handle
+                .link()
+                .stats()
+                .hw_stats_info()
+                .execute()
+                .try_collect()
+                .await
+        })
+        .unwrap();
+    assert!(stats.iter().any(|stats| stats.index == 1));
+    assert!(stats.iter().all(|stats| stats.link_64.is_none()));
+}
+
+#[test]
+fn set_stats_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // ip stats set dev lo l3_stats on
+        let request = handle.link().set_stats(1).l3_stats(false).l3_stats(true);
+        let message = request.raw_message();
+        assert_eq!(message.message_type, 95);
+        assert_eq!(
+            message.payload,
+            vec![
+                0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, // if_stats_msg
+                5, 0, 2, 0, 1, 0, 0, 0, // l3_stats on
+            ]
+        );
+    });
+}
+
+#[test]
+fn set_get_l3_stats_lo() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        // Toggle the loopback of a throwaway network namespace only.
+        let (handle, _netns) = setup_netns(TEST_STATS_NS).await;
+
+        handle.link().set_stats(1).l3_stats(true).execute().await?;
+        let stats: Vec<LinkStats> = handle
+            .link()
+            .stats()
+            .match_index(1)
+            .hw_stats_info()
+            .l3_stats()
+            .execute()
+            .try_collect()
+            .await?;
+        // The loopback does not count in hardware.
+        let info = stats[0].hw_stats_info.clone().unwrap();
+        assert_eq!((info.request, info.used), (Some(1), Some(0)));
+        assert!(stats[0].l3_stats.is_some());
+
+        // The runtime workers stay in the original network namespace, the
+        // request must still reach the one of the handle.
+        let link = handle.link();
+        tokio::spawn(async move {
+            link.set_stats(1).l3_stats(false).execute().await
+        })
+        .await
+        .unwrap()?;
+        let stats: Vec<LinkStats> = handle
+            .link()
+            .stats()
+            .match_index(1)
+            .hw_stats_info()
+            .execute()
+            .try_collect()
+            .await?;
+        assert_eq!(stats[0].hw_stats_info.clone().unwrap().request, Some(0));
+
+        let result = handle.link().set_stats(0x7fff_ffff).execute().await;
+        assert!(matches!(result, Err(Error::NetlinkError(_))));
+        Ok::<(), Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn link_rate_delta() {
+    let mut previous = Stats64::default();
+    previous.rx_bytes = 1000;
+    previous.rx_packets = 10;
+    previous.tx_dropped = 1;
+    let mut current = previous;
+    current.rx_bytes = 3000;
+    current.rx_packets = 30;
+    current.tx_dropped = 3;
+
+    let delta = LinkRateDelta::new(&previous, &current, Duration::from_secs(2))
+        .unwrap();
+    assert_eq!(delta.rx_bps, 8000.0);
+    assert_eq!(delta.rx_pps, 10.0);
+    assert_eq!(delta.tx_dropped, 1.0);
+    assert_eq!(delta.tx_bps, 0.0);
+
+    // Counters going backward were reset
+    assert!(
+        LinkRateDelta::new(&current, &previous, Duration::from_secs(2))
+            .is_none()
+    );
+}
+
+#[test]
+fn link_rate_monitor_lo() {
+    let rt = Runtime::new().unwrap();
+    let samples: Vec<Vec<LinkRate>> = rt
+        .block_on(async {
+            let (conn, handle, _) = new_connection().unwrap();
+            tokio::spawn(conn);
+            handle
+                .link()
+                .rate_monitor()
+                .name("lo")
+                .interval(Duration::from_millis(10))
+                .execute()
+                .take(2)
+                .try_collect()
+                .await
+        })
+        .unwrap();
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[0].len(), 1);
+    assert_eq!(samples[0][0].name, "lo");
+    assert!(samples[0][0].delta.is_none());
+    let delta = samples[1][0].delta.unwrap();
+    assert!(delta.interval >= Duration::from_millis(10));
+}
+
+#[test]
+fn bridge_vlan_tunnel_message() {
+    // bridge vlan add dev vxlan0 vid 10-20 tunnel_info id 1010-1020
+    let message = LinkBridgeVlan::new(4)
+        .vlan_tunnel_range_start(10, 1010)
+        .vlan_tunnel_range_end(20, 1020)
+        .build();
+    let mut buffer = vec![0u8; message.buffer_len()];
+    message.emit(&mut buffer);
+    assert_eq!(buffer[..8], [7, 0, 0, 0, 4, 0, 0, 0]); // AF_BRIDGE, index
+    #[rustfmt::skip]
+    assert_eq!(
+        buffer[16..],
+        [
+            60, 0, 26, 0, // IFLA_AF_SPEC
+            28, 0, 3, 0, // IFLA_BRIDGE_VLAN_TUNNEL_INFO
+            8, 0, 1, 0, 0xf2, 0x03, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_ID
+            6, 0, 2, 0, 10, 0, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_VID
+            6, 0, 3, 0, 8, 0, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_FLAGS
+            28, 0, 3, 0, // IFLA_BRIDGE_VLAN_TUNNEL_INFO
+            8, 0, 1, 0, 0xfc, 0x03, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_ID
+            6, 0, 2, 0, 20, 0, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_VID
+            6, 0, 3, 0, 16, 0, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_FLAGS
+        ]
+    );
+
+    // bridge vlan add dev vxlan0 vid 30 tunnel_info id 1030 self
+    let message = LinkBridgeVlan::new(4)
+        .vlan_tunnel(30, 1030)
+        .bridge_self()
+        .build();
+    let mut buffer = vec![0u8; message.buffer_len()];
+    message.emit(&mut buffer);
+    #[rustfmt::skip]
+    assert_eq!(
+        buffer[16..],
+        [
+            40, 0, 26, 0, // IFLA_AF_SPEC
+            28, 0, 3, 0, // IFLA_BRIDGE_VLAN_TUNNEL_INFO
+            8, 0, 1, 0, 0x06, 0x04, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_ID
+            6, 0, 2, 0, 30, 0, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_VID
+            6, 0, 3, 0, 0, 0, 0, 0, // IFLA_BRIDGE_VLAN_TUNNEL_FLAGS
+            6, 0, 0, 0, 2, 0, 0, 0, // IFLA_BRIDGE_FLAGS
+        ]
+    );
+}
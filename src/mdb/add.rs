@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::route::RouteProtocol;
+
+use super::message::{MdbSetEntry, RTM_NEWMDB};
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle, MdbFilterMode, MdbGroup,
+};
+
+/// Add or replace a bridge mdb entry.
+/// Equivalent to `bridge mdb { add | replace }`.
+pub struct MdbAddRequest {
+    handle: Handle,
+    entry: MdbSetEntry,
+    flags: u16,
+}
+
+impl MdbAddRequest {
+    pub(crate) fn new(
+        handle: Handle,
+        bridge: u32,
+        port: u32,
+        group: MdbGroup,
+        flags: u16,
+    ) -> Self {
+        MdbAddRequest {
+            handle,
+            entry: MdbSetEntry::new(bridge, port, group),
+            flags,
+        }
+    }
+
+    /// Equivalent to `vid VID`.
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        self.entry.vlan = vlan;
+        self
+    }
+
+    /// Keep the entry until it is deleted, instead of letting it expire.
+    /// Equivalent to `permanent`, the default being `temp`.
+    pub fn permanent(mut self) -> Self {
+        self.entry.permanent = true;
+        self
+    }
+
+    /// Add a (S, G) entry.
+    /// Equivalent to `src SOURCE`.
+    pub fn source(mut self, source: IpAddr) -> Self {
+        self.entry.source = Some(source);
+        self
+    }
+
+    /// Set the source list of a (*, G) entry, replacing any previous one.
+    /// Equivalent to `source_list SOURCE[,SOURCE]...`.
+    pub fn source_list(mut self, sources: Vec<IpAddr>) -> Self {
+        self.entry.source_list = sources;
+        self
+    }
+
+    /// Equivalent to `filter_mode { include | exclude }`.
+    pub fn filter_mode(mut self, mode: MdbFilterMode) -> Self {
+        self.entry.filter_mode = Some(mode);
+        self
+    }
+
+    /// Set the protocol installing the entry.
+    /// Equivalent to `proto PROTO`.
+    pub fn protocol(mut self, protocol: RouteProtocol) -> Self {
+        self.entry.protocol = Some(protocol);
+        self
+    }
+
+    pub(crate) fn raw_message(&self) -> Result<RawRouteMessage, Error> {
+        self.entry.build(RTM_NEWMDB)
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message()?);
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | self.flags;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
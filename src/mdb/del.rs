@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+
+use super::message::{MdbSetEntry, RTM_DELMDB};
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle, MdbGroup,
+};
+
+/// Delete a bridge mdb entry.
+/// Equivalent to `bridge mdb del`.
+pub struct MdbDelRequest {
+    handle: Handle,
+    entry: MdbSetEntry,
+}
+
+impl MdbDelRequest {
+    pub(crate) fn new(
+        handle: Handle,
+        bridge: u32,
+        port: u32,
+        group: MdbGroup,
+    ) -> Self {
+        MdbDelRequest {
+            handle,
+            entry: MdbSetEntry::new(bridge, port, group),
+        }
+    }
+
+    /// Equivalent to `vid VID`.
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        self.entry.vlan = vlan;
+        self
+    }
+
+    /// Delete a (S, G) entry.
+    /// Equivalent to `src SOURCE`.
+    pub fn source(mut self, source: IpAddr) -> Self {
+        self.entry.source = Some(source);
+        self
+    }
+
+    pub(crate) fn raw_message(&self) -> Result<RawRouteMessage, Error> {
+        self.entry.build(RTM_DELMDB)
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message()?);
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
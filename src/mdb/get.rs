@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::{
+    future,
+    stream::{self, Stream, TryStreamExt},
+};
+use netlink_packet_core::{NetlinkMessage, NLM_F_DUMP, NLM_F_REQUEST};
+
+use super::message::{br_port_msg, parse_mdb, RTM_GETMDB, RTM_NEWMDB};
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle, MdbMessage,
+};
+
+/// List the bridge mdb entries and router ports.
+/// Equivalent to `bridge mdb show`.
+pub struct MdbGetRequest {
+    handle: Handle,
+    bridge: Option<u32>,
+    port: Option<u32>,
+    vlan: Option<u16>,
+}
+
+impl MdbGetRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        MdbGetRequest {
+            handle,
+            bridge: None,
+            port: None,
+            vlan: None,
+        }
+    }
+
+    /// Only list the entries of this bridge (equivalent to `dev DEVICE`).
+    pub fn bridge(mut self, index: u32) -> Self {
+        self.bridge = Some(index);
+        self
+    }
+
+    /// Only list the entries of this port (equivalent to `port PORT`).
+    pub fn port(mut self, index: u32) -> Self {
+        self.port = Some(index);
+        self
+    }
+
+    /// Only list the entries of this VLAN (equivalent to `vid VID`).
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        self.vlan = Some(vlan);
+        self
+    }
+
+    /// Execute the request.
+    ///
+    /// The filters are applied on the dumped entries, as the kernel does
+    /// not filter mdb dumps.
+    pub fn execute(self) -> impl Stream<Item = Result<MdbMessage, Error>> {
+        let MdbGetRequest {
+            handle,
+            bridge,
+            port,
+            vlan,
+        } = self;
+
+        let mut req = NetlinkMessage::from(RawRouteMessage::new(
+            RTM_GETMDB,
+            br_port_msg(0),
+        ));
+        req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+
+        stream::once(async move {
+            let mut mdb = Vec::new();
+            for message in raw::request(&handle, req).await? {
+                // The kernel replies to dumps with RTM_GETMDB messages.
+                if matches!(message.message_type, RTM_GETMDB | RTM_NEWMDB) {
+                    mdb.extend(
+                        parse_mdb(&message.payload).map_err(|e| {
+                            Error::InvalidMessage(e.to_string())
+                        })?,
+                    );
+                }
+            }
+            Ok(stream::iter(mdb.into_iter().map(Ok)))
+        })
+        .try_flatten()
+        .try_filter(move |message| {
+            let (message_bridge, message_port, message_vlan) = match message {
+                MdbMessage::Entry(entry) => {
+                    (entry.bridge, entry.port, Some(entry.vlan))
+                }
+                MdbMessage::RouterPort(port) => {
+                    (port.bridge, port.port, port.vlan)
+                }
+            };
+            let matched = bridge
+                .map_or(true, |bridge| message_bridge == bridge)
+                && port.map_or(true, |port| message_port == port)
+                && vlan.map_or(true, |vlan| message_vlan == Some(vlan));
+            future::ready(matched)
+        })
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{NLM_F_CREATE, NLM_F_EXCL, NLM_F_REPLACE};
+
+use crate::{
+    Handle, MdbAddRequest, MdbDelRequest, MdbGetRequest, MdbGroup, MdbMonitor,
+};
+
+/// Handle of the bridge multicast database.
+///
+/// The MDB messages have no variant in
+/// [RouteNetlinkMessage](netlink_packet_route::RouteNetlinkMessage), so the
+/// requests are sent on their own netlink connection, opened in the network
+/// namespace of the [Handle].
+#[derive(Debug, Clone)]
+pub struct MdbHandle(Handle);
+
+impl MdbHandle {
+    pub fn new(handle: Handle) -> Self {
+        MdbHandle(handle)
+    }
+
+    /// List the mdb entries and router ports (equivalent to
+    /// `bridge mdb show`)
+    pub fn get(&self) -> MdbGetRequest {
+        MdbGetRequest::new(self.0.clone())
+    }
+
+    /// Add a mdb entry to the port `port` of the bridge `bridge`, fail if it
+    /// already exists (equivalent to `bridge mdb add`)
+    pub fn add(
+        &self,
+        bridge: u32,
+        port: u32,
+        group: impl Into<MdbGroup>,
+    ) -> MdbAddRequest {
+        MdbAddRequest::new(
+            self.0.clone(),
+            bridge,
+            port,
+            group.into(),
+            NLM_F_CREATE | NLM_F_EXCL,
+        )
+    }
+
+    /// Add a mdb entry or replace the existing one (equivalent to
+    /// `bridge mdb replace`)
+    pub fn replace(
+        &self,
+        bridge: u32,
+        port: u32,
+        group: impl Into<MdbGroup>,
+    ) -> MdbAddRequest {
+        MdbAddRequest::new(
+            self.0.clone(),
+            bridge,
+            port,
+            group.into(),
+            NLM_F_CREATE | NLM_F_REPLACE,
+        )
+    }
+
+    /// Delete a mdb entry (equivalent to `bridge mdb del`)
+    pub fn del(
+        &self,
+        bridge: u32,
+        port: u32,
+        group: impl Into<MdbGroup>,
+    ) -> MdbDelRequest {
+        MdbDelRequest::new(self.0.clone(), bridge, port, group.into())
+    }
+
+    /// Receive the changes of the mdb entries and router ports (equivalent
+    /// to `bridge monitor mdb`)
+    pub fn monitor(&self) -> MdbMonitor {
+        MdbMonitor::new(self.0.clone())
+    }
+}
@@ -0,0 +1,504 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+use netlink_packet_core::{
+    parse_ip, parse_u32, parse_u8, DecodeError, DefaultNla,
+};
+use netlink_packet_route::{
+    link::BridgeMulticastRouterType, route::RouteProtocol, AddressFamily,
+};
+
+use crate::{
+    nla::{emit_nlas, nla_nested, nla_u8, parse_nlas, read_u16, read_u32},
+    raw::RawRouteMessage,
+    Error,
+};
+
+pub(crate) const RTM_NEWMDB: u16 = 84;
+pub(crate) const RTM_DELMDB: u16 = 85;
+pub(crate) const RTM_GETMDB: u16 = 86;
+
+const MDBA_MDB: u16 = 1;
+const MDBA_ROUTER: u16 = 2;
+
+const MDBA_MDB_ENTRY: u16 = 1;
+const MDBA_MDB_ENTRY_INFO: u16 = 1;
+
+const MDBA_MDB_EATTR_TIMER: u16 = 1;
+const MDBA_MDB_EATTR_SRC_LIST: u16 = 2;
+const MDBA_MDB_EATTR_GROUP_MODE: u16 = 3;
+const MDBA_MDB_EATTR_SOURCE: u16 = 4;
+const MDBA_MDB_EATTR_RTPROT: u16 = 5;
+
+const MDBA_MDB_SRCLIST_ENTRY: u16 = 1;
+const MDBA_MDB_SRCATTR_ADDRESS: u16 = 1;
+const MDBA_MDB_SRCATTR_TIMER: u16 = 2;
+
+const MDBA_ROUTER_PORT: u16 = 1;
+const MDBA_ROUTER_PATTR_TIMER: u16 = 1;
+const MDBA_ROUTER_PATTR_TYPE: u16 = 2;
+const MDBA_ROUTER_PATTR_VID: u16 = 5;
+
+const MDBA_SET_ENTRY: u16 = 1;
+const MDBA_SET_ENTRY_ATTRS: u16 = 2;
+
+const MDBE_ATTR_SOURCE: u16 = 1;
+const MDBE_ATTR_SRC_LIST: u16 = 2;
+const MDBE_ATTR_GROUP_MODE: u16 = 3;
+const MDBE_ATTR_RTPROT: u16 = 4;
+const MDBE_SRC_LIST_ENTRY: u16 = 1;
+const MDBE_SRCATTR_ADDRESS: u16 = 1;
+
+const MDB_TEMPORARY: u8 = 0;
+const MDB_PERMANENT: u8 = 1;
+
+const MCAST_EXCLUDE: u8 = 0;
+const MCAST_INCLUDE: u8 = 1;
+
+const ETH_P_IP: u16 = 0x0800;
+const ETH_P_IPV6: u16 = 0x86DD;
+
+// struct br_port_msg
+const BR_PORT_MSG_LEN: usize = 8;
+// struct br_mdb_entry
+const BR_MDB_ENTRY_LEN: usize = 28;
+const BR_MDB_ENTRY_ADDR: usize = 8;
+const BR_MDB_ENTRY_PROTO: usize = 24;
+
+/// Multicast group of a MDB entry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MdbGroup {
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+    /// Layer 2 multicast address.
+    L2([u8; 6]),
+}
+
+impl From<IpAddr> for MdbGroup {
+    fn from(addr: IpAddr) -> Self {
+        match addr {
+            IpAddr::V4(addr) => Self::Ipv4(addr),
+            IpAddr::V6(addr) => Self::Ipv6(addr),
+        }
+    }
+}
+
+impl From<Ipv4Addr> for MdbGroup {
+    fn from(addr: Ipv4Addr) -> Self {
+        Self::Ipv4(addr)
+    }
+}
+
+impl From<Ipv6Addr> for MdbGroup {
+    fn from(addr: Ipv6Addr) -> Self {
+        Self::Ipv6(addr)
+    }
+}
+
+impl MdbGroup {
+    fn is_ip(&self) -> bool {
+        !matches!(self, Self::L2(_))
+    }
+
+    fn same_family(&self, addr: &IpAddr) -> bool {
+        matches!(
+            (self, addr),
+            (Self::Ipv4(_), IpAddr::V4(_)) | (Self::Ipv6(_), IpAddr::V6(_))
+        )
+    }
+}
+
+/// Filter mode of the source list of a MDB entry, as in IGMPv3/MLDv2.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum MdbFilterMode {
+    Include,
+    Exclude,
+    Other(u8),
+}
+
+impl From<u8> for MdbFilterMode {
+    fn from(d: u8) -> Self {
+        match d {
+            MCAST_INCLUDE => Self::Include,
+            MCAST_EXCLUDE => Self::Exclude,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<MdbFilterMode> for u8 {
+    fn from(v: MdbFilterMode) -> u8 {
+        match v {
+            MdbFilterMode::Include => MCAST_INCLUDE,
+            MdbFilterMode::Exclude => MCAST_EXCLUDE,
+            MdbFilterMode::Other(d) => d,
+        }
+    }
+}
+
+/// Source of a MDB entry source list.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct MdbSource {
+    pub address: IpAddr,
+    /// Time left before the source expires.
+    pub timer: Option<Duration>,
+}
+
+/// Port group entry of the bridge multicast database.
+/// Equivalent to a line of `bridge mdb show`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct MdbEntry {
+    /// Interface index of the bridge.
+    pub bridge: u32,
+    /// Interface index of the port, the bridge itself for host entries.
+    pub port: u32,
+    pub group: MdbGroup,
+    /// VLAN of the entry, 0 for none.
+    pub vlan: u16,
+    /// Whether the entry was added as `permanent` rather than learnt or
+    /// added as `temp`.
+    pub permanent: bool,
+    /// `MDB_FLAGS_*` of the entry, like offload or fast leave.
+    pub flags: u8,
+    /// Time left before the entry expires.
+    pub timer: Option<Duration>,
+    /// Source of a (S, G) entry.
+    pub source: Option<IpAddr>,
+    pub source_list: Vec<MdbSource>,
+    pub filter_mode: Option<MdbFilterMode>,
+    /// Protocol which installed the entry.
+    pub protocol: Option<RouteProtocol>,
+}
+
+/// Multicast router port of a bridge.
+/// Equivalent to a line of `bridge mdb show` with `router`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct MdbRouterPort {
+    /// Interface index of the bridge.
+    pub bridge: u32,
+    /// Interface index of the port.
+    pub port: u32,
+    /// Time left before the port stops being a router port.
+    pub timer: Option<Duration>,
+    pub router_type: Option<BridgeMulticastRouterType>,
+    /// VLAN of the router port, with per-VLAN multicast snooping.
+    pub vlan: Option<u16>,
+}
+
+/// Content of a `RTM_NEWMDB` or `RTM_DELMDB` message.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MdbMessage {
+    Entry(MdbEntry),
+    RouterPort(MdbRouterPort),
+}
+
+/// Change of the bridge multicast database, received from
+/// [crate::MdbHandle::monitor].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MdbEvent {
+    New(MdbMessage),
+    Del(MdbMessage),
+}
+
+impl MdbEvent {
+    pub(crate) fn parse(
+        message: &RawRouteMessage,
+    ) -> Result<Vec<Self>, DecodeError> {
+        let event = match message.message_type {
+            RTM_NEWMDB => Self::New,
+            RTM_DELMDB => Self::Del,
+            _ => return Ok(Vec::new()),
+        };
+        Ok(parse_mdb(&message.payload)?
+            .into_iter()
+            .map(event)
+            .collect())
+    }
+}
+
+// The kernel timers are reported in clock ticks, `USER_HZ` being 100.
+fn parse_timer(payload: &[u8]) -> Result<Duration, DecodeError> {
+    Ok(Duration::from_millis(u64::from(parse_u32(payload)?) * 10))
+}
+
+/// Parse the entries and router ports of a `RTM_NEWMDB` or `RTM_DELMDB`
+/// message.
+pub(crate) fn parse_mdb(
+    payload: &[u8],
+) -> Result<Vec<MdbMessage>, DecodeError> {
+    let bridge = read_u32(payload, 4)?;
+    let mut messages = Vec::new();
+    let nlas = payload.get(BR_PORT_MSG_LEN..).unwrap_or_default();
+    for (kind, value) in parse_nlas(nlas)? {
+        match kind {
+            MDBA_MDB => {
+                for (kind, value) in parse_nlas(value)? {
+                    if kind != MDBA_MDB_ENTRY {
+                        continue;
+                    }
+                    for (kind, value) in parse_nlas(value)? {
+                        if kind == MDBA_MDB_ENTRY_INFO {
+                            messages.push(MdbMessage::Entry(parse_entry(
+                                bridge, value,
+                            )?));
+                        }
+                    }
+                }
+            }
+            MDBA_ROUTER => {
+                for (kind, value) in parse_nlas(value)? {
+                    if kind == MDBA_ROUTER_PORT {
+                        messages.push(MdbMessage::RouterPort(
+                            parse_router_port(bridge, value)?,
+                        ));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(messages)
+}
+
+fn parse_group(entry: &[u8]) -> Result<MdbGroup, DecodeError> {
+    let addr = &entry[BR_MDB_ENTRY_ADDR..BR_MDB_ENTRY_PROTO];
+    let proto = u16::from_be_bytes([
+        entry[BR_MDB_ENTRY_PROTO],
+        entry[BR_MDB_ENTRY_PROTO + 1],
+    ]);
+    Ok(match proto {
+        ETH_P_IP => {
+            MdbGroup::Ipv4(Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]))
+        }
+        ETH_P_IPV6 => MdbGroup::Ipv6(Ipv6Addr::from(
+            <[u8; 16]>::try_from(addr).unwrap_or_default(),
+        )),
+        _ => {
+            let mut mac = [0u8; 6];
+            mac.copy_from_slice(&addr[..6]);
+            MdbGroup::L2(mac)
+        }
+    })
+}
+
+fn parse_entry(bridge: u32, payload: &[u8]) -> Result<MdbEntry, DecodeError> {
+    if payload.len() < BR_MDB_ENTRY_LEN {
+        return Err(DecodeError::buffer_too_small(
+            payload.len(),
+            BR_MDB_ENTRY_LEN,
+        ));
+    }
+    let mut entry = MdbEntry {
+        bridge,
+        port: read_u32(payload, 0)?,
+        group: parse_group(payload)?,
+        vlan: read_u16(payload, 6)?,
+        permanent: payload[4] == MDB_PERMANENT,
+        flags: payload[5],
+        timer: None,
+        source: None,
+        source_list: Vec::new(),
+        filter_mode: None,
+        protocol: None,
+    };
+    for (kind, value) in parse_nlas(&payload[BR_MDB_ENTRY_LEN..])? {
+        match kind {
+            MDBA_MDB_EATTR_TIMER => entry.timer = Some(parse_timer(value)?),
+            MDBA_MDB_EATTR_SRC_LIST => {
+                for (kind, value) in parse_nlas(value)? {
+                    if kind == MDBA_MDB_SRCLIST_ENTRY {
+                        entry.source_list.push(parse_source(value)?);
+                    }
+                }
+            }
+            MDBA_MDB_EATTR_GROUP_MODE => {
+                entry.filter_mode = Some(parse_u8(value)?.into())
+            }
+            MDBA_MDB_EATTR_SOURCE => entry.source = Some(parse_ip(value)?),
+            MDBA_MDB_EATTR_RTPROT => {
+                entry.protocol = Some(parse_u8(value)?.into())
+            }
+            _ => (),
+        }
+    }
+    Ok(entry)
+}
+
+fn parse_source(payload: &[u8]) -> Result<MdbSource, DecodeError> {
+    let mut address = None;
+    let mut timer = None;
+    for (kind, value) in parse_nlas(payload)? {
+        match kind {
+            MDBA_MDB_SRCATTR_ADDRESS => address = Some(parse_ip(value)?),
+            MDBA_MDB_SRCATTR_TIMER => timer = Some(parse_timer(value)?),
+            _ => (),
+        }
+    }
+    Ok(MdbSource {
+        address: address
+            .ok_or_else(|| DecodeError::from("MDB source without address"))?,
+        timer,
+    })
+}
+
+fn parse_router_port(
+    bridge: u32,
+    payload: &[u8],
+) -> Result<MdbRouterPort, DecodeError> {
+    let mut port = MdbRouterPort {
+        bridge,
+        port: read_u32(payload, 0)?,
+        timer: None,
+        router_type: None,
+        vlan: None,
+    };
+    // Kernels before 4.6 only report the interface index.
+    for (kind, value) in parse_nlas(payload.get(4..).unwrap_or_default())? {
+        match kind {
+            MDBA_ROUTER_PATTR_TIMER => port.timer = Some(parse_timer(value)?),
+            MDBA_ROUTER_PATTR_TYPE => {
+                port.router_type = Some(parse_u8(value)?.into())
+            }
+            MDBA_ROUTER_PATTR_VID => port.vlan = Some(read_u16(value, 0)?),
+            _ => (),
+        }
+    }
+    Ok(port)
+}
+
+/// Entry of a `RTM_NEWMDB` or `RTM_DELMDB` request.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct MdbSetEntry {
+    pub(crate) bridge: u32,
+    pub(crate) port: u32,
+    pub(crate) group: MdbGroup,
+    pub(crate) vlan: u16,
+    pub(crate) permanent: bool,
+    pub(crate) source: Option<IpAddr>,
+    pub(crate) source_list: Vec<IpAddr>,
+    pub(crate) filter_mode: Option<MdbFilterMode>,
+    pub(crate) protocol: Option<RouteProtocol>,
+}
+
+impl MdbSetEntry {
+    pub(crate) fn new(bridge: u32, port: u32, group: MdbGroup) -> Self {
+        MdbSetEntry {
+            bridge,
+            port,
+            group,
+            vlan: 0,
+            permanent: false,
+            source: None,
+            source_list: Vec::new(),
+            filter_mode: None,
+            protocol: None,
+        }
+    }
+
+    fn emit_entry(&self) -> Vec<u8> {
+        let mut entry = vec![0u8; BR_MDB_ENTRY_LEN];
+        entry[..4].copy_from_slice(&self.port.to_ne_bytes());
+        entry[4] = if self.permanent {
+            MDB_PERMANENT
+        } else {
+            MDB_TEMPORARY
+        };
+        entry[6..8].copy_from_slice(&self.vlan.to_ne_bytes());
+        let (addr, proto) = match &self.group {
+            MdbGroup::Ipv4(addr) => (addr.octets().to_vec(), ETH_P_IP),
+            MdbGroup::Ipv6(addr) => (addr.octets().to_vec(), ETH_P_IPV6),
+            MdbGroup::L2(mac) => (mac.to_vec(), 0),
+        };
+        entry[BR_MDB_ENTRY_ADDR..BR_MDB_ENTRY_ADDR + addr.len()]
+            .copy_from_slice(&addr);
+        entry[BR_MDB_ENTRY_PROTO..BR_MDB_ENTRY_PROTO + 2]
+            .copy_from_slice(&proto.to_be_bytes());
+        entry
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        let sources = self.source.iter().chain(self.source_list.iter());
+        if (self.source.is_some()
+            || !self.source_list.is_empty()
+            || self.filter_mode.is_some())
+            && !self.group.is_ip()
+        {
+            return Err(Error::InvalidNla(
+                "sources and filter mode need an IP multicast group".into(),
+            ));
+        }
+        for source in sources {
+            if !self.group.same_family(source) {
+                return Err(Error::InvalidNla(format!(
+                    "source {source} is not of the family of group {:?}",
+                    self.group
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the `RTM_NEWMDB` or `RTM_DELMDB` request.
+    pub(crate) fn build(
+        &self,
+        message_type: u16,
+    ) -> Result<RawRouteMessage, Error> {
+        self.check()?;
+        let ip_nla =
+            |kind, addr: &IpAddr| DefaultNla::new(kind, ip_octets(addr));
+        let mut attrs = Vec::new();
+        if let Some(source) = &self.source {
+            attrs.push(ip_nla(MDBE_ATTR_SOURCE, source));
+        }
+        if !self.source_list.is_empty() {
+            let entries: Vec<_> = self
+                .source_list
+                .iter()
+                .map(|addr| {
+                    nla_nested(
+                        MDBE_SRC_LIST_ENTRY,
+                        &[ip_nla(MDBE_SRCATTR_ADDRESS, addr)],
+                    )
+                })
+                .collect();
+            attrs.push(nla_nested(MDBE_ATTR_SRC_LIST, &entries));
+        }
+        if let Some(mode) = self.filter_mode {
+            attrs.push(nla_u8(MDBE_ATTR_GROUP_MODE, mode.into()));
+        }
+        if let Some(protocol) = self.protocol {
+            attrs.push(nla_u8(MDBE_ATTR_RTPROT, protocol.into()));
+        }
+
+        let mut nlas = vec![DefaultNla::new(MDBA_SET_ENTRY, self.emit_entry())];
+        if !attrs.is_empty() {
+            nlas.push(nla_nested(MDBA_SET_ENTRY_ATTRS, &attrs));
+        }
+        let mut payload = br_port_msg(self.bridge);
+        payload.extend(emit_nlas(&nlas));
+        Ok(RawRouteMessage::new(message_type, payload))
+    }
+}
+
+fn ip_octets(addr: &IpAddr) -> Vec<u8> {
+    match addr {
+        IpAddr::V4(addr) => addr.octets().to_vec(),
+        IpAddr::V6(addr) => addr.octets().to_vec(),
+    }
+}
+
+/// `struct br_port_msg` of the bridge `index`.
+pub(crate) fn br_port_msg(index: u32) -> Vec<u8> {
+    let mut header = vec![0u8; BR_PORT_MSG_LEN];
+    header[0] = AddressFamily::Bridge.into();
+    header[4..].copy_from_slice(&index.to_ne_bytes());
+    header
+}
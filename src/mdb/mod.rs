@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: MIT
+
+mod add;
+mod del;
+mod get;
+mod handle;
+mod message;
+mod monitor;
+
+#[cfg(test)]
+mod test;
+
+pub use self::{
+    add::MdbAddRequest,
+    del::MdbDelRequest,
+    get::MdbGetRequest,
+    handle::MdbHandle,
+    message::{
+        MdbEntry, MdbEvent, MdbFilterMode, MdbGroup, MdbMessage, MdbRouterPort,
+        MdbSource,
+    },
+    monitor::MdbMonitor,
+};
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::{self, Stream, StreamExt};
+
+use crate::{raw, Error, Handle, MdbEvent, MulticastGroup};
+
+/// Receive the changes of the bridge multicast database, which the
+/// connections of this crate cannot decode.
+/// Equivalent to `bridge monitor mdb`.
+pub struct MdbMonitor {
+    handle: Handle,
+}
+
+impl MdbMonitor {
+    pub(crate) fn new(handle: Handle) -> Self {
+        MdbMonitor { handle }
+    }
+
+    /// Execute the request, subscribing to [MulticastGroup::Mdb] on a
+    /// dedicated socket: the changes made after this call are received.
+    ///
+    /// Must be called from the context of the async runtime.
+    pub fn execute(
+        self,
+    ) -> Result<impl Stream<Item = Result<MdbEvent, Error>>, Error> {
+        let messages = raw::subscribe(&self.handle, &[MulticastGroup::Mdb])?;
+        Ok(messages.flat_map(|message| {
+            stream::iter(match MdbEvent::parse(&message) {
+                Ok(events) => events.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(Error::InvalidMessage(e.to_string()))],
+            })
+        }))
+    }
+}
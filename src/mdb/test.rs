@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+use futures_util::stream::{StreamExt, TryStreamExt};
+use netlink_packet_route::{
+    link::BridgeMulticastRouterType, route::RouteProtocol,
+};
+use tokio::runtime::Runtime;
+
+use super::message::parse_mdb;
+use crate::{
+    new_connection,
+    test_netns::{setup_bridge, TEST_BRIDGE},
+    Error, LinkBridge, MdbEntry, MdbEvent, MdbFilterMode, MdbGroup, MdbMessage,
+    MdbRouterPort,
+};
+
+const TEST_NS: &str = "netlink_test_mdb_ns";
+
+#[test]
+fn mdb_add_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge mdb add dev br0 port eth0 grp 239.1.1.1 permanent vid 10
+        let message = handle
+            .mdb()
+            .add(3, 4, Ipv4Addr::new(239, 1, 1, 1))
+            .permanent()
+            .vlan(10)
+            .raw_message()
+            .unwrap();
+        assert_eq!(message.message_type, 84);
+        assert_eq!(
+            message.payload,
+            vec![
+                7, 0, 0, 0, 3, 0, 0, 0, // br_port_msg
+                32, 0, 1, 0, // MDBA_SET_ENTRY
+                4, 0, 0, 0, 1, 0, 10, 0, // ifindex, state, flags, vid
+                239, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, // addr
+                0x08, 0x00, 0, 0, // proto
+            ]
+        );
+    });
+}
+
+#[test]
+fn mdb_add_request_source_list() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge mdb replace dev br0 port eth0 grp 239.1.1.1 permanent \
+        //     filter_mode include source_list 192.0.2.1 proto static
+        let message = handle
+            .mdb()
+            .replace(3, 4, Ipv4Addr::new(239, 1, 1, 1))
+            .permanent()
+            .filter_mode(MdbFilterMode::Include)
+            .source_list(vec![Ipv4Addr::new(192, 0, 2, 1).into()])
+            .protocol(RouteProtocol::Static)
+            .raw_message()
+            .unwrap();
+        assert_eq!(
+            message.payload[40..],
+            [
+                36, 0, 2, 0x80, // MDBA_SET_ENTRY_ATTRS
+                16, 0, 2, 0x80, // MDBE_ATTR_SRC_LIST
+                12, 0, 1, 0x80, // MDBE_SRC_LIST_ENTRY
+                8, 0, 1, 0, 192, 0, 2, 1, // MDBE_SRCATTR_ADDRESS
+                5, 0, 3, 0, 1, 0, 0, 0, // MDBE_ATTR_GROUP_MODE
+                5, 0, 4, 0, 4, 0, 0, 0, // MDBE_ATTR_RTPROT
+            ]
+        );
+
+        // bridge mdb del dev br0 port eth0 grp 01:02:03:04:05:06
+        let message = handle
+            .mdb()
+            .del(3, 4, MdbGroup::L2([1, 2, 3, 4, 5, 6]))
+            .raw_message()
+            .unwrap();
+        assert_eq!(message.message_type, 85);
+        assert_eq!(message.payload.len(), 40);
+        assert_eq!(message.payload[20..26], [1, 2, 3, 4, 5, 6]);
+        assert_eq!(message.payload[36..38], [0, 0]); // no proto
+    });
+}
+
+#[test]
+fn mdb_add_request_invalid_source() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        let result = handle
+            .mdb()
+            .add(3, 4, Ipv4Addr::new(239, 1, 1, 1))
+            .source(Ipv6Addr::LOCALHOST.into())
+            .raw_message();
+        assert!(matches!(result, Err(Error::InvalidNla(_))));
+
+        let result = handle
+            .mdb()
+            .add(3, 4, MdbGroup::L2([1, 2, 3, 4, 5, 6]))
+            .filter_mode(MdbFilterMode::Exclude)
+            .raw_message();
+        assert!(matches!(result, Err(Error::InvalidNla(_))));
+    });
+}
+
+#[test]
+fn mdb_parse() {
+    #[rustfmt::skip]
+    let payload = vec![
+        7, 0, 0, 0, 3, 0, 0, 0, // br_port_msg
+        48, 0, 1, 0x80, // MDBA_MDB
+        44, 0, 1, 0x80, // MDBA_MDB_ENTRY
+        40, 0, 1, 0, // MDBA_MDB_ENTRY_INFO
+        4, 0, 0, 0, 0, 1, 0, 0, // ifindex, state, flags, vid
+        0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xfb, // addr
+        0x86, 0xdd, 0, 0, // proto
+        8, 0, 1, 0, 0x2c, 0x01, 0, 0, // MDBA_MDB_EATTR_TIMER
+        20, 0, 2, 0x80, // MDBA_ROUTER
+        16, 0, 1, 0, // MDBA_ROUTER_PORT
+        4, 0, 0, 0, // ifindex
+        5, 0, 2, 0, 2, 0, 0, 0, // MDBA_ROUTER_PATTR_TYPE
+    ];
+    let mut entry = MdbEntry {
+        bridge: 3,
+        port: 4,
+        group: MdbGroup::Ipv6("ff02::fb".parse().unwrap()),
+        vlan: 0,
+        permanent: false,
+        flags: 1,
+        timer: Some(Duration::from_secs(3)),
+        source: None,
+        source_list: Vec::new(),
+        filter_mode: None,
+        protocol: None,
+    };
+    let router_port = MdbRouterPort {
+        bridge: 3,
+        port: 4,
+        timer: None,
+        router_type: Some(BridgeMulticastRouterType::Permanent),
+        vlan: None,
+    };
+    assert_eq!(
+        parse_mdb(&payload).unwrap(),
+        vec![
+            MdbMessage::Entry(entry.clone()),
+            MdbMessage::RouterPort(router_port)
+        ]
+    );
+    entry.flags = 0;
+    assert_ne!(parse_mdb(&payload).unwrap()[0], MdbMessage::Entry(entry));
+}
+
+async fn mdb_entries(handle: &crate::Handle, bridge: u32) -> Vec<MdbEntry> {
+    handle
+        .mdb()
+        .get()
+        .bridge(bridge)
+        .execute()
+        .try_filter_map(|message| async move {
+            Ok(match message {
+                MdbMessage::Entry(entry) => Some(entry),
+                MdbMessage::RouterPort(_) => None,
+            })
+        })
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+async fn test_async_mdb() {
+    let (handle, bridge, port, _netns) = setup_bridge(
+        TEST_NS,
+        // The source lists are only reported with MLDv2.
+        LinkBridge::new(TEST_BRIDGE).mcast_mld_version(2).build(),
+    )
+    .await;
+    let (bridge, port) = (bridge.header.index, port.header.index);
+    let mut events = handle.mdb().monitor().execute().unwrap();
+
+    let group_v4 = Ipv4Addr::new(239, 1, 1, 1);
+    let group_v6: Ipv6Addr = "ff0e::1".parse().unwrap();
+    let group_l2 = MdbGroup::L2([1, 2, 3, 4, 5, 6]);
+    let source: IpAddr = "2001:db8::1".parse().unwrap();
+    handle
+        .mdb()
+        .add(bridge, port, group_v4)
+        .permanent()
+        .execute()
+        .await
+        .unwrap();
+    handle
+        .mdb()
+        .add(bridge, port, group_v6)
+        .permanent()
+        .filter_mode(MdbFilterMode::Include)
+        .source_list(vec![source])
+        .execute()
+        .await
+        .unwrap();
+    handle
+        .mdb()
+        .add(bridge, port, group_l2)
+        .permanent()
+        .execute()
+        .await
+        .unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(1), events.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        event,
+        MdbEvent::New(MdbMessage::Entry(MdbEntry { group, .. }))
+            if group == MdbGroup::Ipv4(group_v4)
+    ));
+
+    let entries = mdb_entries(&handle, bridge).await;
+    let find = |group: MdbGroup| {
+        entries
+            .iter()
+            .find(|entry| {
+                entry.group == group
+                    && entry.port == port
+                    && entry.source.is_none()
+            })
+            .unwrap_or_else(|| panic!("{group:?} not in {entries:?}"))
+    };
+    assert!(find(group_v4.into()).permanent);
+    assert!(find(group_l2).permanent);
+    let entry = find(group_v6.into());
+    assert_eq!(entry.filter_mode, Some(MdbFilterMode::Include));
+    assert_eq!(
+        entry
+            .source_list
+            .iter()
+            .map(|source| source.address)
+            .collect::<Vec<_>>(),
+        vec![source]
+    );
+    // The kernel adds a (S, G) entry for each source of an include list.
+    assert!(entries.iter().any(|entry| entry.source == Some(source)));
+
+    for group in [group_v4.into(), group_v6.into(), group_l2] {
+        handle
+            .mdb()
+            .del(bridge, port, group)
+            .execute()
+            .await
+            .unwrap();
+    }
+    let result = handle.mdb().del(bridge, port, group_l2).execute().await;
+    assert!(matches!(result, Err(Error::NetlinkError(_))));
+    assert!(mdb_entries(&handle, bridge)
+        .await
+        .iter()
+        .all(|entry| entry.port != port));
+}
+
+#[test]
+fn test_mdb() {
+    Runtime::new().unwrap().block_on(test_async_mdb());
+}
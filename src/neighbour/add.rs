@@ -9,13 +9,14 @@ use netlink_packet_core::{
 };
 use netlink_packet_route::{
     neighbour::{
-        NeighbourAddress, NeighbourAttribute, NeighbourFlags, NeighbourMessage,
-        NeighbourState,
+        NeighbourAttribute, NeighbourExtFlags, NeighbourFlags,
+        NeighbourMessage, NeighbourState,
     },
-    route::RouteType,
+    route::{RouteProtocol, RouteType},
     AddressFamily, RouteNetlinkMessage,
 };
 
+use super::{set_attribute, set_destination, set_nhid};
 use crate::{Error, Handle};
 
 pub struct NeighbourAddRequest {
@@ -37,12 +38,7 @@ impl NeighbourAddRequest {
         message.header.state = NeighbourState::Permanent;
         message.header.kind = RouteType::Unspec;
 
-        message.attributes.push(NeighbourAttribute::Destination(
-            match destination {
-                IpAddr::V4(v4) => NeighbourAddress::Inet(v4),
-                IpAddr::V6(v6) => NeighbourAddress::Inet6(v6),
-            },
-        ));
+        set_destination(&mut message, destination);
 
         NeighbourAddRequest {
             handle,
@@ -128,28 +124,91 @@ impl NeighbourAddRequest {
     /// Set the destination address for the neighbour (see `NDA_DST` for
     /// details).
     pub fn destination(mut self, addr: IpAddr) -> Self {
-        let dst =
-            self.message
-                .attributes
-                .iter_mut()
-                .find_map(|nla| match nla {
-                    NeighbourAttribute::Destination(dst) => Some(dst),
-                    _ => None,
-                });
+        set_destination(&mut self.message, addr);
+        self
+    }
 
-        let addr = match addr {
-            IpAddr::V4(v4) => NeighbourAddress::Inet(v4),
-            IpAddr::V6(v6) => NeighbourAddress::Inet6(v6),
-        };
+    /// Set the protocol which installed the entry (see `NDA_PROTOCOL` for
+    /// details).
+    pub fn protocol(mut self, protocol: RouteProtocol) -> Self {
+        set_attribute(
+            &mut self.message,
+            NeighbourAttribute::Protocol(protocol),
+        );
+        self
+    }
 
-        if let Some(dst) = dst {
-            *dst = addr;
-        } else {
-            self.message
-                .attributes
-                .push(NeighbourAttribute::Destination(addr));
-        }
+    /// Let the kernel manage the entry: it keeps it resolved even without
+    /// traffic (`NTF_EXT_MANAGED`).
+    pub fn managed(mut self) -> Self {
+        let flags = self
+            .message
+            .attributes
+            .iter()
+            .find_map(|nla| match nla {
+                NeighbourAttribute::ExtFlags(flags) => Some(*flags),
+                _ => None,
+            })
+            .unwrap_or_else(NeighbourExtFlags::empty);
+        set_attribute(
+            &mut self.message,
+            NeighbourAttribute::ExtFlags(flags | NeighbourExtFlags::Managed),
+        );
+        self
+    }
+
+    /// Mark the entry as learned by an external control plane
+    /// (`NTF_EXT_LEARNED`). The kernel will not age it out.
+    pub fn extern_learn(mut self) -> Self {
+        self.message.header.flags |= NeighbourFlags::ExtLearned;
+        self
+    }
+
+    /// Set the VLAN of a bridge fdb entry (see `NDA_VLAN` for details).
+    pub fn vlan(mut self, vlan: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vlan(vlan));
+        self
+    }
 
+    /// Set the VXLAN VNI used to reach the remote of a fdb entry (see
+    /// `NDA_VNI` for details).
+    pub fn vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Vni(vni));
+        self
+    }
+
+    /// Set the source VNI of a fdb entry on a collect metadata VXLAN device
+    /// (see `NDA_SRC_VNI` for details).
+    pub fn src_vni(mut self, vni: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::SourceVni(vni));
+        self
+    }
+
+    /// Set the outgoing interface used to reach the remote of a VXLAN fdb
+    /// entry (see `NDA_IFINDEX` for details).
+    pub fn ifindex(mut self, index: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::IfIndex(index));
+        self
+    }
+
+    /// Set the UDP destination port of the remote of a VXLAN fdb entry
+    /// (see `NDA_PORT` for details).
+    pub fn port(mut self, port: u16) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Port(port));
+        self
+    }
+
+    /// Set the master (bridge) device of a fdb entry (see `NDA_MASTER` for
+    /// details).
+    pub fn master(mut self, index: u32) -> Self {
+        set_attribute(&mut self.message, NeighbourAttribute::Controller(index));
+        self
+    }
+
+    /// Set the nexthop group id to use for a fdb entry (see `NDA_NH_ID` for
+    /// details).
+    pub fn nhid(mut self, id: u32) -> Self {
+        set_nhid(&mut self.message, id);
         self
     }
 
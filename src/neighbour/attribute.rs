@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT
+
+// Attribute setters shared by NeighbourAddRequest and the fdb requests.
+
+use std::net::IpAddr;
+
+use netlink_packet_core::{DefaultNla, Nla};
+use netlink_packet_route::neighbour::{
+    NeighbourAddress, NeighbourAttribute, NeighbourMessage,
+};
+
+pub(crate) const NDA_NH_ID: u16 = 13;
+
+pub(crate) fn neighbour_address(addr: IpAddr) -> NeighbourAddress {
+    match addr {
+        IpAddr::V4(v4) => NeighbourAddress::Inet(v4),
+        IpAddr::V6(v6) => NeighbourAddress::Inet6(v6),
+    }
+}
+
+/// Replace any attribute of the same kind.
+pub(crate) fn set_attribute(
+    message: &mut NeighbourMessage,
+    attr: NeighbourAttribute,
+) {
+    message.attributes.retain(|nla| nla.kind() != attr.kind());
+    message.attributes.push(attr);
+}
+
+/// Set `NDA_DST`, the neighbour address or the remote VXLAN tunnel endpoint
+/// of a fdb entry.
+pub(crate) fn set_destination(message: &mut NeighbourMessage, addr: IpAddr) {
+    set_attribute(
+        message,
+        NeighbourAttribute::Destination(neighbour_address(addr)),
+    );
+}
+
+/// Set `NDA_NH_ID`, which has no [NeighbourAttribute] variant.
+pub(crate) fn set_nhid(message: &mut NeighbourMessage, id: u32) {
+    set_attribute(
+        message,
+        NeighbourAttribute::Other(DefaultNla::new(
+            NDA_NH_ID,
+            id.to_ne_bytes().to_vec(),
+        )),
+    );
+}
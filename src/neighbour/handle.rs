@@ -6,6 +6,7 @@ use netlink_packet_route::neighbour::NeighbourMessage;
 
 use crate::{
     Handle, NeighbourAddRequest, NeighbourDelRequest, NeighbourGetRequest,
+    NeighbourResolveRequest,
 };
 
 pub struct NeighbourHandle(Handle);
@@ -31,6 +32,18 @@ impl NeighbourHandle {
         NeighbourAddRequest::new_bridge(self.0.clone(), index, lla)
     }
 
+    /// Trigger the resolution of a neighbour and wait until it is reachable
+    /// or failed. Requires a connection subscribed to
+    /// [crate::MulticastGroup::Neigh], see
+    /// [NeighbourResolveRequest::execute()] for details.
+    pub fn resolve(
+        &self,
+        index: u32,
+        destination: IpAddr,
+    ) -> NeighbourResolveRequest {
+        NeighbourResolveRequest::new(self.0.clone(), index, destination)
+    }
+
     /// Delete a neighbour entry (equivalent to `ip neighbour delete`)
     pub fn del(&self, message: NeighbourMessage) -> NeighbourDelRequest {
         NeighbourDelRequest::new(self.0.clone(), message)
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_core::Nla;
+use netlink_packet_route::{
+    neighbour::{
+        NeighbourAddress, NeighbourAttribute, NeighbourExtFlags,
+        NeighbourFlags, NeighbourMessage,
+    },
+    route::RouteProtocol,
+};
+
+use super::NDA_NH_ID;
+
+/// Typed accessors for the attributes of a [NeighbourMessage], like the ones
+/// returned by [crate::NeighbourGetRequest].
+pub trait NeighbourMessageExt {
+    /// Destination address (`NDA_DST`). For VXLAN fdb entries, this is the
+    /// address of the remote VTEP.
+    fn destination(&self) -> Option<IpAddr>;
+
+    /// Link layer address (`NDA_LLADDR`).
+    fn link_layer_address(&self) -> Option<&[u8]>;
+
+    /// Protocol which installed the entry (`NDA_PROTOCOL`).
+    fn protocol(&self) -> Option<RouteProtocol>;
+
+    /// Whether the entry is managed by the kernel (`NTF_EXT_MANAGED`).
+    fn is_managed(&self) -> bool;
+
+    /// Whether the entry was learned externally (`NTF_EXT_LEARNED`).
+    fn is_extern_learn(&self) -> bool;
+
+    /// VLAN of a bridge fdb entry (`NDA_VLAN`).
+    fn vlan(&self) -> Option<u16>;
+
+    /// VXLAN VNI of the remote (`NDA_VNI`).
+    fn vni(&self) -> Option<u32>;
+
+    /// Source VNI (`NDA_SRC_VNI`).
+    fn src_vni(&self) -> Option<u32>;
+
+    /// Outgoing interface towards the remote (`NDA_IFINDEX`).
+    fn ifindex(&self) -> Option<u32>;
+
+    /// UDP destination port of the remote (`NDA_PORT`).
+    fn port(&self) -> Option<u16>;
+
+    /// Master device of the entry (`NDA_MASTER`).
+    fn master(&self) -> Option<u32>;
+
+    /// Nexthop group id (`NDA_NH_ID`).
+    fn nhid(&self) -> Option<u32>;
+}
+
+impl NeighbourMessageExt for NeighbourMessage {
+    fn destination(&self) -> Option<IpAddr> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::Destination(NeighbourAddress::Inet(v4)) => {
+                Some(IpAddr::V4(*v4))
+            }
+            NeighbourAttribute::Destination(NeighbourAddress::Inet6(v6)) => {
+                Some(IpAddr::V6(*v6))
+            }
+            // Bridge fdb entries are parsed without knowing the address
+            // family of the remote.
+            NeighbourAttribute::Destination(NeighbourAddress::Other(bytes)) => {
+                if let Ok(v4) = <[u8; 4]>::try_from(bytes.as_slice()) {
+                    Some(IpAddr::from(v4))
+                } else if let Ok(v6) = <[u8; 16]>::try_from(bytes.as_slice()) {
+                    Some(IpAddr::from(v6))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    }
+
+    fn link_layer_address(&self) -> Option<&[u8]> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::LinkLayerAddress(lla) => Some(lla.as_slice()),
+            _ => None,
+        })
+    }
+
+    fn protocol(&self) -> Option<RouteProtocol> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::Protocol(protocol) => Some(*protocol),
+            _ => None,
+        })
+    }
+
+    fn is_managed(&self) -> bool {
+        self.attributes.iter().any(|nla| match nla {
+            NeighbourAttribute::ExtFlags(flags) => {
+                flags.contains(NeighbourExtFlags::Managed)
+            }
+            _ => false,
+        })
+    }
+
+    fn is_extern_learn(&self) -> bool {
+        self.header.flags.contains(NeighbourFlags::ExtLearned)
+    }
+
+    fn vlan(&self) -> Option<u16> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::Vlan(vlan) => Some(*vlan),
+            _ => None,
+        })
+    }
+
+    fn vni(&self) -> Option<u32> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::Vni(vni) => Some(*vni),
+            _ => None,
+        })
+    }
+
+    fn src_vni(&self) -> Option<u32> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::SourceVni(vni) => Some(*vni),
+            _ => None,
+        })
+    }
+
+    fn ifindex(&self) -> Option<u32> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::IfIndex(index) => Some(*index),
+            _ => None,
+        })
+    }
+
+    fn port(&self) -> Option<u16> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::Port(port) => Some(*port),
+            _ => None,
+        })
+    }
+
+    fn master(&self) -> Option<u32> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::Controller(index) => Some(*index),
+            _ => None,
+        })
+    }
+
+    fn nhid(&self) -> Option<u32> {
+        self.attributes.iter().find_map(|nla| match nla {
+            NeighbourAttribute::Other(nla) if nla.kind() == NDA_NH_ID => {
+                let mut value = [0u8; 4];
+                if nla.value_len() != value.len() {
+                    return None;
+                }
+                nla.emit_value(&mut value);
+                Some(u32::from_ne_bytes(value))
+            }
+            _ => None,
+        })
+    }
+}
@@ -1,11 +1,21 @@
 // SPDX-License-Identifier: MIT
 
 mod add;
+mod attribute;
 mod del;
 mod get;
 mod handle;
+mod message_ext;
+mod resolve;
 
+#[cfg(test)]
+mod test;
+
+pub(crate) use self::attribute::{
+    set_attribute, set_destination, set_nhid, NDA_NH_ID,
+};
 pub use self::{
     add::NeighbourAddRequest, del::NeighbourDelRequest,
     get::NeighbourGetRequest, handle::NeighbourHandle,
+    message_ext::NeighbourMessageExt, resolve::NeighbourResolveRequest,
 };
@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: MIT
+
+use std::{net::IpAddr, time::Duration};
+
+use futures_util::{
+    future::{self, Either},
+    pin_mut,
+    stream::{Stream, StreamExt, TryStreamExt},
+};
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload};
+use netlink_packet_route::{
+    neighbour::{
+        NeighbourAddress, NeighbourAttribute, NeighbourFlags, NeighbourMessage,
+        NeighbourState,
+    },
+    AddressFamily, RouteNetlinkMessage,
+};
+use netlink_sys::SocketAddr;
+
+use crate::{
+    time::sleep, Error, Handle, NeighbourAddRequest, NeighbourGetRequest,
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Make the kernel probe a neighbour (`NTF_USE`) and wait for the entry to
+/// become `REACHABLE` or `FAILED`.
+pub struct NeighbourResolveRequest {
+    handle: Handle,
+    index: u32,
+    destination: IpAddr,
+    timeout: Duration,
+}
+
+impl NeighbourResolveRequest {
+    pub(crate) fn new(handle: Handle, index: u32, destination: IpAddr) -> Self {
+        NeighbourResolveRequest {
+            handle,
+            index,
+            destination,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Set how long to wait for the neighbour to be resolved.
+    /// Default: 3 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Execute the request and return the resolved neighbour entry.
+    ///
+    /// The kernel reports the end of the resolution through a notification
+    /// of the `RTNLGRP_NEIGH` group, which the connections of this crate
+    /// do not receive unless asked to. `messages` must be the receiver of a
+    /// connection subscribed to [crate::MulticastGroup::Neigh], created
+    /// before calling this function, otherwise the state change is missed
+    /// and [Error::Timeout] is returned:
+    ///
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    ///
+    /// use rtnetlink::{new_multicast_connection, MulticastGroup};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), rtnetlink::Error> {
+    ///     let (connection, handle, messages) =
+    ///         new_multicast_connection(&[MulticastGroup::Neigh]).unwrap();
+    ///     tokio::spawn(connection);
+    ///
+    ///     let neighbour = handle
+    ///         .neighbours()
+    ///         .resolve(2, Ipv4Addr::new(192, 0, 2, 2).into())
+    ///         .execute(messages)
+    ///         .await?;
+    ///     println!("{neighbour:?}");
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Entries in `PERMANENT` or `NOARP` state are never probed by the kernel
+    /// and are returned as is.
+    pub async fn execute<S>(
+        self,
+        messages: S,
+    ) -> Result<NeighbourMessage, Error>
+    where
+        S: Stream<Item = (NetlinkMessage<RouteNetlinkMessage>, SocketAddr)>
+            + Unpin,
+    {
+        let NeighbourResolveRequest {
+            handle,
+            index,
+            destination,
+            timeout,
+        } = self;
+
+        let resolve = async move {
+            NeighbourAddRequest::new(handle.clone(), index, destination)
+                .state(NeighbourState::None)
+                .flags(NeighbourFlags::Use)
+                .replace()
+                .execute()
+                .await?;
+
+            // The kernel does not send any notification when the entry is
+            // already valid, so check the current state first.
+            let family = match destination {
+                IpAddr::V4(_) => AddressFamily::Inet,
+                IpAddr::V6(_) => AddressFamily::Inet6,
+            };
+            let mut neighbours = NeighbourGetRequest::new(handle)
+                .set_address_family(family)
+                .execute();
+            while let Some(msg) = neighbours.try_next().await? {
+                if let Some(settled) = settled(&msg, index, destination) {
+                    return settled.map(|_| msg);
+                }
+            }
+
+            let mut messages = messages;
+            while let Some((message, _)) = messages.next().await {
+                if let NetlinkPayload::InnerMessage(
+                    RouteNetlinkMessage::NewNeighbour(msg),
+                ) = message.payload
+                {
+                    if let Some(settled) = settled(&msg, index, destination) {
+                        return settled.map(|_| msg);
+                    }
+                }
+            }
+            Err(Error::RequestFailed)
+        };
+        let deadline = sleep(timeout);
+        pin_mut!(resolve, deadline);
+
+        match future::select(resolve, deadline).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(Error::Timeout),
+        }
+    }
+}
+
+// Return `None` if `msg` is not the entry we wait for, or if it is still being
+// resolved.
+fn settled(
+    msg: &NeighbourMessage,
+    index: u32,
+    destination: IpAddr,
+) -> Option<Result<(), Error>> {
+    if msg.header.ifindex != index {
+        return None;
+    }
+    let found = msg.attributes.iter().any(|nla| match (nla, destination) {
+        (
+            NeighbourAttribute::Destination(NeighbourAddress::Inet(addr)),
+            IpAddr::V4(dst),
+        ) => *addr == dst,
+        (
+            NeighbourAttribute::Destination(NeighbourAddress::Inet6(addr)),
+            IpAddr::V6(dst),
+        ) => *addr == dst,
+        _ => false,
+    });
+    if !found {
+        return None;
+    }
+    match msg.header.state {
+        NeighbourState::Reachable
+        | NeighbourState::Permanent
+        | NeighbourState::Noarp => Some(Ok(())),
+        NeighbourState::Failed => {
+            Some(Err(Error::NeighbourUnreachable(destination)))
+        }
+        _ => None,
+    }
+}
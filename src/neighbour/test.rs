@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    time::Duration,
+};
+
+use netlink_packet_core::{Emitable, Parseable};
+use tokio::runtime::Runtime;
+
+use crate::{
+    new_connection, new_multicast_connection,
+    packet_route::{
+        link::LinkAttribute,
+        neighbour::{NeighbourMessage, NeighbourState},
+        route::RouteProtocol,
+    },
+    test_netns::{setup_veth, Netns, TEST_VETH_2},
+    Error, Handle, LinkUnspec, MulticastGroup, NeighbourMessageExt,
+};
+
+const TEST_RESOLVE_NS: &str = "netlink_test_neigh_resolve_ns";
+const TEST_TIMEOUT_NS: &str = "netlink_test_neigh_timeout_ns";
+const LOCAL: Ipv4Addr = Ipv4Addr::new(192, 0, 2, 1);
+const PEER: Ipv4Addr = Ipv4Addr::new(192, 0, 2, 2);
+
+// Address the veth pair and bring it up, returning the index of the local
+// end and the MAC address of the peer.
+async fn setup_peers(ns: &str) -> (Handle, u32, Vec<u8>, Netns) {
+    let (handle, local, peer, netns) = setup_veth(ns).await;
+    // Both ends live in the same namespace: let the peer answer the ARP
+    // requests coming from a local address.
+    std::fs::write(
+        format!("/proc/sys/net/ipv4/conf/{TEST_VETH_2}/accept_local"),
+        "1",
+    )
+    .unwrap();
+    for (link, address) in [(&local, LOCAL), (&peer, PEER)] {
+        handle
+            .address()
+            .add(link.header.index, address.into(), 24)
+            .execute()
+            .await
+            .unwrap();
+        handle
+            .link()
+            .set(LinkUnspec::new_with_index(link.header.index).up().build())
+            .execute()
+            .await
+            .unwrap();
+    }
+    let peer_mac = peer
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            LinkAttribute::Address(mac) => Some(mac.clone()),
+            _ => None,
+        })
+        .unwrap();
+    (handle, local.header.index, peer_mac, netns)
+}
+
+async fn test_async_resolve() {
+    let (handle, index, peer_mac, _netns) = setup_peers(TEST_RESOLVE_NS).await;
+    let (connection, _, messages) =
+        new_multicast_connection(&[MulticastGroup::Neigh]).unwrap();
+    tokio::spawn(connection);
+
+    let neighbour = handle
+        .neighbours()
+        .resolve(index, PEER.into())
+        .execute(messages)
+        .await
+        .unwrap();
+    assert_eq!(neighbour.header.state, NeighbourState::Reachable);
+    assert_eq!(neighbour.destination(), Some(IpAddr::V4(PEER)));
+    assert_eq!(neighbour.link_layer_address(), Some(peer_mac.as_slice()));
+}
+
+#[test]
+fn test_resolve() {
+    Runtime::new().unwrap().block_on(test_async_resolve());
+}
+
+async fn test_async_resolve_timeout() {
+    let (handle, index, _, _netns) = setup_peers(TEST_TIMEOUT_NS).await;
+    let (connection, _, messages) =
+        new_multicast_connection(&[MulticastGroup::Neigh]).unwrap();
+    tokio::spawn(connection);
+
+    // Nobody answers for this address, and the kernel only gives up after
+    // its probes, long after the timeout.
+    let result = handle
+        .neighbours()
+        .resolve(index, Ipv4Addr::new(192, 0, 2, 3).into())
+        .timeout(Duration::from_millis(200))
+        .execute(messages)
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+}
+
+#[test]
+fn test_resolve_timeout() {
+    Runtime::new()
+        .unwrap()
+        .block_on(test_async_resolve_timeout());
+}
+
+fn emit(message: &NeighbourMessage) -> Vec<u8> {
+    let mut buffer = vec![0u8; message.buffer_len()];
+    message.emit(&mut buffer);
+    buffer
+}
+
+#[test]
+fn add_request_attributes() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // ip neigh add 192.0.2.2 dev eth0 lladdr 02:00:00:00:00:01 \
+        //     proto static managed extern_learn
+        let mut request = handle
+            .neighbours()
+            .add(2, PEER.into())
+            .link_layer_address(&[2, 0, 0, 0, 0, 1])
+            .protocol(RouteProtocol::Static)
+            .managed()
+            .extern_learn();
+        let buffer = emit(request.message_mut());
+        #[rustfmt::skip]
+        assert_eq!(
+            buffer,
+            vec![
+                2, 0, 0, 0, 2, 0, 0, 0, // family, ifindex
+                0x80, 0, 0x10, 0, // NUD_PERMANENT, NTF_EXT_LEARNED, type
+                8, 0, 1, 0, 192, 0, 2, 2, // NDA_DST
+                10, 0, 2, 0, 2, 0, 0, 0, 0, 1, 0, 0, // NDA_LLADDR
+                5, 0, 12, 0, 4, 0, 0, 0, // NDA_PROTOCOL
+                8, 0, 15, 0, 1, 0, 0, 0, // NDA_FLAGS_EXT
+            ]
+        );
+    });
+}
+
+#[test]
+fn add_bridge_request_attributes() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge fdb add 02:00:00:00:00:01 dev vxlan0 master br0 vlan 10 \
+        //     dst 192.0.2.2 vni 100 src_vni 200 via eth0 port 4789 nhid 7
+        let mut request = handle
+            .neighbours()
+            .add_bridge(4, &[2, 0, 0, 0, 0, 1])
+            .master(3)
+            .vlan(10)
+            .destination(PEER.into())
+            .vni(100)
+            .src_vni(200)
+            .ifindex(2)
+            .port(4789)
+            .nhid(7);
+        let buffer = emit(request.message_mut());
+        #[rustfmt::skip]
+        assert_eq!(
+            buffer[12..],
+            [
+                10, 0, 2, 0, 2, 0, 0, 0, 0, 1, 0, 0, // NDA_LLADDR
+                8, 0, 9, 0, 3, 0, 0, 0, // NDA_MASTER
+                6, 0, 5, 0, 10, 0, 0, 0, // NDA_VLAN
+                8, 0, 1, 0, 192, 0, 2, 2, // NDA_DST
+                8, 0, 7, 0, 100, 0, 0, 0, // NDA_VNI
+                8, 0, 11, 0, 200, 0, 0, 0, // NDA_SRC_VNI
+                8, 0, 8, 0, 2, 0, 0, 0, // NDA_IFINDEX
+                6, 0, 6, 0, 0x12, 0xb5, 0, 0, // NDA_PORT
+                8, 0, 13, 0, 7, 0, 0, 0, // NDA_NH_ID
+            ]
+        );
+
+        // The setters replace the attribute instead of appending a new one.
+        let mut request = request.nhid(8).vni(101);
+        let buffer = emit(request.message_mut());
+        assert_eq!(buffer.len(), 12 + 76);
+        assert_eq!(
+            buffer[72..],
+            [8, 0, 13, 0, 8, 0, 0, 0, 8, 0, 7, 0, 101, 0, 0, 0]
+        );
+    });
+}
+
+#[test]
+fn message_ext_round_trip() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        let mut request = handle
+            .neighbours()
+            .add_bridge(4, &[2, 0, 0, 0, 0, 1])
+            .protocol(RouteProtocol::Bgp)
+            .managed()
+            .extern_learn()
+            .master(3)
+            .vlan(10)
+            .destination(PEER.into())
+            .vni(100)
+            .src_vni(200)
+            .ifindex(2)
+            .port(4789)
+            .nhid(7);
+        let buffer = emit(request.message_mut());
+        let message = NeighbourMessage::parse(buffer.as_slice()).unwrap();
+
+        assert_eq!(message.destination(), Some(IpAddr::V4(PEER)));
+        assert_eq!(message.link_layer_address(), Some(&[2, 0, 0, 0, 0, 1][..]));
+        assert_eq!(message.protocol(), Some(RouteProtocol::Bgp));
+        assert!(message.is_managed());
+        assert!(message.is_extern_learn());
+        assert_eq!(message.vlan(), Some(10));
+        assert_eq!(message.vni(), Some(100));
+        assert_eq!(message.src_vni(), Some(200));
+        assert_eq!(message.ifindex(), Some(2));
+        assert_eq!(message.port(), Some(4789));
+        assert_eq!(message.master(), Some(3));
+        assert_eq!(message.nhid(), Some(7));
+
+        let message = NeighbourMessage::default();
+        assert_eq!(message.destination(), None);
+        assert!(!message.is_managed());
+        assert!(!message.is_extern_learn());
+        assert_eq!(message.nhid(), None);
+    });
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT
+
+// Helpers to encode and decode the netlink attributes which are not modeled
+// by netlink-packet-route.
+
+use netlink_packet_core::{
+    DecodeError, DefaultNla, Emitable, Nla, NlasIterator, NLA_F_NESTED,
+    NLA_HEADER_SIZE,
+};
+
+pub(crate) fn nla_u8(kind: u16, value: u8) -> DefaultNla {
+    DefaultNla::new(kind, vec![value])
+}
+
+pub(crate) fn nla_u16(kind: u16, value: u16) -> DefaultNla {
+    DefaultNla::new(kind, value.to_ne_bytes().to_vec())
+}
+
+pub(crate) fn nla_u32(kind: u16, value: u32) -> DefaultNla {
+    DefaultNla::new(kind, value.to_ne_bytes().to_vec())
+}
+
+pub(crate) fn nla_u64(kind: u16, value: u64) -> DefaultNla {
+    DefaultNla::new(kind, value.to_ne_bytes().to_vec())
+}
+
+pub(crate) fn nla_flag(kind: u16) -> DefaultNla {
+    DefaultNla::new(kind, Vec::new())
+}
+
+pub(crate) fn nla_nested(kind: u16, nlas: &[DefaultNla]) -> DefaultNla {
+    DefaultNla::new(kind | NLA_F_NESTED, emit_nlas(nlas))
+}
+
+pub(crate) fn emit_nlas(nlas: &[DefaultNla]) -> Vec<u8> {
+    let mut buffer = vec![0u8; nlas.buffer_len()];
+    nlas.emit(&mut buffer);
+    buffer
+}
+
+pub(crate) fn nla_value(nla: &impl Nla) -> Vec<u8> {
+    let mut value = vec![0u8; nla.value_len()];
+    nla.emit_value(&mut value);
+    value
+}
+
+/// Parse nested attributes into `(kind, value)` pairs, without the
+/// `NLA_F_NESTED` and `NLA_F_NET_BYTEORDER` flags in `kind`.
+pub(crate) fn parse_nlas(
+    payload: &[u8],
+) -> Result<Vec<(u16, &[u8])>, DecodeError> {
+    let mut nlas = Vec::new();
+    for nla in NlasIterator::new(payload) {
+        let nla = nla?;
+        let kind = nla.kind();
+        let len = nla.value_length();
+        let data: &[u8] = nla.into_inner();
+        nlas.push((kind, &data[NLA_HEADER_SIZE..NLA_HEADER_SIZE + len]));
+    }
+    Ok(nlas)
+}
+
+/// Read a native endian `u16` at `offset` of a kernel struct.
+pub(crate) fn read_u16(buf: &[u8], offset: usize) -> Result<u16, DecodeError> {
+    buf.get(offset..offset + 2)
+        .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+        .ok_or_else(|| DecodeError::buffer_too_small(buf.len(), offset + 2))
+}
+
+/// Read a native endian `u32` at `offset` of a kernel struct.
+pub(crate) fn read_u32(buf: &[u8], offset: usize) -> Result<u32, DecodeError> {
+    buf.get(offset..offset + 4)
+        .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| DecodeError::buffer_too_small(buf.len(), offset + 4))
+}
+
+/// Read a native endian `u64` at `offset` of a kernel struct.
+pub(crate) fn read_u64(buf: &[u8], offset: usize) -> Result<u64, DecodeError> {
+    buf.get(offset..offset + 8)
+        .map(|b| {
+            u64::from_ne_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        })
+        .ok_or_else(|| DecodeError::buffer_too_small(buf.len(), offset + 8))
+}
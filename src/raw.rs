@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: MIT
+
+//! Requests whose message types have no variant in [RouteNetlinkMessage],
+//! like `RTM_SETSTATS`, `RTM_NEWMDB` or `RTM_NEWVLAN`.
+//!
+//! [crate::Handle] cannot carry them: its connection rewrites the message
+//! type from the [RouteNetlinkMessage] variant. They are instead sent on a
+//! short-lived connection opened in the network namespace of the
+//! [crate::Handle], the one of the thread which created it.
+//!
+//! [RouteNetlinkMessage]: netlink_packet_route::RouteNetlinkMessage
+
+use std::{
+    fmt, io,
+    os::fd::{FromRawFd, IntoRawFd, OwnedFd},
+    thread,
+};
+
+use futures_util::{
+    future::{self, Either},
+    stream::{self, Stream},
+    FutureExt, StreamExt,
+};
+use netlink_packet_core::{
+    NetlinkDeserializable, NetlinkHeader, NetlinkMessage, NetlinkPayload,
+    NetlinkSerializable,
+};
+use netlink_proto::NetlinkCodec;
+use netlink_sys::{protocols::NETLINK_ROUTE, Socket, SocketAddr};
+use nix::{
+    sched::{setns, CloneFlags},
+    sys::stat::{fstat, stat},
+};
+
+use crate::{Error, Handle, MulticastGroup};
+
+#[cfg(feature = "smol_socket")]
+type RawSocket = netlink_sys::SmolSocket;
+#[cfg(all(not(feature = "smol_socket"), feature = "tokio_socket"))]
+type RawSocket = netlink_sys::TokioSocket;
+
+const THREAD_NS_PATH: &str = "/proc/thread-self/ns/net";
+
+/// A route netlink message kept as its type and serialized payload.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct RawRouteMessage {
+    pub(crate) message_type: u16,
+    pub(crate) payload: Vec<u8>,
+}
+
+impl RawRouteMessage {
+    pub(crate) fn new(message_type: u16, payload: Vec<u8>) -> Self {
+        RawRouteMessage {
+            message_type,
+            payload,
+        }
+    }
+}
+
+impl NetlinkSerializable for RawRouteMessage {
+    fn message_type(&self) -> u16 {
+        self.message_type
+    }
+
+    fn buffer_len(&self) -> usize {
+        self.payload.len()
+    }
+
+    fn serialize(&self, buffer: &mut [u8]) {
+        buffer.copy_from_slice(&self.payload)
+    }
+}
+
+impl From<RawRouteMessage> for NetlinkPayload<RawRouteMessage> {
+    fn from(message: RawRouteMessage) -> Self {
+        NetlinkPayload::InnerMessage(message)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RawRouteMessageError;
+
+impl fmt::Display for RawRouteMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid route netlink message")
+    }
+}
+
+impl std::error::Error for RawRouteMessageError {}
+
+impl NetlinkDeserializable for RawRouteMessage {
+    type Error = RawRouteMessageError;
+
+    fn deserialize(
+        header: &NetlinkHeader,
+        payload: &[u8],
+    ) -> Result<Self, Self::Error> {
+        Ok(RawRouteMessage::new(header.message_type, payload.to_vec()))
+    }
+}
+
+/// Open the network namespace of the current thread.
+pub(crate) fn current_netns() -> Option<OwnedFd> {
+    std::fs::File::open(THREAD_NS_PATH).ok().map(OwnedFd::from)
+}
+
+fn is_current_netns(netns: &OwnedFd) -> bool {
+    match (fstat(netns), stat(THREAD_NS_PATH)) {
+        (Ok(netns), Ok(current)) => {
+            netns.st_dev == current.st_dev && netns.st_ino == current.st_ino
+        }
+        _ => false,
+    }
+}
+
+// The network namespace of a netlink socket is the one of the thread
+// creating it, so switch a scratch thread to `netns` when it differs.
+fn socket(netns: Option<&OwnedFd>) -> io::Result<Socket> {
+    match netns {
+        Some(netns) if !is_current_netns(netns) => thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    setns(netns, CloneFlags::CLONE_NEWNET)?;
+                    Socket::new(NETLINK_ROUTE)
+                })
+                .join()
+                .unwrap_or_else(|e| std::panic::resume_unwind(e))
+        }),
+        _ => Socket::new(NETLINK_ROUTE),
+    }
+}
+
+/// Send `message` in the network namespace of `handle` and return the
+/// messages received in response, acknowledgements excluded.
+pub(crate) async fn request(
+    handle: &Handle,
+    message: NetlinkMessage<RawRouteMessage>,
+) -> Result<Vec<RawRouteMessage>, Error> {
+    let socket = socket(handle.netns()).map_err(|e| {
+        log::warn!("failed to open a netlink socket: {e}");
+        Error::RequestFailed
+    })?;
+    // SAFETY: the file descriptor is a netlink socket owned by nothing else.
+    let socket = unsafe { RawSocket::from_raw_fd(socket.into_raw_fd()) };
+    let (connection, conn_handle, _) = netlink_proto::from_socket_with_codec::<
+        RawRouteMessage,
+        RawSocket,
+        NetlinkCodec,
+    >(socket);
+
+    let mut response = conn_handle
+        .request(message, SocketAddr::new(0, 0))
+        .map_err(|_| Error::RequestFailed)?;
+    let messages = async move {
+        let mut messages = Vec::new();
+        while let Some(message) = response.next().await {
+            match message.payload {
+                NetlinkPayload::InnerMessage(message) => messages.push(message),
+                NetlinkPayload::Error(err) if err.code.is_some() => {
+                    return Err(Error::NetlinkError(err))
+                }
+                _ => (),
+            }
+        }
+        Ok(messages)
+    };
+    futures_util::pin_mut!(messages);
+    match future::select(connection, messages).await {
+        Either::Left(_) => Err(Error::RequestFailed),
+        Either::Right((result, _)) => result,
+    }
+}
+
+/// Receive the messages of the multicast `groups` in the network namespace
+/// of `handle`, including the ones [RouteNetlinkMessage] cannot decode.
+///
+/// [RouteNetlinkMessage]: netlink_packet_route::RouteNetlinkMessage
+pub(crate) fn subscribe(
+    handle: &Handle,
+    groups: &[MulticastGroup],
+) -> Result<impl Stream<Item = RawRouteMessage>, Error> {
+    let open = || {
+        let mut socket = socket(handle.netns())?;
+        socket.bind_auto()?;
+        for group in groups {
+            socket.add_membership(*group as u32)?;
+        }
+        Ok::<_, io::Error>(socket)
+    };
+    let socket = open().map_err(|e| {
+        log::warn!("failed to subscribe to {groups:?}: {e}");
+        Error::RequestFailed
+    })?;
+    // SAFETY: the file descriptor is a netlink socket owned by nothing else.
+    let socket = unsafe { RawSocket::from_raw_fd(socket.into_raw_fd()) };
+    let (connection, _, messages) = netlink_proto::from_socket_with_codec::<
+        RawRouteMessage,
+        RawSocket,
+        NetlinkCodec,
+    >(socket);
+
+    // Drive the connection while the messages are consumed.
+    let connection =
+        connection.into_stream().filter_map(|_| future::ready(None));
+    let messages = messages.filter_map(|(message, _)| {
+        future::ready(match message.payload {
+            NetlinkPayload::InnerMessage(message) => Some(message),
+            _ => None,
+        })
+    });
+    Ok(stream::select(connection, messages))
+}
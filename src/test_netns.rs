@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+
+use std::{fs::File, os::fd::AsFd, path::Path};
+
+use futures_util::stream::TryStreamExt;
+use nix::sched::{setns, CloneFlags};
+
+use crate::{
+    new_connection, packet_route::link::LinkMessage, Handle, LinkUnspec,
+    LinkVeth, NetworkNamespace, NETNS_PATH, SELF_NS_PATH,
+};
+
+pub(crate) const TEST_VETH_1: &str = "test_veth_1";
+pub(crate) const TEST_VETH_2: &str = "test_veth_2";
+pub(crate) const TEST_BRIDGE: &str = "test_br";
+
+/// Network namespace entered by the current thread for the duration of a
+/// test, and deleted when dropped.
+pub(crate) struct Netns {
+    path: String,
+    _cur: File,
+    last: File,
+}
+
+impl Netns {
+    pub(crate) async fn new(path: &str) -> Self {
+        // record current ns
+        let last = File::open(Path::new(SELF_NS_PATH)).unwrap();
+
+        // create new ns
+        NetworkNamespace::add(path.to_string()).await.unwrap();
+
+        // entry new ns
+        let ns_path = Path::new(NETNS_PATH);
+        let file = File::open(ns_path.join(path)).unwrap();
+        setns(file.as_fd(), CloneFlags::CLONE_NEWNET).unwrap();
+
+        Self {
+            path: path.to_string(),
+            _cur: file,
+            last,
+        }
+    }
+}
+
+impl Drop for Netns {
+    fn drop(&mut self) {
+        println!("exit ns: {}", self.path);
+        setns(self.last.as_fd(), CloneFlags::CLONE_NEWNET).unwrap();
+
+        let ns_path = Path::new(NETNS_PATH).join(&self.path);
+        nix::mount::umount2(&ns_path, nix::mount::MntFlags::MNT_DETACH)
+            .unwrap();
+        nix::unistd::unlink(&ns_path).unwrap();
+        // _cur File will be closed auto
+        // Since there is no async drop, NetworkNamespace::del cannot be
+        // called here. The interfaces will be deleted automatically after
+        // netns is deleted.
+    }
+}
+
+/// Enter the new network namespace `ns` and connect to it.
+pub(crate) async fn setup_netns(ns: &str) -> (Handle, Netns) {
+    let netns = Netns::new(ns).await;
+
+    // Notice: The Handle can only be created after the setns, so that the
+    // Handle is the connection within the new ns.
+    let (connection, handle, _) = new_connection().unwrap();
+    tokio::spawn(connection);
+    (handle, netns)
+}
+
+pub(crate) async fn get_link(handle: &Handle, name: &str) -> LinkMessage {
+    let mut links = handle.link().get().match_name(name.to_string()).execute();
+    links.try_next().await.unwrap().unwrap()
+}
+
+/// Enter the new network namespace `ns` and create a pair of veth in it,
+/// [TEST_VETH_1] and [TEST_VETH_2].
+pub(crate) async fn setup_veth(
+    ns: &str,
+) -> (Handle, LinkMessage, LinkMessage, Netns) {
+    let (handle, netns) = setup_netns(ns).await;
+    handle
+        .link()
+        .add(LinkVeth::new(TEST_VETH_1, TEST_VETH_2).build())
+        .execute()
+        .await
+        .unwrap();
+
+    let link1 = get_link(&handle, TEST_VETH_1).await;
+    let link2 = get_link(&handle, TEST_VETH_2).await;
+    (handle, link1, link2, netns)
+}
+
+/// Enter the new network namespace `ns`, create the bridge `bridge`, named
+/// [TEST_BRIDGE], and enslave [TEST_VETH_1] to it, everything up.
+pub(crate) async fn setup_bridge(
+    ns: &str,
+    bridge: LinkMessage,
+) -> (Handle, LinkMessage, LinkMessage, Netns) {
+    let (handle, port, peer, netns) = setup_veth(ns).await;
+    handle.link().add(bridge).execute().await.unwrap();
+    let bridge = get_link(&handle, TEST_BRIDGE).await;
+    for message in [
+        LinkUnspec::new_with_index(port.header.index)
+            .controller(bridge.header.index)
+            .up()
+            .build(),
+        LinkUnspec::new_with_index(peer.header.index).up().build(),
+        LinkUnspec::new_with_index(bridge.header.index).up().build(),
+    ] {
+        handle.link().set(message).execute().await.unwrap();
+    }
+    (handle, bridge, port, netns)
+}
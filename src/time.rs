@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+// Same runtime selection as `try_spawn_blocking()` in `ns.rs`: prefer smol
+// when enabled as it does not require an active tokio runtime.
+#[cfg(feature = "smol_socket")]
+pub(crate) async fn sleep(duration: Duration) {
+    async_io::Timer::after(duration).await;
+}
+
+#[cfg(all(not(feature = "smol_socket"), feature = "tokio_socket"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+// neither smol nor tokio - park a thread instead of the executor.
+#[cfg(all(not(feature = "smol_socket"), not(feature = "tokio_socket")))]
+pub(crate) async fn sleep(duration: Duration) {
+    let (tx, rx) = futures_channel::oneshot::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let _ = tx.send(());
+    });
+    let _ = rx.await;
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcActionGeneric, TcActionType};
+
+use super::{emit_tc_gen, new_raw_action, TC_GEN_LEN};
+use crate::traffic_control::nla::{parse_nlas, read_u32};
+
+const TCA_CSUM_PARMS: u16 = 1;
+
+/// Recompute the checksums of the packets.
+/// Equivalent to `action csum UPDATE`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionCsum {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    /// Checksums to update, see [Self::IPV4_HEADER] and the following
+    /// constants.
+    pub update_flags: u32,
+}
+
+impl TrafficActionCsum {
+    pub const KIND: &'static str = "csum";
+
+    pub const IPV4_HEADER: u32 = 1 << 0;
+    pub const ICMP: u32 = 1 << 1;
+    pub const IGMP: u32 = 1 << 2;
+    pub const TCP: u32 = 1 << 3;
+    pub const UDP: u32 = 1 << 4;
+    pub const UDPLITE: u32 = 1 << 5;
+    pub const SCTP: u32 = 1 << 6;
+
+    /// Equivalent to `action csum UPDATE`, `update_flags` is a combination
+    /// of [Self::IPV4_HEADER] and the following constants.
+    pub fn new(update_flags: u32) -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Ok,
+            update_flags,
+        }
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `pass` by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut csum = Self::new(0);
+        for (kind, value) in parse_nlas(payload)? {
+            if kind == TCA_CSUM_PARMS {
+                let generic = TcActionGeneric::parse(value)?;
+                csum.index = generic.index;
+                csum.control = generic.action;
+                csum.update_flags = read_u32(value, TC_GEN_LEN)?;
+            }
+        }
+        Ok(csum)
+    }
+}
+
+impl From<TrafficActionCsum> for TcAction {
+    fn from(csum: TrafficActionCsum) -> Self {
+        let mut parms = emit_tc_gen(csum.index, csum.control);
+        parms.extend(csum.update_flags.to_ne_bytes());
+        new_raw_action(
+            TrafficActionCsum::KIND,
+            vec![DefaultNla::new(TCA_CSUM_PARMS, parms)],
+        )
+    }
+}
@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_core::{parse_u16, parse_u32, DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcActionGeneric, TcActionType};
+
+use super::{emit_tc_gen, new_raw_action};
+use crate::traffic_control::nla::{nla_u16, nla_u32, parse_nlas};
+
+const TCA_CT_PARMS: u16 = 1;
+const TCA_CT_ACTION: u16 = 3;
+const TCA_CT_ZONE: u16 = 4;
+const TCA_CT_MARK: u16 = 5;
+const TCA_CT_MARK_MASK: u16 = 6;
+const TCA_CT_NAT_IPV4_MIN: u16 = 9;
+const TCA_CT_NAT_IPV4_MAX: u16 = 10;
+const TCA_CT_NAT_IPV6_MIN: u16 = 11;
+const TCA_CT_NAT_IPV6_MAX: u16 = 12;
+const TCA_CT_NAT_PORT_MIN: u16 = 13;
+const TCA_CT_NAT_PORT_MAX: u16 = 14;
+
+const TCA_CT_ACT_COMMIT: u16 = 1 << 0;
+const TCA_CT_ACT_FORCE: u16 = 1 << 1;
+const TCA_CT_ACT_CLEAR: u16 = 1 << 2;
+const TCA_CT_ACT_NAT: u16 = 1 << 3;
+const TCA_CT_ACT_NAT_SRC: u16 = 1 << 4;
+const TCA_CT_ACT_NAT_DST: u16 = 1 << 5;
+
+/// Send the packets through the connection tracking.
+/// Equivalent to `action ct`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionCt {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    /// Commit the connection to the connection tracking table.
+    pub commit: bool,
+    /// Commit over an existing connection in the opposite direction.
+    pub force: bool,
+    /// Clear the connection tracking state of the packets.
+    pub clear: bool,
+    pub zone: Option<u16>,
+    /// Connection mark and the mask of its bits to set, with `commit`.
+    pub mark: Option<(u32, u32)>,
+    /// Apply the NAT of the connection, or set it up with `commit`.
+    pub nat: bool,
+    /// Translate the source, when `nat` and `commit` are set.
+    pub nat_src: bool,
+    /// Translate the destination, when `nat` and `commit` are set.
+    pub nat_dst: bool,
+    /// Range of the translated addresses.
+    pub nat_addr: Option<(IpAddr, IpAddr)>,
+    /// Range of the translated ports.
+    pub nat_port: Option<(u16, u16)>,
+}
+
+impl Default for TrafficActionCt {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Pipe,
+            commit: false,
+            force: false,
+            clear: false,
+            zone: None,
+            mark: None,
+            nat: false,
+            nat_src: false,
+            nat_dst: false,
+            nat_addr: None,
+            nat_port: None,
+        }
+    }
+}
+
+impl TrafficActionCt {
+    pub const KIND: &'static str = "ct";
+
+    /// Equivalent to `action ct`, which only looks up the connection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `action ct commit`.
+    pub fn commit() -> Self {
+        Self {
+            commit: true,
+            ..Self::default()
+        }
+    }
+
+    /// Equivalent to `action ct clear`.
+    pub fn clear() -> Self {
+        Self {
+            clear: true,
+            ..Self::default()
+        }
+    }
+
+    /// Equivalent to `force`.
+    pub fn force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// Equivalent to `zone ZONE`.
+    pub fn zone(mut self, zone: u16) -> Self {
+        self.zone = Some(zone);
+        self
+    }
+
+    /// Equivalent to `mark MARK/MASK`.
+    pub fn mark(mut self, mark: u32, mask: u32) -> Self {
+        self.mark = Some((mark, mask));
+        self
+    }
+
+    /// Equivalent to `nat`.
+    pub fn nat(mut self) -> Self {
+        self.nat = true;
+        self
+    }
+
+    /// Equivalent to `nat src addr MIN-MAX`.
+    pub fn nat_src(mut self, min: IpAddr, max: IpAddr) -> Self {
+        self.nat = true;
+        self.nat_src = true;
+        self.nat_addr = Some((min, max));
+        self
+    }
+
+    /// Equivalent to `nat dst addr MIN-MAX`.
+    pub fn nat_dst(mut self, min: IpAddr, max: IpAddr) -> Self {
+        self.nat = true;
+        self.nat_dst = true;
+        self.nat_addr = Some((min, max));
+        self
+    }
+
+    /// Equivalent to `port MIN-MAX`.
+    pub fn nat_port(mut self, min: u16, max: u16) -> Self {
+        self.nat_port = Some((min, max));
+        self
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `pipe` by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    fn flags(&self) -> u16 {
+        [
+            (self.commit, TCA_CT_ACT_COMMIT),
+            (self.force, TCA_CT_ACT_FORCE),
+            (self.clear, TCA_CT_ACT_CLEAR),
+            (self.nat, TCA_CT_ACT_NAT),
+            (self.nat_src, TCA_CT_ACT_NAT_SRC),
+            (self.nat_dst, TCA_CT_ACT_NAT_DST),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .fold(0, |flags, (_, flag)| flags | flag)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut ct = Self::default();
+        let (mut mark, mut mask) = (None, u32::MAX);
+        let (mut addr_min, mut addr_max) = (None, None);
+        let (mut port_min, mut port_max) = (None, None);
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_CT_PARMS => {
+                    let generic = TcActionGeneric::parse(value)?;
+                    ct.index = generic.index;
+                    ct.control = generic.action;
+                }
+                TCA_CT_ACTION => {
+                    let flags = parse_u16(value)?;
+                    ct.commit = flags & TCA_CT_ACT_COMMIT != 0;
+                    ct.force = flags & TCA_CT_ACT_FORCE != 0;
+                    ct.clear = flags & TCA_CT_ACT_CLEAR != 0;
+                    ct.nat = flags & TCA_CT_ACT_NAT != 0;
+                    ct.nat_src = flags & TCA_CT_ACT_NAT_SRC != 0;
+                    ct.nat_dst = flags & TCA_CT_ACT_NAT_DST != 0;
+                }
+                TCA_CT_ZONE => ct.zone = Some(parse_u16(value)?),
+                TCA_CT_MARK => mark = Some(parse_u32(value)?),
+                TCA_CT_MARK_MASK => mask = parse_u32(value)?,
+                TCA_CT_NAT_IPV4_MIN | TCA_CT_NAT_IPV6_MIN => {
+                    addr_min = Some(parse_ip(value)?)
+                }
+                TCA_CT_NAT_IPV4_MAX | TCA_CT_NAT_IPV6_MAX => {
+                    addr_max = Some(parse_ip(value)?)
+                }
+                TCA_CT_NAT_PORT_MIN => {
+                    port_min = Some(u16::from_be(parse_u16(value)?))
+                }
+                TCA_CT_NAT_PORT_MAX => {
+                    port_max = Some(u16::from_be(parse_u16(value)?))
+                }
+                _ => (),
+            }
+        }
+        ct.mark = mark.map(|mark| (mark, mask));
+        ct.nat_addr = addr_min.map(|min| (min, addr_max.unwrap_or(min)));
+        ct.nat_port = port_min.map(|min| (min, port_max.unwrap_or(min)));
+        Ok(ct)
+    }
+}
+
+fn parse_ip(value: &[u8]) -> Result<IpAddr, DecodeError> {
+    if let Ok(octets) = <[u8; 4]>::try_from(value) {
+        Ok(Ipv4Addr::from(octets).into())
+    } else if let Ok(octets) = <[u8; 16]>::try_from(value) {
+        Ok(Ipv6Addr::from(octets).into())
+    } else {
+        Err(format!("invalid ct nat address {value:?}").into())
+    }
+}
+
+fn nla_ip(v4_kind: u16, v6_kind: u16, addr: IpAddr) -> DefaultNla {
+    match addr {
+        IpAddr::V4(addr) => DefaultNla::new(v4_kind, addr.octets().to_vec()),
+        IpAddr::V6(addr) => DefaultNla::new(v6_kind, addr.octets().to_vec()),
+    }
+}
+
+impl From<TrafficActionCt> for TcAction {
+    fn from(ct: TrafficActionCt) -> Self {
+        let mut nlas = Vec::new();
+        if let Some(zone) = ct.zone {
+            nlas.push(nla_u16(TCA_CT_ZONE, zone));
+        }
+        if let Some((mark, mask)) = ct.mark {
+            nlas.push(nla_u32(TCA_CT_MARK, mark));
+            nlas.push(nla_u32(TCA_CT_MARK_MASK, mask));
+        }
+        if let Some((min, max)) = ct.nat_addr {
+            nlas.push(nla_ip(TCA_CT_NAT_IPV4_MIN, TCA_CT_NAT_IPV6_MIN, min));
+            nlas.push(nla_ip(TCA_CT_NAT_IPV4_MAX, TCA_CT_NAT_IPV6_MAX, max));
+        }
+        if let Some((min, max)) = ct.nat_port {
+            nlas.push(nla_u16(TCA_CT_NAT_PORT_MIN, min.to_be()));
+            nlas.push(nla_u16(TCA_CT_NAT_PORT_MAX, max.to_be()));
+        }
+        nlas.push(nla_u16(TCA_CT_ACTION, ct.flags()));
+        nlas.push(DefaultNla::new(
+            TCA_CT_PARMS,
+            emit_tc_gen(ct.index, ct.control),
+        ));
+        new_raw_action(TrafficActionCt::KIND, nlas)
+    }
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcActionGeneric, TcActionType};
+
+use super::{emit_tc_gen, new_raw_action};
+use crate::traffic_control::nla::parse_nlas;
+
+const TCA_GACT_PARMS: u16 = 2;
+
+const TC_ACT_GOTO_CHAIN: i32 = 0x2000_0000;
+const TC_ACT_EXT_VAL_MASK: i32 = 0x0fff_ffff;
+const TC_ACT_EXT_OPCODE_MASK: i32 = !TC_ACT_EXT_VAL_MASK;
+
+/// Generic action, which gives a verdict on the packets.
+/// Equivalent to `action gact CONTROL`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionGact {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+}
+
+impl Default for TrafficActionGact {
+    fn default() -> Self {
+        Self::new(TcActionType::Ok)
+    }
+}
+
+impl TrafficActionGact {
+    pub const KIND: &'static str = "gact";
+
+    pub fn new(control: TcActionType) -> Self {
+        Self { index: 0, control }
+    }
+
+    /// Equivalent to `action pass`.
+    pub fn pass() -> Self {
+        Self::new(TcActionType::Ok)
+    }
+
+    /// Equivalent to `action drop`.
+    pub fn drop() -> Self {
+        Self::new(TcActionType::Shot)
+    }
+
+    /// Continue the classification in another chain of the same block.
+    /// Equivalent to `action goto chain CHAIN_INDEX`.
+    pub fn goto_chain(chain: u32) -> Self {
+        Self::new(TcActionType::Other(
+            TC_ACT_GOTO_CHAIN | (chain as i32 & TC_ACT_EXT_VAL_MASK),
+        ))
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Chain the packets continue their classification in, if the control
+    /// is a `goto chain`.
+    pub fn chain(&self) -> Option<u32> {
+        match i32::from(self.control) {
+            c if c & TC_ACT_EXT_OPCODE_MASK == TC_ACT_GOTO_CHAIN => {
+                Some((c & TC_ACT_EXT_VAL_MASK) as u32)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut gact = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            if kind == TCA_GACT_PARMS {
+                let generic = TcActionGeneric::parse(value)?;
+                gact.index = generic.index;
+                gact.control = generic.action;
+            }
+        }
+        Ok(gact)
+    }
+}
+
+impl From<TrafficActionGact> for TcAction {
+    fn from(gact: TrafficActionGact) -> Self {
+        new_raw_action(
+            TrafficActionGact::KIND,
+            vec![DefaultNla::new(
+                TCA_GACT_PARMS,
+                emit_tc_gen(gact.index, gact.control),
+            )],
+        )
+    }
+}
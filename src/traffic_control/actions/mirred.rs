@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, NlasIterator, Parseable};
+use netlink_packet_route::tc::{
+    TcAction, TcActionMirror, TcActionMirrorOption, TcActionOption,
+    TcActionType, TcMirror, TcMirrorActionType,
+};
+
+use super::{new_action, tc_gen};
+
+/// Mirror or redirect the packets to another device.
+/// Equivalent to `action mirred DIRECTION ACTION dev DEVICE`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionMirred {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    pub eaction: TcMirrorActionType,
+    /// Index of the target device.
+    pub ifindex: u32,
+}
+
+impl TrafficActionMirred {
+    pub const KIND: &'static str = TcActionMirror::KIND;
+
+    /// Equivalent to `action mirred egress redirect dev DEVICE`.
+    pub fn redirect(ifindex: u32) -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Stolen,
+            eaction: TcMirrorActionType::EgressRedir,
+            ifindex,
+        }
+    }
+
+    /// Equivalent to `action mirred egress mirror dev DEVICE`.
+    pub fn mirror(ifindex: u32) -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Pipe,
+            eaction: TcMirrorActionType::EgressMirror,
+            ifindex,
+        }
+    }
+
+    /// Send the packets to the ingress of the target device instead of its
+    /// egress.
+    /// Equivalent to `action mirred ingress ...`.
+    pub fn ingress(mut self) -> Self {
+        self.eaction = match self.eaction {
+            TcMirrorActionType::EgressRedir => TcMirrorActionType::IngressRedir,
+            TcMirrorActionType::EgressMirror => {
+                TcMirrorActionType::IngressMirror
+            }
+            other => other,
+        };
+        self
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `stolen` for redirect and `pipe` for
+    /// mirror by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut mirred = Self::redirect(0);
+        for nla in NlasIterator::new(payload) {
+            if let TcActionMirrorOption::Parms(parms) =
+                TcActionMirrorOption::parse(&nla?)?
+            {
+                mirred.index = parms.generic.index;
+                mirred.control = parms.generic.action;
+                mirred.eaction = parms.eaction;
+                mirred.ifindex = parms.ifindex;
+            }
+        }
+        Ok(mirred)
+    }
+}
+
+impl From<TrafficActionMirred> for TcAction {
+    fn from(mirred: TrafficActionMirred) -> Self {
+        let mut parms = TcMirror::default();
+        parms.generic = tc_gen(mirred.index, mirred.control);
+        parms.eaction = mirred.eaction;
+        parms.ifindex = mirred.ifindex;
+        new_action(
+            TrafficActionMirred::KIND,
+            vec![TcActionOption::Mirror(TcActionMirrorOption::Parms(parms))],
+        )
+    }
+}
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MIT
+
+mod csum;
+mod ct;
+mod gact;
+mod mirred;
+mod nat;
+mod pedit;
+mod police;
+mod skbedit;
+#[cfg(test)]
+mod test;
+mod tunnel_key;
+mod vlan;
+
+use netlink_packet_core::{DecodeError, DefaultNla, Emitable};
+use netlink_packet_route::tc::{
+    TcAction, TcActionAttribute, TcActionGeneric, TcActionOption, TcActionType,
+};
+
+pub use self::{
+    csum::TrafficActionCsum,
+    ct::TrafficActionCt,
+    gact::TrafficActionGact,
+    mirred::TrafficActionMirred,
+    nat::TrafficActionNat,
+    pedit::{PeditCmd, PeditHeader, PeditKey, TrafficActionPedit},
+    police::TrafficActionPolice,
+    skbedit::TrafficActionSkbedit,
+    tunnel_key::TrafficActionTunnelKey,
+    vlan::{TrafficActionVlan, VlanOp},
+};
+
+// Size of `tc_gen`, the header of the parameters of every action.
+const TC_GEN_LEN: usize = 20;
+
+/// Decoded options of an action, see
+/// [crate::TcActionExt::action_options].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TrafficActionOptions {
+    Gact(TrafficActionGact),
+    Mirred(TrafficActionMirred),
+    Vlan(TrafficActionVlan),
+    Skbedit(TrafficActionSkbedit),
+    Pedit(TrafficActionPedit),
+    TunnelKey(TrafficActionTunnelKey),
+    Csum(TrafficActionCsum),
+    Police(TrafficActionPolice),
+    Ct(TrafficActionCt),
+    Nat(TrafficActionNat),
+    /// Action kind without typed options.
+    Other(String),
+}
+
+impl TrafficActionOptions {
+    pub(crate) fn parse(
+        kind: &str,
+        payload: &[u8],
+    ) -> Result<Self, DecodeError> {
+        Ok(match kind {
+            TrafficActionGact::KIND => {
+                Self::Gact(TrafficActionGact::parse(payload)?)
+            }
+            TrafficActionMirred::KIND => {
+                Self::Mirred(TrafficActionMirred::parse(payload)?)
+            }
+            TrafficActionVlan::KIND => {
+                Self::Vlan(TrafficActionVlan::parse(payload)?)
+            }
+            TrafficActionSkbedit::KIND => {
+                Self::Skbedit(TrafficActionSkbedit::parse(payload)?)
+            }
+            TrafficActionPedit::KIND => {
+                Self::Pedit(TrafficActionPedit::parse(payload)?)
+            }
+            TrafficActionTunnelKey::KIND => {
+                Self::TunnelKey(TrafficActionTunnelKey::parse(payload)?)
+            }
+            TrafficActionCsum::KIND => {
+                Self::Csum(TrafficActionCsum::parse(payload)?)
+            }
+            TrafficActionPolice::KIND => {
+                Self::Police(TrafficActionPolice::parse(payload)?)
+            }
+            TrafficActionCt::KIND => Self::Ct(TrafficActionCt::parse(payload)?),
+            TrafficActionNat::KIND => {
+                Self::Nat(TrafficActionNat::parse(payload)?)
+            }
+            _ => Self::Other(kind.to_string()),
+        })
+    }
+}
+
+// The kernel runs the actions in the order of their attribute kind.
+pub(crate) fn numbered_actions(actions: &[TcAction]) -> Vec<TcAction> {
+    let mut actions = actions.to_vec();
+    for (i, action) in actions.iter_mut().enumerate() {
+        action.tab = i as u16 + 1;
+    }
+    actions
+}
+
+pub(crate) fn action_kind(action: &TcAction) -> Option<&str> {
+    action.attributes.iter().find_map(|attr| match attr {
+        TcActionAttribute::Kind(kind) => Some(kind.as_str()),
+        _ => None,
+    })
+}
+
+/// Raw payload of `TCA_ACT_OPTIONS`, whether it was parsed by
+/// netlink-packet-route or built by this crate.
+pub(crate) fn action_options_payload(action: &TcAction) -> Option<Vec<u8>> {
+    action.attributes.iter().find_map(|attr| match attr {
+        TcActionAttribute::Options(options) => {
+            let mut buffer = vec![0u8; options.as_slice().buffer_len()];
+            options.as_slice().emit(&mut buffer);
+            Some(buffer)
+        }
+        _ => None,
+    })
+}
+
+fn new_action(kind: &str, options: Vec<TcActionOption>) -> TcAction {
+    let mut action = TcAction::default();
+    action.attributes = vec![
+        TcActionAttribute::Kind(kind.to_string()),
+        TcActionAttribute::Options(options),
+    ];
+    action
+}
+
+// Action whose options are not modeled by netlink-packet-route.
+fn new_raw_action(kind: &str, nlas: Vec<DefaultNla>) -> TcAction {
+    new_action(kind, nlas.into_iter().map(TcActionOption::Other).collect())
+}
+
+fn tc_gen(index: u32, control: TcActionType) -> TcActionGeneric {
+    let mut generic = TcActionGeneric::default();
+    generic.index = index;
+    generic.action = control;
+    generic
+}
+
+fn emit_tc_gen(index: u32, control: TcActionType) -> Vec<u8> {
+    let mut buffer = vec![0u8; TC_GEN_LEN];
+    tc_gen(index, control).emit(&mut buffer);
+    buffer
+}
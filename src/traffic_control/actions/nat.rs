@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::Ipv4Addr;
+
+use netlink_packet_core::{DecodeError, NlasIterator, Parseable};
+use netlink_packet_route::tc::{
+    TcAction, TcActionNatOption, TcActionOption, TcActionType, TcNat,
+    TcNatFlags,
+};
+
+use super::{new_action, tc_gen};
+
+/// Stateless translation of IPv4 addresses.
+/// Equivalent to `action nat DIRECTION OLD NEW`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionNat {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    /// Translate the source address instead of the destination one.
+    pub egress: bool,
+    pub old_addr: Ipv4Addr,
+    pub prefix_len: u8,
+    pub new_addr: Ipv4Addr,
+}
+
+impl TrafficActionNat {
+    pub const KIND: &'static str = "nat";
+
+    /// Translate the destination addresses within `old_addr/prefix_len`.
+    /// Equivalent to `action nat ingress OLD/PREFIX_LEN NEW`.
+    pub fn ingress(
+        old_addr: Ipv4Addr,
+        prefix_len: u8,
+        new_addr: Ipv4Addr,
+    ) -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Ok,
+            egress: false,
+            old_addr,
+            prefix_len,
+            new_addr,
+        }
+    }
+
+    /// Translate the source addresses within `old_addr/prefix_len`.
+    /// Equivalent to `action nat egress OLD/PREFIX_LEN NEW`.
+    pub fn egress(
+        old_addr: Ipv4Addr,
+        prefix_len: u8,
+        new_addr: Ipv4Addr,
+    ) -> Self {
+        Self {
+            egress: true,
+            ..Self::ingress(old_addr, prefix_len, new_addr)
+        }
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `pass` by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut nat =
+            Self::ingress(Ipv4Addr::UNSPECIFIED, 0, Ipv4Addr::UNSPECIFIED);
+        for nla in NlasIterator::new(payload) {
+            if let TcActionNatOption::Parms(parms) =
+                TcActionNatOption::parse(&nla?)?
+            {
+                nat.index = parms.generic.index;
+                nat.control = parms.generic.action;
+                nat.egress = parms.flags.contains(TcNatFlags::Egress);
+                nat.old_addr = parms.old_addr;
+                nat.prefix_len = u32::from(parms.mask).leading_ones() as u8;
+                nat.new_addr = parms.new_addr;
+            }
+        }
+        Ok(nat)
+    }
+}
+
+impl From<TrafficActionNat> for TcAction {
+    fn from(nat: TrafficActionNat) -> Self {
+        let mut parms = TcNat::default();
+        parms.generic = tc_gen(nat.index, nat.control);
+        parms.old_addr = nat.old_addr;
+        parms.new_addr = nat.new_addr;
+        parms.mask = u32::MAX
+            .checked_shl(32 - u32::from(nat.prefix_len.min(32)))
+            .unwrap_or(0)
+            .into();
+        parms.flags = if nat.egress {
+            TcNatFlags::Egress
+        } else {
+            TcNatFlags::empty()
+        };
+        new_action(
+            TrafficActionNat::KIND,
+            vec![TcActionOption::Nat(TcActionNatOption::Parms(parms))],
+        )
+    }
+}
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcActionGeneric, TcActionType};
+
+use super::{emit_tc_gen, new_raw_action, TC_GEN_LEN};
+use crate::traffic_control::nla::{
+    nla_nested, nla_u16, parse_nlas, read_u16, read_u32,
+};
+
+const TCA_PEDIT_PARMS: u16 = 2;
+const TCA_PEDIT_PARMS_EX: u16 = 4;
+const TCA_PEDIT_KEYS_EX: u16 = 5;
+const TCA_PEDIT_KEY_EX: u16 = 6;
+const TCA_PEDIT_KEY_EX_HTYPE: u16 = 1;
+const TCA_PEDIT_KEY_EX_CMD: u16 = 2;
+
+const TCA_PEDIT_KEY_EX_HDR_TYPE_NETWORK: u16 = 0;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_ETH: u16 = 1;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_IP4: u16 = 2;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_IP6: u16 = 3;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_TCP: u16 = 4;
+const TCA_PEDIT_KEY_EX_HDR_TYPE_UDP: u16 = 5;
+
+const TCA_PEDIT_KEY_EX_CMD_SET: u16 = 0;
+const TCA_PEDIT_KEY_EX_CMD_ADD: u16 = 1;
+
+// Size of `struct tc_pedit_key`.
+const PEDIT_KEY_LEN: usize = 24;
+// Size of `struct tc_pedit_sel` without its keys.
+const PEDIT_SEL_LEN: usize = TC_GEN_LEN + 4;
+
+/// Header the offset of a [PeditKey] is relative to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum PeditHeader {
+    /// Start of the network header, the only header of legacy keys.
+    #[default]
+    Network,
+    Eth,
+    Ip4,
+    Ip6,
+    Tcp,
+    Udp,
+    Other(u16),
+}
+
+impl From<u16> for PeditHeader {
+    fn from(d: u16) -> Self {
+        match d {
+            TCA_PEDIT_KEY_EX_HDR_TYPE_NETWORK => Self::Network,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_ETH => Self::Eth,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_IP4 => Self::Ip4,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_IP6 => Self::Ip6,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_TCP => Self::Tcp,
+            TCA_PEDIT_KEY_EX_HDR_TYPE_UDP => Self::Udp,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<PeditHeader> for u16 {
+    fn from(v: PeditHeader) -> u16 {
+        match v {
+            PeditHeader::Network => TCA_PEDIT_KEY_EX_HDR_TYPE_NETWORK,
+            PeditHeader::Eth => TCA_PEDIT_KEY_EX_HDR_TYPE_ETH,
+            PeditHeader::Ip4 => TCA_PEDIT_KEY_EX_HDR_TYPE_IP4,
+            PeditHeader::Ip6 => TCA_PEDIT_KEY_EX_HDR_TYPE_IP6,
+            PeditHeader::Tcp => TCA_PEDIT_KEY_EX_HDR_TYPE_TCP,
+            PeditHeader::Udp => TCA_PEDIT_KEY_EX_HDR_TYPE_UDP,
+            PeditHeader::Other(d) => d,
+        }
+    }
+}
+
+/// Operation of a [PeditKey].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum PeditCmd {
+    #[default]
+    Set,
+    Add,
+    Other(u16),
+}
+
+impl From<u16> for PeditCmd {
+    fn from(d: u16) -> Self {
+        match d {
+            TCA_PEDIT_KEY_EX_CMD_SET => Self::Set,
+            TCA_PEDIT_KEY_EX_CMD_ADD => Self::Add,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<PeditCmd> for u16 {
+    fn from(v: PeditCmd) -> u16 {
+        match v {
+            PeditCmd::Set => TCA_PEDIT_KEY_EX_CMD_SET,
+            PeditCmd::Add => TCA_PEDIT_KEY_EX_CMD_ADD,
+            PeditCmd::Other(d) => d,
+        }
+    }
+}
+
+/// Edit of the 32 bits word at `offset` of `header`.
+///
+/// `value` and `mask` are the word in network byte order: the bits set in
+/// `mask` are kept and the other ones are set to, or added with, the ones
+/// of `value`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct PeditKey {
+    pub header: PeditHeader,
+    pub cmd: PeditCmd,
+    /// Offset of the word, a multiple of 4.
+    pub offset: i32,
+    pub value: u32,
+    pub mask: u32,
+}
+
+impl PeditKey {
+    /// Set the 32 bits at `offset`, which must be a multiple of 4.
+    /// Equivalent to `munge offset OFFSET u32 set VALUE`.
+    pub fn u32(header: PeditHeader, offset: i32, value: u32) -> Self {
+        Self::sub_word(header, offset, value, 4)
+    }
+
+    /// Set the 16 bits at `offset`, which must be a multiple of 2.
+    /// Equivalent to `munge offset OFFSET u16 set VALUE`.
+    pub fn u16(header: PeditHeader, offset: i32, value: u16) -> Self {
+        Self::sub_word(header, offset, value.into(), 2)
+    }
+
+    /// Set the byte at `offset`.
+    /// Equivalent to `munge offset OFFSET u8 set VALUE`.
+    pub fn u8(header: PeditHeader, offset: i32, value: u8) -> Self {
+        Self::sub_word(header, offset, value.into(), 1)
+    }
+
+    fn sub_word(
+        header: PeditHeader,
+        offset: i32,
+        value: u32,
+        size: i32,
+    ) -> Self {
+        let shift = 8 * (4 - size - (offset & 3 & !(size - 1)));
+        let bits = u32::MAX >> (32 - 8 * size);
+        Self {
+            header,
+            cmd: PeditCmd::Set,
+            offset: offset & !3,
+            value: value << shift,
+            mask: !(bits << shift),
+        }
+    }
+
+    /// Add the value to the field instead of replacing it, which requires
+    /// a header other than [PeditHeader::Network] on old kernels.
+    /// Equivalent to `add VALUE`.
+    pub fn add(mut self) -> Self {
+        self.cmd = PeditCmd::Add;
+        self
+    }
+
+    fn is_legacy(&self) -> bool {
+        self.header == PeditHeader::Network && self.cmd == PeditCmd::Set
+    }
+
+    fn emit(&self, buf: &mut Vec<u8>) {
+        buf.extend(self.mask.to_be_bytes());
+        buf.extend(self.value.to_be_bytes());
+        buf.extend(self.offset.to_ne_bytes());
+        // at, offmask and shift, for offsets read from the packet.
+        buf.extend([0u8; 12]);
+    }
+}
+
+/// Edit arbitrary fields of the packets.
+/// Equivalent to `action pedit [ex] munge ...`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TrafficActionPedit {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    pub keys: Vec<PeditKey>,
+}
+
+impl Default for TrafficActionPedit {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Ok,
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl TrafficActionPedit {
+    pub const KIND: &'static str = "pedit";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a key, the keys are applied in order.
+    pub fn key(mut self, key: PeditKey) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `pass` by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut pedit = Self::default();
+        let mut keys_ex = Vec::new();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_PEDIT_PARMS | TCA_PEDIT_PARMS_EX => {
+                    let generic = TcActionGeneric::parse(value)?;
+                    pedit.index = generic.index;
+                    pedit.control = generic.action;
+                    let nkeys = *value.get(TC_GEN_LEN).ok_or_else(|| {
+                        DecodeError::buffer_too_small(
+                            value.len(),
+                            PEDIT_SEL_LEN,
+                        )
+                    })?;
+                    pedit.keys = (0..usize::from(nkeys))
+                        .map(|i| {
+                            let off = PEDIT_SEL_LEN + i * PEDIT_KEY_LEN;
+                            Ok(PeditKey {
+                                header: PeditHeader::Network,
+                                cmd: PeditCmd::Set,
+                                mask: u32::from_be(read_u32(value, off)?),
+                                value: u32::from_be(read_u32(value, off + 4)?),
+                                offset: read_u32(value, off + 8)? as i32,
+                            })
+                        })
+                        .collect::<Result<_, DecodeError>>()?;
+                }
+                TCA_PEDIT_KEYS_EX => {
+                    for (_, key_ex) in parse_nlas(value)? {
+                        let mut ex = (PeditHeader::Network, PeditCmd::Set);
+                        for (kind, value) in parse_nlas(key_ex)? {
+                            match kind {
+                                TCA_PEDIT_KEY_EX_HTYPE => {
+                                    ex.0 = read_u16(value, 0)?.into()
+                                }
+                                TCA_PEDIT_KEY_EX_CMD => {
+                                    ex.1 = read_u16(value, 0)?.into()
+                                }
+                                _ => (),
+                            }
+                        }
+                        keys_ex.push(ex);
+                    }
+                }
+                _ => (),
+            }
+        }
+        for (key, (header, cmd)) in pedit.keys.iter_mut().zip(keys_ex) {
+            key.header = header;
+            key.cmd = cmd;
+        }
+        Ok(pedit)
+    }
+}
+
+impl From<TrafficActionPedit> for TcAction {
+    fn from(pedit: TrafficActionPedit) -> Self {
+        let mut parms = emit_tc_gen(pedit.index, pedit.control);
+        // nkeys, flags and padding.
+        parms.extend([pedit.keys.len() as u8, 0, 0, 0]);
+        for key in &pedit.keys {
+            key.emit(&mut parms);
+        }
+        let nlas = if pedit.keys.iter().all(PeditKey::is_legacy) {
+            vec![DefaultNla::new(TCA_PEDIT_PARMS, parms)]
+        } else {
+            let keys_ex: Vec<DefaultNla> = pedit
+                .keys
+                .iter()
+                .map(|key| {
+                    nla_nested(
+                        TCA_PEDIT_KEY_EX,
+                        &[
+                            nla_u16(TCA_PEDIT_KEY_EX_HTYPE, key.header.into()),
+                            nla_u16(TCA_PEDIT_KEY_EX_CMD, key.cmd.into()),
+                        ],
+                    )
+                })
+                .collect();
+            vec![
+                DefaultNla::new(TCA_PEDIT_PARMS_EX, parms),
+                nla_nested(TCA_PEDIT_KEYS_EX, &keys_ex),
+            ]
+        };
+        new_raw_action(TrafficActionPedit::KIND, nlas)
+    }
+}
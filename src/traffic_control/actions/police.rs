@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, parse_u64, DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcActionType};
+
+use super::new_raw_action;
+use crate::traffic_control::{
+    nla::{nla_u32, nla_u64, parse_nlas, read_u32},
+    ratespec::{
+        emit_ratespec, needs_rate64, parse_ratespec, ticks_to_size, xmit_ticks,
+        RATESPEC_LEN,
+    },
+};
+
+const TCA_POLICE_TBF: u16 = 1;
+const TCA_POLICE_RATE: u16 = 2;
+const TCA_POLICE_PEAKRATE: u16 = 3;
+const TCA_POLICE_AVRATE: u16 = 4;
+const TCA_POLICE_RESULT: u16 = 5;
+const TCA_POLICE_RATE64: u16 = 8;
+const TCA_POLICE_PEAKRATE64: u16 = 9;
+
+// Size of `struct tc_police`.
+const TC_POLICE_LEN: usize = 56;
+const TC_POLICE_RATE_OFFSET: usize = 20;
+const TC_POLICE_PEAKRATE_OFFSET: usize = TC_POLICE_RATE_OFFSET + RATESPEC_LEN;
+// Entries of the rate tables the kernel requires.
+const RTAB_LEN: usize = 256;
+// MTU assumed by `tc` to size the rate tables when none is given.
+const DEFAULT_MTU: u32 = 2047;
+
+/// Rate limit the packets with a token bucket.
+/// Equivalent to `action police rate RATE burst BURST`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionPolice {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    /// Rate in bytes per second.
+    pub rate: u64,
+    /// Size of the bucket in bytes.
+    pub burst: u32,
+    /// Largest packet conforming to the rate, 0 for no limit.
+    pub mtu: u32,
+    /// Peak rate in bytes per second, which requires `mtu`.
+    pub peakrate: Option<u64>,
+    /// Rate in bytes per second estimated by the kernel above which the
+    /// packets exceed.
+    pub avrate: Option<u32>,
+    /// Control applied to the packets exceeding the rate.
+    pub exceed: TcActionType,
+    /// Control applied to the packets conforming to the rate, `pass` if
+    /// unset.
+    pub conform: Option<TcActionType>,
+}
+
+impl TrafficActionPolice {
+    pub const KIND: &'static str = "police";
+
+    /// Equivalent to `action police rate RATE burst BURST`, with `rate` in
+    /// bytes per second and `burst` in bytes.
+    pub fn new(rate: u64, burst: u32) -> Self {
+        Self {
+            index: 0,
+            rate,
+            burst,
+            mtu: 0,
+            peakrate: None,
+            avrate: None,
+            exceed: TcActionType::Reclassify,
+            conform: None,
+        }
+    }
+
+    /// Equivalent to `mtu MTU`.
+    pub fn mtu(mut self, mtu: u32) -> Self {
+        self.mtu = mtu;
+        self
+    }
+
+    /// Equivalent to `peakrate PEAKRATE`, in bytes per second.
+    pub fn peakrate(mut self, peakrate: u64) -> Self {
+        self.peakrate = Some(peakrate);
+        self
+    }
+
+    /// Equivalent to `avrate AVRATE`, in bytes per second.
+    pub fn avrate(mut self, avrate: u32) -> Self {
+        self.avrate = Some(avrate);
+        self
+    }
+
+    /// Equivalent to `conform-exceed EXCEED/CONFORM`.
+    pub fn conform_exceed(
+        mut self,
+        exceed: TcActionType,
+        conform: TcActionType,
+    ) -> Self {
+        self.exceed = exceed;
+        self.conform = Some(conform);
+        self
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut police = Self::new(0, 0);
+        let mut burst_ticks = 0;
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_POLICE_TBF => {
+                    if value.len() < TC_POLICE_LEN {
+                        return Err(DecodeError::buffer_too_small(
+                            value.len(),
+                            TC_POLICE_LEN,
+                        ));
+                    }
+                    police.index = read_u32(value, 0)?;
+                    police.exceed = (read_u32(value, 4)? as i32).into();
+                    burst_ticks = read_u32(value, 12)?;
+                    police.mtu = read_u32(value, 16)?;
+                    // The 64 bits attributes, parsed later, take over
+                    // saturated rates.
+                    police.rate =
+                        parse_ratespec(&value[TC_POLICE_RATE_OFFSET..])?;
+                    let peakrate =
+                        parse_ratespec(&value[TC_POLICE_PEAKRATE_OFFSET..])?;
+                    if peakrate != 0 {
+                        police.peakrate = Some(peakrate);
+                    }
+                }
+                TCA_POLICE_AVRATE => police.avrate = Some(parse_u32(value)?),
+                TCA_POLICE_RESULT => {
+                    police.conform = Some((parse_u32(value)? as i32).into())
+                }
+                TCA_POLICE_RATE64 => police.rate = parse_u64(value)?,
+                TCA_POLICE_PEAKRATE64 => {
+                    police.peakrate = Some(parse_u64(value)?)
+                }
+                _ => (),
+            }
+        }
+        police.burst = ticks_to_size(police.rate, burst_ticks);
+        Ok(police)
+    }
+}
+
+// Smallest cell size, as a power of 2, for which the rate table covers
+// `mtu`.
+fn cell_log(mtu: u32) -> u8 {
+    let mut cell_log = 0;
+    while (mtu >> cell_log) > RTAB_LEN as u32 - 1 {
+        cell_log += 1;
+    }
+    cell_log
+}
+
+// Time to send packets of each cell size, required by the kernel even
+// though it only uses the rate of the ratespec nowadays.
+fn rate_table(rate: u64, cell_log: u8) -> Vec<u8> {
+    (0..RTAB_LEN as u32)
+        .flat_map(|i| xmit_ticks(rate, (i + 1) << cell_log).to_ne_bytes())
+        .collect()
+}
+
+impl From<TrafficActionPolice> for TcAction {
+    fn from(police: TrafficActionPolice) -> Self {
+        let cell_log = cell_log(if police.mtu == 0 {
+            DEFAULT_MTU
+        } else {
+            police.mtu
+        });
+        let mut tbf = vec![0u8; TC_POLICE_LEN];
+        tbf[0..4].copy_from_slice(&police.index.to_ne_bytes());
+        tbf[4..8].copy_from_slice(&i32::from(police.exceed).to_ne_bytes());
+        tbf[12..16].copy_from_slice(
+            &xmit_ticks(police.rate, police.burst).to_ne_bytes(),
+        );
+        tbf[16..20].copy_from_slice(&police.mtu.to_ne_bytes());
+        emit_ratespec(&mut tbf[TC_POLICE_RATE_OFFSET..], police.rate);
+        tbf[TC_POLICE_RATE_OFFSET] = cell_log;
+        if let Some(peakrate) = police.peakrate {
+            emit_ratespec(&mut tbf[TC_POLICE_PEAKRATE_OFFSET..], peakrate);
+            tbf[TC_POLICE_PEAKRATE_OFFSET] = cell_log;
+        }
+
+        let mut nlas = vec![
+            DefaultNla::new(TCA_POLICE_TBF, tbf),
+            DefaultNla::new(TCA_POLICE_RATE, rate_table(police.rate, cell_log)),
+        ];
+        if let Some(peakrate) = police.peakrate {
+            nlas.push(DefaultNla::new(
+                TCA_POLICE_PEAKRATE,
+                rate_table(peakrate, cell_log),
+            ));
+        }
+        if let Some(avrate) = police.avrate {
+            nlas.push(nla_u32(TCA_POLICE_AVRATE, avrate));
+        }
+        if let Some(conform) = police.conform {
+            nlas.push(nla_u32(TCA_POLICE_RESULT, i32::from(conform) as u32));
+        }
+        if needs_rate64(police.rate) {
+            nlas.push(nla_u64(TCA_POLICE_RATE64, police.rate));
+        }
+        if let Some(peakrate) = police.peakrate.filter(|r| needs_rate64(*r)) {
+            nlas.push(nla_u64(TCA_POLICE_PEAKRATE64, peakrate));
+        }
+        new_raw_action(TrafficActionPolice::KIND, nlas)
+    }
+}
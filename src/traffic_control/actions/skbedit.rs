@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcActionGeneric, TcActionType};
+
+use super::{emit_tc_gen, new_raw_action};
+use crate::traffic_control::nla::{
+    nla_u16, nla_u32, parse_nlas, read_u16, read_u32,
+};
+
+const TCA_SKBEDIT_PARMS: u16 = 2;
+const TCA_SKBEDIT_PRIORITY: u16 = 3;
+const TCA_SKBEDIT_QUEUE_MAPPING: u16 = 4;
+const TCA_SKBEDIT_MARK: u16 = 5;
+const TCA_SKBEDIT_PTYPE: u16 = 7;
+const TCA_SKBEDIT_MASK: u16 = 8;
+
+/// Edit the metadata of the packets.
+/// Equivalent to `action skbedit`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionSkbedit {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    /// Class id set as the priority of the packets.
+    pub priority: Option<u32>,
+    pub queue_mapping: Option<u16>,
+    /// Firewall mark and the mask of its bits to set.
+    pub mark: Option<(u32, u32)>,
+    /// Packet type, like `PACKET_HOST`.
+    pub ptype: Option<u16>,
+}
+
+impl Default for TrafficActionSkbedit {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Pipe,
+            priority: None,
+            queue_mapping: None,
+            mark: None,
+            ptype: None,
+        }
+    }
+}
+
+impl TrafficActionSkbedit {
+    pub const KIND: &'static str = "skbedit";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `priority PRIORITY`.
+    pub fn priority(mut self, priority: u32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Equivalent to `queue_mapping QUEUE_MAPPING`.
+    pub fn queue_mapping(mut self, queue: u16) -> Self {
+        self.queue_mapping = Some(queue);
+        self
+    }
+
+    /// Equivalent to `mark MARK/MASK`.
+    pub fn mark(mut self, mark: u32, mask: u32) -> Self {
+        self.mark = Some((mark, mask));
+        self
+    }
+
+    /// Equivalent to `ptype PTYPE`.
+    pub fn ptype(mut self, ptype: u16) -> Self {
+        self.ptype = Some(ptype);
+        self
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `pipe` by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut skbedit = Self::default();
+        let mut mask = u32::MAX;
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_SKBEDIT_PARMS => {
+                    let generic = TcActionGeneric::parse(value)?;
+                    skbedit.index = generic.index;
+                    skbedit.control = generic.action;
+                }
+                TCA_SKBEDIT_PRIORITY => {
+                    skbedit.priority = Some(read_u32(value, 0)?)
+                }
+                TCA_SKBEDIT_QUEUE_MAPPING => {
+                    skbedit.queue_mapping = Some(read_u16(value, 0)?)
+                }
+                TCA_SKBEDIT_MARK => {
+                    skbedit.mark = Some((read_u32(value, 0)?, u32::MAX))
+                }
+                TCA_SKBEDIT_MASK => mask = read_u32(value, 0)?,
+                TCA_SKBEDIT_PTYPE => skbedit.ptype = Some(read_u16(value, 0)?),
+                _ => (),
+            }
+        }
+        if let Some((_, m)) = skbedit.mark.as_mut() {
+            *m = mask;
+        }
+        Ok(skbedit)
+    }
+}
+
+impl From<TrafficActionSkbedit> for TcAction {
+    fn from(skbedit: TrafficActionSkbedit) -> Self {
+        let mut nlas = vec![DefaultNla::new(
+            TCA_SKBEDIT_PARMS,
+            emit_tc_gen(skbedit.index, skbedit.control),
+        )];
+        if let Some(queue) = skbedit.queue_mapping {
+            nlas.push(nla_u16(TCA_SKBEDIT_QUEUE_MAPPING, queue));
+        }
+        if let Some(priority) = skbedit.priority {
+            nlas.push(nla_u32(TCA_SKBEDIT_PRIORITY, priority));
+        }
+        if let Some((mark, mask)) = skbedit.mark {
+            nlas.push(nla_u32(TCA_SKBEDIT_MARK, mark));
+            if mask != u32::MAX {
+                nlas.push(nla_u32(TCA_SKBEDIT_MASK, mask));
+            }
+        }
+        if let Some(ptype) = skbedit.ptype {
+            nlas.push(nla_u16(TCA_SKBEDIT_PTYPE, ptype));
+        }
+        new_raw_action(TrafficActionSkbedit::KIND, nlas)
+    }
+}
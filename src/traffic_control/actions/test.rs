@@ -0,0 +1,534 @@
+// SPDX-License-Identifier: MIT
+
+// Expected payloads are the actions sent by iproute2 for the equivalent
+// `tc filter add ... matchall action ...` command.
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use futures_util::stream::TryStreamExt;
+use netlink_packet_core::{Emitable, NlaBuffer, Parseable};
+use netlink_packet_route::tc::{TcAction, TcActionMessage, TcActionType};
+use tokio::runtime::Runtime;
+
+use super::*;
+use crate::{
+    new_connection, test_netns::setup_netns, traffic_control::nla::parse_nlas,
+    Error, Handle, TcActionExt,
+};
+
+const TEST_NS: &str = "netlink_test_action_ns";
+const TEST_MIRRED_NS: &str = "netlink_test_action_mirred_ns";
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn emit(action: impl Into<TcAction>) -> Vec<u8> {
+    let mut action = action.into();
+    action.tab = 1;
+    let mut buffer = vec![0u8; action.buffer_len()];
+    action.emit(&mut buffer);
+    buffer
+}
+
+fn parse(payload: &[u8]) -> TrafficActionOptions {
+    TcAction::parse(&NlaBuffer::new(payload))
+        .unwrap()
+        .action_options()
+        .unwrap()
+        .unwrap()
+}
+
+fn assert_action(
+    action: impl Into<TcAction>,
+    options: TrafficActionOptions,
+    expected: &str,
+) {
+    assert_eq!(emit(action), hex(expected));
+    assert_eq!(parse(&hex(expected)), options);
+}
+
+#[test]
+fn test_gact() {
+    // action drop
+    let gact = TrafficActionGact::drop();
+    assert_action(
+        gact,
+        TrafficActionOptions::Gact(gact),
+        "2c0001000900010067616374000000001c000280180002000000000000000000\
+         020000000000000000000000",
+    );
+
+    // action goto chain 5
+    let gact = TrafficActionGact::goto_chain(5);
+    assert_eq!(gact.chain(), Some(5));
+    assert_action(
+        gact,
+        TrafficActionOptions::Gact(gact),
+        "2c0001000900010067616374000000001c000280180002000000000000000000\
+         050000200000000000000000",
+    );
+
+    // action pass index 7
+    let gact = TrafficActionGact::pass().index(7);
+    assert_eq!(gact.chain(), None);
+    assert_action(
+        gact,
+        TrafficActionOptions::Gact(gact),
+        "2c0001000900010067616374000000001c000280180002000700000000000000\
+         000000000000000000000000",
+    );
+}
+
+#[test]
+fn test_mirred() {
+    // action mirred egress redirect dev v1
+    let mirred = TrafficActionMirred::redirect(2);
+    assert_action(
+        mirred,
+        TrafficActionOptions::Mirred(mirred),
+        "340001000b0001006d697272656400002400028020000200000000000000000004\
+         00000000000000000000000100000002000000",
+    );
+
+    // action mirred ingress mirror dev v1
+    let mirred = TrafficActionMirred::mirror(2).ingress();
+    assert_action(
+        mirred,
+        TrafficActionOptions::Mirred(mirred),
+        "340001000b0001006d697272656400002400028020000200000000000000000003\
+         00000000000000000000000400000002000000",
+    );
+}
+
+#[test]
+fn test_vlan() {
+    // action vlan push id 100 protocol 802.1ad priority 3
+    let vlan = TrafficActionVlan::push(100).protocol(0x88a8).priority(3);
+    assert_action(
+        vlan,
+        TrafficActionOptions::Vlan(vlan),
+        "4800010009000100766c616e00000000380002801c00020000000000000000000300\
+         0000000000000000000002000000060003006400000006000400\
+         88a800000500060003000000",
+    );
+
+    // action vlan modify id 5 pass
+    let vlan = TrafficActionVlan::modify(5).control(TcActionType::Ok);
+    assert_action(
+        vlan,
+        TrafficActionOptions::Vlan(vlan),
+        "3800010009000100766c616e00000000280002801c000200000000000000000000\
+         0000000000000000000000030000000600030005000000",
+    );
+}
+
+#[test]
+fn test_skbedit() {
+    // action skbedit priority 1:10 mark 5/0xff queue_mapping 3
+    let skbedit = TrafficActionSkbedit::new()
+        .priority(0x10010)
+        .mark(5, 0xff)
+        .queue_mapping(3);
+    assert_action(
+        skbedit,
+        TrafficActionOptions::Skbedit(skbedit),
+        "4c0001000c000100736b6265646974003c0002801800020000000000000000000300\
+         0000000000000000000006000400030000000800030010000100080005000500\
+         000008000800ff000000",
+    );
+}
+
+#[test]
+fn test_pedit() {
+    // action pedit munge ip ttl set 10
+    let pedit = TrafficActionPedit::new().key(PeditKey::u8(
+        PeditHeader::Network,
+        8,
+        10,
+    ));
+    assert_action(
+        pedit.clone(),
+        TrafficActionOptions::Pedit(pedit),
+        "480001000a0001007065646974000000380002803400020000000000000000000000\
+         000000000000000000000100000000ffffff0a0000000800000000000000000000\
+         0000000000",
+    );
+
+    // action pedit ex munge ip ttl add 1
+    let pedit = TrafficActionPedit::new()
+        .key(PeditKey::u8(PeditHeader::Ip4, 8, 1).add());
+    assert_action(
+        pedit.clone(),
+        TrafficActionOptions::Pedit(pedit),
+        "600001000a0001007065646974000000500002803400040000000000000000000000\
+         000000000000000000000100000000ffffff0100000008000000000000000000\
+         000000000000180005801400068006000100020000000600020001000000",
+    );
+
+    // munge offset 12 u32 set 0x01020304
+    let key = PeditKey::u32(PeditHeader::Network, 12, 0x01020304);
+    assert_eq!((key.offset, key.value, key.mask), (12, 0x01020304, 0));
+    // munge offset 14 u16 set 0x0102
+    let key = PeditKey::u16(PeditHeader::Network, 14, 0x0102);
+    assert_eq!((key.offset, key.value, key.mask), (12, 0x0102, 0xffff0000));
+}
+
+#[test]
+fn test_tunnel_key() {
+    // action tunnel_key set src_ip 1.1.1.1 dst_ip 2.2.2.2 id 10 \
+    //     dst_port 4789
+    let key = TrafficActionTunnelKey::set()
+        .src_ip(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)))
+        .dst_ip(IpAddr::V4(Ipv4Addr::new(2, 2, 2, 2)))
+        .key_id(10)
+        .dst_port(4789);
+    assert_action(
+        key.clone(),
+        TrafficActionOptions::TunnelKey(key),
+        "5c0001000f00010074756e6e656c5f6b6579000048000280080003000101010108\
+         00040002020202080007000000000a0600090012b5000005000a00000000001c00\
+         0200000000000000000003000000000000000000000001000000",
+    );
+
+    // action tunnel_key unset
+    let key = TrafficActionTunnelKey::unset();
+    assert_action(
+        key.clone(),
+        TrafficActionOptions::TunnelKey(key),
+        "3c0001000f00010074756e6e656c5f6b657900002800028005000a0000000000\
+         1c000200000000000000000003000000000000000000000002000000",
+    );
+}
+
+#[test]
+fn test_csum() {
+    // action csum ip4h tcp udp
+    let csum = TrafficActionCsum::new(
+        TrafficActionCsum::IPV4_HEADER
+            | TrafficActionCsum::TCP
+            | TrafficActionCsum::UDP,
+    );
+    assert_action(
+        csum,
+        TrafficActionOptions::Csum(csum),
+        "30000100090001006373756d00000000200002801c0001000000000000000000\
+         00000000000000000000000019000000",
+    );
+}
+
+#[test]
+fn test_police() {
+    // action police rate 1mbit burst 10k conform-exceed drop/pipe
+    let police = TrafficActionPolice::new(125000, 10240)
+        .conform_exceed(TcActionType::Shot, TcActionType::Pipe);
+    let payload = emit(police);
+    let TrafficActionOptions::Police(parsed) = parse(&payload) else {
+        panic!("not a police action");
+    };
+    assert_eq!(parsed, police);
+
+    let action = TcAction::parse(&NlaBuffer::new(&payload)).unwrap();
+    let options = action_options_payload(&action).unwrap();
+    let nlas = parse_nlas(&options).unwrap();
+    assert_eq!(
+        nlas[0],
+        (
+            1,
+            hex("00000000020000000000000000881300000000000301\
+                 0000ffff000048e80100000000000000000000000000\
+                 000000000000000000000000")
+            .as_slice()
+        )
+    );
+    // The rate table, only checked against the first entry as iproute2
+    // rounds the later ones differently.
+    assert_eq!(nlas[1].0, 2);
+    assert_eq!(nlas[1].1.len(), 1024);
+    assert_eq!(nlas[1].1[..4], 1000u32.to_ne_bytes());
+    assert_eq!(nlas[2], (5, hex("03000000").as_slice()));
+}
+
+#[test]
+fn test_ct() {
+    // action ct commit zone 3 mark 5/0xff
+    let ct = TrafficActionCt::commit().zone(3).mark(5, 0xff);
+    assert_action(
+        ct,
+        TrafficActionOptions::Ct(ct),
+        "4800010007000100637400003c0002800600040003000000080005000500000008\
+         000600ff00000006000300010000001800010000000000000000000300000000\
+         00000000000000",
+    );
+
+    // action ct clear
+    let ct = TrafficActionCt::clear();
+    assert_action(
+        ct,
+        TrafficActionOptions::Ct(ct),
+        "3000010007000100637400002400028006000300040000001800010000000000\
+         00000000030000000000000000000000",
+    );
+
+    // action ct commit nat src addr 10.0.0.1-10.0.0.5 port 1000-2000
+    let ct = TrafficActionCt::commit()
+        .nat_src(
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)),
+        )
+        .nat_port(1000, 2000);
+    assert_action(
+        ct,
+        TrafficActionOptions::Ct(ct),
+        "50000100070001006374000044000280080009000a00000108000a000a00000506\
+         000d0003e8000006000e0007d000000600030019000000180001000000000000\
+         000000030000000000000000000000",
+    );
+}
+
+#[test]
+fn test_nat() {
+    // action nat ingress 10.0.0.0/8 192.168.0.1
+    let nat = TrafficActionNat::ingress(
+        Ipv4Addr::new(10, 0, 0, 0),
+        8,
+        Ipv4Addr::new(192, 168, 0, 1),
+    );
+    assert_action(
+        nat,
+        TrafficActionOptions::Nat(nat),
+        "38000100080001006e6174002c00028028000100000000000000000000000000\
+         00000000000000000a000000c0a80001ff00000000000000",
+    );
+}
+
+#[test]
+fn test_filter_actions() {
+    let gact = TrafficActionGact::drop();
+    let filter = crate::TrafficFilterMatchall::new()
+        .action(TrafficActionSkbedit::new().mark(1, u32::MAX))
+        .action(gact);
+    assert_eq!(filter.actions.len(), 2);
+    assert_eq!(
+        filter.actions[1].action_options().unwrap(),
+        Some(TrafficActionOptions::Gact(gact))
+    );
+}
+
+fn emit_message(message: &TcActionMessage) -> Vec<u8> {
+    let mut buffer = vec![0u8; message.buffer_len()];
+    message.emit(&mut buffer);
+    buffer
+}
+
+#[test]
+fn test_action_requests() {
+    Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        let mut actions = handle.traffic_action();
+
+        // tc actions add action gact drop index 3
+        let mut request =
+            actions.add().action(TrafficActionGact::drop().index(3));
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("00000000300001002c0001000900010067616374000000001c0002801800\
+                 02000300000000000000020000000000000000000000")
+        );
+
+        // tc actions ls action gact
+        let mut request = actions.get("gact");
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("0000000014000100100001000900010067616374000000000c000200010000\
+                 0001000000")
+        );
+
+        // tc actions get action gact index 3
+        let mut request = actions.get("gact").index(3);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("000000001c000100180001000900010067616374000000000800030003000000")
+        );
+
+        // tc actions del action gact index 3
+        let mut request = actions.del("gact", 3);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("000000001c000100180001000900010067616374000000000800030003000000")
+        );
+
+        // tc actions flush action gact
+        let mut request = actions.flush("gact");
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("0000000014000100100001000900010067616374000000000c000200010000\
+                 0001000000")
+        );
+    });
+}
+
+async fn dump_actions(
+    handle: &Handle,
+    kind: &str,
+) -> Vec<TrafficActionOptions> {
+    let actions: Vec<_> = handle
+        .traffic_action()
+        .get(kind)
+        .execute()
+        .try_collect()
+        .await
+        .unwrap();
+    actions
+        .iter()
+        .map(|action| action.action_options().unwrap().unwrap())
+        .collect()
+}
+
+// Add the actions, returning false when the kernel has no support for their
+// kind.
+async fn add_actions(handle: &Handle, actions: &[TcAction]) -> bool {
+    let request = actions
+        .iter()
+        .fold(handle.traffic_action().add(), |request, action| {
+            request.action(action.clone())
+        });
+    match request.execute().await {
+        Err(Error::NetlinkError(e)) if e.raw_code() == -nix::libc::ENOENT => {
+            eprintln!(
+                "skipped: the kernel has no {} action",
+                actions[0].kind().unwrap_or_default()
+            );
+            false
+        }
+        result => {
+            result.unwrap();
+            true
+        }
+    }
+}
+
+async fn test_async_gact_police_actions() {
+    let (handle, _netns) = setup_netns(TEST_NS).await;
+    let drop = TrafficActionGact::drop().index(1);
+    let pass = TrafficActionGact::pass().index(2);
+    let police = TrafficActionPolice::new(125_000, 10 * 1024)
+        .conform_exceed(TcActionType::Shot, TcActionType::Ok)
+        .index(1);
+
+    // tc actions add action gact drop index 1 action gact pass index 2
+    // tc actions add action police rate 1mbit burst 10k drop index 1
+    if !add_actions(&handle, &[drop.into(), pass.into()]).await
+        || !add_actions(&handle, &[police.into()]).await
+    {
+        return;
+    }
+    assert!(handle
+        .traffic_action()
+        .add()
+        .action(TrafficActionGact::pass().index(1))
+        .execute()
+        .await
+        .is_err());
+
+    assert_eq!(
+        dump_actions(&handle, TrafficActionGact::KIND).await,
+        vec![
+            TrafficActionOptions::Gact(drop),
+            TrafficActionOptions::Gact(pass)
+        ]
+    );
+    let actions = dump_actions(&handle, TrafficActionPolice::KIND).await;
+    assert!(matches!(
+        actions[..],
+        [TrafficActionOptions::Police(TrafficActionPolice {
+            index: 1,
+            rate: 125_000,
+            exceed: TcActionType::Shot,
+            ..
+        })]
+    ));
+
+    // tc actions del action gact index 1
+    handle
+        .traffic_action()
+        .del(TrafficActionGact::KIND, 1)
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(
+        dump_actions(&handle, TrafficActionGact::KIND).await,
+        vec![TrafficActionOptions::Gact(pass)]
+    );
+
+    // tc actions flush action gact; tc actions flush action police
+    for kind in [TrafficActionGact::KIND, TrafficActionPolice::KIND] {
+        handle.traffic_action().flush(kind).execute().await.unwrap();
+        assert!(dump_actions(&handle, kind).await.is_empty(), "{kind}");
+    }
+}
+
+#[test]
+fn test_gact_police_actions() {
+    Runtime::new()
+        .unwrap()
+        .block_on(test_async_gact_police_actions());
+}
+
+async fn test_async_mirred_actions() {
+    let (handle, _netns) = setup_netns(TEST_MIRRED_NS).await;
+    let redirect = TrafficActionMirred::redirect(1).index(1);
+    let mirror = TrafficActionMirred::mirror(1).index(2);
+
+    // tc actions add action mirred egress redirect dev lo index 1 \
+    //     action mirred egress mirror dev lo index 2
+    if !add_actions(&handle, &[redirect.into(), mirror.into()]).await {
+        return;
+    }
+    assert_eq!(
+        dump_actions(&handle, TrafficActionMirred::KIND).await,
+        vec![
+            TrafficActionOptions::Mirred(redirect),
+            TrafficActionOptions::Mirred(mirror)
+        ]
+    );
+
+    // tc actions del action mirred index 2
+    handle
+        .traffic_action()
+        .del(TrafficActionMirred::KIND, 2)
+        .execute()
+        .await
+        .unwrap();
+    assert!(handle
+        .traffic_action()
+        .del(TrafficActionMirred::KIND, 2)
+        .execute()
+        .await
+        .is_err());
+    assert_eq!(
+        dump_actions(&handle, TrafficActionMirred::KIND).await,
+        vec![TrafficActionOptions::Mirred(redirect)]
+    );
+
+    // tc actions flush action mirred
+    handle
+        .traffic_action()
+        .flush(TrafficActionMirred::KIND)
+        .execute()
+        .await
+        .unwrap();
+    assert!(dump_actions(&handle, TrafficActionMirred::KIND)
+        .await
+        .is_empty());
+}
+
+#[test]
+fn test_mirred_actions() {
+    Runtime::new()
+        .unwrap()
+        .block_on(test_async_mirred_actions());
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_core::{DecodeError, NlasIterator, Parseable};
+use netlink_packet_route::tc::{
+    TcAction, TcActionOption, TcActionTunnelKey, TcActionTunnelKeyOption,
+    TcActionType, TcTunnelKey,
+};
+
+use super::{new_action, tc_gen};
+
+const TCA_TUNNEL_KEY_ACT_SET: i32 = 1;
+const TCA_TUNNEL_KEY_ACT_RELEASE: i32 = 2;
+
+/// Set or release the tunnel metadata used by the collect metadata tunnel
+/// devices, like `vxlan external`.
+/// Equivalent to `action tunnel_key { set | unset }`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TrafficActionTunnelKey {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    /// Release the metadata instead of setting it.
+    pub unset: bool,
+    pub src_ip: Option<IpAddr>,
+    pub dst_ip: Option<IpAddr>,
+    /// Tunnel key id, like the VNI of VXLAN.
+    pub key_id: Option<u32>,
+    pub dst_port: Option<u16>,
+    pub tos: Option<u8>,
+    pub ttl: Option<u8>,
+    /// Do not compute the UDP checksum of the outer header.
+    pub no_csum: bool,
+}
+
+impl TrafficActionTunnelKey {
+    pub const KIND: &'static str = TcActionTunnelKey::KIND;
+
+    /// Equivalent to `action tunnel_key set`.
+    pub fn set() -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Pipe,
+            unset: false,
+            src_ip: None,
+            dst_ip: None,
+            key_id: None,
+            dst_port: None,
+            tos: None,
+            ttl: None,
+            no_csum: false,
+        }
+    }
+
+    /// Equivalent to `action tunnel_key unset`.
+    pub fn unset() -> Self {
+        Self {
+            unset: true,
+            ..Self::set()
+        }
+    }
+
+    /// Equivalent to `src_ip ADDR`.
+    pub fn src_ip(mut self, addr: IpAddr) -> Self {
+        self.src_ip = Some(addr);
+        self
+    }
+
+    /// Equivalent to `dst_ip ADDR`.
+    pub fn dst_ip(mut self, addr: IpAddr) -> Self {
+        self.dst_ip = Some(addr);
+        self
+    }
+
+    /// Equivalent to `id KEY_ID`.
+    pub fn key_id(mut self, key_id: u32) -> Self {
+        self.key_id = Some(key_id);
+        self
+    }
+
+    /// Equivalent to `dst_port PORT`.
+    pub fn dst_port(mut self, port: u16) -> Self {
+        self.dst_port = Some(port);
+        self
+    }
+
+    /// Equivalent to `tos TOS`.
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.tos = Some(tos);
+        self
+    }
+
+    /// Equivalent to `ttl TTL`.
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Equivalent to `nocsum`.
+    pub fn no_csum(mut self) -> Self {
+        self.no_csum = true;
+        self
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `pipe` by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        use TcActionTunnelKeyOption as O;
+
+        let mut key = Self::set();
+        for nla in NlasIterator::new(payload) {
+            match O::parse(&nla?)? {
+                O::Parms(parms) => {
+                    key.index = parms.generic.index;
+                    key.control = parms.generic.action;
+                    key.unset = parms.t_action == TCA_TUNNEL_KEY_ACT_RELEASE;
+                }
+                O::EncIpv4Src(addr) => key.src_ip = Some(addr.into()),
+                O::EncIpv4Dst(addr) => key.dst_ip = Some(addr.into()),
+                O::EncIpv6Src(addr) => key.src_ip = Some(addr.into()),
+                O::EncIpv6Dst(addr) => key.dst_ip = Some(addr.into()),
+                O::EncKeyId(id) => key.key_id = Some(id),
+                O::EncDstPort(port) => key.dst_port = Some(port),
+                O::EncTos(tos) => key.tos = Some(tos),
+                O::EncTtl(ttl) => key.ttl = Some(ttl),
+                O::NoCsum(no_csum) => key.no_csum = no_csum,
+                _ => (),
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl From<TrafficActionTunnelKey> for TcAction {
+    fn from(key: TrafficActionTunnelKey) -> Self {
+        use TcActionTunnelKeyOption as O;
+
+        let mut opts = Vec::new();
+        match key.src_ip {
+            Some(IpAddr::V4(addr)) => opts.push(O::EncIpv4Src(addr)),
+            Some(IpAddr::V6(addr)) => opts.push(O::EncIpv6Src(addr)),
+            None => (),
+        }
+        match key.dst_ip {
+            Some(IpAddr::V4(addr)) => opts.push(O::EncIpv4Dst(addr)),
+            Some(IpAddr::V6(addr)) => opts.push(O::EncIpv6Dst(addr)),
+            None => (),
+        }
+        if let Some(id) = key.key_id {
+            opts.push(O::EncKeyId(id));
+        }
+        if let Some(port) = key.dst_port {
+            opts.push(O::EncDstPort(port));
+        }
+        if let Some(tos) = key.tos {
+            opts.push(O::EncTos(tos));
+        }
+        if let Some(ttl) = key.ttl {
+            opts.push(O::EncTtl(ttl));
+        }
+        opts.push(O::NoCsum(key.no_csum));
+        let parms = TcTunnelKey {
+            generic: tc_gen(key.index, key.control),
+            t_action: if key.unset {
+                TCA_TUNNEL_KEY_ACT_RELEASE
+            } else {
+                TCA_TUNNEL_KEY_ACT_SET
+            },
+        };
+        opts.push(O::Parms(parms));
+        new_action(
+            TrafficActionTunnelKey::KIND,
+            opts.into_iter().map(TcActionOption::TunnelKey).collect(),
+        )
+    }
+}
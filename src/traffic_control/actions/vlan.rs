@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcActionGeneric, TcActionType};
+
+use super::{emit_tc_gen, new_raw_action, TC_GEN_LEN};
+use crate::traffic_control::nla::{
+    nla_u16, nla_u8, parse_nlas, read_u16, read_u32,
+};
+
+const TCA_VLAN_PARMS: u16 = 2;
+const TCA_VLAN_PUSH_VLAN_ID: u16 = 3;
+const TCA_VLAN_PUSH_VLAN_PROTOCOL: u16 = 4;
+const TCA_VLAN_PUSH_VLAN_PRIORITY: u16 = 6;
+
+const TCA_VLAN_ACT_POP: i32 = 1;
+const TCA_VLAN_ACT_PUSH: i32 = 2;
+const TCA_VLAN_ACT_MODIFY: i32 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum VlanOp {
+    Pop,
+    Push,
+    Modify,
+    Other(i32),
+}
+
+impl From<i32> for VlanOp {
+    fn from(d: i32) -> Self {
+        match d {
+            TCA_VLAN_ACT_POP => Self::Pop,
+            TCA_VLAN_ACT_PUSH => Self::Push,
+            TCA_VLAN_ACT_MODIFY => Self::Modify,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<VlanOp> for i32 {
+    fn from(v: VlanOp) -> i32 {
+        match v {
+            VlanOp::Pop => TCA_VLAN_ACT_POP,
+            VlanOp::Push => TCA_VLAN_ACT_PUSH,
+            VlanOp::Modify => TCA_VLAN_ACT_MODIFY,
+            VlanOp::Other(d) => d,
+        }
+    }
+}
+
+/// Push, pop or modify the VLAN header of the packets.
+/// Equivalent to `action vlan { pop | push | modify }`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TrafficActionVlan {
+    /// Index of the action, 0 to let the kernel pick one.
+    pub index: u32,
+    pub control: TcActionType,
+    pub op: VlanOp,
+    pub id: Option<u16>,
+    /// VLAN protocol, like `ETH_P_8021Q` or `ETH_P_8021AD`, in host byte
+    /// order.
+    pub protocol: Option<u16>,
+    pub priority: Option<u8>,
+}
+
+impl TrafficActionVlan {
+    pub const KIND: &'static str = "vlan";
+
+    fn new(op: VlanOp) -> Self {
+        Self {
+            index: 0,
+            control: TcActionType::Pipe,
+            op,
+            id: None,
+            protocol: None,
+            priority: None,
+        }
+    }
+
+    /// Equivalent to `action vlan pop`.
+    pub fn pop() -> Self {
+        Self::new(VlanOp::Pop)
+    }
+
+    /// Equivalent to `action vlan push id VLANID`.
+    pub fn push(id: u16) -> Self {
+        Self {
+            id: Some(id),
+            ..Self::new(VlanOp::Push)
+        }
+    }
+
+    /// Equivalent to `action vlan modify id VLANID`.
+    pub fn modify(id: u16) -> Self {
+        Self {
+            id: Some(id),
+            ..Self::new(VlanOp::Modify)
+        }
+    }
+
+    /// Equivalent to `protocol PROTOCOL`.
+    pub fn protocol(mut self, protocol: u16) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// Equivalent to `priority PRIORITY`.
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Equivalent to `index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Equivalent to `CONTROL`, `pipe` by default.
+    pub fn control(mut self, control: TcActionType) -> Self {
+        self.control = control;
+        self
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut vlan = Self::pop();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_VLAN_PARMS => {
+                    let generic = TcActionGeneric::parse(value)?;
+                    vlan.index = generic.index;
+                    vlan.control = generic.action;
+                    vlan.op = (read_u32(value, TC_GEN_LEN)? as i32).into();
+                }
+                TCA_VLAN_PUSH_VLAN_ID => vlan.id = Some(read_u16(value, 0)?),
+                TCA_VLAN_PUSH_VLAN_PROTOCOL => {
+                    vlan.protocol = Some(u16::from_be(read_u16(value, 0)?))
+                }
+                TCA_VLAN_PUSH_VLAN_PRIORITY => {
+                    vlan.priority = value.first().copied()
+                }
+                _ => (),
+            }
+        }
+        Ok(vlan)
+    }
+}
+
+impl From<TrafficActionVlan> for TcAction {
+    fn from(vlan: TrafficActionVlan) -> Self {
+        let mut parms = emit_tc_gen(vlan.index, vlan.control);
+        parms.extend(i32::from(vlan.op).to_ne_bytes());
+        let mut nlas = vec![DefaultNla::new(TCA_VLAN_PARMS, parms)];
+        if let Some(id) = vlan.id {
+            nlas.push(nla_u16(TCA_VLAN_PUSH_VLAN_ID, id));
+        }
+        if let Some(protocol) = vlan.protocol {
+            nlas.push(nla_u16(TCA_VLAN_PUSH_VLAN_PROTOCOL, protocol.to_be()));
+        }
+        if let Some(priority) = vlan.priority {
+            nlas.push(nla_u8(TCA_VLAN_PUSH_VLAN_PRIORITY, priority));
+        }
+        new_raw_action(TrafficActionVlan::KIND, nlas)
+    }
+}
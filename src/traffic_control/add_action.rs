@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::StreamExt;
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    tc::{TcAction, TcActionMessage, TcActionMessageAttribute},
+    RouteNetlinkMessage,
+};
+
+use crate::{
+    traffic_control::actions::numbered_actions, try_nl, Error, Handle,
+};
+
+#[derive(Debug, Clone)]
+pub struct TrafficActionNewRequest {
+    handle: Handle,
+    message: TcActionMessage,
+    flags: u16,
+}
+
+impl TrafficActionNewRequest {
+    pub(crate) fn new(handle: Handle, flags: u16) -> Self {
+        Self {
+            handle,
+            message: TcActionMessage::default(),
+            flags: NLM_F_REQUEST | NLM_F_ACK | flags,
+        }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let Self {
+            mut handle,
+            message,
+            flags,
+        } = self;
+
+        if message.attributes.is_empty() {
+            return Err(Error::InvalidNla(
+                "at least one action is required".to_string(),
+            ));
+        }
+        let mut req = NetlinkMessage::from(
+            RouteNetlinkMessage::NewTrafficAction(message),
+        );
+        req.header.flags = flags;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Append an action, like a [crate::TrafficActionPolice]. Its index
+    /// identifies it for the filters sharing it, or is picked by the
+    /// kernel when 0.
+    /// Equivalent to `action KIND ... index INDEX`.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        let action = action.into();
+        match self
+            .message
+            .attributes
+            .iter_mut()
+            .find_map(|attr| match attr {
+                TcActionMessageAttribute::Actions(actions) => Some(actions),
+                _ => None,
+            }) {
+            Some(actions) => {
+                actions.push(action);
+                *actions = numbered_actions(actions);
+            }
+            None => {
+                self.message
+                    .attributes
+                    .push(TcActionMessageAttribute::Actions(numbered_actions(
+                        &[action],
+                    )))
+            }
+        }
+        self
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcActionMessage {
+        &mut self.message
+    }
+}
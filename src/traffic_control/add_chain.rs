@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::StreamExt;
+
+use crate::{
+    packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST},
+    packet_route::{
+        tc::{TcAttribute, TcHandle, TcHeader, TcMessage},
+        RouteNetlinkMessage,
+    },
+    traffic_control::TrafficFilterFlower,
+    try_nl, Error, Handle,
+};
+
+#[derive(Debug, Clone)]
+pub struct TrafficChainNewRequest {
+    handle: Handle,
+    message: TcMessage,
+    flags: u16,
+}
+
+impl TrafficChainNewRequest {
+    pub(crate) fn new(handle: Handle, ifindex: i32, flags: u16) -> Self {
+        Self {
+            handle,
+            message: TcMessage::with_index(ifindex),
+            flags: NLM_F_REQUEST | flags,
+        }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let Self {
+            mut handle,
+            message,
+            flags,
+        } = self;
+
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::NewTrafficChain(message));
+        req.header.flags = NLM_F_ACK | flags;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Set block index, to create the chain in a shared block.
+    /// Equivalent to `block BLOCK_INDEX`.
+    pub fn block(mut self, block_index: u32) -> Self {
+        self.message.header.index = TcHeader::TCM_IFINDEX_MAGIC_BLOCK as i32;
+        self.message.header.parent = block_index.into();
+        self
+    }
+
+    /// Set parent.
+    /// Equivalent to `[ root | ingress | egress | parent CLASSID ]`
+    /// command args. They are mutually exclusive.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.message.header.parent = parent.into();
+        self
+    }
+
+    /// Set parent to root.
+    pub fn root(mut self) -> Self {
+        self.message.header.parent = TcHandle::ROOT;
+        self
+    }
+
+    /// Set parent to ingress.
+    pub fn ingress(mut self) -> Self {
+        self.message.header.parent = TcHandle {
+            major: 0xffff,
+            minor: TcHandle::MIN_INGRESS,
+        };
+        self
+    }
+
+    /// Set parent to egress.
+    pub fn egress(mut self) -> Self {
+        self.message.header.parent = TcHandle {
+            major: 0xffff,
+            minor: TcHandle::MIN_EGRESS,
+        };
+        self
+    }
+
+    /// Set chain index, the chain 0 otherwise.
+    /// Equivalent to `chain CHAIN_INDEX`.
+    pub fn chain(mut self, chain: u32) -> Self {
+        self.message
+            .attributes
+            .retain(|attr| !matches!(attr, TcAttribute::Chain(_)));
+        self.message.attributes.push(TcAttribute::Chain(chain));
+        self
+    }
+
+    /// Set the protocol of the template, in network byte order like
+    /// [crate::TrafficFilterNewRequest::protocol].
+    /// Equivalent to `protocol PROT`.
+    pub fn protocol(mut self, protocol: u16) -> Self {
+        self.message.header.info = u32::from(protocol);
+        self
+    }
+
+    /// Restrict the filters of the chain to the flower keys set in
+    /// `template`, the values of the keys are ignored. Filters with other
+    /// keys or another kind are rejected by the kernel.
+    /// When no protocol was set, it is taken from
+    /// [TrafficFilterFlower::eth_type].
+    /// Equivalent to `tc chain add ... flower TEMPLATE`.
+    pub fn flower(
+        mut self,
+        template: TrafficFilterFlower,
+    ) -> Result<Self, Error> {
+        if self
+            .message
+            .attributes
+            .iter()
+            .any(|attr| matches!(attr, TcAttribute::Kind(_)))
+        {
+            return Err(Error::InvalidNla(
+                "chain template has already been set.".to_string(),
+            ));
+        }
+        template.validate()?;
+        if let (0, Some(eth_type)) =
+            (self.message.header.info, template.eth_type)
+        {
+            self = self.protocol(eth_type.to_be());
+        }
+        self.message
+            .attributes
+            .push(TcAttribute::Kind(TrafficFilterFlower::KIND.to_string()));
+        self.message.attributes.push(template.options());
+        Ok(self)
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
+}
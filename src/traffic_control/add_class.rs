@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::StreamExt;
+
+use crate::{
+    packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST},
+    packet_route::{
+        tc::{TcHandle, TcMessage},
+        RouteNetlinkMessage,
+    },
+    traffic_control::{
+        nla::set_kind_options, TcClassId, TrafficClassDrr, TrafficClassEts,
+        TrafficClassHfsc, TrafficClassHtb,
+    },
+    try_nl, Error, Handle,
+};
+
+#[derive(Debug, Clone)]
+pub struct TrafficClassNewRequest {
+    handle: Handle,
+    message: TcMessage,
+    flags: u16,
+}
+
+impl TrafficClassNewRequest {
+    pub(crate) fn new(handle: Handle, message: TcMessage, flags: u16) -> Self {
+        Self {
+            handle,
+            message,
+            flags: NLM_F_REQUEST | flags,
+        }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let Self {
+            mut handle,
+            message,
+            flags,
+        } = self;
+
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::NewTrafficClass(message));
+        req.header.flags = NLM_F_ACK | flags;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Set class id.
+    /// Equivalent to `classid MAJOR:MINOR`.
+    pub fn classid(mut self, major: u16, minor: u16) -> Self {
+        self.message.header.handle = TcHandle { major, minor };
+        self
+    }
+
+    /// Set parent to root.
+    pub fn root(mut self) -> Self {
+        self.message.header.parent = TcHandle::ROOT;
+        self
+    }
+
+    /// Set parent, either a qdisc handle or a class id.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.message.header.parent = parent.into();
+        self
+    }
+
+    /// Set class id in the `tc` notation, see [TcClassId].
+    /// Equivalent to `classid CLASSID`.
+    pub fn classid_str(mut self, classid: &str) -> Result<Self, Error> {
+        self.message.header.handle = classid.parse::<TcClassId>()?.into();
+        Ok(self)
+    }
+
+    /// Set parent in the `tc` notation, see [TcClassId].
+    /// Equivalent to `[ root | parent CLASSID ]`.
+    pub fn parent_str(mut self, parent: &str) -> Result<Self, Error> {
+        self.message.header.parent = parent.parse::<TcClassId>()?.into();
+        Ok(self)
+    }
+
+    /// New a htb class.
+    /// Equivalent to `tc class add ... htb`.
+    pub fn htb(mut self, options: TrafficClassHtb) -> Self {
+        set_kind_options(
+            &mut self.message,
+            TrafficClassHtb::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a hfsc class.
+    /// Equivalent to `tc class add ... hfsc`.
+    pub fn hfsc(mut self, options: TrafficClassHfsc) -> Self {
+        set_kind_options(
+            &mut self.message,
+            TrafficClassHfsc::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a drr class.
+    /// Equivalent to `tc class add ... drr`.
+    pub fn drr(mut self, options: TrafficClassDrr) -> Self {
+        set_kind_options(
+            &mut self.message,
+            TrafficClassDrr::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a ets class. Classes of the `ets` qdisc are created along with
+    /// the qdisc, so this is only useful to change them.
+    /// Equivalent to `tc class change ... ets`.
+    pub fn ets(mut self, options: TrafficClassEts) -> Self {
+        set_kind_options(
+            &mut self.message,
+            TrafficClassEts::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::stream::TryStreamExt;
+    use tokio::runtime::Runtime;
+
+    use crate::{
+        test_netns::setup_veth, Handle, QDiscHtb, TcMessageExt,
+        TrafficClassHtb, TrafficClassOptions,
+    };
+
+    const TEST_NS: &str = "netlink_test_class_ns";
+
+    async fn htb_class(
+        handle: &Handle,
+        index: i32,
+        minor: u16,
+    ) -> Option<TrafficClassHtb> {
+        let mut classes = handle.traffic_class(index).get().execute();
+        while let Some(msg) = classes.try_next().await.unwrap() {
+            if msg.header.handle.minor == minor {
+                match msg.class_options().unwrap() {
+                    Some(TrafficClassOptions::Htb(htb)) => return Some(htb),
+                    other => panic!("unexpected class options {other:?}"),
+                }
+            }
+        }
+        None
+    }
+
+    async fn test_async_new_class() {
+        let (handle, link, _, _netns) = setup_veth(TEST_NS).await;
+        let index = link.header.index as i32;
+
+        handle
+            .qdisc()
+            .add(index)
+            .root()
+            .handle(1, 0)
+            .htb(QDiscHtb::new())
+            .execute()
+            .await
+            .unwrap();
+
+        let mut classes = handle.traffic_class(index);
+        classes
+            .add()
+            .parent(0x10000)
+            .classid(1, 1)
+            .htb(TrafficClassHtb::new(125_000).ceil(250_000).prio(1))
+            .execute()
+            .await
+            .unwrap();
+        let htb = htb_class(&handle, index, 1).await.unwrap();
+        assert_eq!(htb.rate, 125_000);
+        assert_eq!(htb.ceil, 250_000);
+        assert_eq!(htb.prio, 1);
+
+        // add refuses to overwrite an existing class
+        assert!(classes
+            .add()
+            .parent(0x10000)
+            .classid(1, 1)
+            .htb(TrafficClassHtb::new(125_000))
+            .execute()
+            .await
+            .is_err());
+
+        classes
+            .change()
+            .parent(0x10000)
+            .classid(1, 1)
+            .htb(TrafficClassHtb::new(62_500))
+            .execute()
+            .await
+            .unwrap();
+        let htb = htb_class(&handle, index, 1).await.unwrap();
+        assert_eq!(htb.rate, 62_500);
+        assert_eq!(htb.ceil, 62_500);
+
+        classes
+            .del()
+            .parent(0x10000)
+            .classid(1, 1)
+            .execute()
+            .await
+            .unwrap();
+        assert!(htb_class(&handle, index, 1).await.is_none());
+    }
+
+    #[test]
+    fn test_new_class() {
+        Runtime::new().unwrap().block_on(test_async_new_class());
+    }
+}
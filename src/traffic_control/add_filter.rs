@@ -1,27 +1,38 @@
 // SPDX-License-Identifier: MIT
 
+use std::{
+    os::fd::{AsRawFd, OwnedFd},
+    sync::Arc,
+};
+
 use futures_util::stream::StreamExt;
 
 use crate::{
     packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST},
     packet_route::{
         tc::{
-            TcAction, TcActionAttribute, TcActionGeneric, TcActionMirror,
-            TcActionMirrorOption, TcActionOption, TcActionType, TcAttribute,
-            TcFilterU32, TcFilterU32Option, TcHandle, TcHeader, TcMessage,
-            TcMirror, TcMirrorActionType, TcOption, TcU32Key, TcU32Selector,
-            TcU32SelectorFlags,
+            TcAttribute, TcFilterU32, TcFilterU32Option, TcHandle, TcHeader,
+            TcMessage, TcOption, TcU32Key, TcU32Selector, TcU32SelectorFlags,
         },
         RouteNetlinkMessage,
     },
+    traffic_control::{
+        nla::set_kind_options, TrafficActionMirred, TrafficFilterBasic,
+        TrafficFilterBpf, TrafficFilterFlower, TrafficFilterFw,
+        TrafficFilterMatchall, TrafficFilterRoute, TrafficFilterU32,
+    },
     try_nl, Error, Handle,
 };
 
+const ETH_P_ALL: u16 = 0x0003;
+
 #[derive(Debug, Clone)]
 pub struct TrafficFilterNewRequest {
     handle: Handle,
     message: TcMessage,
     flags: u16,
+    // Pinned BPF program opened by `bpf()`.
+    program: Option<Arc<OwnedFd>>,
 }
 
 impl TrafficFilterNewRequest {
@@ -30,6 +41,7 @@ impl TrafficFilterNewRequest {
             handle,
             message: TcMessage::with_index(ifindex),
             flags: NLM_F_REQUEST | flags,
+            program: None,
         }
     }
 
@@ -39,6 +51,7 @@ impl TrafficFilterNewRequest {
             mut handle,
             message,
             flags,
+            program: _program,
         } = self;
 
         let mut req = NetlinkMessage::from(
@@ -105,7 +118,7 @@ impl TrafficFilterNewRequest {
     pub fn priority(mut self, priority: u16) -> Self {
         self.message.header.info = u32::from(TcHandle {
             major: priority,
-            minor: priority,
+            minor: self.message.header.info as u16,
         });
         self
     }
@@ -121,9 +134,174 @@ impl TrafficFilterNewRequest {
         self
     }
 
+    /// Set chain.
+    /// Equivalent to `chain CHAIN_INDEX`.
+    pub fn chain(mut self, chain: u32) -> Self {
+        self.message
+            .attributes
+            .retain(|attr| !matches!(attr, TcAttribute::Chain(_)));
+        self.message.attributes.push(TcAttribute::Chain(chain));
+        self
+    }
+
+    /// Set the filter handle.
+    /// Equivalent to `handle FILTERID`, the meaning of the handle depends on
+    /// the classifier kind.
+    pub fn handle(mut self, handle: u32) -> Self {
+        self.message.header.handle = handle.into();
+        self
+    }
+
     /// The 32bit filter allows to match arbitrary bitfields in the packet.
     /// Equivalent to `tc filter ... u32`.
     pub fn u32(mut self, options: &[TcFilterU32Option]) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        self.message
+            .attributes
+            .push(TcAttribute::Kind(TcFilterU32::KIND.to_string()));
+        let mut nla_opts = Vec::new();
+        for opt in options {
+            nla_opts.push(TcOption::U32(opt.clone()));
+        }
+        self.message.attributes.push(TcAttribute::Options(nla_opts));
+        Ok(self)
+    }
+
+    /// The u32 classifier matches the packets with keys compiled from
+    /// [crate::U32Match].
+    /// Equivalent to `tc filter ... u32 match ...`.
+    /// When no protocol was set, it defaults to `ip` or `ipv6` for the IP
+    /// matches, and to `all` otherwise.
+    pub fn u32_match(
+        mut self,
+        options: TrafficFilterU32,
+    ) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        let attribute = options.options()?;
+        self = self.default_protocol(options.protocol().unwrap_or(ETH_P_ALL));
+        set_kind_options(
+            &mut self.message,
+            TrafficFilterU32::KIND,
+            Some(attribute),
+        );
+        Ok(self)
+    }
+
+    /// The flower classifier matches on the dissected packet headers.
+    /// Equivalent to `tc filter ... flower`.
+    /// When no protocol was set, it is taken from
+    /// [TrafficFilterFlower::eth_type].
+    pub fn flower(
+        mut self,
+        options: TrafficFilterFlower,
+    ) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        options.validate()?;
+        if let Some(eth_type) = options.eth_type {
+            self = self.default_protocol(eth_type);
+        }
+        set_kind_options(
+            &mut self.message,
+            TrafficFilterFlower::KIND,
+            Some(options.options()),
+        );
+        Ok(self)
+    }
+
+    /// The matchall classifier matches every packet.
+    /// Equivalent to `tc filter ... matchall`.
+    /// When no protocol was set, it defaults to `all` like `tc` does.
+    pub fn matchall(
+        mut self,
+        options: TrafficFilterMatchall,
+    ) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        self = self.default_protocol(ETH_P_ALL);
+        set_kind_options(
+            &mut self.message,
+            TrafficFilterMatchall::KIND,
+            Some(options.options()),
+        );
+        Ok(self)
+    }
+
+    /// The fw classifier matches the firewall mark of the packets against
+    /// the filter handle, set it with [Self::handle].
+    /// Equivalent to `tc filter ... handle FWMARK fw`.
+    /// When no protocol was set, it defaults to `all` like `tc` does.
+    pub fn fw(mut self, options: TrafficFilterFw) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        self = self.default_protocol(ETH_P_ALL);
+        set_kind_options(
+            &mut self.message,
+            TrafficFilterFw::KIND,
+            Some(options.options()),
+        );
+        Ok(self)
+    }
+
+    /// The route classifier matches the realms of the route of the
+    /// packets.
+    /// Equivalent to `tc filter ... route`.
+    /// When no protocol was set, it defaults to `all` like `tc` does.
+    pub fn route(mut self, options: TrafficFilterRoute) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        self = self.default_protocol(ETH_P_ALL);
+        set_kind_options(
+            &mut self.message,
+            TrafficFilterRoute::KIND,
+            Some(options.options()),
+        );
+        Ok(self)
+    }
+
+    /// The basic classifier matches the packets with an extended match
+    /// expression.
+    /// Equivalent to `tc filter ... basic`.
+    /// When no protocol was set, it defaults to `all` like `tc` does.
+    pub fn basic(mut self, options: TrafficFilterBasic) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        self = self.default_protocol(ETH_P_ALL);
+        set_kind_options(
+            &mut self.message,
+            TrafficFilterBasic::KIND,
+            Some(options.options()),
+        );
+        Ok(self)
+    }
+
+    /// The bpf classifier runs an eBPF program on the packets, attached by
+    /// file descriptor or by pinned path.
+    /// Equivalent to `tc filter ... bpf`.
+    /// When no protocol was set, it defaults to `all` like `tc` does.
+    pub fn bpf(mut self, options: TrafficFilterBpf) -> Result<Self, Error> {
+        self.ensure_no_kind()?;
+        let program = options.open_pinned()?;
+        let fd = match &program {
+            Some(program) => program.as_raw_fd(),
+            None => options.fd.unwrap_or_default(),
+        };
+        self = self.default_protocol(ETH_P_ALL);
+        set_kind_options(
+            &mut self.message,
+            TrafficFilterBpf::KIND,
+            Some(options.options(fd)),
+        );
+        self.program = program.map(Arc::new);
+        Ok(self)
+    }
+
+    // Like `tc`, a filter without protocol matches all of them, the kernel
+    // never matches the protocol 0.
+    fn default_protocol(self, protocol: u16) -> Self {
+        if self.message.header.info as u16 == 0 {
+            self.protocol(protocol.to_be())
+        } else {
+            self
+        }
+    }
+
+    fn ensure_no_kind(&self) -> Result<(), Error> {
         if self
             .message
             .attributes
@@ -134,15 +312,7 @@ impl TrafficFilterNewRequest {
                 "message kind has already been set.".to_string(),
             ));
         }
-        self.message
-            .attributes
-            .push(TcAttribute::Kind(TcFilterU32::KIND.to_string()));
-        let mut nla_opts = Vec::new();
-        for opt in options {
-            nla_opts.push(TcOption::U32(opt.clone()));
-        }
-        self.message.attributes.push(TcAttribute::Options(nla_opts));
-        Ok(self)
+        Ok(())
     }
 
     /// Use u32 to implement traffic redirect.
@@ -155,123 +325,52 @@ impl TrafficFilterNewRequest {
         sel_na.flags = TcU32SelectorFlags::Terminal;
         sel_na.nkeys = 1;
         sel_na.keys = vec![TcU32Key::default()];
-        let mut tc_mirror_nla = TcMirror::default();
-        tc_mirror_nla.generic = TcActionGeneric::default();
-        tc_mirror_nla.generic.action = TcActionType::Stolen;
-        tc_mirror_nla.eaction = TcMirrorActionType::EgressRedir;
-        tc_mirror_nla.ifindex = dst_index;
-        let mut action_nla = TcAction::default();
-        action_nla.attributes = vec![
-            TcActionAttribute::Kind(TcActionMirror::KIND.to_string()),
-            TcActionAttribute::Options(vec![TcActionOption::Mirror(
-                TcActionMirrorOption::Parms(tc_mirror_nla),
-            )]),
-        ];
         let u32_nla = vec![
             TcFilterU32Option::Selector(sel_na),
-            TcFilterU32Option::Action(vec![action_nla]),
+            TcFilterU32Option::Action(vec![TrafficActionMirred::redirect(
+                dst_index,
+            )
+            .into()]),
         ];
         self.u32(&u32_nla)
     }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{fs::File, os::fd::AsFd, path::Path};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
 
     use futures_util::stream::TryStreamExt;
-    use nix::sched::{setns, CloneFlags};
+    use nix::libc;
     use tokio::runtime::Runtime;
 
+    use super::ETH_P_ALL;
     use crate::{
-        new_connection,
-        packet_route::{
-            link::LinkMessage,
-            tc::{
-                TcAttribute, TcFilterU32, TcFilterU32Option, TcOption,
-                TcU32Key, TcU32SelectorFlags,
-            },
+        packet_route::tc::{
+            TcAttribute, TcFilterU32, TcFilterU32Option, TcOption, TcU32Key,
+            TcU32SelectorFlags,
         },
-        Handle, LinkVeth, NetworkNamespace, NETNS_PATH, SELF_NS_PATH,
+        test_netns::setup_veth,
+        Error, Handle, TcMessageExt, TrafficBlock, TrafficFilterBasic,
+        TrafficFilterBpf, TrafficFilterFw, TrafficFilterMatchall,
+        TrafficFilterOptions, TrafficFilterRoute,
     };
 
     const TEST_NS: &str = "netlink_test_filter_ns";
-    const TEST_VETH_1: &str = "test_veth_1";
-    const TEST_VETH_2: &str = "test_veth_2";
-
-    struct Netns {
-        path: String,
-        _cur: File,
-        last: File,
-    }
-
-    impl Netns {
-        async fn new(path: &str) -> Self {
-            // record current ns
-            let last = File::open(Path::new(SELF_NS_PATH)).unwrap();
-
-            // create new ns
-            NetworkNamespace::add(path.to_string()).await.unwrap();
-
-            // entry new ns
-            let ns_path = Path::new(NETNS_PATH);
-            let file = File::open(ns_path.join(path)).unwrap();
-            setns(file.as_fd(), CloneFlags::CLONE_NEWNET).unwrap();
-
-            Self {
-                path: path.to_string(),
-                _cur: file,
-                last,
-            }
-        }
-    }
-    impl Drop for Netns {
-        fn drop(&mut self) {
-            println!("exit ns: {}", self.path);
-            setns(self.last.as_fd(), CloneFlags::CLONE_NEWNET).unwrap();
-
-            let ns_path = Path::new(NETNS_PATH).join(&self.path);
-            nix::mount::umount2(&ns_path, nix::mount::MntFlags::MNT_DETACH)
-                .unwrap();
-            nix::unistd::unlink(&ns_path).unwrap();
-            // _cur File will be closed auto
-            // Since there is no async drop, NetworkNamespace::del cannot be
-            // called here. Dummy interface will be deleted
-            // automatically after netns is deleted.
-        }
-    }
-
-    async fn setup_env() -> (Handle, LinkMessage, LinkMessage, Netns) {
-        let netns = Netns::new(TEST_NS).await;
-
-        // Notice: The Handle can only be created after the setns, so that the
-        // Handle is the connection within the new ns.
-        let (connection, handle, _) = new_connection().unwrap();
-        tokio::spawn(connection);
-        handle
-            .link()
-            .add(LinkVeth::new(TEST_VETH_1, TEST_VETH_2).build())
-            .execute()
-            .await
-            .unwrap();
-
-        let mut links = handle
-            .link()
-            .get()
-            .match_name(TEST_VETH_1.to_string())
-            .execute();
-        let link1 = links.try_next().await.unwrap();
-        links = handle
-            .link()
-            .get()
-            .match_name(TEST_VETH_2.to_string())
-            .execute();
-        let link2 = links.try_next().await.unwrap();
-        (handle, link1.unwrap(), link2.unwrap(), netns)
-    }
+    const TEST_BPF_NS: &str = "netlink_test_filter_bpf_ns";
+    const TEST_DEL_NS: &str = "netlink_test_filter_del_ns";
+    const TEST_CHAIN_NS: &str = "netlink_test_filter_chain_ns";
+    const TEST_BLOCK_NS: &str = "netlink_test_filter_block_ns";
+    const TEST_PROTOCOL_NS: &str = "netlink_test_filter_protocol_ns";
+    const TEST_BPF_FS: &str = "netlink_test_filter_bpffs";
 
     async fn test_async_new_filter() {
-        let (handle, test1, test2, _netns) = setup_env().await;
+        let (handle, test1, test2, _netns) = setup_veth(TEST_NS).await;
         handle
             .qdisc()
             .add(test1.header.index as i32)
@@ -363,4 +462,558 @@ mod test {
     fn test_new_filter() {
         Runtime::new().unwrap().block_on(test_async_new_filter());
     }
+
+    const BPF_PROG_LOAD: libc::c_int = 5;
+    const BPF_OBJ_PIN: libc::c_int = 6;
+    const BPF_PROG_TYPE_SCHED_CLS: u32 = 3;
+
+    fn bpf(cmd: libc::c_int, attr: &mut [u64]) -> i64 {
+        unsafe {
+            libc::syscall(
+                libc::SYS_bpf,
+                cmd,
+                attr.as_mut_ptr(),
+                std::mem::size_of_val(attr),
+            )
+        }
+    }
+
+    // Load `r0 = TC_ACT_OK; exit`.
+    fn load_sched_cls() -> OwnedFd {
+        let insns: [u64; 2] = [0xb7, 0x95];
+        let license = b"GPL\0";
+        let mut attr = [0u64; 16];
+        attr[0] = u64::from(BPF_PROG_TYPE_SCHED_CLS) | (2 << 32);
+        attr[1] = insns.as_ptr() as u64;
+        attr[2] = license.as_ptr() as u64;
+        let fd = bpf(BPF_PROG_LOAD, &mut attr);
+        assert!(fd >= 0, "{}", nix::errno::Errno::last());
+        unsafe { OwnedFd::from_raw_fd(fd as i32) }
+    }
+
+    fn pin(fd: &OwnedFd, path: &str) {
+        let path = std::ffi::CString::new(path).unwrap();
+        let mut attr = [0u64; 2];
+        attr[0] = path.as_ptr() as u64;
+        attr[1] = fd.as_raw_fd() as u64;
+        assert_eq!(bpf(BPF_OBJ_PIN, &mut attr), 0);
+    }
+
+    // A private bpffs mounted in a temporary directory, so that the test
+    // does not depend on `/sys/fs/bpf`.
+    struct BpfFs {
+        path: std::path::PathBuf,
+    }
+
+    impl BpfFs {
+        fn mount() -> Option<Self> {
+            let path = std::env::temp_dir().join(TEST_BPF_FS);
+            std::fs::create_dir_all(&path).unwrap();
+            let bpffs = Self { path };
+            nix::mount::mount(
+                Some("bpf"),
+                &bpffs.path,
+                Some("bpf"),
+                nix::mount::MsFlags::empty(),
+                None::<&str>,
+            )
+            .map_err(|e| eprintln!("skipped: cannot mount bpffs: {e}"))
+            .ok()?;
+            Some(bpffs)
+        }
+
+        fn pin_path(&self) -> String {
+            self.path.join("prog").to_str().unwrap().to_string()
+        }
+    }
+
+    impl Drop for BpfFs {
+        fn drop(&mut self) {
+            let _ = nix::mount::umount2(
+                &self.path,
+                nix::mount::MntFlags::MNT_DETACH,
+            );
+            let _ = std::fs::remove_dir(&self.path);
+        }
+    }
+
+    async fn test_async_new_filter_bpf() {
+        let Some(bpffs) = BpfFs::mount() else {
+            return;
+        };
+        let pin_path = bpffs.pin_path();
+        let (handle, test1, _, _netns) = setup_veth(TEST_BPF_NS).await;
+        let index = test1.header.index as i32;
+        handle.qdisc().add(index).clsact().execute().await.unwrap();
+
+        let program = load_sched_cls();
+        handle
+            .traffic_filter(index)
+            .add()
+            .ingress()
+            .priority(10)
+            .chain(0)
+            .bpf(
+                TrafficFilterBpf::new()
+                    .fd(program.as_raw_fd())
+                    .name("prog")
+                    .direct_action(),
+            )
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        pin(&program, &pin_path);
+        let request = handle
+            .traffic_filter(index)
+            .add()
+            .egress()
+            .bpf(TrafficFilterBpf::new().pinned(&pin_path));
+        std::fs::remove_file(&pin_path).unwrap();
+        request.unwrap().execute().await.unwrap();
+
+        assert!(handle
+            .traffic_filter(index)
+            .add()
+            .egress()
+            .bpf(TrafficFilterBpf::new().pinned(&pin_path))
+            .is_err());
+        assert!(handle
+            .traffic_filter(index)
+            .add()
+            .bpf(TrafficFilterBpf::new())
+            .is_err());
+
+        let mut ids = Vec::new();
+        for (direction, name, direct_action) in
+            [("ingress", Some("prog"), true), ("egress", None, false)]
+        {
+            let request = handle.traffic_filter(index).get();
+            let request = match direction {
+                "ingress" => request.ingress(),
+                _ => request.egress(),
+            };
+            let filters: Vec<_> =
+                request.execute().try_collect().await.unwrap();
+            let bpf = filters
+                .iter()
+                .find_map(|filter| match filter.filter_options() {
+                    // Skip the message of the classifier itself.
+                    Ok(Some(TrafficFilterOptions::Bpf(bpf)))
+                        if bpf.id.is_some() =>
+                    {
+                        Some(bpf)
+                    }
+                    _ => None,
+                })
+                .unwrap();
+            // Both filters match all the protocols, even with a priority.
+            assert!(filters.iter().all(|filter| {
+                filter.header.info as u16 == ETH_P_ALL.to_be()
+            }));
+            assert_eq!(bpf.name.as_deref(), name, "{direction}");
+            assert_eq!(bpf.direct_action, direct_action, "{direction}");
+            assert!(bpf.tag.is_some());
+            ids.extend(bpf.id);
+        }
+        assert_eq!(ids[0], ids[1]);
+    }
+
+    #[test]
+    fn test_new_filter_bpf() {
+        Runtime::new()
+            .unwrap()
+            .block_on(test_async_new_filter_bpf());
+    }
+
+    async fn filter_ids(
+        handle: &Handle,
+        index: i32,
+        egress: bool,
+    ) -> Vec<(u32, u32, u32)> {
+        let request = handle.traffic_filter(index).get();
+        let request = if egress {
+            request.egress()
+        } else {
+            request.ingress()
+        };
+        let filters: Vec<_> = request.execute().try_collect().await.unwrap();
+        let mut ids: Vec<_> = filters
+            .iter()
+            .filter(|filter| u32::from(filter.header.handle) != 0)
+            .map(|filter| {
+                let chain = filter
+                    .attributes
+                    .iter()
+                    .find_map(|attr| match attr {
+                        TcAttribute::Chain(chain) => Some(*chain),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                (chain, filter.header.info >> 16, filter.header.handle.into())
+            })
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    async fn test_async_del_filter() {
+        let (handle, test1, _, _netns) = setup_veth(TEST_DEL_NS).await;
+        let index = test1.header.index as i32;
+        handle.qdisc().add(index).clsact().execute().await.unwrap();
+
+        let program = load_sched_cls();
+        for (egress, chain, priority, filter_handle) in [
+            (false, 0, 10, 1),
+            (false, 0, 10, 2),
+            (false, 0, 20, 1),
+            (false, 1, 10, 1),
+            (true, 0, 10, 1),
+            (true, 2, 10, 1),
+        ] {
+            let request = handle.traffic_filter(index).add();
+            let request = if egress {
+                request.egress()
+            } else {
+                request.ingress()
+            };
+            request
+                .chain(chain)
+                .priority(priority)
+                .protocol(ETH_P_ALL.to_be())
+                .handle(filter_handle)
+                .bpf(TrafficFilterBpf::new().fd(program.as_raw_fd()))
+                .unwrap()
+                .execute()
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            filter_ids(&handle, index, false).await,
+            [(0, 10, 1), (0, 10, 2), (0, 20, 1), (1, 10, 1)]
+        );
+        assert_eq!(
+            filter_ids(&handle, index, true).await,
+            [(0, 10, 1), (2, 10, 1)]
+        );
+
+        handle
+            .traffic_filter(index)
+            .del()
+            .ingress()
+            .priority(10)
+            .protocol(ETH_P_ALL.to_be())
+            .handle(2)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(
+            filter_ids(&handle, index, false).await,
+            [(0, 10, 1), (0, 20, 1), (1, 10, 1)]
+        );
+
+        // Both chains of the ingress are flushed, not the egress.
+        handle
+            .traffic_filter(index)
+            .flush()
+            .ingress()
+            .execute()
+            .await
+            .unwrap();
+        assert!(filter_ids(&handle, index, false).await.is_empty());
+        assert_eq!(
+            filter_ids(&handle, index, true).await,
+            [(0, 10, 1), (2, 10, 1)]
+        );
+
+        // Without parent, both directions of the clsact qdisc are flushed.
+        handle
+            .traffic_filter(index)
+            .add()
+            .ingress()
+            .chain(3)
+            .bpf(TrafficFilterBpf::new().fd(program.as_raw_fd()))
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+        handle
+            .traffic_filter(index)
+            .flush()
+            .execute()
+            .await
+            .unwrap();
+        assert!(filter_ids(&handle, index, false).await.is_empty());
+        assert!(filter_ids(&handle, index, true).await.is_empty());
+    }
+
+    #[test]
+    fn test_del_filter() {
+        Runtime::new().unwrap().block_on(test_async_del_filter());
+    }
+
+    async fn chain_ids(handle: &Handle, index: i32) -> Vec<u32> {
+        let chains: Vec<_> = handle
+            .traffic_chain(index)
+            .get()
+            .ingress()
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        chains
+            .iter()
+            .filter_map(|chain| {
+                chain.attributes.iter().find_map(|attr| match attr {
+                    TcAttribute::Chain(chain) => Some(*chain),
+                    _ => None,
+                })
+            })
+            .collect()
+    }
+
+    async fn test_async_chain() {
+        let (handle, test1, _, _netns) = setup_veth(TEST_CHAIN_NS).await;
+        let index = test1.header.index as i32;
+        handle.qdisc().add(index).clsact().execute().await.unwrap();
+
+        handle
+            .traffic_chain(index)
+            .add()
+            .ingress()
+            .chain(5)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(chain_ids(&handle, index).await, [5]);
+        assert!(handle
+            .traffic_chain(index)
+            .add()
+            .ingress()
+            .chain(5)
+            .execute()
+            .await
+            .is_err());
+
+        let program = load_sched_cls();
+        handle
+            .traffic_filter(index)
+            .add()
+            .ingress()
+            .chain(5)
+            .priority(10)
+            .protocol(ETH_P_ALL.to_be())
+            .bpf(TrafficFilterBpf::new().fd(program.as_raw_fd()))
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(filter_ids(&handle, index, false).await, [(5, 10, 1)]);
+
+        handle
+            .traffic_chain(index)
+            .del()
+            .ingress()
+            .chain(5)
+            .execute()
+            .await
+            .unwrap();
+        assert!(chain_ids(&handle, index).await.is_empty());
+        assert!(filter_ids(&handle, index, false).await.is_empty());
+    }
+
+    #[test]
+    fn test_chain() {
+        Runtime::new().unwrap().block_on(test_async_chain());
+    }
+
+    async fn test_async_block() {
+        let (handle, test1, test2, _netns) = setup_veth(TEST_BLOCK_NS).await;
+        let index1 = test1.header.index as i32;
+        let index2 = test2.header.index as i32;
+        handle
+            .qdisc()
+            .add(index1)
+            .clsact()
+            .ingress_block(5)
+            .egress_block(6)
+            .execute()
+            .await
+            .unwrap();
+        handle
+            .qdisc()
+            .add(index2)
+            .clsact()
+            .ingress_block(5)
+            .execute()
+            .await
+            .unwrap();
+
+        let mut blocks: Vec<_> = handle
+            .qdisc()
+            .blocks()
+            .execute()
+            .map_ok(|block| (block.block_index, block.ifindex, block.egress))
+            .try_collect()
+            .await
+            .unwrap();
+        blocks.sort();
+        let mut expected =
+            [(5, index1, false), (5, index2, false), (6, index1, true)];
+        expected.sort();
+        assert_eq!(blocks, expected);
+        let blocks: Vec<TrafficBlock> = handle
+            .qdisc()
+            .blocks()
+            .index(index2)
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+
+        let program = load_sched_cls();
+        handle
+            .traffic_filter(0)
+            .add()
+            .block(5)
+            .chain(2)
+            .priority(10)
+            .protocol(ETH_P_ALL.to_be())
+            .bpf(TrafficFilterBpf::new().fd(program.as_raw_fd()))
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let filters: Vec<_> = handle
+            .traffic_filter(0)
+            .get()
+            .block(5)
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(filters
+            .iter()
+            .any(|filter| filter.attributes.contains(&TcAttribute::Chain(2))));
+        let filters: Vec<_> = handle
+            .traffic_filter(0)
+            .get()
+            .block(6)
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(filters.is_empty());
+
+        let chains: Vec<_> = handle
+            .traffic_chain(0)
+            .get()
+            .block(5)
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chains.len(), 1);
+        assert!(chains[0].attributes.contains(&TcAttribute::Chain(2)));
+
+        // Flushing the device leaves the shared blocks alone.
+        handle
+            .traffic_filter(index1)
+            .flush()
+            .execute()
+            .await
+            .unwrap();
+        let block_filters = |block_index| {
+            handle
+                .traffic_filter(0)
+                .get()
+                .block(block_index)
+                .execute()
+                .try_collect::<Vec<_>>()
+        };
+        assert!(!block_filters(5).await.unwrap().is_empty());
+
+        handle
+            .traffic_filter(0)
+            .flush()
+            .block(5)
+            .execute()
+            .await
+            .unwrap();
+        assert!(block_filters(5).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_block() {
+        Runtime::new().unwrap().block_on(test_async_block());
+    }
+
+    async fn test_async_default_protocol() {
+        let (handle, test1, _, _netns) = setup_veth(TEST_PROTOCOL_NS).await;
+        let index = test1.header.index as i32;
+        handle.qdisc().add(index).clsact().execute().await.unwrap();
+
+        let request = || handle.traffic_filter(index).add().ingress();
+        let requests = [
+            (
+                10,
+                request()
+                    .priority(10)
+                    .matchall(TrafficFilterMatchall::new()),
+            ),
+            (
+                20,
+                request().priority(20).handle(1).fw(TrafficFilterFw::new()),
+            ),
+            (30, request().priority(30).route(TrafficFilterRoute::new())),
+            (40, request().priority(40).basic(TrafficFilterBasic::new())),
+        ];
+        let mut kinds = Vec::new();
+        for (priority, request) in requests {
+            let mut request = request.unwrap();
+            let kind = request.message_mut().kind().unwrap().to_string();
+            match request.execute().await {
+                // The classifier is not built in this kernel.
+                Err(Error::NetlinkError(e))
+                    if e.raw_code() == -libc::ENOENT =>
+                {
+                    eprintln!("skipped: the kernel has no {kind} classifier");
+                }
+                result => {
+                    result.unwrap();
+                    kinds.push((priority, kind));
+                }
+            }
+        }
+
+        let filters: Vec<_> = handle
+            .traffic_filter(index)
+            .get()
+            .ingress()
+            .execute()
+            .try_collect()
+            .await
+            .unwrap();
+        for (priority, kind) in kinds {
+            let info = filters
+                .iter()
+                .find(|filter| filter.header.info >> 16 == priority)
+                .map(|filter| filter.header.info);
+            assert_eq!(
+                info,
+                Some((priority << 16) | u32::from(ETH_P_ALL.to_be())),
+                "{kind}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_protocol() {
+        Runtime::new()
+            .unwrap()
+            .block_on(test_async_default_protocol());
+    }
 }
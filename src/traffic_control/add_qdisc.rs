@@ -8,6 +8,15 @@ use crate::{
         tc::{TcAttribute, TcHandle, TcMessage},
         RouteNetlinkMessage,
     },
+    traffic_control::{
+        nla::{
+            set_kind_options, set_message_u32, TCA_EGRESS_BLOCK,
+            TCA_INGRESS_BLOCK,
+        },
+        QDiscBfifo, QDiscCake, QDiscDrr, QDiscEts, QDiscFq, QDiscFqCodel,
+        QDiscHfsc, QDiscHtb, QDiscMq, QDiscMqprio, QDiscNetem, QDiscPfifo,
+        QDiscPrio, QDiscSfq, QDiscTaprio, QDiscTbf, TcClassId,
+    },
     try_nl, Error, Handle,
 };
 
@@ -65,6 +74,20 @@ impl QDiscNewRequest {
         self
     }
 
+    /// Set handle in the `tc` notation, see [TcClassId].
+    /// Equivalent to `handle MAJOR:`.
+    pub fn handle_str(mut self, handle: &str) -> Result<Self, Error> {
+        self.message.header.handle = handle.parse::<TcClassId>()?.into();
+        Ok(self)
+    }
+
+    /// Set parent in the `tc` notation, see [TcClassId].
+    /// Equivalent to `[ root | parent CLASSID ]`.
+    pub fn parent_str(mut self, parent: &str) -> Result<Self, Error> {
+        self.message.header.parent = parent.parse::<TcClassId>()?.into();
+        Ok(self)
+    }
+
     /// New a ingress qdisc
     pub fn ingress(mut self) -> Self {
         self.message.header.parent = TcHandle::INGRESS;
@@ -74,88 +97,230 @@ impl QDiscNewRequest {
             .push(TcAttribute::Kind("ingress".to_string()));
         self
     }
+
+    /// New a clsact qdisc, which holds the filters of both the ingress and
+    /// the egress of the device, see [crate::TrafficFilterNewRequest::ingress]
+    /// and [crate::TrafficFilterNewRequest::egress].
+    /// Equivalent to `tc qdisc add ... clsact`.
+    pub fn clsact(mut self) -> Self {
+        self.message.header.parent = TcHandle::CLSACT;
+        self.message.header.handle = TcHandle::from(0xffff0000);
+        set_kind_options(&mut self.message, "clsact", None);
+        self
+    }
+
+    /// Bind the ingress of the device to the shared filter block
+    /// `block_index`, which is created along with the first qdisc bound to
+    /// it. Only supported by the ingress and clsact qdiscs.
+    /// Equivalent to `ingress_block BLOCK_INDEX`.
+    pub fn ingress_block(mut self, block_index: u32) -> Self {
+        set_message_u32(&mut self.message, TCA_INGRESS_BLOCK, block_index);
+        self
+    }
+
+    /// Bind the egress of the device to the shared filter block
+    /// `block_index`. Only supported by the clsact qdisc.
+    /// Equivalent to `egress_block BLOCK_INDEX`.
+    pub fn egress_block(mut self, block_index: u32) -> Self {
+        set_message_u32(&mut self.message, TCA_EGRESS_BLOCK, block_index);
+        self
+    }
+
+    /// New a htb qdisc.
+    /// Equivalent to `tc qdisc add ... htb`.
+    pub fn htb(mut self, options: QDiscHtb) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscHtb::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a prio qdisc.
+    /// Equivalent to `tc qdisc add ... prio`.
+    pub fn prio(mut self, options: QDiscPrio) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscPrio::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a drr qdisc.
+    /// Equivalent to `tc qdisc add ... drr`.
+    pub fn drr(mut self) -> Self {
+        set_kind_options(&mut self.message, QDiscDrr::KIND, None);
+        self
+    }
+
+    /// New a ets qdisc.
+    /// Equivalent to `tc qdisc add ... ets`.
+    pub fn ets(mut self, options: QDiscEts) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscEts::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a hfsc qdisc.
+    /// Equivalent to `tc qdisc add ... hfsc`.
+    pub fn hfsc(mut self, options: QDiscHfsc) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscHfsc::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a tbf qdisc.
+    /// Equivalent to `tc qdisc add ... tbf`.
+    pub fn tbf(mut self, options: QDiscTbf) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscTbf::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a fq_codel qdisc.
+    /// Equivalent to `tc qdisc add ... fq_codel`.
+    pub fn fq_codel(mut self, options: QDiscFqCodel) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscFqCodel::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a fq qdisc.
+    /// Equivalent to `tc qdisc add ... fq`.
+    pub fn fq(mut self, options: QDiscFq) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscFq::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a cake qdisc.
+    /// Equivalent to `tc qdisc add ... cake`.
+    pub fn cake(mut self, options: QDiscCake) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscCake::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a sfq qdisc.
+    /// Equivalent to `tc qdisc add ... sfq`.
+    pub fn sfq(mut self, options: QDiscSfq) -> Self {
+        set_kind_options(&mut self.message, QDiscSfq::KIND, options.options());
+        self
+    }
+
+    /// New a pfifo qdisc.
+    /// Equivalent to `tc qdisc add ... pfifo`.
+    pub fn pfifo(mut self, options: QDiscPfifo) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscPfifo::KIND,
+            options.options(),
+        );
+        self
+    }
+
+    /// New a bfifo qdisc.
+    /// Equivalent to `tc qdisc add ... bfifo`.
+    pub fn bfifo(mut self, options: QDiscBfifo) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscBfifo::KIND,
+            options.options(),
+        );
+        self
+    }
+
+    /// New a netem qdisc.
+    /// Equivalent to `tc qdisc add ... netem`.
+    pub fn netem(mut self, options: QDiscNetem) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscNetem::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a mq qdisc.
+    /// Equivalent to `tc qdisc add ... mq`.
+    pub fn mq(mut self) -> Self {
+        set_kind_options(&mut self.message, QDiscMq::KIND, None);
+        self
+    }
+
+    /// New a mqprio qdisc.
+    /// Equivalent to `tc qdisc add ... mqprio`.
+    pub fn mqprio(mut self, options: QDiscMqprio) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscMqprio::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// New a taprio qdisc.
+    /// Equivalent to `tc qdisc add ... taprio`.
+    pub fn taprio(mut self, options: QDiscTaprio) -> Self {
+        set_kind_options(
+            &mut self.message,
+            QDiscTaprio::KIND,
+            Some(options.options()),
+        );
+        self
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{fs::File, os::fd::AsFd, path::Path};
-
     use futures_util::stream::TryStreamExt;
-    use nix::sched::{setns, CloneFlags};
     use tokio::runtime::Runtime;
 
     use super::*;
     use crate::{
-        new_connection,
         packet_route::{link::LinkMessage, AddressFamily},
-        LinkDummy, NetworkNamespace, NETNS_PATH, SELF_NS_PATH,
+        test_netns::{get_link, setup_netns, Netns},
+        LinkDummy,
     };
 
     const TEST_NS: &str = "netlink_test_qdisc_ns";
     const TEST_DUMMY: &str = "test_dummy";
 
-    struct Netns {
-        path: String,
-        _cur: File,
-        last: File,
-    }
-
-    impl Netns {
-        async fn new(path: &str) -> Self {
-            // record current ns
-            let last = File::open(Path::new(SELF_NS_PATH)).unwrap();
-
-            // create new ns
-            NetworkNamespace::add(path.to_string()).await.unwrap();
-
-            // entry new ns
-            let ns_path = Path::new(NETNS_PATH);
-            let file = File::open(ns_path.join(path)).unwrap();
-            setns(file.as_fd(), CloneFlags::CLONE_NEWNET).unwrap();
-
-            Self {
-                path: path.to_string(),
-                _cur: file,
-                last,
-            }
-        }
-    }
-    impl Drop for Netns {
-        fn drop(&mut self) {
-            println!("exit ns: {}", self.path);
-            setns(self.last.as_fd(), CloneFlags::CLONE_NEWNET).unwrap();
-
-            let ns_path = Path::new(NETNS_PATH).join(&self.path);
-            nix::mount::umount2(&ns_path, nix::mount::MntFlags::MNT_DETACH)
-                .unwrap();
-            nix::unistd::unlink(&ns_path).unwrap();
-            // _cur File will be closed auto
-            // Since there is no async drop, NetworkNamespace::del cannot be
-            // called here. Dummy interface will be deleted
-            // automatically after netns is deleted.
-        }
-    }
-
     async fn setup_env() -> (Handle, LinkMessage, Netns) {
-        let netns = Netns::new(TEST_NS).await;
-
-        // Notice: The Handle can only be created after the setns, so that the
-        // Handle is the connection within the new ns.
-        let (connection, handle, _) = new_connection().unwrap();
-        tokio::spawn(connection);
+        let (handle, netns) = setup_netns(TEST_NS).await;
         handle
             .link()
             .add(LinkDummy::new(TEST_DUMMY).up().build())
             .execute()
             .await
             .unwrap();
-        let mut links = handle
-            .link()
-            .get()
-            .match_name(TEST_DUMMY.to_string())
-            .execute();
-        let link = links.try_next().await.unwrap();
-        (handle, link.unwrap(), netns)
+        let link = get_link(&handle, TEST_DUMMY).await;
+        (handle, link, netns)
     }
 
     async fn test_async_new_qdisc() {
@@ -201,4 +366,17 @@ mod test {
     fn test_new_qdisc() {
         Runtime::new().unwrap().block_on(test_async_new_qdisc());
     }
+
+    #[test]
+    fn test_drr_request() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let (_, handle, _) = crate::new_connection().unwrap();
+            let mut request = handle.qdisc().add(1).handle(1, 0).root().drr();
+            assert_eq!(
+                request.message_mut().attributes,
+                vec![TcAttribute::Kind(QDiscDrr::KIND.to_string())]
+            );
+        });
+    }
 }
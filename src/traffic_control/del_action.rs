@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::StreamExt;
+use netlink_packet_core::{
+    NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST, NLM_F_ROOT,
+};
+use netlink_packet_route::{
+    tc::{
+        TcAction, TcActionAttribute, TcActionMessage, TcActionMessageAttribute,
+        TcActionMessageFlags, TcActionMessageFlagsWithSelector,
+    },
+    RouteNetlinkMessage,
+};
+
+use crate::{try_nl, Error, Handle};
+
+#[derive(Debug, Clone)]
+pub struct TrafficActionDelRequest {
+    handle: Handle,
+    message: TcActionMessage,
+    flags: u16,
+}
+
+impl TrafficActionDelRequest {
+    pub(crate) fn new(handle: Handle, kind: &str, index: u32) -> Self {
+        let mut action = TcAction::default();
+        action.attributes = vec![
+            TcActionAttribute::Kind(kind.to_string()),
+            TcActionAttribute::Index(index),
+        ];
+        let mut message = TcActionMessage::default();
+        message
+            .attributes
+            .push(TcActionMessageAttribute::Actions(vec![action]));
+        Self {
+            handle,
+            message,
+            flags: NLM_F_REQUEST | NLM_F_ACK,
+        }
+    }
+
+    pub(crate) fn flush(handle: Handle, kind: &str) -> Self {
+        let mut action = TcAction::default();
+        action.attributes = vec![TcActionAttribute::Kind(kind.to_string())];
+        let mut message = TcActionMessage::default();
+        message.attributes = vec![
+            TcActionMessageAttribute::Actions(vec![action]),
+            TcActionMessageAttribute::Flags(
+                TcActionMessageFlagsWithSelector::new(
+                    TcActionMessageFlags::LargeDump,
+                ),
+            ),
+        ];
+        Self {
+            handle,
+            message,
+            flags: NLM_F_REQUEST | NLM_F_ACK | NLM_F_ROOT,
+        }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let Self {
+            mut handle,
+            message,
+            flags,
+        } = self;
+
+        let mut req = NetlinkMessage::from(
+            RouteNetlinkMessage::DelTrafficAction(message),
+        );
+        req.header.flags = flags;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcActionMessage {
+        &mut self.message
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::StreamExt;
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    tc::{TcAttribute, TcHandle, TcHeader, TcMessage},
+    RouteNetlinkMessage,
+};
+
+use crate::{try_nl, Error, Handle};
+
+#[derive(Debug, Clone)]
+pub struct TrafficChainDelRequest {
+    handle: Handle,
+    message: TcMessage,
+}
+
+impl TrafficChainDelRequest {
+    pub(crate) fn new(handle: Handle, ifindex: i32) -> Self {
+        Self {
+            handle,
+            message: TcMessage::with_index(ifindex),
+        }
+    }
+
+    /// Execute the request, the filters of the chain are deleted along
+    /// with it.
+    pub async fn execute(self) -> Result<(), Error> {
+        let Self {
+            mut handle,
+            message,
+        } = self;
+
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::DelTrafficChain(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+        let mut response = handle.request(req)?;
+        if let Some(message) = response.next().await {
+            try_nl!(message);
+        }
+
+        Ok(())
+    }
+
+    /// Set block index, to delete the chain of a shared block.
+    /// Equivalent to `block BLOCK_INDEX`.
+    pub fn block(mut self, block_index: u32) -> Self {
+        self.message.header.index = TcHeader::TCM_IFINDEX_MAGIC_BLOCK as i32;
+        self.message.header.parent = block_index.into();
+        self
+    }
+
+    /// Set parent.
+    /// Equivalent to `[ root | ingress | egress | parent CLASSID ]`
+    /// command args. They are mutually exclusive.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.message.header.parent = parent.into();
+        self
+    }
+
+    /// Set parent to root.
+    pub fn root(mut self) -> Self {
+        self.message.header.parent = TcHandle::ROOT;
+        self
+    }
+
+    /// Set parent to ingress.
+    pub fn ingress(mut self) -> Self {
+        self.message.header.parent = TcHandle {
+            major: 0xffff,
+            minor: TcHandle::MIN_INGRESS,
+        };
+        self
+    }
+
+    /// Set parent to egress.
+    pub fn egress(mut self) -> Self {
+        self.message.header.parent = TcHandle {
+            major: 0xffff,
+            minor: TcHandle::MIN_EGRESS,
+        };
+        self
+    }
+
+    /// Set chain index, the chain 0 otherwise.
+    /// Equivalent to `chain CHAIN_INDEX`.
+    pub fn chain(mut self, chain: u32) -> Self {
+        self.message
+            .attributes
+            .retain(|attr| !matches!(attr, TcAttribute::Chain(_)));
+        self.message.attributes.push(TcAttribute::Chain(chain));
+        self
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
+}
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::StreamExt;
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    tc::{TcHandle, TcMessage},
+    RouteNetlinkMessage,
+};
+
+use crate::{try_nl, Error, Handle};
+
+#[derive(Debug, Clone)]
+pub struct TrafficClassDelRequest {
+    handle: Handle,
+    message: TcMessage,
+}
+
+impl TrafficClassDelRequest {
+    pub(crate) fn new(handle: Handle, message: TcMessage) -> Self {
+        Self { handle, message }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let Self {
+            mut handle,
+            message,
+        } = self;
+
+        let mut req =
+            NetlinkMessage::from(RouteNetlinkMessage::DelTrafficClass(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+        let mut response = handle.request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message)
+        }
+        Ok(())
+    }
+
+    /// Set class id.
+    /// Equivalent to `classid MAJOR:MINOR`.
+    pub fn classid(mut self, major: u16, minor: u16) -> Self {
+        self.message.header.handle = TcHandle { major, minor };
+        self
+    }
+
+    /// Set parent.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.message.header.parent = parent.into();
+        self
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
+}
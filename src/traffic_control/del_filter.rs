@@ -3,7 +3,7 @@
 use futures_util::stream::StreamExt;
 use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
 use netlink_packet_route::{
-    tc::{TcHandle, TcMessage},
+    tc::{TcAttribute, TcHandle, TcHeader, TcMessage},
     RouteNetlinkMessage,
 };
 
@@ -46,6 +46,14 @@ impl TrafficFilterDelRequest {
         Ok(())
     }
 
+    /// Set block index, to delete the filters of a shared block.
+    /// Equivalent to `block BLOCK_INDEX`.
+    pub fn block(mut self, block_index: u32) -> Self {
+        self.message.header.index = TcHeader::TCM_IFINDEX_MAGIC_BLOCK as i32;
+        self.message.header.parent = block_index.into();
+        self
+    }
+
     /// Set parent.
     /// Equivalent to `[ root | ingress | egress | parent CLASSID ]`
     /// command args. They are mutually exclusive.
@@ -77,4 +85,50 @@ impl TrafficFilterDelRequest {
         };
         self
     }
+
+    /// Only delete the filters of this priority, all the filters of the
+    /// chain are deleted otherwise.
+    /// Equivalent to `priority PRIO` or `pref PRIO`.
+    pub fn priority(mut self, priority: u16) -> Self {
+        self.message.header.info = u32::from(TcHandle {
+            major: priority,
+            minor: self.message.header.info as u16,
+        });
+        self
+    }
+
+    /// Set protocol, in network byte order like
+    /// [crate::TrafficFilterNewRequest::protocol]. Required along with the
+    /// priority when the kernel has several filters with this priority.
+    /// Equivalent to `protocol PROT`.
+    pub fn protocol(mut self, protocol: u16) -> Self {
+        self.message.header.info = u32::from(TcHandle {
+            major: (self.message.header.info >> 16) as u16,
+            minor: protocol,
+        });
+        self
+    }
+
+    /// Only delete the filter with this handle, which requires the
+    /// priority.
+    /// Equivalent to `handle FILTERID`.
+    pub fn handle(mut self, handle: u32) -> Self {
+        self.message.header.handle = handle.into();
+        self
+    }
+
+    /// Delete the filters of this chain, the chain 0 otherwise.
+    /// Equivalent to `chain CHAIN_INDEX`.
+    pub fn chain(mut self, chain: u32) -> Self {
+        self.message
+            .attributes
+            .retain(|attr| !matches!(attr, TcAttribute::Chain(_)));
+        self.message.attributes.push(TcAttribute::Chain(chain));
+        self
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
 }
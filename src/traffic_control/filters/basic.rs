@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcAttribute};
+
+use super::{actions_nla, parse_actions, Ematch};
+use crate::traffic_control::nla::{nested_options, nla_u32, parse_nlas};
+
+const TCA_BASIC_CLASSID: u16 = 1;
+const TCA_BASIC_EMATCHES: u16 = 2;
+const TCA_BASIC_ACT: u16 = 3;
+
+/// Options of the `basic` classifier, which matches the packets with an
+/// extended match expression.
+/// Equivalent to `tc filter add ... basic`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TrafficFilterBasic {
+    /// Class the matching packets are classified into.
+    pub classid: Option<u32>,
+    /// Expression the packets must match, all packets match when unset.
+    pub ematch: Option<Ematch>,
+    pub actions: Vec<TcAction>,
+}
+
+impl TrafficFilterBasic {
+    pub const KIND: &'static str = "basic";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `classid CLASSID`.
+    pub fn classid(mut self, classid: u32) -> Self {
+        self.classid = Some(classid);
+        self
+    }
+
+    /// Equivalent to `match EXPR`.
+    pub fn ematch(mut self, ematch: Ematch) -> Self {
+        self.ematch = Some(ematch);
+        self
+    }
+
+    /// Append an action run on the matching packets.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut nlas = Vec::new();
+        if let Some(ematch) = &self.ematch {
+            nlas.push(DefaultNla::new(TCA_BASIC_EMATCHES, ematch.emit_tree()));
+        }
+        if let Some(classid) = self.classid {
+            nlas.push(nla_u32(TCA_BASIC_CLASSID, classid));
+        }
+        if !self.actions.is_empty() {
+            nlas.push(actions_nla(TCA_BASIC_ACT, &self.actions));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut basic = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_BASIC_CLASSID => basic.classid = Some(parse_u32(value)?),
+                TCA_BASIC_EMATCHES => {
+                    basic.ematch = Some(Ematch::parse_tree(value)?)
+                }
+                TCA_BASIC_ACT => basic.actions = parse_actions(value)?,
+                _ => (),
+            }
+        }
+        Ok(basic)
+    }
+}
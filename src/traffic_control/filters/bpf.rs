@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    ffi::CString,
+    os::{
+        fd::{FromRawFd, OwnedFd, RawFd},
+        unix::ffi::OsStrExt,
+    },
+    path::{Path, PathBuf},
+};
+
+use netlink_packet_core::{DecodeError, Nla, NlasIterator, Parseable};
+use netlink_packet_route::tc::{
+    TcAction, TcAttribute, TcBpfFlags, TcFilterBpfOption, TcOption,
+    TcU32OptionFlags,
+};
+use nix::libc;
+
+use super::{actions_nla, parse_actions};
+use crate::{traffic_control::nla::nla_value, Error};
+
+const BPF_OBJ_GET: libc::c_int = 7;
+const TCA_BPF_ACT: u16 = 1;
+
+/// Options of the `bpf` classifier, which runs an eBPF program of type
+/// `BPF_PROG_TYPE_SCHED_CLS` on the packets.
+/// Equivalent to `tc filter add ... bpf`.
+///
+/// The program is attached either by file descriptor or by its path on a
+/// bpffs, the kernel only reports its id, name and tag back.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TrafficFilterBpf {
+    /// Class the packets are classified into, when the program returns
+    /// `-1` without `direct_action`.
+    pub classid: Option<u32>,
+    /// File descriptor of the program to attach.
+    pub fd: Option<RawFd>,
+    /// Path of the program pinned on a bpffs to attach.
+    pub pinned: Option<PathBuf>,
+    /// Name shown by `tc filter show`.
+    pub name: Option<String>,
+    /// The return code of the program is the tc action.
+    pub direct_action: bool,
+    /// Do not process the filter in software.
+    pub skip_sw: bool,
+    /// Do not offload the filter to hardware.
+    pub skip_hw: bool,
+    /// Whether the filter is offloaded to hardware, only reported by the
+    /// kernel.
+    pub in_hw: bool,
+    /// Id of the attached program, only reported by the kernel.
+    pub id: Option<u32>,
+    /// Tag of the attached program, only reported by the kernel.
+    pub tag: Option<[u8; 8]>,
+    pub actions: Vec<TcAction>,
+}
+
+impl TrafficFilterBpf {
+    pub const KIND: &'static str = "bpf";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach the program referred by `fd`, which must stay open until the
+    /// request is executed.
+    /// Equivalent to `fd FD`.
+    pub fn fd(mut self, fd: RawFd) -> Self {
+        self.fd = Some(fd);
+        self
+    }
+
+    /// Attach the program pinned at `path`.
+    /// Equivalent to `pinned PATH`.
+    pub fn pinned<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.pinned = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Equivalent to `classid CLASSID`.
+    pub fn classid(mut self, classid: u32) -> Self {
+        self.classid = Some(classid);
+        self
+    }
+
+    /// Equivalent to the `section NAME` annotation of `tc`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Equivalent to `direct-action` or `da`.
+    pub fn direct_action(mut self) -> Self {
+        self.direct_action = true;
+        self
+    }
+
+    /// Equivalent to `skip_sw`.
+    pub fn skip_sw(mut self) -> Self {
+        self.skip_sw = true;
+        self
+    }
+
+    /// Equivalent to `skip_hw`.
+    pub fn skip_hw(mut self) -> Self {
+        self.skip_hw = true;
+        self
+    }
+
+    /// Append an action run on the packets the program classifies.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    // Open the pinned program, the returned file descriptor must be kept
+    // open until the request is sent.
+    pub(crate) fn open_pinned(&self) -> Result<Option<OwnedFd>, Error> {
+        match (self.fd, &self.pinned) {
+            (Some(_), Some(_)) => Err(Error::InvalidNla(
+                "bpf fd and pinned are mutually exclusive".to_string(),
+            )),
+            (None, None) => Err(Error::InvalidNla(
+                "bpf requires a program fd or pinned path".to_string(),
+            )),
+            (Some(_), None) => Ok(None),
+            (None, Some(path)) => bpf_obj_get(path).map(Some),
+        }
+    }
+
+    pub(crate) fn options(&self, fd: RawFd) -> TcAttribute {
+        use TcFilterBpfOption as O;
+
+        let mut opts = vec![O::ProgFd(fd as u32)];
+        if let Some(name) = &self.name {
+            opts.push(O::ProgName(name.clone()));
+        }
+        if let Some(classid) = self.classid {
+            opts.push(O::ClassId(classid.into()));
+        }
+        let mut flags = TcU32OptionFlags::empty();
+        if self.skip_hw {
+            flags |= TcU32OptionFlags::SkipHw;
+        }
+        if self.skip_sw {
+            flags |= TcU32OptionFlags::SkipSw;
+        }
+        if !flags.is_empty() {
+            opts.push(O::FlagsGeneric(flags));
+        }
+        if self.direct_action {
+            opts.push(O::Flags(TcBpfFlags::DirectAction));
+        }
+        if !self.actions.is_empty() {
+            opts.push(O::Other(actions_nla(TCA_BPF_ACT, &self.actions)));
+        }
+        TcAttribute::Options(opts.into_iter().map(TcOption::Bpf).collect())
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        use TcFilterBpfOption as O;
+
+        let mut bpf = Self::default();
+        for nla in NlasIterator::new(payload) {
+            match O::parse(&nla?)? {
+                O::ClassId(classid) => bpf.classid = Some(classid.into()),
+                O::ProgName(name) => bpf.name = Some(name),
+                O::Flags(flags) => {
+                    bpf.direct_action = flags.contains(TcBpfFlags::DirectAction)
+                }
+                O::FlagsGeneric(flags) => {
+                    bpf.skip_hw = flags.contains(TcU32OptionFlags::SkipHw);
+                    bpf.skip_sw = flags.contains(TcU32OptionFlags::SkipSw);
+                    bpf.in_hw = flags.contains(TcU32OptionFlags::InHw);
+                }
+                O::ProgId(id) => bpf.id = Some(id),
+                O::ProgTag(tag) => bpf.tag = Some(tag),
+                O::Other(nla) if nla.kind() == TCA_BPF_ACT => {
+                    bpf.actions = parse_actions(&nla_value(&nla))?
+                }
+                _ => (),
+            }
+        }
+        Ok(bpf)
+    }
+}
+
+fn bpf_obj_get(path: &Path) -> Result<OwnedFd, Error> {
+    let pathname = CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+        Error::BpfError(format!("invalid pinned path {}", path.display()))
+    })?;
+    // The `BPF_OBJ_GET` member of `union bpf_attr`.
+    let mut attr = [0u64; 3];
+    attr[0] = pathname.as_ptr() as u64;
+    // SAFETY: `attr` outlives the call and the kernel only reads
+    // `size_of_val(&attr)` bytes of it.
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            BPF_OBJ_GET,
+            attr.as_mut_ptr(),
+            std::mem::size_of_val(&attr),
+        )
+    };
+    if fd < 0 {
+        return Err(Error::BpfError(format!(
+            "failed to open pinned program {}: {}",
+            path.display(),
+            nix::errno::Errno::last()
+        )));
+    }
+    // SAFETY: the kernel returned a new file descriptor owned by nobody
+    // else.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
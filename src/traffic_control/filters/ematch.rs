@@ -0,0 +1,645 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, DecodeError, DefaultNla};
+
+use crate::traffic_control::nla::{
+    emit_nlas, nla_u32, parse_nlas, read_u16, read_u32,
+};
+
+const TCA_EMATCH_TREE_HDR: u16 = 1;
+const TCA_EMATCH_TREE_LIST: u16 = 2;
+
+const TCF_EM_REL_END: u16 = 0;
+const TCF_EM_REL_AND: u16 = 1 << 0;
+const TCF_EM_REL_OR: u16 = 1 << 1;
+const TCF_EM_INVERT: u16 = 1 << 2;
+const TCF_EM_REL_MASK: u16 = 3;
+
+const TCF_EM_CONTAINER: u16 = 0;
+const TCF_EM_CMP: u16 = 1;
+const TCF_EM_U32: u16 = 3;
+const TCF_EM_META: u16 = 4;
+
+const TCF_EM_CMP_TRANS: u8 = 1;
+
+const TCA_EM_META_HDR: u16 = 1;
+const TCA_EM_META_LVALUE: u16 = 2;
+const TCA_EM_META_RVALUE: u16 = 3;
+
+const TCF_META_TYPE_INT: u16 = 1 << 12;
+const TCF_META_TYPE_MASK: u16 = 0xf << 12;
+const TCF_META_ID_MASK: u16 = 0x7ff;
+const TCF_META_ID_VALUE: u16 = 0;
+
+const EMATCH_HDR_LEN: usize = 8;
+
+/// Extended match of the `basic` classifier.
+/// Equivalent to the `EMATCH_TREE` of `tc filter ... basic match`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Ematch {
+    /// Equivalent to `cmp(...)`.
+    Cmp(EmatchCmp),
+    /// Equivalent to `u32(...)`.
+    U32(EmatchU32),
+    /// Equivalent to `meta(...)`.
+    Meta(EmatchMeta),
+    /// Equivalent to `not EXPR`.
+    Not(Box<Ematch>),
+    /// Equivalent to `EXPR and EXPR ...`.
+    And(Vec<Ematch>),
+    /// Equivalent to `EXPR or EXPR ...`.
+    Or(Vec<Ematch>),
+    /// Ematch kind without typed options, with its raw payload.
+    Other { kind: u16, payload: Vec<u8> },
+}
+
+/// Operand of the comparison done by an ematch.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum EmatchOperand {
+    #[default]
+    Eq,
+    Gt,
+    Lt,
+    Other(u8),
+}
+
+impl From<u8> for EmatchOperand {
+    fn from(d: u8) -> Self {
+        match d {
+            0 => Self::Eq,
+            1 => Self::Gt,
+            2 => Self::Lt,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<EmatchOperand> for u8 {
+    fn from(v: EmatchOperand) -> u8 {
+        match v {
+            EmatchOperand::Eq => 0,
+            EmatchOperand::Gt => 1,
+            EmatchOperand::Lt => 2,
+            EmatchOperand::Other(d) => d,
+        }
+    }
+}
+
+/// Header of the packet an offset is relative to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum EmatchLayer {
+    #[default]
+    Link,
+    Network,
+    Transport,
+    Other(u8),
+}
+
+impl From<u8> for EmatchLayer {
+    fn from(d: u8) -> Self {
+        match d {
+            0 => Self::Link,
+            1 => Self::Network,
+            2 => Self::Transport,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<EmatchLayer> for u8 {
+    fn from(v: EmatchLayer) -> u8 {
+        match v {
+            EmatchLayer::Link => 0,
+            EmatchLayer::Network => 1,
+            EmatchLayer::Transport => 2,
+            EmatchLayer::Other(d) => d,
+        }
+    }
+}
+
+/// Size of the value compared by [EmatchCmp].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum EmatchCmpAlign {
+    #[default]
+    U8,
+    U16,
+    U32,
+    Other(u8),
+}
+
+impl From<u8> for EmatchCmpAlign {
+    fn from(d: u8) -> Self {
+        match d {
+            1 => Self::U8,
+            2 => Self::U16,
+            4 => Self::U32,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<EmatchCmpAlign> for u8 {
+    fn from(v: EmatchCmpAlign) -> u8 {
+        match v {
+            EmatchCmpAlign::U8 => 1,
+            EmatchCmpAlign::U16 => 2,
+            EmatchCmpAlign::U32 => 4,
+            EmatchCmpAlign::Other(d) => d,
+        }
+    }
+}
+
+/// Compare a packet field with a value.
+/// Equivalent to `cmp(ALIGN at OFFSET [ layer LAYER ] [ mask MASK ]
+/// [ trans ] OPERAND VALUE)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct EmatchCmp {
+    pub align: EmatchCmpAlign,
+    pub offset: u16,
+    pub layer: EmatchLayer,
+    /// Mask applied to the packet field, 0 when unmasked.
+    pub mask: u32,
+    /// Convert the packet field from network byte order.
+    pub trans: bool,
+    pub operand: EmatchOperand,
+    pub value: u32,
+}
+
+impl EmatchCmp {
+    /// Equivalent to `cmp(ALIGN at OFFSET layer LAYER eq VALUE)`.
+    pub fn new(
+        align: EmatchCmpAlign,
+        offset: u16,
+        layer: EmatchLayer,
+        value: u32,
+    ) -> Self {
+        Self {
+            align,
+            offset,
+            layer,
+            value,
+            ..Default::default()
+        }
+    }
+
+    /// Equivalent to `mask MASK`.
+    pub fn mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Equivalent to `trans`.
+    pub fn trans(mut self) -> Self {
+        self.trans = true;
+        self
+    }
+
+    /// Equivalent to `eq`, `gt` or `lt`.
+    pub fn operand(mut self, operand: EmatchOperand) -> Self {
+        self.operand = operand;
+        self
+    }
+
+    fn emit(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; 12];
+        buf[0..4].copy_from_slice(&self.value.to_ne_bytes());
+        buf[4..8].copy_from_slice(&self.mask.to_ne_bytes());
+        buf[8..10].copy_from_slice(&self.offset.to_ne_bytes());
+        let flags = if self.trans { TCF_EM_CMP_TRANS } else { 0 };
+        buf[10] = (u8::from(self.align) & 0xf) | (flags << 4);
+        buf[11] = (u8::from(self.layer) & 0xf) | (u8::from(self.operand) << 4);
+        buf
+    }
+
+    fn parse(buf: &[u8]) -> Result<Self, DecodeError> {
+        let bits = buf
+            .get(10..12)
+            .ok_or_else(|| DecodeError::buffer_too_small(buf.len(), 12))?;
+        Ok(Self {
+            value: read_u32(buf, 0)?,
+            mask: read_u32(buf, 4)?,
+            offset: read_u16(buf, 8)?,
+            align: (bits[0] & 0xf).into(),
+            trans: (bits[0] >> 4) & TCF_EM_CMP_TRANS != 0,
+            layer: (bits[1] & 0xf).into(),
+            operand: (bits[1] >> 4).into(),
+        })
+    }
+}
+
+/// Match 32 bits of the packet from the network header, like a key of the
+/// `u32` classifier.
+/// Equivalent to `u32(ALIGN VALUE MASK at [ nexthdr+ ] OFFSET)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct EmatchU32 {
+    pub value: u32,
+    pub mask: u32,
+    /// Offset of the 32 bits word.
+    pub offset: i32,
+    /// Offset relative to the next header found by the `u32` classifier.
+    pub nexthdr: bool,
+}
+
+impl EmatchU32 {
+    /// Equivalent to `u32(u32 VALUE MASK at OFFSET)`.
+    pub fn u32(value: u32, mask: u32, offset: i32) -> Self {
+        Self {
+            value,
+            mask,
+            offset,
+            nexthdr: false,
+        }
+    }
+
+    /// Equivalent to `u32(u16 VALUE MASK at OFFSET)`.
+    pub fn u16(value: u16, mask: u16, offset: i32) -> Self {
+        let shift = 16 - (offset & 2) * 8;
+        Self {
+            value: u32::from(value) << shift,
+            mask: u32::from(mask) << shift,
+            offset: offset & !3,
+            nexthdr: false,
+        }
+    }
+
+    /// Equivalent to `u32(u8 VALUE MASK at OFFSET)`.
+    pub fn u8(value: u8, mask: u8, offset: i32) -> Self {
+        let shift = 24 - (offset & 3) * 8;
+        Self {
+            value: u32::from(value) << shift,
+            mask: u32::from(mask) << shift,
+            offset: offset & !3,
+            nexthdr: false,
+        }
+    }
+
+    /// Equivalent to `at nexthdr+OFFSET`.
+    pub fn nexthdr(mut self) -> Self {
+        self.nexthdr = true;
+        self
+    }
+
+    fn emit(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; 16];
+        buf[0..4].copy_from_slice(&self.mask.to_be_bytes());
+        buf[4..8].copy_from_slice(&(self.value & self.mask).to_be_bytes());
+        buf[8..12].copy_from_slice(&self.offset.to_ne_bytes());
+        let offmask: i32 = if self.nexthdr { -1 } else { 0 };
+        buf[12..16].copy_from_slice(&offmask.to_ne_bytes());
+        buf
+    }
+
+    fn parse(buf: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            mask: u32::from_be(read_u32(buf, 0)?),
+            value: u32::from_be(read_u32(buf, 4)?),
+            offset: read_u32(buf, 8)? as i32,
+            nexthdr: read_u32(buf, 12)? != 0,
+        })
+    }
+}
+
+/// Packet or socket metadata compared by [EmatchMeta].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum EmatchMetaId {
+    Random,
+    LoadAvg0,
+    LoadAvg1,
+    LoadAvg2,
+    /// Index of the device.
+    Dev,
+    Priority,
+    Protocol,
+    PktType,
+    PktLen,
+    DataLen,
+    MacLen,
+    NfMark,
+    TcIndex,
+    RtClassId,
+    RtIif,
+    SkFamily,
+    SkPrio,
+    VlanTag,
+    RxHash,
+    Other(u16),
+}
+
+impl From<u16> for EmatchMetaId {
+    fn from(d: u16) -> Self {
+        match d {
+            1 => Self::Random,
+            2 => Self::LoadAvg0,
+            3 => Self::LoadAvg1,
+            4 => Self::LoadAvg2,
+            5 => Self::Dev,
+            6 => Self::Priority,
+            7 => Self::Protocol,
+            8 => Self::PktType,
+            9 => Self::PktLen,
+            10 => Self::DataLen,
+            11 => Self::MacLen,
+            12 => Self::NfMark,
+            13 => Self::TcIndex,
+            14 => Self::RtClassId,
+            15 => Self::RtIif,
+            16 => Self::SkFamily,
+            40 => Self::SkPrio,
+            46 => Self::VlanTag,
+            47 => Self::RxHash,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<EmatchMetaId> for u16 {
+    fn from(v: EmatchMetaId) -> u16 {
+        match v {
+            EmatchMetaId::Random => 1,
+            EmatchMetaId::LoadAvg0 => 2,
+            EmatchMetaId::LoadAvg1 => 3,
+            EmatchMetaId::LoadAvg2 => 4,
+            EmatchMetaId::Dev => 5,
+            EmatchMetaId::Priority => 6,
+            EmatchMetaId::Protocol => 7,
+            EmatchMetaId::PktType => 8,
+            EmatchMetaId::PktLen => 9,
+            EmatchMetaId::DataLen => 10,
+            EmatchMetaId::MacLen => 11,
+            EmatchMetaId::NfMark => 12,
+            EmatchMetaId::TcIndex => 13,
+            EmatchMetaId::RtClassId => 14,
+            EmatchMetaId::RtIif => 15,
+            EmatchMetaId::SkFamily => 16,
+            EmatchMetaId::SkPrio => 40,
+            EmatchMetaId::VlanTag => 46,
+            EmatchMetaId::RxHash => 47,
+            EmatchMetaId::Other(d) => d,
+        }
+    }
+}
+
+/// Compare an integer metadata with a value.
+/// Equivalent to `meta(ID [ shift SHIFT ] [ mask MASK ] OPERAND VALUE)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EmatchMeta {
+    pub id: EmatchMetaId,
+    pub shift: u8,
+    /// Mask applied to the metadata, 0 when unmasked.
+    pub mask: u32,
+    pub operand: EmatchOperand,
+    pub value: u32,
+}
+
+impl EmatchMeta {
+    /// Equivalent to `meta(ID eq VALUE)`.
+    pub fn new(id: EmatchMetaId, value: u32) -> Self {
+        Self {
+            id,
+            shift: 0,
+            mask: 0,
+            operand: EmatchOperand::Eq,
+            value,
+        }
+    }
+
+    /// Equivalent to `shift SHIFT`.
+    pub fn shift(mut self, shift: u8) -> Self {
+        self.shift = shift;
+        self
+    }
+
+    /// Equivalent to `mask MASK`.
+    pub fn mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Equivalent to `eq`, `gt` or `lt`.
+    pub fn operand(mut self, operand: EmatchOperand) -> Self {
+        self.operand = operand;
+        self
+    }
+
+    fn emit(&self) -> Vec<u8> {
+        let mut hdr = vec![0u8; 8];
+        let left = TCF_META_TYPE_INT | (u16::from(self.id) & TCF_META_ID_MASK);
+        hdr[0..2].copy_from_slice(&left.to_ne_bytes());
+        hdr[2] = self.shift;
+        hdr[3] = self.operand.into();
+        let right = TCF_META_TYPE_INT | TCF_META_ID_VALUE;
+        hdr[4..6].copy_from_slice(&right.to_ne_bytes());
+        emit_nlas(&[
+            DefaultNla::new(TCA_EM_META_HDR, hdr),
+            nla_u32(TCA_EM_META_LVALUE, self.mask),
+            nla_u32(TCA_EM_META_RVALUE, self.value),
+        ])
+    }
+
+    // `None` for the metadata which are not compared with an integer.
+    fn parse(buf: &[u8]) -> Result<Option<Self>, DecodeError> {
+        let mut meta = None;
+        let mut mask = 0;
+        let mut value = None;
+        for (kind, payload) in parse_nlas(buf)? {
+            match kind {
+                TCA_EM_META_HDR => {
+                    let left = read_u16(payload, 0)?;
+                    let right = read_u16(payload, 4)?;
+                    if left & TCF_META_TYPE_MASK != TCF_META_TYPE_INT
+                        || right != TCF_META_TYPE_INT | TCF_META_ID_VALUE
+                    {
+                        return Ok(None);
+                    }
+                    meta = Some(Self {
+                        id: (left & TCF_META_ID_MASK).into(),
+                        shift: payload[2],
+                        mask: 0,
+                        operand: payload[3].into(),
+                        value: 0,
+                    });
+                }
+                TCA_EM_META_LVALUE => mask = parse_u32(payload)?,
+                TCA_EM_META_RVALUE => match parse_u32(payload) {
+                    Ok(v) => value = Some(v),
+                    Err(_) => return Ok(None),
+                },
+                _ => (),
+            }
+        }
+        Ok(meta.zip(value).map(|(meta, value)| Self {
+            mask,
+            value,
+            ..meta
+        }))
+    }
+}
+
+impl Ematch {
+    /// Equivalent to `not EXPR`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Emit the `TCA_EMATCH_TREE_*` attributes. Nested `and` and `or`
+    /// groups become containers pointing after the sequence they are part
+    /// of, like iproute2 does.
+    pub(crate) fn emit_tree(&self) -> Vec<u8> {
+        let mut list: Vec<(u16, u16, Vec<u8>)> = Vec::new();
+        emit_sequence(&mut list, self.sequence());
+        let count = list.len() as u16;
+        let nlas: Vec<DefaultNla> = list
+            .into_iter()
+            .enumerate()
+            .map(|(i, (kind, flags, data))| {
+                let mut payload = vec![0u8; EMATCH_HDR_LEN];
+                payload[2..4].copy_from_slice(&kind.to_ne_bytes());
+                payload[4..6].copy_from_slice(&flags.to_ne_bytes());
+                payload.extend(data);
+                DefaultNla::new(i as u16 + 1, payload)
+            })
+            .collect();
+        let mut hdr = vec![0u8; 4];
+        hdr[0..2].copy_from_slice(&count.to_ne_bytes());
+        emit_nlas(&[
+            DefaultNla::new(TCA_EMATCH_TREE_HDR, hdr),
+            DefaultNla::new(TCA_EMATCH_TREE_LIST, emit_nlas(&nlas)),
+        ])
+    }
+
+    pub(crate) fn parse_tree(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut list = Vec::new();
+        for (kind, value) in parse_nlas(payload)? {
+            if kind == TCA_EMATCH_TREE_LIST {
+                for (_, value) in parse_nlas(value)? {
+                    if value.len() < EMATCH_HDR_LEN {
+                        return Err(DecodeError::buffer_too_small(
+                            value.len(),
+                            EMATCH_HDR_LEN,
+                        ));
+                    }
+                    list.push((
+                        read_u16(value, 2)?,
+                        read_u16(value, 4)?,
+                        &value[EMATCH_HDR_LEN..],
+                    ));
+                }
+            }
+        }
+        if list.is_empty() {
+            return Err("empty ematch tree".into());
+        }
+        parse_sequence(&list, 0)
+    }
+
+    // The relation and the items of the sequence this expression is
+    // compiled to.
+    fn sequence(&self) -> (u16, Vec<&Ematch>) {
+        match self {
+            Self::And(items) => (TCF_EM_REL_AND, items.iter().collect()),
+            Self::Or(items) => (TCF_EM_REL_OR, items.iter().collect()),
+            other => (TCF_EM_REL_END, vec![other]),
+        }
+    }
+}
+
+fn emit_sequence(
+    list: &mut Vec<(u16, u16, Vec<u8>)>,
+    (relation, items): (u16, Vec<&Ematch>),
+) {
+    let start = list.len();
+    list.resize(start + items.len(), (0, 0, Vec::new()));
+    for (i, item) in items.iter().enumerate() {
+        let mut item = *item;
+        let mut flags = if i + 1 == items.len() {
+            TCF_EM_REL_END
+        } else {
+            relation
+        };
+        while let Ematch::Not(inner) = item {
+            flags ^= TCF_EM_INVERT;
+            item = inner;
+        }
+        let (kind, data) = match item {
+            Ematch::Cmp(cmp) => (TCF_EM_CMP, cmp.emit()),
+            Ematch::U32(key) => (TCF_EM_U32, key.emit()),
+            Ematch::Meta(meta) => (TCF_EM_META, meta.emit()),
+            Ematch::Other { kind, payload } => (*kind, payload.clone()),
+            Ematch::And(_) | Ematch::Or(_) | Ematch::Not(_) => {
+                let target = list.len() as u32;
+                emit_sequence(list, item.sequence());
+                (TCF_EM_CONTAINER, target.to_ne_bytes().to_vec())
+            }
+        };
+        list[start + i] = (kind, flags, data);
+    }
+}
+
+// The kernel stops evaluating a sequence at the first `and` relation of a
+// false match or the first `or` relation of a true match, hence
+// `a and b or c` is `a and (b or c)`.
+fn parse_sequence(
+    list: &[(u16, u16, &[u8])],
+    index: usize,
+) -> Result<Ematch, DecodeError> {
+    let (kind, flags, data) = list
+        .get(index)
+        .ok_or_else(|| format!("invalid ematch index {index}"))?;
+    let item = match *kind {
+        TCF_EM_CONTAINER => {
+            let target = parse_u32(data)? as usize;
+            // Containers may only point forward, which rules out loops.
+            if target <= index {
+                return Err(format!(
+                    "invalid ematch container target {target}"
+                )
+                .into());
+            }
+            parse_sequence(list, target)?
+        }
+        TCF_EM_CMP => Ematch::Cmp(EmatchCmp::parse(data)?),
+        TCF_EM_U32 => Ematch::U32(EmatchU32::parse(data)?),
+        TCF_EM_META => match EmatchMeta::parse(data)? {
+            Some(meta) => Ematch::Meta(meta),
+            None => Ematch::Other {
+                kind: *kind,
+                payload: data.to_vec(),
+            },
+        },
+        kind => Ematch::Other {
+            kind,
+            payload: data.to_vec(),
+        },
+    };
+    let item = if flags & TCF_EM_INVERT != 0 {
+        item.not()
+    } else {
+        item
+    };
+    Ok(match flags & TCF_EM_REL_MASK {
+        TCF_EM_REL_AND => join(item, parse_sequence(list, index + 1)?, true),
+        TCF_EM_REL_OR => join(item, parse_sequence(list, index + 1)?, false),
+        _ => item,
+    })
+}
+
+fn join(item: Ematch, rest: Ematch, and: bool) -> Ematch {
+    let mut items = vec![item];
+    match (rest, and) {
+        (Ematch::And(rest), true) | (Ematch::Or(rest), false) => {
+            items.extend(rest)
+        }
+        (rest, _) => items.push(rest),
+    }
+    if and {
+        Ematch::And(items)
+    } else {
+        Ematch::Or(items)
+    }
+}
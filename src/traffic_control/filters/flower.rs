@@ -0,0 +1,882 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_core::{parse_u32, DecodeError, NlasIterator, Parseable};
+use netlink_packet_route::tc::{
+    TcAction, TcAttribute, TcFilterFlowerOption, TcOption,
+};
+
+use super::{
+    numbered_actions, TCA_CLS_FLAGS_IN_HW, TCA_CLS_FLAGS_SKIP_HW,
+    TCA_CLS_FLAGS_SKIP_SW,
+};
+use crate::Error;
+
+const TCA_FLOWER_CLASSID: u16 = 1;
+const TCA_FLOWER_INDEV: u16 = 2;
+
+const IPPROTO_ICMP: u8 = 1;
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+const IPPROTO_ICMPV6: u8 = 58;
+const IPPROTO_SCTP: u8 = 132;
+
+const FULL_MAC: [u8; 6] = [0xff; 6];
+
+/// Options of the `flower` classifier.
+/// Equivalent to `tc filter add ... flower`.
+///
+/// Masked keys are `(value, mask)` pairs. Ports and ethernet types are in
+/// host byte order.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TrafficFilterFlower {
+    /// Class the matching packets are classified into.
+    pub classid: Option<u32>,
+    /// Do not process the filter in software.
+    pub skip_sw: bool,
+    /// Do not offload the filter to hardware.
+    pub skip_hw: bool,
+    /// Whether the filter is offloaded to hardware, only reported by the
+    /// kernel.
+    pub in_hw: bool,
+    /// Number of hardware devices the filter is offloaded to, only
+    /// reported by the kernel.
+    pub in_hw_count: Option<u32>,
+    pub eth_type: Option<u16>,
+    pub dst_mac: Option<([u8; 6], [u8; 6])>,
+    pub src_mac: Option<([u8; 6], [u8; 6])>,
+    pub vlan_id: Option<u16>,
+    pub vlan_prio: Option<u8>,
+    /// Ethernet type encapsulated in the VLAN header.
+    pub vlan_eth_type: Option<u16>,
+    /// Inner VLAN id of a QinQ packet.
+    pub cvlan_id: Option<u16>,
+    pub cvlan_prio: Option<u8>,
+    pub cvlan_eth_type: Option<u16>,
+    pub ip_proto: Option<u8>,
+    pub src_ip: Option<(IpAddr, IpAddr)>,
+    pub dst_ip: Option<(IpAddr, IpAddr)>,
+    pub ip_tos: Option<(u8, u8)>,
+    pub ip_ttl: Option<(u8, u8)>,
+    /// Fragmentation flags, see [Self::IP_FLAGS_FRAG] and
+    /// [Self::IP_FLAGS_FIRSTFRAG].
+    pub ip_flags: Option<(u32, u32)>,
+    pub src_port: Option<(u16, u16)>,
+    pub dst_port: Option<(u16, u16)>,
+    /// Inclusive `(min, max)` source port range.
+    pub src_port_range: Option<(u16, u16)>,
+    /// Inclusive `(min, max)` destination port range.
+    pub dst_port_range: Option<(u16, u16)>,
+    pub tcp_flags: Option<(u16, u16)>,
+    /// ICMP or ICMPv6 type, depending on `ip_proto`.
+    pub icmp_type: Option<(u8, u8)>,
+    /// ICMP or ICMPv6 code, depending on `ip_proto`.
+    pub icmp_code: Option<(u8, u8)>,
+    pub arp_op: Option<(u8, u8)>,
+    pub arp_sip: Option<(Ipv4Addr, Ipv4Addr)>,
+    pub arp_tip: Option<(Ipv4Addr, Ipv4Addr)>,
+    pub arp_sha: Option<([u8; 6], [u8; 6])>,
+    pub arp_tha: Option<([u8; 6], [u8; 6])>,
+    pub mpls_label: Option<u32>,
+    pub mpls_tc: Option<u8>,
+    pub mpls_bos: Option<u8>,
+    pub mpls_ttl: Option<u8>,
+    /// Tunnel key id, like the VNI of VXLAN.
+    pub enc_key_id: Option<u32>,
+    pub enc_src_ip: Option<(IpAddr, IpAddr)>,
+    pub enc_dst_ip: Option<(IpAddr, IpAddr)>,
+    pub enc_src_port: Option<(u16, u16)>,
+    pub enc_dst_port: Option<(u16, u16)>,
+    pub enc_tos: Option<(u8, u8)>,
+    pub enc_ttl: Option<(u8, u8)>,
+    /// Connection tracking state, see [Self::CT_STATE_NEW] and the
+    /// following constants.
+    pub ct_state: Option<(u16, u16)>,
+    pub ct_zone: Option<(u16, u16)>,
+    pub ct_mark: Option<(u32, u32)>,
+    pub actions: Vec<TcAction>,
+}
+
+impl TrafficFilterFlower {
+    pub const KIND: &'static str = "flower";
+
+    pub const IP_FLAGS_FRAG: u32 = 1 << 0;
+    pub const IP_FLAGS_FIRSTFRAG: u32 = 1 << 1;
+
+    pub const CT_STATE_NEW: u16 = 1 << 0;
+    pub const CT_STATE_ESTABLISHED: u16 = 1 << 1;
+    pub const CT_STATE_RELATED: u16 = 1 << 2;
+    pub const CT_STATE_TRACKED: u16 = 1 << 3;
+    pub const CT_STATE_INVALID: u16 = 1 << 4;
+    pub const CT_STATE_REPLY: u16 = 1 << 5;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `classid CLASSID`.
+    pub fn classid(mut self, classid: u32) -> Self {
+        self.classid = Some(classid);
+        self
+    }
+
+    /// Equivalent to `skip_sw`.
+    pub fn skip_sw(mut self) -> Self {
+        self.skip_sw = true;
+        self
+    }
+
+    /// Equivalent to `skip_hw`.
+    pub fn skip_hw(mut self) -> Self {
+        self.skip_hw = true;
+        self
+    }
+
+    /// Match the ethernet type, the filter protocol is set from it when
+    /// unset.
+    pub fn eth_type(mut self, eth_type: u16) -> Self {
+        self.eth_type = Some(eth_type);
+        self
+    }
+
+    /// Equivalent to `dst_mac MAC`.
+    pub fn dst_mac(self, mac: [u8; 6]) -> Self {
+        self.dst_mac_masked(mac, FULL_MAC)
+    }
+
+    /// Equivalent to `dst_mac MAC/MASK`.
+    pub fn dst_mac_masked(mut self, mac: [u8; 6], mask: [u8; 6]) -> Self {
+        self.dst_mac = Some((mac, mask));
+        self
+    }
+
+    /// Equivalent to `src_mac MAC`.
+    pub fn src_mac(self, mac: [u8; 6]) -> Self {
+        self.src_mac_masked(mac, FULL_MAC)
+    }
+
+    /// Equivalent to `src_mac MAC/MASK`.
+    pub fn src_mac_masked(mut self, mac: [u8; 6], mask: [u8; 6]) -> Self {
+        self.src_mac = Some((mac, mask));
+        self
+    }
+
+    /// Equivalent to `vlan_id VID`.
+    pub fn vlan_id(mut self, id: u16) -> Self {
+        self.vlan_id = Some(id);
+        self
+    }
+
+    /// Equivalent to `vlan_prio PRIORITY`.
+    pub fn vlan_prio(mut self, prio: u8) -> Self {
+        self.vlan_prio = Some(prio);
+        self
+    }
+
+    /// Equivalent to `vlan_ethtype ETH_TYPE`.
+    pub fn vlan_eth_type(mut self, eth_type: u16) -> Self {
+        self.vlan_eth_type = Some(eth_type);
+        self
+    }
+
+    /// Equivalent to `cvlan_id VID`.
+    pub fn cvlan_id(mut self, id: u16) -> Self {
+        self.cvlan_id = Some(id);
+        self
+    }
+
+    /// Equivalent to `cvlan_prio PRIORITY`.
+    pub fn cvlan_prio(mut self, prio: u8) -> Self {
+        self.cvlan_prio = Some(prio);
+        self
+    }
+
+    /// Equivalent to `cvlan_ethtype ETH_TYPE`.
+    pub fn cvlan_eth_type(mut self, eth_type: u16) -> Self {
+        self.cvlan_eth_type = Some(eth_type);
+        self
+    }
+
+    /// Equivalent to `ip_proto IP_PROTO`.
+    pub fn ip_proto(mut self, proto: u8) -> Self {
+        self.ip_proto = Some(proto);
+        self
+    }
+
+    /// Equivalent to `src_ip PREFIX`.
+    pub fn src_ip(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.src_ip = Some((addr, prefix_mask(addr, prefix_len)));
+        self
+    }
+
+    /// Equivalent to `dst_ip PREFIX`.
+    pub fn dst_ip(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.dst_ip = Some((addr, prefix_mask(addr, prefix_len)));
+        self
+    }
+
+    /// Equivalent to `ip_tos TOS/MASK`.
+    pub fn ip_tos(mut self, tos: u8, mask: u8) -> Self {
+        self.ip_tos = Some((tos, mask));
+        self
+    }
+
+    /// Equivalent to `ip_ttl TTL/MASK`.
+    pub fn ip_ttl(mut self, ttl: u8, mask: u8) -> Self {
+        self.ip_ttl = Some((ttl, mask));
+        self
+    }
+
+    /// Equivalent to `ip_flags IP_FLAGS`, flags in `mask` but not in
+    /// `flags` are matched as unset.
+    pub fn ip_flags(mut self, flags: u32, mask: u32) -> Self {
+        self.ip_flags = Some((flags, mask));
+        self
+    }
+
+    /// Equivalent to `src_port PORT`, requires `ip_proto` to be TCP, UDP or
+    /// SCTP.
+    pub fn src_port(self, port: u16) -> Self {
+        self.src_port_masked(port, u16::MAX)
+    }
+
+    /// Equivalent to `src_port PORT/MASK`.
+    pub fn src_port_masked(mut self, port: u16, mask: u16) -> Self {
+        self.src_port = Some((port, mask));
+        self
+    }
+
+    /// Equivalent to `dst_port PORT`, requires `ip_proto` to be TCP, UDP or
+    /// SCTP.
+    pub fn dst_port(self, port: u16) -> Self {
+        self.dst_port_masked(port, u16::MAX)
+    }
+
+    /// Equivalent to `dst_port PORT/MASK`.
+    pub fn dst_port_masked(mut self, port: u16, mask: u16) -> Self {
+        self.dst_port = Some((port, mask));
+        self
+    }
+
+    /// Equivalent to `src_port MIN-MAX`.
+    pub fn src_port_range(mut self, min: u16, max: u16) -> Self {
+        self.src_port_range = Some((min, max));
+        self
+    }
+
+    /// Equivalent to `dst_port MIN-MAX`.
+    pub fn dst_port_range(mut self, min: u16, max: u16) -> Self {
+        self.dst_port_range = Some((min, max));
+        self
+    }
+
+    /// Equivalent to `tcp_flags FLAGS/MASK`, requires `ip_proto` to be TCP.
+    pub fn tcp_flags(mut self, flags: u16, mask: u16) -> Self {
+        self.tcp_flags = Some((flags, mask));
+        self
+    }
+
+    /// Equivalent to `type TYPE`, requires `ip_proto` to be ICMP or ICMPv6.
+    pub fn icmp_type(mut self, icmp_type: u8) -> Self {
+        self.icmp_type = Some((icmp_type, u8::MAX));
+        self
+    }
+
+    /// Equivalent to `code CODE`, requires `ip_proto` to be ICMP or ICMPv6.
+    pub fn icmp_code(mut self, code: u8) -> Self {
+        self.icmp_code = Some((code, u8::MAX));
+        self
+    }
+
+    /// Equivalent to `arp_op OP`.
+    pub fn arp_op(mut self, op: u8) -> Self {
+        self.arp_op = Some((op, u8::MAX));
+        self
+    }
+
+    /// Equivalent to `arp_sip PREFIX`.
+    pub fn arp_sip(mut self, addr: Ipv4Addr, prefix_len: u8) -> Self {
+        self.arp_sip = Some((addr, ipv4_mask(prefix_len)));
+        self
+    }
+
+    /// Equivalent to `arp_tip PREFIX`.
+    pub fn arp_tip(mut self, addr: Ipv4Addr, prefix_len: u8) -> Self {
+        self.arp_tip = Some((addr, ipv4_mask(prefix_len)));
+        self
+    }
+
+    /// Equivalent to `arp_sha MAC`.
+    pub fn arp_sha(mut self, mac: [u8; 6]) -> Self {
+        self.arp_sha = Some((mac, FULL_MAC));
+        self
+    }
+
+    /// Equivalent to `arp_tha MAC`.
+    pub fn arp_tha(mut self, mac: [u8; 6]) -> Self {
+        self.arp_tha = Some((mac, FULL_MAC));
+        self
+    }
+
+    /// Equivalent to `mpls_label LABEL`.
+    pub fn mpls_label(mut self, label: u32) -> Self {
+        self.mpls_label = Some(label);
+        self
+    }
+
+    /// Equivalent to `mpls_tc TC`.
+    pub fn mpls_tc(mut self, tc: u8) -> Self {
+        self.mpls_tc = Some(tc);
+        self
+    }
+
+    /// Equivalent to `mpls_bos BOS`.
+    pub fn mpls_bos(mut self, bos: u8) -> Self {
+        self.mpls_bos = Some(bos);
+        self
+    }
+
+    /// Equivalent to `mpls_ttl TTL`.
+    pub fn mpls_ttl(mut self, ttl: u8) -> Self {
+        self.mpls_ttl = Some(ttl);
+        self
+    }
+
+    /// Equivalent to `enc_key_id KEY_ID`.
+    pub fn enc_key_id(mut self, key_id: u32) -> Self {
+        self.enc_key_id = Some(key_id);
+        self
+    }
+
+    /// Equivalent to `enc_src_ip PREFIX`.
+    pub fn enc_src_ip(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.enc_src_ip = Some((addr, prefix_mask(addr, prefix_len)));
+        self
+    }
+
+    /// Equivalent to `enc_dst_ip PREFIX`.
+    pub fn enc_dst_ip(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.enc_dst_ip = Some((addr, prefix_mask(addr, prefix_len)));
+        self
+    }
+
+    /// Equivalent to `enc_src_port PORT`.
+    pub fn enc_src_port(mut self, port: u16) -> Self {
+        self.enc_src_port = Some((port, u16::MAX));
+        self
+    }
+
+    /// Equivalent to `enc_dst_port PORT`.
+    pub fn enc_dst_port(mut self, port: u16) -> Self {
+        self.enc_dst_port = Some((port, u16::MAX));
+        self
+    }
+
+    /// Equivalent to `enc_tos TOS/MASK`.
+    pub fn enc_tos(mut self, tos: u8, mask: u8) -> Self {
+        self.enc_tos = Some((tos, mask));
+        self
+    }
+
+    /// Equivalent to `enc_ttl TTL/MASK`.
+    pub fn enc_ttl(mut self, ttl: u8, mask: u8) -> Self {
+        self.enc_ttl = Some((ttl, mask));
+        self
+    }
+
+    /// Equivalent to `ct_state CT_STATE`, states in `mask` but not in
+    /// `state` are matched as unset.
+    pub fn ct_state(mut self, state: u16, mask: u16) -> Self {
+        self.ct_state = Some((state, mask));
+        self
+    }
+
+    /// Equivalent to `ct_zone ZONE`.
+    pub fn ct_zone(mut self, zone: u16) -> Self {
+        self.ct_zone = Some((zone, u16::MAX));
+        self
+    }
+
+    /// Equivalent to `ct_mark MARK/MASK`.
+    pub fn ct_mark(mut self, mark: u32, mask: u32) -> Self {
+        self.ct_mark = Some((mark, mask));
+        self
+    }
+
+    /// Append an action run on the matching packets.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    // Checks the keys which depend on `ip_proto`, the kernel silently
+    // ignores them otherwise, and that each address and its mask belong to
+    // the same family.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for (name, key) in [
+            ("src_ip", self.src_ip),
+            ("dst_ip", self.dst_ip),
+            ("enc_src_ip", self.enc_src_ip),
+            ("enc_dst_ip", self.enc_dst_ip),
+        ] {
+            if let Some((addr, mask)) = key {
+                if addr.is_ipv4() != mask.is_ipv4() {
+                    return Err(Error::InvalidNla(format!(
+                        "flower {name} address {addr} and mask {mask} \
+                         are of different families"
+                    )));
+                }
+            }
+        }
+        let proto = self.ip_proto.unwrap_or_default();
+        let has_ports = self.src_port.is_some()
+            || self.dst_port.is_some()
+            || self.src_port_range.is_some()
+            || self.dst_port_range.is_some();
+        if has_ports
+            && !matches!(proto, IPPROTO_TCP | IPPROTO_UDP | IPPROTO_SCTP)
+        {
+            return Err(Error::InvalidNla(
+                "flower ports require ip_proto tcp, udp or sctp".to_string(),
+            ));
+        }
+        if self.tcp_flags.is_some() && proto != IPPROTO_TCP {
+            return Err(Error::InvalidNla(
+                "flower tcp_flags require ip_proto tcp".to_string(),
+            ));
+        }
+        if (self.icmp_type.is_some() || self.icmp_code.is_some())
+            && !matches!(proto, IPPROTO_ICMP | IPPROTO_ICMPV6)
+        {
+            return Err(Error::InvalidNla(
+                "flower icmp type and code require ip_proto icmp or icmpv6"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        use TcFilterFlowerOption as O;
+
+        let mut opts = Vec::new();
+        if let Some(classid) = self.classid {
+            opts.push(O::ClassId(classid));
+        }
+        if let Some((mac, mask)) = self.dst_mac {
+            opts.push(O::EthDst(mac));
+            opts.push(O::EthDstMask(mask));
+        }
+        if let Some((mac, mask)) = self.src_mac {
+            opts.push(O::EthSrc(mac));
+            opts.push(O::EthSrcMask(mask));
+        }
+        if let Some(id) = self.vlan_id {
+            opts.push(O::VlanId(id));
+        }
+        if let Some(prio) = self.vlan_prio {
+            opts.push(O::VlanPrio(prio));
+        }
+        if let Some(eth_type) = self.vlan_eth_type {
+            opts.push(O::VlanEthType(eth_type));
+        }
+        if let Some(id) = self.cvlan_id {
+            opts.push(O::CvlanId(id));
+        }
+        if let Some(prio) = self.cvlan_prio {
+            opts.push(O::CvlanPrio(prio));
+        }
+        if let Some(eth_type) = self.cvlan_eth_type {
+            opts.push(O::CvlanEthType(eth_type));
+        }
+        if let Some(proto) = self.ip_proto {
+            opts.push(O::IpProto(proto));
+        }
+        if let Some((addr, mask)) = self.src_ip {
+            match (addr, mask) {
+                (IpAddr::V4(addr), IpAddr::V4(mask)) => {
+                    opts.push(O::Ipv4Src(addr));
+                    opts.push(O::Ipv4SrcMask(mask));
+                }
+                (IpAddr::V6(addr), IpAddr::V6(mask)) => {
+                    opts.push(O::Ipv6Src(addr));
+                    opts.push(O::Ipv6SrcMask(mask));
+                }
+                // Mixed families are rejected by `validate()`.
+                _ => (),
+            }
+        }
+        if let Some((addr, mask)) = self.dst_ip {
+            match (addr, mask) {
+                (IpAddr::V4(addr), IpAddr::V4(mask)) => {
+                    opts.push(O::Ipv4Dst(addr));
+                    opts.push(O::Ipv4DstMask(mask));
+                }
+                (IpAddr::V6(addr), IpAddr::V6(mask)) => {
+                    opts.push(O::Ipv6Dst(addr));
+                    opts.push(O::Ipv6DstMask(mask));
+                }
+                // Mixed families are rejected by `validate()`.
+                _ => (),
+            }
+        }
+        if let Some((tos, mask)) = self.ip_tos {
+            opts.push(O::IpTos(tos));
+            opts.push(O::IpTosMask(mask));
+        }
+        if let Some((ttl, mask)) = self.ip_ttl {
+            opts.push(O::IpTtl(ttl));
+            opts.push(O::IpTtlMask(mask));
+        }
+        if let Some((port, mask)) = self.src_port {
+            let keys = match self.ip_proto {
+                Some(IPPROTO_UDP) => [O::UdpSrc(port), O::UdpSrcMask(mask)],
+                Some(IPPROTO_SCTP) => [O::SctpSrc(port), O::SctpSrcMask(mask)],
+                _ => [O::TcpSrc(port), O::TcpSrcMask(mask)],
+            };
+            let len = if mask == u16::MAX { 1 } else { 2 };
+            opts.extend(keys.into_iter().take(len));
+        }
+        if let Some((port, mask)) = self.dst_port {
+            let keys = match self.ip_proto {
+                Some(IPPROTO_UDP) => [O::UdpDst(port), O::UdpDstMask(mask)],
+                Some(IPPROTO_SCTP) => [O::SctpDst(port), O::SctpDstMask(mask)],
+                _ => [O::TcpDst(port), O::TcpDstMask(mask)],
+            };
+            let len = if mask == u16::MAX { 1 } else { 2 };
+            opts.extend(keys.into_iter().take(len));
+        }
+        if let Some((min, max)) = self.src_port_range {
+            opts.push(O::PortSrcMin(min));
+            opts.push(O::PortSrcMax(max));
+        }
+        if let Some((min, max)) = self.dst_port_range {
+            opts.push(O::PortDstMin(min));
+            opts.push(O::PortDstMax(max));
+        }
+        if let Some((flags, mask)) = self.tcp_flags {
+            opts.push(O::TcpFlags(flags));
+            opts.push(O::TcpFlagsMask(mask));
+        }
+        let icmpv6 = self.ip_proto == Some(IPPROTO_ICMPV6);
+        if let Some((icmp_type, mask)) = self.icmp_type {
+            if icmpv6 {
+                opts.push(O::Icmpv6Type(icmp_type));
+                opts.push(O::Icmpv6TypeMask(mask));
+            } else {
+                opts.push(O::Icmpv4Type(icmp_type));
+                opts.push(O::Icmpv4TypeMask(mask));
+            }
+        }
+        if let Some((code, mask)) = self.icmp_code {
+            if icmpv6 {
+                opts.push(O::Icmpv6Code(code));
+                opts.push(O::Icmpv6CodeMask(mask));
+            } else {
+                opts.push(O::Icmpv4Code(code));
+                opts.push(O::Icmpv4CodeMask(mask));
+            }
+        }
+        if let Some((op, mask)) = self.arp_op {
+            opts.push(O::ArpOp(op));
+            opts.push(O::ArpOpMask(mask));
+        }
+        if let Some((addr, mask)) = self.arp_sip {
+            opts.push(O::ArpSip(addr));
+            opts.push(O::ArpSipMask(mask));
+        }
+        if let Some((addr, mask)) = self.arp_tip {
+            opts.push(O::ArpTip(addr));
+            opts.push(O::ArpTipMask(mask));
+        }
+        if let Some((mac, mask)) = self.arp_sha {
+            opts.push(O::ArpSha(mac));
+            opts.push(O::ArpShaMask(mask));
+        }
+        if let Some((mac, mask)) = self.arp_tha {
+            opts.push(O::ArpTha(mac));
+            opts.push(O::ArpThaMask(mask));
+        }
+        if let Some(label) = self.mpls_label {
+            opts.push(O::MplsLabel(label));
+        }
+        if let Some(tc) = self.mpls_tc {
+            opts.push(O::MplsTc(tc));
+        }
+        if let Some(bos) = self.mpls_bos {
+            opts.push(O::MplsBos(bos));
+        }
+        if let Some(ttl) = self.mpls_ttl {
+            opts.push(O::MplsTtl(ttl));
+        }
+        if let Some(key_id) = self.enc_key_id {
+            opts.push(O::EncKeyId(key_id));
+        }
+        if let Some((addr, mask)) = self.enc_src_ip {
+            match (addr, mask) {
+                (IpAddr::V4(addr), IpAddr::V4(mask)) => {
+                    opts.push(O::EncKeyIpv4Src(addr));
+                    opts.push(O::EncKeyIpv4SrcMask(mask));
+                }
+                (IpAddr::V6(addr), IpAddr::V6(mask)) => {
+                    opts.push(O::EncKeyIpv6Src(addr));
+                    opts.push(O::EncKeyIpv6SrcMask(mask));
+                }
+                // Mixed families are rejected by `validate()`.
+                _ => (),
+            }
+        }
+        if let Some((addr, mask)) = self.enc_dst_ip {
+            match (addr, mask) {
+                (IpAddr::V4(addr), IpAddr::V4(mask)) => {
+                    opts.push(O::EncKeyIpv4Dst(addr));
+                    opts.push(O::EncKeyIpv4DstMask(mask));
+                }
+                (IpAddr::V6(addr), IpAddr::V6(mask)) => {
+                    opts.push(O::EncKeyIpv6Dst(addr));
+                    opts.push(O::EncKeyIpv6DstMask(mask));
+                }
+                // Mixed families are rejected by `validate()`.
+                _ => (),
+            }
+        }
+        if let Some((port, mask)) = self.enc_src_port {
+            opts.push(O::EncKeyUdpSrcPort(port));
+            if mask != u16::MAX {
+                opts.push(O::EncKeyUdpSrcPortMask(mask));
+            }
+        }
+        if let Some((port, mask)) = self.enc_dst_port {
+            opts.push(O::EncKeyUdpDstPort(port));
+            if mask != u16::MAX {
+                opts.push(O::EncKeyUdpDstPortMask(mask));
+            }
+        }
+        if let Some((tos, mask)) = self.enc_tos {
+            opts.push(O::EncKeyIpTos(tos));
+            opts.push(O::EncKeyIpTosMask(mask));
+        }
+        if let Some((ttl, mask)) = self.enc_ttl {
+            opts.push(O::EncKeyIpTtl(ttl));
+            opts.push(O::EncKeyIpTtlMask(mask));
+        }
+        if let Some((state, mask)) = self.ct_state {
+            opts.push(O::CtState(state));
+            opts.push(O::CtStateMask(mask));
+        }
+        if let Some((zone, mask)) = self.ct_zone {
+            opts.push(O::CtZone(zone));
+            opts.push(O::CtZoneMask(mask));
+        }
+        if let Some((mark, mask)) = self.ct_mark {
+            opts.push(O::CtMark(mark));
+            opts.push(O::CtMarkMask(mask));
+        }
+        if let Some((flags, mask)) = self.ip_flags {
+            opts.push(O::KeyFlags(flags));
+            opts.push(O::KeyFlagsMask(mask));
+        }
+        if !self.actions.is_empty() {
+            opts.push(O::Actions(numbered_actions(&self.actions)));
+        }
+        let mut flags = 0;
+        if self.skip_hw {
+            flags |= TCA_CLS_FLAGS_SKIP_HW;
+        }
+        if self.skip_sw {
+            flags |= TCA_CLS_FLAGS_SKIP_SW;
+        }
+        opts.push(O::Flags(flags));
+        if let Some(eth_type) = self.eth_type {
+            opts.push(O::EthType(eth_type));
+        }
+        TcAttribute::Options(opts.into_iter().map(TcOption::Flower).collect())
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        use TcFilterFlowerOption as O;
+
+        let mut flower = Self::default();
+        // The kernel dumps masks right after the values of the keys which
+        // are set.
+        let mut ip4: [Option<(Ipv4Addr, Ipv4Addr)>; 4] = Default::default();
+        let mut ip6: [Option<(Ipv6Addr, Ipv6Addr)>; 4] = Default::default();
+        for nla in NlasIterator::new(payload) {
+            let nla = nla?;
+            match nla.kind() {
+                TCA_FLOWER_CLASSID => {
+                    flower.classid = Some(parse_u32(nla.value())?);
+                    continue;
+                }
+                // The input device is a string, which netlink-packet-route
+                // fails to parse.
+                TCA_FLOWER_INDEV => continue,
+                _ => (),
+            }
+            match O::parse(&nla)? {
+                O::Flags(flags) => {
+                    flower.skip_hw = flags & TCA_CLS_FLAGS_SKIP_HW != 0;
+                    flower.skip_sw = flags & TCA_CLS_FLAGS_SKIP_SW != 0;
+                    flower.in_hw = flags & TCA_CLS_FLAGS_IN_HW != 0;
+                }
+                O::InHwCount(count) => flower.in_hw_count = Some(count),
+                O::Actions(actions) => flower.actions = actions,
+                O::EthType(v) => flower.eth_type = Some(v),
+                O::EthDst(v) => set_value(&mut flower.dst_mac, v, FULL_MAC),
+                O::EthDstMask(m) => set_mask(&mut flower.dst_mac, m),
+                O::EthSrc(v) => set_value(&mut flower.src_mac, v, FULL_MAC),
+                O::EthSrcMask(m) => set_mask(&mut flower.src_mac, m),
+                O::VlanId(v) => flower.vlan_id = Some(v),
+                O::VlanPrio(v) => flower.vlan_prio = Some(v),
+                O::VlanEthType(v) => flower.vlan_eth_type = Some(v),
+                O::CvlanId(v) => flower.cvlan_id = Some(v),
+                O::CvlanPrio(v) => flower.cvlan_prio = Some(v),
+                O::CvlanEthType(v) => flower.cvlan_eth_type = Some(v),
+                O::IpProto(v) => flower.ip_proto = Some(v),
+                O::Ipv4Src(v) => set_value(&mut ip4[0], v, Ipv4Addr::BROADCAST),
+                O::Ipv4SrcMask(m) => set_mask(&mut ip4[0], m),
+                O::Ipv4Dst(v) => set_value(&mut ip4[1], v, Ipv4Addr::BROADCAST),
+                O::Ipv4DstMask(m) => set_mask(&mut ip4[1], m),
+                O::Ipv6Src(v) => set_value(&mut ip6[0], v, IPV6_FULL),
+                O::Ipv6SrcMask(m) => set_mask(&mut ip6[0], m),
+                O::Ipv6Dst(v) => set_value(&mut ip6[1], v, IPV6_FULL),
+                O::Ipv6DstMask(m) => set_mask(&mut ip6[1], m),
+                O::IpTos(v) => set_value(&mut flower.ip_tos, v, u8::MAX),
+                O::IpTosMask(m) => set_mask(&mut flower.ip_tos, m),
+                O::IpTtl(v) => set_value(&mut flower.ip_ttl, v, u8::MAX),
+                O::IpTtlMask(m) => set_mask(&mut flower.ip_ttl, m),
+                O::KeyFlags(v) => set_value(&mut flower.ip_flags, v, u32::MAX),
+                O::KeyFlagsMask(m) => set_mask(&mut flower.ip_flags, m),
+                O::TcpSrc(v) | O::UdpSrc(v) | O::SctpSrc(v) => {
+                    set_value(&mut flower.src_port, v, u16::MAX)
+                }
+                O::TcpSrcMask(m) | O::UdpSrcMask(m) | O::SctpSrcMask(m) => {
+                    set_mask(&mut flower.src_port, m)
+                }
+                O::TcpDst(v) | O::UdpDst(v) | O::SctpDst(v) => {
+                    set_value(&mut flower.dst_port, v, u16::MAX)
+                }
+                O::TcpDstMask(m) | O::UdpDstMask(m) | O::SctpDstMask(m) => {
+                    set_mask(&mut flower.dst_port, m)
+                }
+                O::PortSrcMin(v) => set_value(&mut flower.src_port_range, v, 0),
+                O::PortSrcMax(v) => set_mask(&mut flower.src_port_range, v),
+                O::PortDstMin(v) => set_value(&mut flower.dst_port_range, v, 0),
+                O::PortDstMax(v) => set_mask(&mut flower.dst_port_range, v),
+                O::TcpFlags(v) => set_value(&mut flower.tcp_flags, v, u16::MAX),
+                O::TcpFlagsMask(m) => set_mask(&mut flower.tcp_flags, m),
+                O::Icmpv4Type(v) | O::Icmpv6Type(v) => {
+                    set_value(&mut flower.icmp_type, v, u8::MAX)
+                }
+                O::Icmpv4TypeMask(m) | O::Icmpv6TypeMask(m) => {
+                    set_mask(&mut flower.icmp_type, m)
+                }
+                O::Icmpv4Code(v) | O::Icmpv6Code(v) => {
+                    set_value(&mut flower.icmp_code, v, u8::MAX)
+                }
+                O::Icmpv4CodeMask(m) | O::Icmpv6CodeMask(m) => {
+                    set_mask(&mut flower.icmp_code, m)
+                }
+                O::ArpOp(v) => set_value(&mut flower.arp_op, v, u8::MAX),
+                O::ArpOpMask(m) => set_mask(&mut flower.arp_op, m),
+                O::ArpSip(v) => {
+                    set_value(&mut flower.arp_sip, v, Ipv4Addr::BROADCAST)
+                }
+                O::ArpSipMask(m) => set_mask(&mut flower.arp_sip, m),
+                O::ArpTip(v) => {
+                    set_value(&mut flower.arp_tip, v, Ipv4Addr::BROADCAST)
+                }
+                O::ArpTipMask(m) => set_mask(&mut flower.arp_tip, m),
+                O::ArpSha(v) => set_value(&mut flower.arp_sha, v, FULL_MAC),
+                O::ArpShaMask(m) => set_mask(&mut flower.arp_sha, m),
+                O::ArpTha(v) => set_value(&mut flower.arp_tha, v, FULL_MAC),
+                O::ArpThaMask(m) => set_mask(&mut flower.arp_tha, m),
+                O::MplsLabel(v) => flower.mpls_label = Some(v),
+                O::MplsTc(v) => flower.mpls_tc = Some(v),
+                O::MplsBos(v) => flower.mpls_bos = Some(v),
+                O::MplsTtl(v) => flower.mpls_ttl = Some(v),
+                O::EncKeyId(v) => flower.enc_key_id = Some(v),
+                O::EncKeyIpv4Src(v) => {
+                    set_value(&mut ip4[2], v, Ipv4Addr::BROADCAST)
+                }
+                O::EncKeyIpv4SrcMask(m) => set_mask(&mut ip4[2], m),
+                O::EncKeyIpv4Dst(v) => {
+                    set_value(&mut ip4[3], v, Ipv4Addr::BROADCAST)
+                }
+                O::EncKeyIpv4DstMask(m) => set_mask(&mut ip4[3], m),
+                O::EncKeyIpv6Src(v) => set_value(&mut ip6[2], v, IPV6_FULL),
+                O::EncKeyIpv6SrcMask(m) => set_mask(&mut ip6[2], m),
+                O::EncKeyIpv6Dst(v) => set_value(&mut ip6[3], v, IPV6_FULL),
+                O::EncKeyIpv6DstMask(m) => set_mask(&mut ip6[3], m),
+                O::EncKeyUdpSrcPort(v) => {
+                    set_value(&mut flower.enc_src_port, v, u16::MAX)
+                }
+                O::EncKeyUdpSrcPortMask(m) => {
+                    set_mask(&mut flower.enc_src_port, m)
+                }
+                O::EncKeyUdpDstPort(v) => {
+                    set_value(&mut flower.enc_dst_port, v, u16::MAX)
+                }
+                O::EncKeyUdpDstPortMask(m) => {
+                    set_mask(&mut flower.enc_dst_port, m)
+                }
+                O::EncKeyIpTos(v) => set_value(&mut flower.enc_tos, v, u8::MAX),
+                O::EncKeyIpTosMask(m) => set_mask(&mut flower.enc_tos, m),
+                O::EncKeyIpTtl(v) => set_value(&mut flower.enc_ttl, v, u8::MAX),
+                O::EncKeyIpTtlMask(m) => set_mask(&mut flower.enc_ttl, m),
+                O::CtState(v) => set_value(&mut flower.ct_state, v, u16::MAX),
+                O::CtStateMask(m) => set_mask(&mut flower.ct_state, m),
+                O::CtZone(v) => set_value(&mut flower.ct_zone, v, u16::MAX),
+                O::CtZoneMask(m) => set_mask(&mut flower.ct_zone, m),
+                O::CtMark(v) => set_value(&mut flower.ct_mark, v, u32::MAX),
+                O::CtMarkMask(m) => set_mask(&mut flower.ct_mark, m),
+                _ => (),
+            }
+        }
+        let ip = |v4: Option<(Ipv4Addr, Ipv4Addr)>,
+                  v6: Option<(Ipv6Addr, Ipv6Addr)>| {
+            v4.map(|(a, m)| (a.into(), m.into()))
+                .or_else(|| v6.map(|(a, m)| (a.into(), m.into())))
+        };
+        flower.src_ip = ip(ip4[0], ip6[0]);
+        flower.dst_ip = ip(ip4[1], ip6[1]);
+        flower.enc_src_ip = ip(ip4[2], ip6[2]);
+        flower.enc_dst_ip = ip(ip4[3], ip6[3]);
+        Ok(flower)
+    }
+}
+
+const IPV6_FULL: Ipv6Addr = Ipv6Addr::new(
+    0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff,
+);
+
+fn set_value<T: Copy>(key: &mut Option<(T, T)>, value: T, full_mask: T) {
+    let mask = key.map(|(_, mask)| mask).unwrap_or(full_mask);
+    *key = Some((value, mask));
+}
+
+// A mask is meaningless without the value it applies to.
+fn set_mask<T>(key: &mut Option<(T, T)>, mask: T) {
+    if let Some((_, key_mask)) = key {
+        *key_mask = mask;
+    }
+}
+
+fn ipv4_mask(prefix_len: u8) -> Ipv4Addr {
+    match prefix_len {
+        0 => Ipv4Addr::UNSPECIFIED,
+        len => Ipv4Addr::from(u32::MAX << (32 - u32::from(len.min(32)))),
+    }
+}
+
+fn prefix_mask(addr: IpAddr, prefix_len: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(_) => ipv4_mask(prefix_len).into(),
+        IpAddr::V6(_) => match prefix_len {
+            0 => Ipv6Addr::UNSPECIFIED.into(),
+            len => Ipv6Addr::from(u128::MAX << (128 - u32::from(len.min(128))))
+                .into(),
+        },
+    }
+}
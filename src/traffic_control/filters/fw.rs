@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_string, parse_u32, DecodeError, DefaultNla};
+use netlink_packet_route::tc::{TcAction, TcAttribute};
+
+use super::{actions_nla, parse_actions};
+use crate::traffic_control::nla::{nested_options, nla_u32, parse_nlas};
+
+const TCA_FW_CLASSID: u16 = 1;
+const TCA_FW_INDEV: u16 = 3;
+const TCA_FW_ACT: u16 = 4;
+const TCA_FW_MASK: u16 = 5;
+
+/// Options of the `fw` classifier, which matches the firewall mark of the
+/// packets against the filter handle, see
+/// [crate::TrafficFilterNewRequest::handle].
+/// Equivalent to `tc filter add ... handle FWMARK[/MASK] fw`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TrafficFilterFw {
+    /// Class the matching packets are classified into.
+    pub classid: Option<u32>,
+    /// Mask applied to the firewall mark before comparing it with the
+    /// handle. The kernel uses `0xffffffff` when unset.
+    pub mask: Option<u32>,
+    /// Only match the packets received on this device.
+    pub indev: Option<String>,
+    pub actions: Vec<TcAction>,
+}
+
+impl TrafficFilterFw {
+    pub const KIND: &'static str = "fw";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `classid CLASSID`.
+    pub fn classid(mut self, classid: u32) -> Self {
+        self.classid = Some(classid);
+        self
+    }
+
+    /// Equivalent to the `/MASK` part of `handle FWMARK/MASK`.
+    pub fn mask(mut self, mask: u32) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Equivalent to `indev DEV`.
+    pub fn indev(mut self, indev: &str) -> Self {
+        self.indev = Some(indev.to_string());
+        self
+    }
+
+    /// Append an action run on the matching packets.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut nlas = Vec::new();
+        if let Some(mask) = self.mask {
+            nlas.push(nla_u32(TCA_FW_MASK, mask));
+        }
+        if let Some(classid) = self.classid {
+            nlas.push(nla_u32(TCA_FW_CLASSID, classid));
+        }
+        if let Some(indev) = &self.indev {
+            let mut value = indev.as_bytes().to_vec();
+            value.push(0);
+            nlas.push(DefaultNla::new(TCA_FW_INDEV, value));
+        }
+        if !self.actions.is_empty() {
+            nlas.push(actions_nla(TCA_FW_ACT, &self.actions));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut fw = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_FW_CLASSID => fw.classid = Some(parse_u32(value)?),
+                TCA_FW_MASK => fw.mask = Some(parse_u32(value)?),
+                TCA_FW_INDEV => fw.indev = Some(parse_string(value)?),
+                TCA_FW_ACT => fw.actions = parse_actions(value)?,
+                _ => (),
+            }
+        }
+        Ok(fw)
+    }
+}
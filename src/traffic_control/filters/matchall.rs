@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, NlasIterator, Parseable};
+use netlink_packet_route::tc::{
+    TcAction, TcAttribute, TcFilterMatchAllOption, TcOption,
+};
+
+use super::{
+    numbered_actions, TCA_CLS_FLAGS_IN_HW, TCA_CLS_FLAGS_SKIP_HW,
+    TCA_CLS_FLAGS_SKIP_SW,
+};
+
+/// Options of the `matchall` classifier, which matches every packet.
+/// Equivalent to `tc filter add ... matchall`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TrafficFilterMatchall {
+    /// Class the packets are classified into.
+    pub classid: Option<u32>,
+    /// Do not process the filter in software.
+    pub skip_sw: bool,
+    /// Do not offload the filter to hardware.
+    pub skip_hw: bool,
+    /// Whether the filter is offloaded to hardware, only reported by the
+    /// kernel.
+    pub in_hw: bool,
+    pub actions: Vec<TcAction>,
+}
+
+impl TrafficFilterMatchall {
+    pub const KIND: &'static str = "matchall";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `classid CLASSID`.
+    pub fn classid(mut self, classid: u32) -> Self {
+        self.classid = Some(classid);
+        self
+    }
+
+    /// Equivalent to `skip_sw`.
+    pub fn skip_sw(mut self) -> Self {
+        self.skip_sw = true;
+        self
+    }
+
+    /// Equivalent to `skip_hw`.
+    pub fn skip_hw(mut self) -> Self {
+        self.skip_hw = true;
+        self
+    }
+
+    /// Append an action run on every packet.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        use TcFilterMatchAllOption as O;
+
+        let mut opts = Vec::new();
+        if let Some(classid) = self.classid {
+            opts.push(O::ClassId(classid.into()));
+        }
+        if !self.actions.is_empty() {
+            opts.push(O::Action(numbered_actions(&self.actions)));
+        }
+        let mut flags = 0;
+        if self.skip_hw {
+            flags |= TCA_CLS_FLAGS_SKIP_HW;
+        }
+        if self.skip_sw {
+            flags |= TCA_CLS_FLAGS_SKIP_SW;
+        }
+        if flags != 0 {
+            opts.push(O::Flags(flags));
+        }
+        TcAttribute::Options(opts.into_iter().map(TcOption::MatchAll).collect())
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        use TcFilterMatchAllOption as O;
+
+        let mut matchall = Self::default();
+        for nla in NlasIterator::new(payload) {
+            match O::parse(&nla?)? {
+                O::ClassId(classid) => matchall.classid = Some(classid.into()),
+                O::Action(actions) => matchall.actions = actions,
+                O::Flags(flags) => {
+                    matchall.skip_hw = flags & TCA_CLS_FLAGS_SKIP_HW != 0;
+                    matchall.skip_sw = flags & TCA_CLS_FLAGS_SKIP_SW != 0;
+                    matchall.in_hw = flags & TCA_CLS_FLAGS_IN_HW != 0;
+                }
+                _ => (),
+            }
+        }
+        Ok(matchall)
+    }
+}
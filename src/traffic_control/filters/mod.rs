@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MIT
+
+mod basic;
+mod bpf;
+mod ematch;
+mod flower;
+mod fw;
+mod matchall;
+mod route;
+#[cfg(test)]
+mod test;
+mod u32;
+
+use netlink_packet_core::{
+    DecodeError, DefaultNla, Emitable, NlasIterator, Parseable,
+};
+use netlink_packet_route::tc::TcAction;
+
+use super::actions::numbered_actions;
+
+pub use self::{
+    basic::TrafficFilterBasic,
+    bpf::TrafficFilterBpf,
+    ematch::{
+        Ematch, EmatchCmp, EmatchCmpAlign, EmatchLayer, EmatchMeta,
+        EmatchMetaId, EmatchOperand, EmatchU32,
+    },
+    flower::TrafficFilterFlower,
+    fw::TrafficFilterFw,
+    matchall::TrafficFilterMatchall,
+    route::TrafficFilterRoute,
+    u32::{TrafficFilterU32, U32Match},
+};
+
+const TCA_CLS_FLAGS_SKIP_HW: u32 = 1 << 0;
+const TCA_CLS_FLAGS_SKIP_SW: u32 = 1 << 1;
+const TCA_CLS_FLAGS_IN_HW: u32 = 1 << 2;
+
+/// Decoded options of a traffic filter, see
+/// [crate::TcMessageExt::filter_options].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
+pub enum TrafficFilterOptions {
+    Flower(TrafficFilterFlower),
+    Matchall(TrafficFilterMatchall),
+    Fw(TrafficFilterFw),
+    Route(TrafficFilterRoute),
+    Basic(TrafficFilterBasic),
+    Bpf(TrafficFilterBpf),
+    /// Filter kind without typed options.
+    Other(String),
+}
+
+impl TrafficFilterOptions {
+    pub(crate) fn parse(
+        kind: &str,
+        payload: &[u8],
+    ) -> Result<Self, DecodeError> {
+        Ok(match kind {
+            TrafficFilterFlower::KIND => {
+                Self::Flower(TrafficFilterFlower::parse(payload)?)
+            }
+            TrafficFilterMatchall::KIND => {
+                Self::Matchall(TrafficFilterMatchall::parse(payload)?)
+            }
+            TrafficFilterFw::KIND => Self::Fw(TrafficFilterFw::parse(payload)?),
+            TrafficFilterRoute::KIND => {
+                Self::Route(TrafficFilterRoute::parse(payload)?)
+            }
+            TrafficFilterBasic::KIND => {
+                Self::Basic(TrafficFilterBasic::parse(payload)?)
+            }
+            TrafficFilterBpf::KIND => {
+                Self::Bpf(TrafficFilterBpf::parse(payload)?)
+            }
+            _ => Self::Other(kind.to_string()),
+        })
+    }
+}
+
+fn actions_nla(kind: u16, actions: &[TcAction]) -> DefaultNla {
+    let actions = numbered_actions(actions);
+    let mut buffer = vec![0u8; actions.as_slice().buffer_len()];
+    actions.as_slice().emit(&mut buffer);
+    DefaultNla::new(kind, buffer)
+}
+
+fn parse_actions(payload: &[u8]) -> Result<Vec<TcAction>, DecodeError> {
+    NlasIterator::new(payload)
+        .map(|nla| TcAction::parse(&nla?))
+        .collect()
+}
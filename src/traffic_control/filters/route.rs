@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, DecodeError};
+use netlink_packet_route::tc::{TcAction, TcAttribute};
+
+use super::{actions_nla, parse_actions};
+use crate::traffic_control::nla::{nested_options, nla_u32, parse_nlas};
+
+const TCA_ROUTE4_CLASSID: u16 = 1;
+const TCA_ROUTE4_TO: u16 = 2;
+const TCA_ROUTE4_FROM: u16 = 3;
+const TCA_ROUTE4_IIF: u16 = 4;
+const TCA_ROUTE4_ACT: u16 = 6;
+
+/// Options of the `route` classifier, which matches the realms of the
+/// route used by the packets.
+/// Equivalent to `tc filter add ... route`.
+///
+/// `from` and `iif` are mutually exclusive, `from` wins when both are set.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TrafficFilterRoute {
+    /// Class the matching packets are classified into.
+    pub classid: Option<u32>,
+    /// Realm of the destination, from 0 to 255.
+    pub to: Option<u32>,
+    /// Realm of the source, from 0 to 255.
+    pub from: Option<u32>,
+    /// Index of the input interface.
+    pub iif: Option<u32>,
+    pub actions: Vec<TcAction>,
+}
+
+impl TrafficFilterRoute {
+    pub const KIND: &'static str = "route";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `classid CLASSID`.
+    pub fn classid(mut self, classid: u32) -> Self {
+        self.classid = Some(classid);
+        self
+    }
+
+    /// Equivalent to `to REALM`.
+    pub fn to(mut self, realm: u32) -> Self {
+        self.to = Some(realm);
+        self
+    }
+
+    /// Equivalent to `from REALM`.
+    pub fn from(mut self, realm: u32) -> Self {
+        self.from = Some(realm);
+        self
+    }
+
+    /// Equivalent to `fromif DEV`.
+    pub fn iif(mut self, index: u32) -> Self {
+        self.iif = Some(index);
+        self
+    }
+
+    /// Append an action run on the matching packets.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut nlas = Vec::new();
+        if let Some(to) = self.to {
+            nlas.push(nla_u32(TCA_ROUTE4_TO, to));
+        }
+        if let Some(from) = self.from {
+            nlas.push(nla_u32(TCA_ROUTE4_FROM, from));
+        } else if let Some(iif) = self.iif {
+            nlas.push(nla_u32(TCA_ROUTE4_IIF, iif));
+        }
+        if let Some(classid) = self.classid {
+            nlas.push(nla_u32(TCA_ROUTE4_CLASSID, classid));
+        }
+        if !self.actions.is_empty() {
+            nlas.push(actions_nla(TCA_ROUTE4_ACT, &self.actions));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut route = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_ROUTE4_CLASSID => route.classid = Some(parse_u32(value)?),
+                TCA_ROUTE4_TO => route.to = Some(parse_u32(value)?),
+                TCA_ROUTE4_FROM => route.from = Some(parse_u32(value)?),
+                TCA_ROUTE4_IIF => route.iif = Some(parse_u32(value)?),
+                TCA_ROUTE4_ACT => route.actions = parse_actions(value)?,
+                _ => (),
+            }
+        }
+        Ok(route)
+    }
+}
@@ -0,0 +1,823 @@
+// SPDX-License-Identifier: MIT
+
+// Expected payloads are the TCA_OPTIONS sent by iproute2 for the equivalent
+// `tc` command.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_core::Emitable;
+use netlink_packet_route::tc::{
+    TcAttribute, TcFilterU32Option, TcMessage, TcOption, TcU32Key,
+    TcU32SelectorFlags,
+};
+use tokio::runtime::Runtime;
+
+use super::*;
+use crate::{
+    new_connection, traffic_control::nla::options_payload, Error, TcMessageExt,
+};
+
+fn payload(kind: &str, options: TcAttribute) -> Vec<u8> {
+    let mut message = TcMessage::default();
+    message.attributes.push(TcAttribute::Kind(kind.to_string()));
+    message.attributes.push(options);
+    options_payload(&message).unwrap()
+}
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn assert_flower(flower: TrafficFilterFlower, expected: &str) {
+    assert_eq!(
+        payload(TrafficFilterFlower::KIND, flower.options()),
+        hex(expected)
+    );
+    assert_eq!(TrafficFilterFlower::parse(&hex(expected)).unwrap(), flower);
+}
+
+#[test]
+fn test_flower_ip() {
+    // tc filter add dev eth0 ingress protocol ip flower ip_proto tcp \
+    //     src_ip 10.0.0.0/8 dst_ip 192.168.1.1 dst_port 80 skip_sw
+    let expected = "050009000600000008000a000a00000008000b00ff00000008000c00\
+                    c0a8010108000d00ffffffff0600130000500000080016000200\
+                    00000600080008000000";
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x0800)
+        .ip_proto(6)
+        .src_ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8)
+        .dst_ip(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)), 32)
+        .dst_port(80)
+        .skip_sw();
+    assert_flower(flower, expected);
+
+    // tc filter add dev eth0 ingress protocol ip flower ip_proto udp \
+    //     ip_tos 0x10/0xf0 ip_ttl 64 src_port 1000-2000
+    let expected = "0500090011000000050049001000000005004a00f000000005004b00\
+                    4000000005004c00ff0000000600570003e800000600580007d0\
+                    000008001600000000000600080008000000";
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x0800)
+        .ip_proto(17)
+        .ip_tos(0x10, 0xf0)
+        .ip_ttl(64, 0xff)
+        .src_port_range(1000, 2000);
+    assert_flower(flower, expected);
+
+    // tc filter add dev eth0 ingress protocol ip flower ip_proto tcp \
+    //     src_port 1024/0xfc00 tcp_flags 0x02/0x12
+    let expected = "0500090006000000060012000400000006002300fc00000006004700\
+                    00020000060048000012000008001600000000000600080008000000";
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x0800)
+        .ip_proto(6)
+        .src_port_masked(1024, 0xfc00)
+        .tcp_flags(0x02, 0x12);
+    assert_flower(flower, expected);
+}
+
+#[test]
+fn test_flower_ipv6_icmp() {
+    // tc filter add dev eth0 ingress protocol ipv6 flower ip_proto icmpv6 \
+    //     src_ip 2001:db8::/32 dst_ip 2001:db8::1 type 128 code 0
+    let expected = format!(
+        "050009003a000000{}{}{}{}050037008000000005003800ff00000005003500\
+         0000000005003600ff00000008001600000000000600080086dd0000",
+        "14000e0020010db8000000000000000000000000",
+        "14000f00ffffffff000000000000000000000000",
+        "1400100020010db8000000000000000000000001",
+        "14001100ffffffffffffffffffffffffffffffff",
+    );
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x86dd)
+        .ip_proto(58)
+        .src_ip(
+            IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0)),
+            32,
+        )
+        .dst_ip(
+            IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+            128,
+        )
+        .icmp_type(128)
+        .icmp_code(0);
+    assert_flower(flower, &expected);
+}
+
+#[test]
+fn test_flower_eth_vlan() {
+    // tc filter add dev eth0 ingress protocol 802.1q flower \
+    //     dst_mac 02:00:00:00:00:01 \
+    //     src_mac 02:00:00:00:00:00/ff:ff:ff:00:00:00 \
+    //     vlan_id 100 vlan_prio 3 vlan_ethtype 802.1q \
+    //     cvlan_id 200 cvlan_prio 1 cvlan_ethtype ipv4
+    let expected = "0a00040002000000000100000a000500ffffffffffff00000a000600\
+                    02000000000000000a000700ffffff00000000000600170064000000\
+                    0500180003000000060019008100000006004d00c800000005004e00\
+                    0100000006004f000800000008001600000000000600080081000000";
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x8100)
+        .dst_mac([2, 0, 0, 0, 0, 1])
+        .src_mac_masked([2, 0, 0, 0, 0, 0], [0xff, 0xff, 0xff, 0, 0, 0])
+        .vlan_id(100)
+        .vlan_prio(3)
+        .vlan_eth_type(0x8100)
+        .cvlan_id(200)
+        .cvlan_prio(1)
+        .cvlan_eth_type(0x0800);
+    assert_flower(flower, expected);
+
+    // tc filter add dev eth0 ingress protocol arp flower arp_op request \
+    //     arp_sip 10.0.0.1 arp_tip 10.0.0.0/24 arp_sha 02:00:00:00:00:01
+    let expected = "05003d000100000005003e00ff000000080039000a00000108003a00\
+                    ffffffff08003b000a00000008003c00ffffff000a003f0002000000\
+                    000100000a004000ffffffffffff0000080016000000000006000800\
+                    08060000";
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x0806)
+        .arp_op(1)
+        .arp_sip(Ipv4Addr::new(10, 0, 0, 1), 32)
+        .arp_tip(Ipv4Addr::new(10, 0, 0, 0), 24)
+        .arp_sha([2, 0, 0, 0, 0, 1]);
+    assert_flower(flower, expected);
+
+    // tc filter add dev eth0 ingress protocol mpls_uc flower mpls_label 100 \
+    //     mpls_tc 3 mpls_bos 1 mpls_ttl 64
+    let expected = "0800460064000000050045000300000005004400010000000500430040\
+                    00000008001600000000000600080088470000";
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x8847)
+        .mpls_label(100)
+        .mpls_tc(3)
+        .mpls_bos(1)
+        .mpls_ttl(64);
+    assert_flower(flower, expected);
+
+    // tc filter add dev eth0 ingress protocol all flower classid 1:10 \
+    //     ip_flags frag/nofirstfrag
+    // (iproute2 sends TCA_FLOWER_FLAGS before the ip flags)
+    let expected =
+        "080001001000010008002f000000000108003000000000030800160000000000";
+    let flower = TrafficFilterFlower::new().classid(0x10010).ip_flags(
+        TrafficFilterFlower::IP_FLAGS_FRAG,
+        TrafficFilterFlower::IP_FLAGS_FRAG
+            | TrafficFilterFlower::IP_FLAGS_FIRSTFRAG,
+    );
+    assert_flower(flower, expected);
+}
+
+#[test]
+fn test_flower_tunnel_ct() {
+    // tc filter add dev eth0 ingress protocol ip flower enc_key_id 42 \
+    //     enc_src_ip 10.0.0.1 enc_dst_ip 10.0.0.0/24 enc_dst_port 4789 \
+    //     enc_tos 0x10/0xff enc_ttl 64 ct_state +trk+est-new ct_zone 5 \
+    //     ct_mark 0x10/0xff skip_hw
+    let expected = "08001a000000002a08001b000a00000108001c00ffffffff08001d00\
+                    0a00000008001e00ffffff0006002d0012b500000500500010000000\
+                    05005100ff000000050052004000000005005300ff00000006005b00\
+                    0a00000006005c000b00000006005d000500000006005e00ffff0000\
+                    08005f001000000008006000ff000000080016000100000006000800\
+                    08000000";
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x0800)
+        .enc_key_id(42)
+        .enc_src_ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 32)
+        .enc_dst_ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 24)
+        .enc_dst_port(4789)
+        .enc_tos(0x10, 0xff)
+        .enc_ttl(64, 0xff)
+        .ct_state(
+            TrafficFilterFlower::CT_STATE_TRACKED
+                | TrafficFilterFlower::CT_STATE_ESTABLISHED,
+            TrafficFilterFlower::CT_STATE_TRACKED
+                | TrafficFilterFlower::CT_STATE_ESTABLISHED
+                | TrafficFilterFlower::CT_STATE_NEW,
+        )
+        .ct_zone(5)
+        .ct_mark(0x10, 0xff)
+        .skip_hw();
+    assert_flower(flower, expected);
+
+    // tc filter add dev eth0 ingress protocol ipv6 flower \
+    //     enc_src_ip 2001:db8::1 enc_dst_ip 2001:db8::/64
+    let expected = format!(
+        "{}{}{}{}08001600000000000600080086dd0000",
+        "14001f0020010db8000000000000000000000001",
+        "14002000ffffffffffffffffffffffffffffffff",
+        "1400210020010db8000000000000000000000000",
+        "14002200ffffffffffffffff0000000000000000",
+    );
+    let flower = TrafficFilterFlower::new()
+        .eth_type(0x86dd)
+        .enc_src_ip(
+            IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+            128,
+        )
+        .enc_dst_ip(
+            IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0)),
+            64,
+        );
+    assert_flower(flower, &expected);
+}
+
+#[test]
+fn test_flower_in_hw() {
+    // Dumped by the kernel for an offloaded filter: TCA_FLOWER_FLAGS with
+    // TCA_CLS_FLAGS_IN_HW and TCA_FLOWER_IN_HW_COUNT
+    let dumped = "080016000600000008005600010000000600080008000000";
+    let flower = TrafficFilterFlower::parse(&hex(dumped)).unwrap();
+    assert!(flower.skip_sw);
+    assert!(flower.in_hw);
+    assert_eq!(flower.in_hw_count, Some(1));
+    assert_eq!(flower.eth_type, Some(0x0800));
+
+    // Decoded through the TCA_OPTIONS parsed by netlink-packet-route
+    let mut message = TcMessage::default();
+    message
+        .attributes
+        .push(TcAttribute::Kind(TrafficFilterFlower::KIND.to_string()));
+    message.attributes.push(
+        TrafficFilterFlower::new()
+            .eth_type(0x0800)
+            .skip_sw()
+            .options(),
+    );
+    assert_eq!(
+        crate::TcMessageExt::filter_options(&message).unwrap(),
+        Some(TrafficFilterOptions::Flower(
+            TrafficFilterFlower::new().eth_type(0x0800).skip_sw()
+        ))
+    );
+}
+
+#[test]
+fn test_flower_request() {
+    Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+
+        // The protocol defaults to the ethernet type, in network order
+        let mut request = handle
+            .traffic_filter(1)
+            .add()
+            .ingress()
+            .flower(TrafficFilterFlower::new().eth_type(0x0800))
+            .unwrap();
+        assert_eq!(request.message_mut().header.info, 0x0008);
+
+        // Also when the priority is set first
+        let mut request = handle
+            .traffic_filter(1)
+            .add()
+            .ingress()
+            .priority(10)
+            .flower(TrafficFilterFlower::new().eth_type(0x86dd))
+            .unwrap();
+        assert_eq!(request.message_mut().header.info, (10 << 16) | 0xdd86);
+
+        let request = handle
+            .traffic_filter(1)
+            .add()
+            .flower(TrafficFilterFlower::new().ip_proto(1).dst_port(80));
+        assert!(matches!(request, Err(Error::InvalidNla(_))));
+        let request = handle
+            .traffic_filter(1)
+            .add()
+            .flower(TrafficFilterFlower::new().ip_proto(17).tcp_flags(2, 2));
+        assert!(matches!(request, Err(Error::InvalidNla(_))));
+        let request = handle
+            .traffic_filter(1)
+            .add()
+            .flower(TrafficFilterFlower::new().ip_proto(6).icmp_type(8));
+        assert!(matches!(request, Err(Error::InvalidNla(_))));
+
+        // An address and its mask must be of the same family
+        let v4: IpAddr = Ipv4Addr::new(192, 0, 2, 1).into();
+        let v6: IpAddr = Ipv6Addr::LOCALHOST.into();
+        for (addr, mask) in [(v4, v6), (v6, v4)] {
+            let flowers = [
+                TrafficFilterFlower {
+                    src_ip: Some((addr, mask)),
+                    ..Default::default()
+                },
+                TrafficFilterFlower {
+                    dst_ip: Some((addr, mask)),
+                    ..Default::default()
+                },
+                TrafficFilterFlower {
+                    enc_src_ip: Some((addr, mask)),
+                    ..Default::default()
+                },
+                TrafficFilterFlower {
+                    enc_dst_ip: Some((addr, mask)),
+                    ..Default::default()
+                },
+            ];
+            for flower in flowers {
+                let request = handle.traffic_filter(1).add().flower(flower);
+                assert!(matches!(request, Err(Error::InvalidNla(_))));
+            }
+        }
+    });
+}
+
+fn emit_message(message: &TcMessage) -> Vec<u8> {
+    let mut buffer = vec![0u8; message.buffer_len()];
+    message.emit(&mut buffer);
+    buffer
+}
+
+#[test]
+fn test_filter_del_request() {
+    Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+
+        // tc filter del block 5 pref 10 protocol ip
+        let mut request = handle
+            .traffic_filter(0)
+            .del()
+            .block(5)
+            .priority(10)
+            .protocol(0x0800u16.to_be());
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("00000000ffffffff000000000500000008000a00")
+        );
+
+        // tc filter del dev v0 ingress chain 3
+        let mut request = handle.traffic_filter(3).flush().ingress().chain(3);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("000000000300000000000000f2ffffff0000000008000b0003000000")
+        );
+
+        // tc filter del dev v0 ingress pref 10 protocol ip handle 1
+        let mut request = handle
+            .traffic_filter(3)
+            .del()
+            .ingress()
+            .protocol(0x0800u16.to_be())
+            .priority(10)
+            .handle(1);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("000000000300000001000000f2ffffff08000a00")
+        );
+    });
+}
+
+#[test]
+fn test_chain_request() {
+    Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+
+        // tc chain add dev v0 ingress chain 5
+        let mut request = handle.traffic_chain(3).add().ingress().chain(5);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("000000000300000000000000f2ffffff0000000008000b0005000000")
+        );
+
+        // tc chain del dev v0 ingress chain 5
+        let mut request = handle.traffic_chain(3).del().ingress().chain(5);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("000000000300000000000000f2ffffff0000000008000b0005000000")
+        );
+
+        // tc chain add block 5 chain 1
+        let mut request = handle.traffic_chain(0).add().block(5).chain(1);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex("00000000ffffffff00000000050000000000000008000b0001000000")
+        );
+
+        // tc chain add dev v0 ingress chain 7 protocol ip \
+        //     flower dst_ip 0.0.0.0/16
+        let template = TrafficFilterFlower::new()
+            .eth_type(0x0800)
+            .dst_ip(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 16);
+        let mut request = handle
+            .traffic_chain(3)
+            .add()
+            .ingress()
+            .chain(7)
+            .flower(template.clone())
+            .unwrap();
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex(concat!(
+                "000000000300000000000000f2ffffff0800000008000b0007000000",
+                "0b000100666c6f77657200002400020008000c000000000008000d00",
+                "ffff000008001600000000000600080008000000"
+            ))
+        );
+
+        let request = handle
+            .traffic_chain(3)
+            .add()
+            .flower(template.clone())
+            .unwrap()
+            .flower(template);
+        assert!(matches!(request, Err(Error::InvalidNla(_))));
+    });
+}
+
+#[test]
+fn test_block_request() {
+    Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+
+        // tc qdisc add dev v1 ingress_block 6 egress_block 7 clsact
+        let mut request = handle
+            .qdisc()
+            .add(2)
+            .clsact()
+            .ingress_block(6)
+            .egress_block(7);
+        let message = request.message_mut();
+        assert_eq!(
+            emit_message(message),
+            hex(concat!(
+                "00000000020000000000fffff1ffffff000000000b000100636c736163",
+                "74000008000d000600000008000e0007000000"
+            ))
+        );
+        assert_eq!(message.ingress_block(), Some(6));
+        assert_eq!(message.egress_block(), Some(7));
+
+        // tc qdisc add dev v1x ingress_block 8 ingress
+        let mut request = handle.qdisc().add(5).ingress().ingress_block(8);
+        assert_eq!(
+            emit_message(request.message_mut()),
+            hex(concat!(
+                "00000000050000000000fffff1ffffff000000000c000100696e677265",
+                "73730008000d0008000000"
+            ))
+        );
+    });
+}
+
+#[test]
+fn test_matchall() {
+    // tc filter add dev eth0 parent 1: matchall skip_hw classid 1:10
+    let expected = "08000100100001000800030001000000";
+    let matchall = TrafficFilterMatchall::new().classid(0x10010).skip_hw();
+    assert_eq!(
+        payload(TrafficFilterMatchall::KIND, matchall.options()),
+        hex(expected)
+    );
+    assert_eq!(
+        TrafficFilterMatchall::parse(&hex(expected)).unwrap(),
+        matchall
+    );
+
+    // tc filter add dev eth0 parent 1: matchall classid 1:10 action drop
+    let expected = "0800010010000100300002002c0001000900010067616374000000\
+                    001c00028018000200000000000000000002000000000000000000\
+                    0000";
+    let matchall = TrafficFilterMatchall::parse(&hex(expected)).unwrap();
+    assert_eq!(matchall.classid, Some(0x10010));
+    assert_eq!(matchall.actions.len(), 1);
+    assert_eq!(
+        payload(TrafficFilterMatchall::KIND, matchall.options()),
+        hex(expected)
+    );
+}
+
+#[test]
+fn test_fw() {
+    // tc filter add dev eth0 parent 1: handle 10/0xff fw classid 1:10 \
+    //     indev eth1
+    let expected = "08000500ff00000008000100100001000900030065746831000000\
+                    00";
+    let fw = TrafficFilterFw::new()
+        .mask(0xff)
+        .classid(0x10010)
+        .indev("eth1");
+    assert_eq!(payload(TrafficFilterFw::KIND, fw.options()), hex(expected));
+    assert_eq!(TrafficFilterFw::parse(&hex(expected)).unwrap(), fw);
+
+    // tc filter add dev eth0 parent 1: handle 10 fw classid 1:10 action pass
+    let expected = "0800010010000100300004002c0001000900010067616374000000\
+                    001c00028018000200000000000000000000000000000000000000\
+                    0000";
+    let fw = TrafficFilterFw::parse(&hex(expected)).unwrap();
+    assert_eq!(fw.classid, Some(0x10010));
+    assert_eq!(fw.actions.len(), 1);
+    assert_eq!(payload(TrafficFilterFw::KIND, fw.options()), hex(expected));
+}
+
+#[test]
+fn test_route() {
+    // tc filter add dev eth0 parent 1: route to 10 from 20 classid 1:10
+    let expected = "080002000a00000008000300140000000800010010000100";
+    let route = TrafficFilterRoute::new().to(10).from(20).classid(0x10010);
+    assert_eq!(
+        payload(TrafficFilterRoute::KIND, route.options()),
+        hex(expected)
+    );
+    assert_eq!(TrafficFilterRoute::parse(&hex(expected)).unwrap(), route);
+
+    // tc filter add dev eth0 parent 1: route fromif eth1 classid 1:10
+    let expected = "08000400020000000800010010000100";
+    let route = TrafficFilterRoute::new().iif(2).classid(0x10010);
+    assert_eq!(
+        payload(TrafficFilterRoute::KIND, route.options()),
+        hex(expected)
+    );
+    assert_eq!(TrafficFilterRoute::parse(&hex(expected)).unwrap(), route);
+}
+
+fn assert_basic(ematch: Ematch, expected: &str) {
+    let basic = TrafficFilterBasic::new().ematch(ematch).classid(0x10010);
+    assert_eq!(
+        payload(TrafficFilterBasic::KIND, basic.options()),
+        hex(expected)
+    );
+    assert_eq!(TrafficFilterBasic::parse(&hex(expected)).unwrap(), basic);
+}
+
+#[test]
+fn test_basic_meta() {
+    // tc filter add dev eth0 parent 1: basic \
+    //     match 'not meta(nf_mark eq 1)' classid 1:10
+    let expected = "3800020008000100010000002c00020028000100000004000400\
+                    00000c0001000c1000000010000008000200000000000800030001\
+                    0000000800010010000100";
+    let ematch = Ematch::Meta(EmatchMeta::new(EmatchMetaId::NfMark, 1)).not();
+    assert_basic(ematch, expected);
+
+    // tc filter add dev eth0 parent 1: basic match 'meta(nf_mark eq 10) \
+    //     and meta(priority mask 0xf gt 3)' classid 1:10
+    let expected = "60000200080001000200000054000200280001000000040001\
+                    0000000c0001000c100000001000000800020000000000080003\
+                    000a0000002800020000000400000000000c0001000610000100\
+                    100000080002000f000000080003000300000008000100100001\
+                    00";
+    let ematch = Ematch::And(vec![
+        Ematch::Meta(EmatchMeta::new(EmatchMetaId::NfMark, 10)),
+        Ematch::Meta(
+            EmatchMeta::new(EmatchMetaId::Priority, 3)
+                .mask(0xf)
+                .operand(EmatchOperand::Gt),
+        ),
+    ]);
+    assert_basic(ematch, expected);
+}
+
+#[test]
+fn test_basic_nested() {
+    // tc filter add dev eth0 parent 1: basic match 'meta(nf_mark eq 1) \
+    //     or not (cmp(u8 at 9 layer network eq 6) and meta(nf_mark eq 2))' \
+    //     classid 1:10
+    let expected = "8800020008000100040000007c00020028000100000004000200\
+                    00000c0001000c1000000010000008000200000000000800030001\
+                    000000100002000000000004000000020000001800030000000100\
+                    010000000600000000000000090001012800040000000400000000\
+                    000c0001000c100000001000000800020000000000080003000200\
+                    00000800010010000100";
+    let ematch = Ematch::Or(vec![
+        Ematch::Meta(EmatchMeta::new(EmatchMetaId::NfMark, 1)),
+        Ematch::And(vec![
+            Ematch::Cmp(EmatchCmp::new(
+                EmatchCmpAlign::U8,
+                9,
+                EmatchLayer::Network,
+                6,
+            )),
+            Ematch::Meta(EmatchMeta::new(EmatchMetaId::NfMark, 2)),
+        ])
+        .not(),
+    ]);
+    assert_basic(ematch, expected);
+
+    // Container pointing backward.
+    let mut invalid = hex(expected);
+    invalid[68] = 1;
+    assert!(TrafficFilterBasic::parse(&invalid).is_err());
+}
+
+#[test]
+fn test_basic_cmp_u32() {
+    // tc filter add dev eth0 parent 1: basic match \
+    //     'cmp(u16 at 2 layer network mask 0xff00 gt 0x100)' classid 1:10
+    let expected = "2800020008000100010000001c0002001800010000000100000000\
+                    000001000000ff0000020002110800010010000100";
+    let ematch = Ematch::Cmp(
+        EmatchCmp::new(EmatchCmpAlign::U16, 2, EmatchLayer::Network, 0x100)
+            .mask(0xff00)
+            .operand(EmatchOperand::Gt),
+    );
+    assert_basic(ematch, expected);
+
+    // tc filter add dev eth0 parent 1: basic match \
+    //     'u32(u32 0x0a000000 0xff000000 at 12)' classid 1:10
+    let expected = "2c0002000800010001000000200002001c0001000000030000000000\
+                    ff0000000a0000000c000000000000000800010010000100";
+    let ematch = Ematch::U32(EmatchU32::u32(0x0a000000, 0xff000000, 12));
+    assert_basic(ematch, expected);
+
+    // tc filter add dev eth0 parent 1: basic match \
+    //     'u32(u16 0x0050 0xffff at nexthdr+2)' classid 1:10
+    let expected = "2c0002000800010001000000200002001c0001000000030000000000\
+                    0000ffff0000005000000000ffffffff0800010010000100";
+    let ematch = Ematch::U32(EmatchU32::u16(0x0050, 0xffff, 2).nexthdr());
+    assert_basic(ematch, expected);
+}
+
+fn u32_key(mask: u32, val: u32, off: i32, offmask: i32) -> TcU32Key {
+    let mut key = TcU32Key::default();
+    key.mask = mask;
+    key.val = val;
+    key.off = off;
+    key.offmask = offmask;
+    key
+}
+
+#[test]
+fn test_u32_keys() {
+    // tc filter add ... u32 match ip src 10.0.0.0/8 \
+    //     match ip protocol 6 0xff flowid 1:10
+    let selector = TrafficFilterU32::new()
+        .matches(U32Match::IpSrc(Ipv4Addr::new(10, 0, 0, 0), 8))
+        .matches(U32Match::IpProtocol(6))
+        .classid(0x1_0010)
+        .selector()
+        .unwrap();
+    assert_eq!(selector.flags, TcU32SelectorFlags::Terminal);
+    assert_eq!(
+        selector.keys,
+        vec![
+            u32_key(0xff000000, 0x0a000000, 12, 0),
+            u32_key(0x00ff0000, 0x00060000, 8, 0),
+        ]
+    );
+    assert_eq!(selector.nkeys, 2);
+
+    // match tcp src 1024 0xffff match tcp dst 80 0xffff share a word
+    let selector = TrafficFilterU32::new()
+        .matches(U32Match::Sport(1024))
+        .matches(U32Match::Dport(80))
+        .selector()
+        .unwrap();
+    assert_eq!(selector.keys, vec![u32_key(0xffffffff, 0x04000050, 0, -1)]);
+
+    // match icmp type 8 0xff
+    let selector = TrafficFilterU32::new()
+        .matches(U32Match::IcmpType(8))
+        .selector()
+        .unwrap();
+    assert_eq!(selector.keys, vec![u32_key(0xff000000, 0x08000000, 0, -1)]);
+
+    // match u16 0x0800 0xffff at -2
+    let selector = TrafficFilterU32::new()
+        .matches(U32Match::EtherProto(0x0800))
+        .selector()
+        .unwrap();
+    assert_eq!(selector.keys, vec![u32_key(0x0000ffff, 0x00000800, -4, 0)]);
+
+    // match ip6 src 2001:db8::/32 match ip6 dst ::1/128
+    let filter = TrafficFilterU32::new()
+        .matches(U32Match::Ip6Src("2001:db8::".parse().unwrap(), 32))
+        .matches(U32Match::Ip6Dst(Ipv6Addr::LOCALHOST, 128));
+    assert_eq!(filter.protocol(), Some(0x86dd));
+    assert_eq!(
+        filter.selector().unwrap().keys,
+        vec![
+            u32_key(0xffffffff, 0x20010db8, 8, 0),
+            u32_key(0xffffffff, 0, 24, 0),
+            u32_key(0xffffffff, 0, 28, 0),
+            u32_key(0xffffffff, 0, 32, 0),
+            u32_key(0xffffffff, 1, 36, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_u32_link() {
+    // tc filter add ... u32 link 1: offset at 0 mask 0f00 shift 6 \
+    //     hashkey mask 0x000000ff at 8
+    let filter = TrafficFilterU32::new()
+        .link(1)
+        .offset_ip_header()
+        .hashkey(0xff, 8);
+    let selector = filter.selector().unwrap();
+    assert_eq!(selector.flags, TcU32SelectorFlags::VarOffset);
+    assert_eq!(selector.offmask, 0x0f00u16.to_be());
+    assert_eq!(selector.offshift, 6);
+    assert_eq!(selector.hmask, 0xffu32.to_be());
+    assert_eq!(selector.hoff, 8);
+    assert_eq!(selector.keys, vec![TcU32Key::default()]);
+    assert_eq!(filter.link, Some(0x0010_0000));
+
+    assert_eq!(TrafficFilterU32::new().ht(1, 2).ht, Some(0x0010_2000));
+
+    // The hash table itself has no selector
+    let TcAttribute::Options(options) =
+        TrafficFilterU32::new().divisor(256).options().unwrap()
+    else {
+        panic!("not options");
+    };
+    assert_eq!(
+        options,
+        vec![TcOption::U32(TcFilterU32Option::Divisor(256))]
+    );
+}
+
+#[test]
+fn test_u32_invalid() {
+    let invalid = [
+        TrafficFilterU32::new()
+            .matches(U32Match::IpProtocol(6))
+            .matches(U32Match::Ip6Protocol(6)),
+        TrafficFilterU32::new()
+            .matches(U32Match::IpTos(0x10, 0xff))
+            .matches(U32Match::IpTos(0x08, 0xff)),
+        TrafficFilterU32::new().matches(U32Match::U16 {
+            value: 1,
+            mask: 0xffff,
+            at: 3,
+            nexthdr: false,
+        }),
+        TrafficFilterU32::new()
+            .matches(U32Match::IpDst(Ipv4Addr::LOCALHOST, 33)),
+    ];
+    for filter in invalid {
+        assert!(matches!(filter.selector(), Err(Error::InvalidNla(_))));
+    }
+}
+
+#[test]
+fn test_u32_request() {
+    Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+
+        // The protocol defaults to the family of the IP matches
+        let mut request = handle
+            .traffic_filter(1)
+            .add()
+            .u32_match(
+                TrafficFilterU32::new()
+                    .matches(U32Match::IpDst(Ipv4Addr::LOCALHOST, 32)),
+            )
+            .unwrap();
+        assert_eq!(request.message_mut().header.info, 0x0008);
+
+        let mut request = handle
+            .traffic_filter(1)
+            .add()
+            .u32_match(TrafficFilterU32::new().matches(U32Match::Dport(80)))
+            .unwrap();
+        assert_eq!(request.message_mut().header.info, 0x0300);
+
+        // The priority set first keeps the protocol unset
+        let mut request = handle
+            .traffic_filter(1)
+            .add()
+            .parent(0x1_0000)
+            .priority(1)
+            .u32_match(
+                TrafficFilterU32::new()
+                    .matches(U32Match::IpProtocol(6))
+                    .link(1)
+                    .offset_ip_header(),
+            )
+            .unwrap();
+        let info = request.message_mut().header.info;
+        assert_eq!(info >> 16, 1);
+        assert_eq!(info & 0xffff, u32::from(0x0800u16.to_be()));
+    });
+}
+
+#[test]
+fn test_default_protocol() {
+    Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        let request = || handle.traffic_filter(1).add().ingress().priority(10);
+
+        // tc filter add dev eth0 ingress prio 10 KIND: protocol all
+        let requests = [
+            request().matchall(TrafficFilterMatchall::new()),
+            request().fw(TrafficFilterFw::new()),
+            request().route(TrafficFilterRoute::new()),
+            request().basic(TrafficFilterBasic::new()),
+        ];
+        for request in requests {
+            let mut request = request.unwrap();
+            assert_eq!(request.message_mut().header.info, (10 << 16) | 0x0300);
+        }
+
+        // An explicit protocol is kept
+        let mut request = handle
+            .traffic_filter(1)
+            .add()
+            .protocol(0x0800u16.to_be())
+            .matchall(TrafficFilterMatchall::new())
+            .unwrap();
+        assert_eq!(request.message_mut().header.info, 0x0008);
+    });
+}
@@ -0,0 +1,549 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_route::tc::{
+    TcAction, TcAttribute, TcFilterU32Option, TcOption, TcU32Key,
+    TcU32OptionFlags, TcU32Selector, TcU32SelectorFlags,
+};
+
+use super::numbered_actions;
+use crate::Error;
+
+const ETH_P_IP: u16 = 0x0800;
+const ETH_P_IPV6: u16 = 0x86dd;
+
+// Mask of the IHL field in the first 16 bits of the IPv4 header, shifted to
+// the header length in bytes.
+const IHL_MASK: u16 = 0x0f00;
+const IHL_SHIFT: u8 = 6;
+const IPV6_HEADER_LEN: i16 = 40;
+
+/// Match of the `u32` classifier, see [TrafficFilterU32].
+///
+/// Offsets are relative to the network header. The transport matches and
+/// the `nexthdr` ones are relative to the next header instead, whose offset
+/// is only known in a hash table linked with [TrafficFilterU32::offset] or
+/// [TrafficFilterU32::offset_ip_header].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum U32Match {
+    /// Equivalent to `match ip src ADDR/LEN`.
+    IpSrc(Ipv4Addr, u8),
+    /// Equivalent to `match ip dst ADDR/LEN`.
+    IpDst(Ipv4Addr, u8),
+    /// Equivalent to `match ip protocol PROTO 0xff`.
+    IpProtocol(u8),
+    /// Equivalent to `match ip tos TOS MASK`.
+    IpTos(u8, u8),
+    /// Equivalent to `match ip6 src ADDR/LEN`.
+    Ip6Src(Ipv6Addr, u8),
+    /// Equivalent to `match ip6 dst ADDR/LEN`.
+    Ip6Dst(Ipv6Addr, u8),
+    /// Next header of the IPv6 header, without following the extension
+    /// headers.
+    /// Equivalent to `match ip6 protocol PROTO 0xff`.
+    Ip6Protocol(u8),
+    /// Source port of TCP, UDP or SCTP, in the next header.
+    /// Equivalent to `match tcp src PORT 0xffff`.
+    Sport(u16),
+    /// Destination port of TCP, UDP or SCTP, in the next header.
+    /// Equivalent to `match tcp dst PORT 0xffff`.
+    Dport(u16),
+    /// ICMP or ICMPv6 type, in the next header.
+    /// Equivalent to `match icmp type TYPE 0xff`.
+    IcmpType(u8),
+    /// ICMP or ICMPv6 code, in the next header.
+    /// Equivalent to `match icmp code CODE 0xff`.
+    IcmpCode(u8),
+    /// Ethertype of the frame, only available when the link layer header
+    /// is still in front of the packet, like on ingress.
+    /// Equivalent to `match u16 PROTO 0xffff at -2`.
+    EtherProto(u16),
+    /// Equivalent to `match u8 VALUE MASK at AT [nexthdr+]`.
+    U8 {
+        value: u8,
+        mask: u8,
+        at: i32,
+        nexthdr: bool,
+    },
+    /// Equivalent to `match u16 VALUE MASK at AT [nexthdr+]`.
+    U16 {
+        value: u16,
+        mask: u16,
+        at: i32,
+        nexthdr: bool,
+    },
+    /// Equivalent to `match u32 VALUE MASK at AT [nexthdr+]`.
+    U32 {
+        value: u32,
+        mask: u32,
+        at: i32,
+        nexthdr: bool,
+    },
+}
+
+// Field of `width` bytes at `at` of the network or next header.
+struct Field {
+    value: u32,
+    mask: u32,
+    at: i32,
+    width: i32,
+    nexthdr: bool,
+}
+
+impl U32Match {
+    fn fields(&self) -> Vec<Field> {
+        let field = |value: u32, mask: u32, at: i32, width: i32| Field {
+            value,
+            mask,
+            at,
+            width,
+            nexthdr: false,
+        };
+        let nexthdr = |value: u32, at: i32, width: i32| Field {
+            value,
+            mask: u32::MAX >> (32 - width * 8),
+            at,
+            width,
+            nexthdr: true,
+        };
+        match *self {
+            Self::IpSrc(addr, len) => ipv4_fields(addr, len, 12),
+            Self::IpDst(addr, len) => ipv4_fields(addr, len, 16),
+            Self::IpProtocol(proto) => vec![field(proto.into(), 0xff, 9, 1)],
+            Self::IpTos(tos, mask) => {
+                vec![field(tos.into(), mask.into(), 1, 1)]
+            }
+            Self::Ip6Src(addr, len) => ipv6_fields(addr, len, 8),
+            Self::Ip6Dst(addr, len) => ipv6_fields(addr, len, 24),
+            Self::Ip6Protocol(proto) => vec![field(proto.into(), 0xff, 6, 1)],
+            Self::Sport(port) => vec![nexthdr(port.into(), 0, 2)],
+            Self::Dport(port) => vec![nexthdr(port.into(), 2, 2)],
+            Self::IcmpType(icmp_type) => vec![nexthdr(icmp_type.into(), 0, 1)],
+            Self::IcmpCode(code) => vec![nexthdr(code.into(), 1, 1)],
+            Self::EtherProto(proto) => {
+                vec![field(proto.into(), 0xffff, -2, 2)]
+            }
+            Self::U8 {
+                value,
+                mask,
+                at,
+                nexthdr,
+            } => vec![Field {
+                nexthdr,
+                ..field(value.into(), mask.into(), at, 1)
+            }],
+            Self::U16 {
+                value,
+                mask,
+                at,
+                nexthdr,
+            } => vec![Field {
+                nexthdr,
+                ..field(value.into(), mask.into(), at, 2)
+            }],
+            Self::U32 {
+                value,
+                mask,
+                at,
+                nexthdr,
+            } => vec![Field {
+                nexthdr,
+                ..field(value, mask, at, 4)
+            }],
+        }
+    }
+
+    fn family(&self) -> Option<u16> {
+        match self {
+            Self::IpSrc(..)
+            | Self::IpDst(..)
+            | Self::IpProtocol(_)
+            | Self::IpTos(..) => Some(ETH_P_IP),
+            Self::Ip6Src(..) | Self::Ip6Dst(..) | Self::Ip6Protocol(_) => {
+                Some(ETH_P_IPV6)
+            }
+            _ => None,
+        }
+    }
+
+    fn prefix_len(&self) -> Option<(u8, u8)> {
+        match self {
+            Self::IpSrc(_, len) | Self::IpDst(_, len) => Some((*len, 32)),
+            Self::Ip6Src(_, len) | Self::Ip6Dst(_, len) => Some((*len, 128)),
+            _ => None,
+        }
+    }
+}
+
+fn prefix_mask(len: u8) -> u32 {
+    match len {
+        0 => 0,
+        len if len >= 32 => u32::MAX,
+        len => u32::MAX << (32 - len),
+    }
+}
+
+fn ipv4_fields(addr: Ipv4Addr, len: u8, at: i32) -> Vec<Field> {
+    vec![Field {
+        value: u32::from(addr),
+        mask: prefix_mask(len),
+        at,
+        width: 4,
+        nexthdr: false,
+    }]
+}
+
+fn ipv6_fields(addr: Ipv6Addr, len: u8, at: i32) -> Vec<Field> {
+    let octets = addr.octets();
+    (0..4)
+        .map(|i| Field {
+            value: u32::from_be_bytes([
+                octets[i * 4],
+                octets[i * 4 + 1],
+                octets[i * 4 + 2],
+                octets[i * 4 + 3],
+            ]),
+            mask: prefix_mask(len.saturating_sub(i as u8 * 32)),
+            at: at + i as i32 * 4,
+            width: 4,
+            nexthdr: false,
+        })
+        // Words outside of the prefix match every packet.
+        .filter(|field| field.mask != 0)
+        .collect()
+}
+
+/// Options of the `u32` classifier, compiled from [U32Match] into the keys
+/// of a `tc_u32_sel`.
+/// Equivalent to `tc filter add ... u32`.
+///
+/// Transport matches need the length of the IP header, which is only known
+/// to the kernel when following a link to a hash table:
+///
+/// ```no_run
+/// # async fn example(handle: rtnetlink::Handle) -> Result<(), rtnetlink::Error> {
+/// use rtnetlink::{TrafficFilterU32, U32Match};
+///
+/// let index = 1;
+/// // tc filter add dev eth0 parent 1: prio 1 handle 1: u32 divisor 1
+/// handle
+///     .traffic_filter(index)
+///     .add()
+///     .parent(0x1_0000)
+///     .priority(1)
+///     .handle(TrafficFilterU32::table_handle(1))
+///     .u32_match(TrafficFilterU32::new().divisor(1))?
+///     .execute()
+///     .await?;
+/// // tc filter add dev eth0 parent 1: prio 1 u32 link 1: \
+/// //     offset at 0 mask 0f00 shift 6 match ip protocol 6 0xff
+/// handle
+///     .traffic_filter(index)
+///     .add()
+///     .parent(0x1_0000)
+///     .priority(1)
+///     .u32_match(
+///         TrafficFilterU32::new()
+///             .matches(U32Match::IpProtocol(6))
+///             .link(1)
+///             .offset_ip_header(),
+///     )?
+///     .execute()
+///     .await?;
+/// // tc filter add dev eth0 parent 1: prio 1 protocol ip u32 ht 1: \
+/// //     match tcp dst 80 0xffff flowid 1:10
+/// handle
+///     .traffic_filter(index)
+///     .add()
+///     .parent(0x1_0000)
+///     .priority(1)
+///     .protocol(0x0800u16.to_be())
+///     .u32_match(
+///         TrafficFilterU32::new()
+///             .ht(1, 0)
+///             .matches(U32Match::Dport(80))
+///             .classid(0x1_0010),
+///     )?
+///     .execute()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TrafficFilterU32 {
+    /// All the matches have to match for the filter to match.
+    pub matches: Vec<U32Match>,
+    /// Class the matching packets are classified into.
+    pub classid: Option<u32>,
+    /// Hash table and bucket the filter is added to, see [Self::ht].
+    pub ht: Option<u32>,
+    /// Hash table the matching packets are looked up in, see [Self::link].
+    pub link: Option<u32>,
+    /// Number of buckets of the hash table created by the filter.
+    pub divisor: Option<u32>,
+    /// Mask and offset of the word hashed to select the bucket of the
+    /// linked hash table.
+    pub hashkey: Option<(u32, i16)>,
+    /// Offset of the next header, see [Self::offset].
+    pub offset: Option<(i16, u16, u8)>,
+    /// Constant added to the offset of the next header.
+    pub offset_plus: Option<i16>,
+    /// Move the network header to the next header before following the
+    /// link.
+    pub eat: bool,
+    /// Do not process the filter in software.
+    pub skip_sw: bool,
+    /// Do not offload the filter to hardware.
+    pub skip_hw: bool,
+    pub actions: Vec<TcAction>,
+}
+
+impl TrafficFilterU32 {
+    pub const KIND: &'static str = "u32";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle of the hash table `table`, to create it with
+    /// [crate::TrafficFilterNewRequest::handle] and [Self::divisor].
+    /// Equivalent to `handle TABLE:`.
+    pub fn table_handle(table: u16) -> u32 {
+        u32::from(table & 0xfff) << 20
+    }
+
+    /// Append a match.
+    /// Equivalent to `match ...`.
+    pub fn matches(mut self, m: U32Match) -> Self {
+        self.matches.push(m);
+        self
+    }
+
+    /// Equivalent to `classid CLASSID` or `flowid CLASSID`.
+    pub fn classid(mut self, classid: u32) -> Self {
+        self.classid = Some(classid);
+        self
+    }
+
+    /// Add the filter to the bucket of the hash table `table` instead of
+    /// the root table.
+    /// Equivalent to `ht TABLE:BUCKET:`.
+    pub fn ht(mut self, table: u16, bucket: u8) -> Self {
+        self.ht = Some(Self::table_handle(table) | u32::from(bucket) << 12);
+        self
+    }
+
+    /// Look the matching packets up in the hash table `table`.
+    /// Equivalent to `link TABLE:`.
+    pub fn link(mut self, table: u16) -> Self {
+        self.link = Some(Self::table_handle(table));
+        self
+    }
+
+    /// Create a hash table of `divisor` buckets, the handle of the filter
+    /// has to be set to [Self::table_handle].
+    /// Equivalent to `divisor DIVISOR`.
+    pub fn divisor(mut self, divisor: u32) -> Self {
+        self.divisor = Some(divisor);
+        self
+    }
+
+    /// Select the bucket of the linked hash table with the word at `at`
+    /// masked by `mask`.
+    /// Equivalent to `hashkey mask MASK at AT`.
+    pub fn hashkey(mut self, mask: u32, at: i16) -> Self {
+        self.hashkey = Some((mask, at));
+        self
+    }
+
+    /// Read the offset of the next header from the 16 bits at `at`, masked
+    /// by `mask` and shifted right by `shift`.
+    /// Equivalent to `offset at AT mask MASK shift SHIFT`.
+    pub fn offset(mut self, at: i16, mask: u16, shift: u8) -> Self {
+        self.offset = Some((at, mask, shift));
+        self
+    }
+
+    /// Take the offset of the next header from the IHL field of the IPv4
+    /// header, so that the transport matches of the linked hash table
+    /// account for the IP options.
+    /// Equivalent to `offset at 0 mask 0f00 shift 6`.
+    pub fn offset_ip_header(self) -> Self {
+        self.offset(0, IHL_MASK, IHL_SHIFT)
+    }
+
+    /// Skip the fixed IPv6 header for the transport matches of the linked
+    /// hash table, extension headers are not followed.
+    /// Equivalent to `offset plus 40`.
+    pub fn offset_ipv6_header(self) -> Self {
+        self.offset_plus(IPV6_HEADER_LEN)
+    }
+
+    /// Equivalent to `offset plus PLUS`.
+    pub fn offset_plus(mut self, plus: i16) -> Self {
+        self.offset_plus = Some(plus);
+        self
+    }
+
+    /// Equivalent to `offset ... eat`.
+    pub fn eat(mut self) -> Self {
+        self.eat = true;
+        self
+    }
+
+    /// Equivalent to `skip_sw`.
+    pub fn skip_sw(mut self) -> Self {
+        self.skip_sw = true;
+        self
+    }
+
+    /// Equivalent to `skip_hw`.
+    pub fn skip_hw(mut self) -> Self {
+        self.skip_hw = true;
+        self
+    }
+
+    /// Append an action run on the matching packets.
+    pub fn action(mut self, action: impl Into<TcAction>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    /// Protocol matched by the IPv4 or IPv6 matches, if any.
+    pub(crate) fn protocol(&self) -> Option<u16> {
+        self.matches.iter().find_map(U32Match::family)
+    }
+
+    /// Compile the matches into the selector sent to the kernel.
+    pub fn selector(&self) -> Result<TcU32Selector, Error> {
+        let mut families = self.matches.iter().filter_map(U32Match::family);
+        if let Some(family) = families.next() {
+            if families.any(|other| other != family) {
+                return Err(Error::InvalidNla(
+                    "u32 cannot match both IPv4 and IPv6 headers".to_string(),
+                ));
+            }
+        }
+
+        let mut keys: Vec<TcU32Key> = Vec::new();
+        for m in &self.matches {
+            if let Some((len, max)) = m.prefix_len() {
+                if len > max {
+                    return Err(Error::InvalidNla(format!(
+                        "invalid u32 prefix length {len}"
+                    )));
+                }
+            }
+            for field in m.fields() {
+                pack_key(&mut keys, field)?;
+            }
+        }
+        // The kernel needs at least one key, match every packet.
+        if keys.is_empty() {
+            keys.push(TcU32Key::default());
+        }
+
+        let mut selector = TcU32Selector::default();
+        if self.classid.is_some() || !self.actions.is_empty() {
+            selector.flags |= TcU32SelectorFlags::Terminal;
+        }
+        if let Some((mask, at)) = self.hashkey {
+            selector.hmask = mask.to_be();
+            selector.hoff = at as u16;
+        }
+        if let Some((at, mask, shift)) = self.offset {
+            selector.flags |= TcU32SelectorFlags::VarOffset;
+            selector.offoff = at as u16;
+            selector.offmask = mask.to_be();
+            selector.offshift = shift;
+        }
+        if let Some(plus) = self.offset_plus {
+            selector.flags |= TcU32SelectorFlags::Offset;
+            selector.off = plus as u16;
+        }
+        if self.eat {
+            selector.flags |= TcU32SelectorFlags::Eat;
+        }
+        selector.nkeys = u8::try_from(keys.len())
+            .map_err(|_| Error::InvalidNla("too many u32 keys".to_string()))?;
+        selector.keys = keys;
+        Ok(selector)
+    }
+
+    pub(crate) fn options(&self) -> Result<TcAttribute, Error> {
+        use TcFilterU32Option as O;
+
+        let mut opts = Vec::new();
+        if let Some(divisor) = self.divisor {
+            opts.push(O::Divisor(divisor));
+        } else {
+            opts.push(O::Selector(self.selector()?));
+        }
+        if let Some(classid) = self.classid {
+            opts.push(O::ClassId(classid.into()));
+        }
+        if let Some(ht) = self.ht {
+            opts.push(O::Hash(ht));
+        }
+        if let Some(link) = self.link {
+            opts.push(O::Link(link));
+        }
+        if !self.actions.is_empty() {
+            opts.push(O::Action(numbered_actions(&self.actions)));
+        }
+        let mut flags = TcU32OptionFlags::empty();
+        if self.skip_hw {
+            flags |= TcU32OptionFlags::SkipHw;
+        }
+        if self.skip_sw {
+            flags |= TcU32OptionFlags::SkipSw;
+        }
+        if !flags.is_empty() {
+            opts.push(O::Flags(flags));
+        }
+        Ok(TcAttribute::Options(
+            opts.into_iter().map(TcOption::U32).collect(),
+        ))
+    }
+}
+
+// Place `field` in the 32 bits word holding it, merging it with the key of
+// the same word if any, like pack_key() of iproute2/tc/f_u32.c.
+fn pack_key(keys: &mut Vec<TcU32Key>, field: Field) -> Result<(), Error> {
+    let byte = field.at.rem_euclid(4);
+    if byte + field.width > 4 {
+        return Err(Error::InvalidNla(format!(
+            "u32 match of {} bytes at {} crosses a 32 bits boundary",
+            field.width, field.at
+        )));
+    }
+    let shift = (4 - byte - field.width) * 8;
+    let width_mask = u32::MAX >> (32 - field.width * 8);
+    let mask = (field.mask & width_mask) << shift;
+    let value = (field.value & field.mask & width_mask) << shift;
+    let off = field.at - byte;
+    let offmask = if field.nexthdr { -1 } else { 0 };
+
+    if let Some(key) = keys
+        .iter_mut()
+        .find(|key| key.off == off && key.offmask == offmask)
+    {
+        if (key.val ^ value) & key.mask & mask != 0 {
+            return Err(Error::InvalidNla(format!(
+                "conflicting u32 matches at {off}"
+            )));
+        }
+        key.mask |= mask;
+        key.val |= value;
+    } else {
+        let mut key = TcU32Key::default();
+        key.mask = mask;
+        key.val = value;
+        key.off = off;
+        key.offmask = offmask;
+        keys.push(key);
+    }
+    Ok(())
+}
@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::{StreamExt, TryStreamExt};
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    tc::{TcAttribute, TcHandle, TcHeader, TcMessage},
+    RouteNetlinkMessage,
+};
+
+use super::{QDiscGetRequest, TrafficChainGetRequest, TrafficClassGetRequest};
+use crate::{try_nl, Error, Handle, TcMessageExt};
+
+const INGRESS: TcHandle = TcHandle {
+    major: 0xffff,
+    minor: TcHandle::MIN_INGRESS,
+};
+const EGRESS: TcHandle = TcHandle {
+    major: 0xffff,
+    minor: TcHandle::MIN_EGRESS,
+};
+
+#[derive(Debug, Clone)]
+pub struct TrafficFilterFlushRequest {
+    handle: Handle,
+    message: TcMessage,
+    // Whether the parent or the block was set, all the parents of the
+    // device are flushed otherwise.
+    parent_set: bool,
+}
+
+impl TrafficFilterFlushRequest {
+    pub(crate) fn new(handle: Handle, ifindex: i32) -> Self {
+        Self {
+            handle,
+            message: TcMessage::with_index(ifindex),
+            parent_set: false,
+        }
+    }
+
+    /// Execute the request, deleting the filters of every chain of the
+    /// parents with one request per chain.
+    pub async fn execute(self) -> Result<(), Error> {
+        let Self {
+            handle,
+            message,
+            parent_set,
+        } = self;
+
+        let parents = if parent_set {
+            vec![message.header.parent]
+        } else {
+            device_parents(&handle, message.header.index).await?
+        };
+        let chain = message.attributes.iter().find_map(|attr| match attr {
+            TcAttribute::Chain(chain) => Some(*chain),
+            _ => None,
+        });
+        for parent in parents {
+            let chains = match chain {
+                Some(chain) => vec![chain],
+                None => {
+                    parent_chains(&handle, message.header.index, parent).await?
+                }
+            };
+            for chain in chains {
+                let mut message = message.clone();
+                message.header.parent = parent;
+                message
+                    .attributes
+                    .retain(|attr| !matches!(attr, TcAttribute::Chain(_)));
+                message.attributes.push(TcAttribute::Chain(chain));
+                delete_chain_filters(handle.clone(), message).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set block index, to delete the filters of a shared block.
+    /// Equivalent to `block BLOCK_INDEX`.
+    pub fn block(mut self, block_index: u32) -> Self {
+        self.message.header.index = TcHeader::TCM_IFINDEX_MAGIC_BLOCK as i32;
+        self.message.header.parent = block_index.into();
+        self.parent_set = true;
+        self
+    }
+
+    /// Set parent.
+    /// Equivalent to `[ root | ingress | egress | parent CLASSID ]`
+    /// command args. They are mutually exclusive.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.message.header.parent = parent.into();
+        self.parent_set = true;
+        self
+    }
+
+    /// Set parent to root.
+    pub fn root(mut self) -> Self {
+        self.message.header.parent = TcHandle::ROOT;
+        self.parent_set = true;
+        self
+    }
+
+    /// Set parent to ingress.
+    pub fn ingress(mut self) -> Self {
+        self.message.header.parent = INGRESS;
+        self.parent_set = true;
+        self
+    }
+
+    /// Set parent to egress.
+    pub fn egress(mut self) -> Self {
+        self.message.header.parent = EGRESS;
+        self.parent_set = true;
+        self
+    }
+
+    /// Only delete the filters of this chain, the filters of all the
+    /// chains are deleted otherwise.
+    /// Equivalent to `chain CHAIN_INDEX`.
+    pub fn chain(mut self, chain: u32) -> Self {
+        self.message
+            .attributes
+            .retain(|attr| !matches!(attr, TcAttribute::Chain(_)));
+        self.message.attributes.push(TcAttribute::Chain(chain));
+        self
+    }
+
+    /// Return a mutable reference to the message sent for each chain,
+    /// before its parent and chain are set.
+    pub fn message_mut(&mut self) -> &mut TcMessage {
+        &mut self.message
+    }
+}
+
+// The parents the filters of a device can be attached to: both directions
+// of a clsact qdisc, the ingress qdisc, and the other qdiscs and their
+// classes. The directions bound to a shared block are left alone, as its
+// filters are shared with the other devices.
+async fn device_parents(
+    handle: &Handle,
+    ifindex: i32,
+) -> Result<Vec<TcHandle>, Error> {
+    let mut parents = Vec::new();
+    let mut qdiscs = QDiscGetRequest::new(handle.clone())
+        .index(ifindex)
+        .execute();
+    while let Some(qdisc) = qdiscs.try_next().await? {
+        if qdisc.header.index != ifindex {
+            continue;
+        }
+        match qdisc.kind() {
+            Some("clsact") => {
+                if qdisc.ingress_block().is_none() {
+                    parents.push(INGRESS);
+                }
+                if qdisc.egress_block().is_none() {
+                    parents.push(EGRESS);
+                }
+            }
+            Some("ingress") => {
+                if qdisc.ingress_block().is_none() {
+                    parents.push(INGRESS);
+                }
+            }
+            _ => parents.push(qdisc.header.handle),
+        }
+    }
+    let mut classes =
+        TrafficClassGetRequest::new(handle.clone(), ifindex).execute();
+    while let Some(class) = classes.try_next().await? {
+        parents.push(class.header.handle);
+    }
+    let mut unique = Vec::new();
+    for parent in parents {
+        if !unique.contains(&parent) {
+            unique.push(parent);
+        }
+    }
+    Ok(unique)
+}
+
+async fn parent_chains(
+    handle: &Handle,
+    index: i32,
+    parent: TcHandle,
+) -> Result<Vec<u32>, Error> {
+    let chains: Vec<_> = TrafficChainGetRequest::new(handle.clone(), index)
+        .parent(parent.into())
+        .execute()
+        .try_collect()
+        .await?;
+    Ok(chains
+        .iter()
+        .filter_map(|chain| {
+            chain.attributes.iter().find_map(|attr| match attr {
+                TcAttribute::Chain(chain) => Some(*chain),
+                _ => None,
+            })
+        })
+        .collect())
+}
+
+// Without priority, the kernel deletes all the filters of the chain.
+async fn delete_chain_filters(
+    mut handle: Handle,
+    message: TcMessage,
+) -> Result<(), Error> {
+    let mut req =
+        NetlinkMessage::from(RouteNetlinkMessage::DelTrafficFilter(message));
+    req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+    let mut response = handle.request(req)?;
+    if let Some(message) = response.next().await {
+        try_nl!(message);
+    }
+    Ok(())
+}
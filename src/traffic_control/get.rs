@@ -2,16 +2,22 @@
 
 use futures_util::{
     future::{self, Either},
-    stream::{Stream, StreamExt},
+    stream::{self, Stream, StreamExt},
     FutureExt,
 };
-use netlink_packet_core::{NetlinkMessage, NLM_F_DUMP, NLM_F_REQUEST};
+use netlink_packet_core::{
+    NetlinkMessage, NetlinkPayload, NLM_F_DUMP, NLM_F_REQUEST,
+};
 use netlink_packet_route::{
-    tc::{TcHandle, TcMessage},
+    tc::{
+        TcAction, TcActionAttribute, TcActionMessage, TcActionMessageAttribute,
+        TcActionMessageFlags, TcActionMessageFlagsWithSelector, TcHandle,
+        TcHeader, TcMessage,
+    },
     RouteNetlinkMessage,
 };
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{try_rtnl, Error, Handle, TcMessageExt};
 
 #[derive(Debug, Clone)]
 pub struct QDiscGetRequest {
@@ -131,6 +137,12 @@ impl TrafficFilterGetRequest {
         }
     }
 
+    /// Set parent, the filters of the root qdisc are listed otherwise.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.message.header.parent = parent.into();
+        self
+    }
+
     /// Set parent to root.
     pub fn root(mut self) -> Self {
         self.message.header.parent = TcHandle::ROOT;
@@ -154,6 +166,15 @@ impl TrafficFilterGetRequest {
         };
         self
     }
+
+    /// Set block index, to list the filters of a shared block instead of
+    /// a device.
+    /// Equivalent to `block BLOCK_INDEX`.
+    pub fn block(mut self, block_index: u32) -> Self {
+        self.message.header.index = TcHeader::TCM_IFINDEX_MAGIC_BLOCK as i32;
+        self.message.header.parent = block_index.into();
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -189,4 +210,240 @@ impl TrafficChainGetRequest {
             }
         }
     }
+
+    /// Set parent, the chains of the root qdisc are listed otherwise.
+    pub fn parent(mut self, parent: u32) -> Self {
+        self.message.header.parent = parent.into();
+        self
+    }
+
+    /// Set parent to ingress.
+    pub fn ingress(mut self) -> Self {
+        self.message.header.parent = TcHandle {
+            major: 0xffff,
+            minor: TcHandle::MIN_INGRESS,
+        };
+        self
+    }
+
+    /// Set parent to egress.
+    pub fn egress(mut self) -> Self {
+        self.message.header.parent = TcHandle {
+            major: 0xffff,
+            minor: TcHandle::MIN_EGRESS,
+        };
+        self
+    }
+
+    /// Set block index, to list the chains of a shared block instead of
+    /// a device.
+    /// Equivalent to `block BLOCK_INDEX`.
+    pub fn block(mut self, block_index: u32) -> Self {
+        self.message.header.index = TcHeader::TCM_IFINDEX_MAGIC_BLOCK as i32;
+        self.message.header.parent = block_index.into();
+        self
+    }
+}
+
+/// Binding of a shared filter block to a device, see
+/// [crate::QDiscNewRequest::ingress_block] and
+/// [crate::QDiscNewRequest::egress_block].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TrafficBlock {
+    /// Index of the shared block.
+    pub block_index: u32,
+    /// Interface index of the device bound to the block.
+    pub ifindex: i32,
+    /// Whether the block holds the egress filters of the device, or the
+    /// ingress ones.
+    pub egress: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrafficBlockGetRequest {
+    handle: Handle,
+    message: TcMessage,
+}
+
+impl TrafficBlockGetRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        TrafficBlockGetRequest {
+            handle,
+            message: TcMessage::default(),
+        }
+    }
+
+    /// Execute the request, returning one [TrafficBlock] per device
+    /// direction bound to a shared block.
+    pub fn execute(self) -> impl Stream<Item = Result<TrafficBlock, Error>> {
+        let TrafficBlockGetRequest {
+            mut handle,
+            message,
+        } = self;
+
+        // The kernel dumps the qdiscs of all the devices.
+        let index = message.header.index;
+        let mut req = NetlinkMessage::from(
+            RouteNetlinkMessage::GetQueueDiscipline(message),
+        );
+        req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+
+        let blocks = move |msg: NetlinkMessage<RouteNetlinkMessage>| {
+            let qdisc = match msg.payload {
+                NetlinkPayload::InnerMessage(
+                    RouteNetlinkMessage::NewQueueDiscipline(qdisc),
+                ) => qdisc,
+                NetlinkPayload::Error(err) => {
+                    return Either::Left(stream::once(future::err(
+                        Error::NetlinkError(err),
+                    )))
+                }
+                _ => {
+                    return Either::Left(stream::once(future::err(
+                        Error::UnexpectedMessage(msg),
+                    )))
+                }
+            };
+            let ifindex = qdisc.header.index;
+            if index != 0 && index != ifindex {
+                return Either::Right(stream::iter(Vec::new()));
+            }
+            let blocks =
+                [(qdisc.ingress_block(), false), (qdisc.egress_block(), true)];
+            Either::Right(stream::iter(
+                blocks
+                    .into_iter()
+                    .filter_map(|(block_index, egress)| {
+                        block_index.map(|block_index| {
+                            Ok(TrafficBlock {
+                                block_index,
+                                ifindex,
+                                egress,
+                            })
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            ))
+        };
+
+        match handle.request(req) {
+            Ok(response) => Either::Left(response.flat_map(blocks)),
+            Err(e) => Either::Right(
+                future::err::<TrafficBlock, Error>(e).into_stream(),
+            ),
+        }
+    }
+
+    /// Only list the blocks bound to this device.
+    pub fn index(mut self, index: i32) -> Self {
+        self.message.header.index = index;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrafficActionGetRequest {
+    handle: Handle,
+    message: TcActionMessage,
+    dump: bool,
+}
+
+impl TrafficActionGetRequest {
+    pub(crate) fn new(handle: Handle, kind: &str) -> Self {
+        let mut action = TcAction::default();
+        action.attributes = vec![TcActionAttribute::Kind(kind.to_string())];
+        let mut message = TcActionMessage::default();
+        message.attributes = vec![
+            TcActionMessageAttribute::Actions(vec![action]),
+            TcActionMessageAttribute::Flags(
+                TcActionMessageFlagsWithSelector::new(
+                    TcActionMessageFlags::LargeDump,
+                ),
+            ),
+        ];
+        TrafficActionGetRequest {
+            handle,
+            message,
+            dump: true,
+        }
+    }
+
+    /// Execute the request, returning the actions with their statistics.
+    pub fn execute(self) -> impl Stream<Item = Result<TcAction, Error>> {
+        let TrafficActionGetRequest {
+            mut handle,
+            message,
+            dump,
+        } = self;
+
+        let mut req = NetlinkMessage::from(
+            RouteNetlinkMessage::GetTrafficAction(message),
+        );
+        req.header.flags = if dump {
+            NLM_F_REQUEST | NLM_F_DUMP
+        } else {
+            NLM_F_REQUEST
+        };
+
+        // The kernel replies with the type of the request.
+        let actions = |msg: NetlinkMessage<RouteNetlinkMessage>| {
+            let msg = match msg.payload {
+                NetlinkPayload::InnerMessage(
+                    RouteNetlinkMessage::GetTrafficAction(msg)
+                    | RouteNetlinkMessage::NewTrafficAction(msg),
+                ) => msg,
+                NetlinkPayload::Error(err) => {
+                    return Either::Left(stream::once(future::err(
+                        Error::NetlinkError(err),
+                    )))
+                }
+                _ => {
+                    return Either::Left(stream::once(future::err(
+                        Error::UnexpectedMessage(msg),
+                    )))
+                }
+            };
+            Either::Right(stream::iter(
+                msg.attributes
+                    .into_iter()
+                    .filter_map(|attr| match attr {
+                        TcActionMessageAttribute::Actions(actions) => {
+                            Some(actions)
+                        }
+                        _ => None,
+                    })
+                    .flatten()
+                    .map(Ok),
+            ))
+        };
+
+        match handle.request(req) {
+            Ok(response) => Either::Left(response.flat_map(actions)),
+            Err(e) => {
+                Either::Right(future::err::<TcAction, Error>(e).into_stream())
+            }
+        }
+    }
+
+    /// Only retrieve the action of this kind with the given index.
+    /// Equivalent to `tc actions get action KIND index INDEX`.
+    pub fn index(mut self, index: u32) -> Self {
+        // Dump flags are rejected by the kernel outside of dumps.
+        self.message.attributes.retain(|attr| {
+            matches!(attr, TcActionMessageAttribute::Actions(_))
+        });
+        if let Some(TcActionMessageAttribute::Actions(actions)) =
+            self.message.attributes.first_mut()
+        {
+            actions[0].attributes.push(TcActionAttribute::Index(index));
+        }
+        self.dump = false;
+        self
+    }
+
+    /// Return a mutable reference to the request
+    pub fn message_mut(&mut self) -> &mut TcActionMessage {
+        &mut self.message
+    }
 }
@@ -4,9 +4,12 @@ use netlink_packet_core::{NLM_F_CREATE, NLM_F_EXCL, NLM_F_REPLACE};
 use netlink_packet_route::tc::TcMessage;
 
 use super::{
-    QDiscDelRequest, QDiscGetRequest, QDiscNewRequest, TrafficChainGetRequest,
-    TrafficClassGetRequest, TrafficFilterDelRequest, TrafficFilterGetRequest,
-    TrafficFilterNewRequest,
+    QDiscDelRequest, QDiscGetRequest, QDiscNewRequest, QDiscStatsPollRequest,
+    TrafficActionDelRequest, TrafficActionGetRequest, TrafficActionNewRequest,
+    TrafficBlockGetRequest, TrafficChainDelRequest, TrafficChainGetRequest,
+    TrafficChainNewRequest, TrafficClassDelRequest, TrafficClassGetRequest,
+    TrafficClassNewRequest, TrafficFilterDelRequest, TrafficFilterFlushRequest,
+    TrafficFilterGetRequest, TrafficFilterNewRequest,
 };
 use crate::Handle;
 
@@ -23,6 +26,19 @@ impl QDiscHandle {
         QDiscGetRequest::new(self.0.clone())
     }
 
+    /// Retrieve the shared filter blocks bound to the devices by their
+    /// ingress or clsact qdisc (equivalent to the `ingress_block` and
+    /// `egress_block` of `tc qdisc show`)
+    pub fn blocks(&mut self) -> TrafficBlockGetRequest {
+        TrafficBlockGetRequest::new(self.0.clone())
+    }
+
+    /// Periodically sample the statistics of the qdiscs and classes of a
+    /// device (equivalent to `tc -s qdisc show dev STRING` in a loop)
+    pub fn poll_stats(&mut self, index: i32) -> QDiscStatsPollRequest {
+        QDiscStatsPollRequest::new(self.0.clone(), index)
+    }
+
     /// Create a new qdisc, don't replace if the object already exists.
     /// ( equivalent to `tc qdisc add dev STRING`)
     pub fn add(&mut self, index: i32) -> QDiscNewRequest {
@@ -75,6 +91,44 @@ impl TrafficClassHandle {
     pub fn get(&mut self) -> TrafficClassGetRequest {
         TrafficClassGetRequest::new(self.handle.clone(), self.ifindex)
     }
+
+    /// Create a new traffic class, don't replace if the object already
+    /// exists. ( equivalent to `tc class add dev STRING`)
+    pub fn add(&mut self) -> TrafficClassNewRequest {
+        TrafficClassNewRequest::new(
+            self.handle.clone(),
+            TcMessage::with_index(self.ifindex),
+            NLM_F_EXCL | NLM_F_CREATE,
+        )
+    }
+
+    /// Change the traffic class, the class id cannot be changed and neither
+    /// can the parent. ( equivalent to `tc class change dev STRING`)
+    pub fn change(&mut self) -> TrafficClassNewRequest {
+        TrafficClassNewRequest::new(
+            self.handle.clone(),
+            TcMessage::with_index(self.ifindex),
+            0,
+        )
+    }
+
+    /// Replace existing matching traffic class, create it if it doesn't
+    /// already exist. ( equivalent to `tc class replace dev STRING`)
+    pub fn replace(&mut self) -> TrafficClassNewRequest {
+        TrafficClassNewRequest::new(
+            self.handle.clone(),
+            TcMessage::with_index(self.ifindex),
+            NLM_F_CREATE,
+        )
+    }
+
+    /// Delete the traffic class ( equivalent to `tc class del dev STRING`)
+    pub fn del(&mut self) -> TrafficClassDelRequest {
+        TrafficClassDelRequest::new(
+            self.handle.clone(),
+            TcMessage::with_index(self.ifindex),
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -110,6 +164,15 @@ impl TrafficFilterHandle {
         TrafficFilterDelRequest::new(self.handle.clone(), self.ifindex)
     }
 
+    /// Delete the filters of every chain under the parent or shared block
+    /// set on the request, or under all the parents of the device: both
+    /// directions of a clsact qdisc, and the other qdiscs and classes.
+    /// ( equivalent to `tc filter del dev STRING [ parent CLASSID ]` for
+    /// each chain listed by `tc chain show`)
+    pub fn flush(&mut self) -> TrafficFilterFlushRequest {
+        TrafficFilterFlushRequest::new(self.handle.clone(), self.ifindex)
+    }
+
     /// Change the filter, the handle cannot be changed and neither can the
     /// parent. In other words, change cannot move a node.
     /// ( equivalent to `tc filter change dev STRING`)
@@ -144,4 +207,65 @@ impl TrafficChainHandle {
     pub fn get(&mut self) -> TrafficChainGetRequest {
         TrafficChainGetRequest::new(self.handle.clone(), self.ifindex)
     }
+
+    /// Create a chain, optionally with a template restricting the keys of
+    /// its filters, fail if it already exists.
+    /// ( equivalent to `tc chain add dev STRING`)
+    pub fn add(&mut self) -> TrafficChainNewRequest {
+        TrafficChainNewRequest::new(
+            self.handle.clone(),
+            self.ifindex,
+            NLM_F_EXCL | NLM_F_CREATE,
+        )
+    }
+
+    /// Delete a chain and all of its filters.
+    /// ( equivalent to `tc chain del dev STRING`)
+    pub fn del(&mut self) -> TrafficChainDelRequest {
+        TrafficChainDelRequest::new(self.handle.clone(), self.ifindex)
+    }
+}
+
+/// Actions created on their own, shared by the filters referring to their
+/// kind and index, like a policer enforcing one rate for several filters.
+#[derive(Debug, Clone)]
+pub struct TrafficActionHandle(Handle);
+
+impl TrafficActionHandle {
+    pub fn new(handle: Handle) -> Self {
+        TrafficActionHandle(handle)
+    }
+
+    /// Retrieve the actions of a kind with their statistics (equivalent to
+    /// `tc -s actions ls action KIND`)
+    pub fn get(&mut self, kind: &str) -> TrafficActionGetRequest {
+        TrafficActionGetRequest::new(self.0.clone(), kind)
+    }
+
+    /// Create new actions, don't replace if one of them already exists.
+    /// ( equivalent to `tc actions add`)
+    pub fn add(&mut self) -> TrafficActionNewRequest {
+        TrafficActionNewRequest::new(self.0.clone(), NLM_F_EXCL | NLM_F_CREATE)
+    }
+
+    /// Replace existing actions, create them if they don't already exist.
+    /// ( equivalent to `tc actions replace`)
+    pub fn replace(&mut self) -> TrafficActionNewRequest {
+        TrafficActionNewRequest::new(
+            self.0.clone(),
+            NLM_F_CREATE | NLM_F_REPLACE,
+        )
+    }
+
+    /// Delete an action, which must not be used by any filter anymore.
+    /// ( equivalent to `tc actions del action KIND index INDEX`)
+    pub fn del(&mut self, kind: &str, index: u32) -> TrafficActionDelRequest {
+        TrafficActionDelRequest::new(self.0.clone(), kind, index)
+    }
+
+    /// Delete all the actions of a kind.
+    /// ( equivalent to `tc actions flush action KIND`)
+    pub fn flush(&mut self, kind: &str) -> TrafficActionDelRequest {
+        TrafficActionDelRequest::flush(self.0.clone(), kind)
+    }
 }
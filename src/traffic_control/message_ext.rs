@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::DecodeError;
+use netlink_packet_route::tc::{TcAction, TcActionAttribute, TcMessage};
+
+use super::{
+    actions::{action_kind, action_options_payload},
+    nla::{
+        message_kind, message_u32, options_payload, TCA_EGRESS_BLOCK,
+        TCA_INGRESS_BLOCK,
+    },
+    QDiscOptions, TrafficActionOptions, TrafficClassOptions,
+    TrafficFilterOptions, TrafficStats, TrafficXstats,
+};
+
+/// Typed accessors for the attributes of a [TcMessage], like the ones
+/// returned by [crate::QDiscGetRequest], [crate::TrafficClassGetRequest] or
+/// [crate::TrafficFilterGetRequest].
+pub trait TcMessageExt {
+    /// Kind of the qdisc, class or filter (`TCA_KIND`).
+    fn kind(&self) -> Option<&str>;
+
+    /// Options of a qdisc (`TCA_OPTIONS`), `None` if the message has no
+    /// kind.
+    fn qdisc_options(&self) -> Result<Option<QDiscOptions>, DecodeError>;
+
+    /// Options of a traffic class (`TCA_OPTIONS`), `None` if the message
+    /// has no kind.
+    fn class_options(&self)
+        -> Result<Option<TrafficClassOptions>, DecodeError>;
+
+    /// Options of a traffic filter (`TCA_OPTIONS`), `None` if the message
+    /// has no kind.
+    fn filter_options(
+        &self,
+    ) -> Result<Option<TrafficFilterOptions>, DecodeError>;
+
+    /// Shared block bound to the ingress of an ingress or clsact qdisc
+    /// (`TCA_INGRESS_BLOCK`).
+    fn ingress_block(&self) -> Option<u32>;
+
+    /// Shared block bound to the egress of a clsact qdisc
+    /// (`TCA_EGRESS_BLOCK`).
+    fn egress_block(&self) -> Option<u32>;
+
+    /// Generic statistics (`TCA_STATS2` or `TCA_STATS`), only reported
+    /// when the message was dumped with statistics.
+    fn stats(&self) -> Option<TrafficStats>;
+
+    /// Statistics specific to the kind of the qdisc or class
+    /// (`TCA_STATS_APP` or `TCA_XSTATS`).
+    fn xstats(&self) -> Result<Option<TrafficXstats>, DecodeError>;
+}
+
+impl TcMessageExt for TcMessage {
+    fn kind(&self) -> Option<&str> {
+        message_kind(self)
+    }
+
+    fn qdisc_options(&self) -> Result<Option<QDiscOptions>, DecodeError> {
+        let Some(kind) = message_kind(self) else {
+            return Ok(None);
+        };
+        let payload = options_payload(self).unwrap_or_default();
+        QDiscOptions::parse(kind, &payload).map(Some)
+    }
+
+    fn class_options(
+        &self,
+    ) -> Result<Option<TrafficClassOptions>, DecodeError> {
+        let Some(kind) = message_kind(self) else {
+            return Ok(None);
+        };
+        let payload = options_payload(self).unwrap_or_default();
+        TrafficClassOptions::parse(kind, &payload).map(Some)
+    }
+
+    fn filter_options(
+        &self,
+    ) -> Result<Option<TrafficFilterOptions>, DecodeError> {
+        let Some(kind) = message_kind(self) else {
+            return Ok(None);
+        };
+        let payload = options_payload(self).unwrap_or_default();
+        TrafficFilterOptions::parse(kind, &payload).map(Some)
+    }
+
+    fn ingress_block(&self) -> Option<u32> {
+        message_u32(self, TCA_INGRESS_BLOCK)
+    }
+
+    fn egress_block(&self) -> Option<u32> {
+        message_u32(self, TCA_EGRESS_BLOCK)
+    }
+
+    fn stats(&self) -> Option<TrafficStats> {
+        TrafficStats::from_message(self)
+    }
+
+    fn xstats(&self) -> Result<Option<TrafficXstats>, DecodeError> {
+        TrafficXstats::from_message(self)
+    }
+}
+
+/// Typed accessors for the attributes of a [TcAction], like the ones of
+/// [TrafficFilterOptions].
+pub trait TcActionExt {
+    /// Kind of the action (`TCA_ACT_KIND`).
+    fn kind(&self) -> Option<&str>;
+
+    /// Options of the action (`TCA_ACT_OPTIONS`), `None` if the action has
+    /// no kind.
+    fn action_options(
+        &self,
+    ) -> Result<Option<TrafficActionOptions>, DecodeError>;
+
+    /// Statistics of the action (`TCA_ACT_STATS`).
+    fn stats(&self) -> Option<TrafficStats>;
+}
+
+impl TcActionExt for TcAction {
+    fn kind(&self) -> Option<&str> {
+        action_kind(self)
+    }
+
+    fn action_options(
+        &self,
+    ) -> Result<Option<TrafficActionOptions>, DecodeError> {
+        let Some(kind) = action_kind(self) else {
+            return Ok(None);
+        };
+        let payload = action_options_payload(self).unwrap_or_default();
+        TrafficActionOptions::parse(kind, &payload).map(Some)
+    }
+
+    fn stats(&self) -> Option<TrafficStats> {
+        self.attributes.iter().find_map(|attr| match attr {
+            TcActionAttribute::Stats(stats2) => {
+                Some(TrafficStats::from_stats2(stats2))
+            }
+            _ => None,
+        })
+    }
+}
@@ -1,25 +1,81 @@
 // SPDX-License-Identifier: MIT
 
+mod actions;
+mod add_action;
+mod add_chain;
+mod add_class;
 mod add_filter;
 mod add_qdisc;
+mod del_action;
+mod del_chain;
+mod del_class;
 mod del_filter;
 mod del_qdisc;
+mod filters;
+mod flush_filter;
 mod get;
 mod handle;
+mod message_ext;
+pub(crate) mod nla;
+mod notation;
+mod poll_stats;
+mod qdiscs;
+mod ratespec;
+mod stats;
 #[cfg(test)]
 mod test;
+mod tree;
 
 pub use self::{
+    actions::{
+        PeditCmd, PeditHeader, PeditKey, TrafficActionCsum, TrafficActionCt,
+        TrafficActionGact, TrafficActionMirred, TrafficActionNat,
+        TrafficActionOptions, TrafficActionPedit, TrafficActionPolice,
+        TrafficActionSkbedit, TrafficActionTunnelKey, TrafficActionVlan,
+        VlanOp,
+    },
+    add_action::TrafficActionNewRequest,
+    add_chain::TrafficChainNewRequest,
+    add_class::TrafficClassNewRequest,
     add_filter::TrafficFilterNewRequest,
     add_qdisc::QDiscNewRequest,
+    del_action::TrafficActionDelRequest,
+    del_chain::TrafficChainDelRequest,
+    del_class::TrafficClassDelRequest,
     del_filter::TrafficFilterDelRequest,
     del_qdisc::QDiscDelRequest,
+    filters::{
+        Ematch, EmatchCmp, EmatchCmpAlign, EmatchLayer, EmatchMeta,
+        EmatchMetaId, EmatchOperand, EmatchU32, TrafficFilterBasic,
+        TrafficFilterBpf, TrafficFilterFlower, TrafficFilterFw,
+        TrafficFilterMatchall, TrafficFilterOptions, TrafficFilterRoute,
+        TrafficFilterU32, U32Match,
+    },
+    flush_filter::TrafficFilterFlushRequest,
     get::{
-        QDiscGetRequest, TrafficChainGetRequest, TrafficClassGetRequest,
+        QDiscGetRequest, TrafficActionGetRequest, TrafficBlock,
+        TrafficBlockGetRequest, TrafficChainGetRequest, TrafficClassGetRequest,
         TrafficFilterGetRequest,
     },
     handle::{
-        QDiscHandle, TrafficChainHandle, TrafficClassHandle,
-        TrafficFilterHandle,
+        QDiscHandle, TrafficActionHandle, TrafficChainHandle,
+        TrafficClassHandle, TrafficFilterHandle,
+    },
+    message_ext::{TcActionExt, TcMessageExt},
+    notation::{TcClassId, TcRate, TcSize, TcTime},
+    poll_stats::{
+        QDiscStatsPollRequest, TrafficStatsDelta, TrafficStatsSample,
+    },
+    qdiscs::{
+        CakeAckFilter, CakeAtm, CakeDiffserv, CakeFlowMode, CakeTinStats,
+        CakeXstats, FqXstats, HfscServiceCurve, HtbXstats, MqprioMode,
+        MqprioShaper, NetemLoss, NetemRate, NetemSlot, QDiscBfifo, QDiscCake,
+        QDiscDrr, QDiscEts, QDiscFq, QDiscFqCodel, QDiscHfsc, QDiscHtb,
+        QDiscMq, QDiscMqprio, QDiscNetem, QDiscOptions, QDiscPfifo, QDiscPrio,
+        QDiscSfq, QDiscTaprio, QDiscTbf, TaprioFramePreemption,
+        TaprioGateCommand, TaprioSchedEntry, TrafficClassDrr, TrafficClassEts,
+        TrafficClassHfsc, TrafficClassHtb, TrafficClassOptions,
     },
+    stats::{TrafficStats, TrafficXstats},
+    tree::{TrafficTree, TrafficTreeGetRequest, TrafficTreeNode},
 };
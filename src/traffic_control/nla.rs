@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+
+// Helpers to encode and decode the TCA_OPTIONS of the tc kinds which are
+// not modeled by netlink-packet-route.
+
+use netlink_packet_core::{DefaultNla, Emitable, Nla, NLA_F_NESTED};
+use netlink_packet_route::tc::{TcAttribute, TcMessage, TcOption};
+
+pub(crate) use crate::nla::{
+    emit_nlas, nla_flag, nla_nested, nla_u16, nla_u32, nla_u64, nla_u8,
+    nla_value, parse_nlas, read_u16, read_u32, read_u64,
+};
+
+pub(crate) const TCA_OPTIONS: u16 = 2;
+pub(crate) const TCA_INGRESS_BLOCK: u16 = 13;
+pub(crate) const TCA_EGRESS_BLOCK: u16 = 14;
+
+// Kinds whose TCA_OPTIONS are parsed into typed [TcOption] by
+// netlink-packet-route.
+const TYPED_KINDS: [&str; 6] =
+    ["ingress", "fq_codel", "flower", "u32", "matchall", "bpf"];
+
+/// `TCA_OPTIONS` holding nested attributes.
+pub(crate) fn nested_options(nlas: &[DefaultNla]) -> TcAttribute {
+    TcAttribute::Other(nla_nested(TCA_OPTIONS, nlas))
+}
+
+/// `TCA_OPTIONS` holding a raw kernel struct.
+pub(crate) fn raw_options(payload: Vec<u8>) -> TcAttribute {
+    TcAttribute::Other(DefaultNla::new(TCA_OPTIONS, payload))
+}
+
+pub(crate) fn message_kind(message: &TcMessage) -> Option<&str> {
+    message.attributes.iter().find_map(|attr| match attr {
+        TcAttribute::Kind(kind) => Some(kind.as_str()),
+        _ => None,
+    })
+}
+
+/// Value of a `u32` attribute not modeled by netlink-packet-route.
+pub(crate) fn message_u32(message: &TcMessage, kind: u16) -> Option<u32> {
+    message.attributes.iter().find_map(|attr| match attr {
+        TcAttribute::Other(nla) if nla.kind() == kind => {
+            read_u32(&nla_value(nla), 0).ok()
+        }
+        _ => None,
+    })
+}
+
+/// Replace the `u32` attribute `kind` of `message`.
+pub(crate) fn set_message_u32(message: &mut TcMessage, kind: u16, value: u32) {
+    message.attributes.retain(|attr| match attr {
+        TcAttribute::Other(nla) => nla.kind() != kind,
+        _ => true,
+    });
+    message
+        .attributes
+        .push(TcAttribute::Other(nla_u32(kind, value)));
+}
+
+/// Raw payload of `TCA_OPTIONS`, whether it was parsed by
+/// netlink-packet-route or built by this crate.
+pub(crate) fn options_payload(message: &TcMessage) -> Option<Vec<u8>> {
+    let typed = message_kind(message)
+        .map(|kind| TYPED_KINDS.contains(&kind))
+        .unwrap_or_default();
+    message.attributes.iter().find_map(|attr| match attr {
+        TcAttribute::Options(options) if typed => {
+            Some(emit_tc_options(options))
+        }
+        TcAttribute::Options(options) => match options.as_slice() {
+            [TcOption::Other(nla)] => Some(nla_value(nla)),
+            _ => Some(emit_tc_options(options)),
+        },
+        TcAttribute::Other(nla) if is_options(nla) => Some(nla_value(nla)),
+        _ => None,
+    })
+}
+
+fn emit_tc_options(options: &[TcOption]) -> Vec<u8> {
+    let mut buffer = vec![0u8; options.buffer_len()];
+    options.emit(&mut buffer);
+    buffer
+}
+
+fn is_options(nla: &DefaultNla) -> bool {
+    nla.kind() & !NLA_F_NESTED == TCA_OPTIONS
+}
+
+/// Replace `TCA_KIND` and `TCA_OPTIONS` of `message`.
+pub(crate) fn set_kind_options(
+    message: &mut TcMessage,
+    kind: &str,
+    options: Option<TcAttribute>,
+) {
+    message.attributes.retain(|attr| match attr {
+        TcAttribute::Kind(_) | TcAttribute::Options(_) => false,
+        TcAttribute::Other(nla) => !is_options(nla),
+        _ => true,
+    });
+    message.attributes.push(TcAttribute::Kind(kind.to_string()));
+    if let Some(options) = options {
+        message.attributes.push(options);
+    }
+}
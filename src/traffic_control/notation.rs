@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: MIT
+
+// Handles, rates, sizes and times written like the arguments of `tc`, see
+// get_tc_classid(), get_rate64(), get_size() and get_time() in
+// iproute2/tc/tc_util.c, parsed with `FromStr` and formatted with
+// `Display`.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use netlink_packet_route::tc::TcHandle;
+
+use crate::Error;
+
+// Rate units in bits per second, matched case insensitively.
+const RATE_UNITS: [(&str, f64); 19] = [
+    ("", 1.),
+    ("bit", 1.),
+    ("kibit", 1024.),
+    ("kbit", 1e3),
+    ("mibit", 1024. * 1024.),
+    ("mbit", 1e6),
+    ("gibit", 1024. * 1024. * 1024.),
+    ("gbit", 1e9),
+    ("tibit", 1024. * 1024. * 1024. * 1024.),
+    ("tbit", 1e12),
+    ("bps", 8.),
+    ("kibps", 8. * 1024.),
+    ("kbps", 8e3),
+    ("mibps", 8. * 1024. * 1024.),
+    ("mbps", 8e6),
+    ("gibps", 8. * 1024. * 1024. * 1024.),
+    ("gbps", 8e9),
+    ("tibps", 8. * 1024. * 1024. * 1024. * 1024.),
+    ("tbps", 8e12),
+];
+
+// Size units in bytes, matched case insensitively.
+const SIZE_UNITS: [(&str, f64); 11] = [
+    ("", 1.),
+    ("b", 1.),
+    ("k", 1024.),
+    ("kb", 1024.),
+    ("kbit", 1024. / 8.),
+    ("m", 1024. * 1024.),
+    ("mb", 1024. * 1024.),
+    ("mbit", 1024. * 1024. / 8.),
+    ("g", 1024. * 1024. * 1024.),
+    ("gb", 1024. * 1024. * 1024.),
+    ("gbit", 1024. * 1024. * 1024. / 8.),
+];
+
+// Time units in nanoseconds, `tc` defaults to microseconds.
+const TIME_UNITS: [(&str, f64); 13] = [
+    ("", 1e3),
+    ("s", 1e9),
+    ("sec", 1e9),
+    ("secs", 1e9),
+    ("ms", 1e6),
+    ("msec", 1e6),
+    ("msecs", 1e6),
+    ("us", 1e3),
+    ("usec", 1e3),
+    ("usecs", 1e3),
+    ("ns", 1.),
+    ("nsec", 1.),
+    ("nsecs", 1.),
+];
+
+fn invalid(s: &str) -> Error {
+    Error::InvalidTcNotation(s.to_string())
+}
+
+/// A qdisc handle or class id in the `tc` notation: `MAJOR:MINOR`,
+/// `MAJOR:`, `:MINOR` with hexadecimal numbers, `root`, `ingress`, `clsact`
+/// or `none`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TcClassId(pub TcHandle);
+
+impl FromStr for TcClassId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "root" => return Ok(Self(TcHandle::ROOT)),
+            "ingress" => return Ok(Self(TcHandle::INGRESS)),
+            "clsact" => return Ok(Self(TcHandle::CLSACT)),
+            "none" => return Ok(Self(TcHandle::UNSPEC)),
+            _ => (),
+        }
+        let hex = |n: &str| {
+            if n.is_empty() {
+                Ok(0)
+            } else {
+                u16::from_str_radix(n, 16).map_err(|_| invalid(s))
+            }
+        };
+        let (major, minor) = s.split_once(':').unwrap_or((s, ""));
+        if s.is_empty()
+            || s == ":"
+            || !s.chars().all(|c| c.is_ascii_hexdigit() || c == ':')
+        {
+            return Err(invalid(s));
+        }
+        Ok(Self(TcHandle {
+            major: hex(major)?,
+            minor: hex(minor)?,
+        }))
+    }
+}
+
+impl fmt::Display for TcClassId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let handle = self.0;
+        if handle == TcHandle::ROOT {
+            write!(f, "root")
+        } else if handle == TcHandle::UNSPEC {
+            write!(f, "none")
+        } else if handle.major == 0 {
+            write!(f, ":{:x}", handle.minor)
+        } else if handle.minor == 0 {
+            write!(f, "{:x}:", handle.major)
+        } else {
+            write!(f, "{:x}:{:x}", handle.major, handle.minor)
+        }
+    }
+}
+
+impl From<TcHandle> for TcClassId {
+    fn from(handle: TcHandle) -> Self {
+        Self(handle)
+    }
+}
+
+impl From<TcClassId> for TcHandle {
+    fn from(classid: TcClassId) -> Self {
+        classid.0
+    }
+}
+
+impl From<TcClassId> for u32 {
+    fn from(classid: TcClassId) -> Self {
+        classid.0.into()
+    }
+}
+
+// Split a number and its unit, and scale it by the unit.
+fn parse_scaled(s: &str, units: &[(&str, f64)]) -> Result<f64, Error> {
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().map_err(|_| invalid(s))?;
+    let scale = units
+        .iter()
+        .find_map(|(name, scale)| {
+            name.eq_ignore_ascii_case(unit).then_some(*scale)
+        })
+        .ok_or_else(|| invalid(s))?;
+    Ok(number * scale)
+}
+
+/// A rate in bytes per second as expected by the qdisc and class builders,
+/// written like `10mbit`, `1gibit` or `100kbps`. Without unit, the rate is
+/// in bits per second.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct TcRate(pub u64);
+
+impl FromStr for TcRate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bits = parse_scaled(s, &RATE_UNITS)?;
+        let bytes = bits / 8.;
+        if bytes >= u64::MAX as f64 {
+            return Err(invalid(s));
+        }
+        Ok(Self(bytes as u64))
+    }
+}
+
+impl fmt::Display for TcRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 5] = ["", "K", "M", "G", "T"];
+        let mut bits = u128::from(self.0) * 8;
+        let mut unit = 0;
+        while unit < UNITS.len() - 1 && bits >= 1000 && bits % 1000 == 0 {
+            bits /= 1000;
+            unit += 1;
+        }
+        write!(f, "{bits}{}bit", UNITS[unit])
+    }
+}
+
+impl From<TcRate> for u64 {
+    fn from(rate: TcRate) -> Self {
+        rate.0
+    }
+}
+
+/// A size in bytes, written like `1500b`, `64kb`, `1m` or `32kbit`.
+/// Without unit, the size is in bytes.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct TcSize(pub u32);
+
+impl FromStr for TcSize {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = parse_scaled(s, &SIZE_UNITS)?;
+        if bytes > f64::from(u32::MAX) {
+            return Err(invalid(s));
+        }
+        Ok(Self(bytes as u32))
+    }
+}
+
+impl fmt::Display for TcSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = self.0;
+        if size >= 1024 * 1024 && size % (1024 * 1024) == 0 {
+            write!(f, "{}Mb", size / (1024 * 1024))
+        } else if size >= 1024 && size % 1024 == 0 {
+            write!(f, "{}Kb", size / 1024)
+        } else {
+            write!(f, "{size}b")
+        }
+    }
+}
+
+impl From<TcSize> for u32 {
+    fn from(size: TcSize) -> Self {
+        size.0
+    }
+}
+
+/// A time written like `10ms`, `1.5s` or `500us`. Without unit, the time
+/// is in microseconds.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct TcTime(pub Duration);
+
+impl FromStr for TcTime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let nanos = parse_scaled(s, &TIME_UNITS)?;
+        if nanos >= u64::MAX as f64 {
+            return Err(invalid(s));
+        }
+        Ok(Self(Duration::from_nanos(nanos.round() as u64)))
+    }
+}
+
+impl fmt::Display for TcTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let us = self.0.as_micros();
+        if us >= 1_000_000 && us % 100_000 == 0 {
+            write!(f, "{}s", self.0.as_secs_f64())
+        } else if us >= 1000 && us % 100 == 0 {
+            write!(f, "{}ms", us as f64 / 1000.)
+        } else {
+            write!(f, "{us}us")
+        }
+    }
+}
+
+impl From<TcTime> for Duration {
+    fn from(time: TcTime) -> Self {
+        time.0
+    }
+}
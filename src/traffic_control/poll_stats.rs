@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use futures_util::{
+    future,
+    stream::{self, Stream, TryStreamExt},
+};
+use netlink_packet_route::tc::{TcHandle, TcMessage};
+
+use super::{QDiscGetRequest, TrafficClassGetRequest, TrafficStats};
+use crate::{time::sleep, Error, Handle, TcMessageExt};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Statistics of a qdisc or class sampled by [QDiscStatsPollRequest].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TrafficStatsSample {
+    /// Whether the sample is about a class, or a qdisc.
+    pub class: bool,
+    /// Kind of the qdisc or class.
+    pub kind: String,
+    pub handle: TcHandle,
+    pub parent: TcHandle,
+    /// Statistics read from the kernel.
+    pub stats: TrafficStats,
+    /// Difference with the previous sample, `None` for the first sample
+    /// of a qdisc or class, or when its counters were reset.
+    pub delta: Option<TrafficStatsDelta>,
+}
+
+/// Rates and counter increments between two [TrafficStatsSample].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TrafficStatsDelta {
+    /// Time elapsed since the previous sample.
+    pub interval: Duration,
+    /// Bits per second.
+    pub bps: u64,
+    /// Packets per second.
+    pub pps: u64,
+    /// Packets dropped since the previous sample.
+    pub drops: u64,
+    /// Overlimits since the previous sample.
+    pub overlimits: u64,
+    /// Packets requeued since the previous sample.
+    pub requeues: u64,
+}
+
+impl TrafficStatsDelta {
+    fn new(
+        previous: &TrafficStats,
+        current: &TrafficStats,
+        interval: Duration,
+    ) -> Option<Self> {
+        // 64 bits counters only go backward when the qdisc was recreated.
+        if current.bytes < previous.bytes || current.packets < previous.packets
+        {
+            return None;
+        }
+        let per_sec = |count: u64| {
+            let secs = interval.as_secs_f64();
+            if secs > 0.0 {
+                (count as f64 / secs) as u64
+            } else {
+                0
+            }
+        };
+        let delta32 = |current: u32, previous: u32| {
+            u64::from(current.wrapping_sub(previous))
+        };
+        Some(Self {
+            interval,
+            bps: per_sec((current.bytes - previous.bytes) * 8),
+            pps: per_sec(current.packets - previous.packets),
+            drops: delta32(current.drops, previous.drops),
+            overlimits: delta32(current.overlimits, previous.overlimits),
+            requeues: delta32(current.requeues, previous.requeues),
+        })
+    }
+}
+
+// Identify a qdisc or class across samples.
+type NodeKey = (bool, u32, u32, String);
+
+/// Periodically sample the statistics of the qdiscs and classes of a device.
+/// Equivalent to running `tc -s qdisc show dev DEV` and
+/// `tc -s class show dev DEV` in a loop.
+#[derive(Debug, Clone)]
+pub struct QDiscStatsPollRequest {
+    handle: Handle,
+    index: i32,
+    interval: Duration,
+}
+
+impl QDiscStatsPollRequest {
+    pub(crate) fn new(handle: Handle, index: i32) -> Self {
+        QDiscStatsPollRequest {
+            handle,
+            index,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+
+    /// Set the time between two samples. Default: 1 second.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Execute the request, returning the statistics of all the qdiscs and
+    /// classes of the device at each interval. The first item is returned
+    /// immediately, without any [TrafficStatsSample::delta].
+    ///
+    /// The stream ends after the first error.
+    pub fn execute(
+        self,
+    ) -> impl Stream<Item = Result<Vec<TrafficStatsSample>, Error>> {
+        let state = (self, HashMap::<NodeKey, TrafficStats>::new(), None);
+        stream::try_unfold(state, |(request, previous, last)| async move {
+            if last.is_some() {
+                sleep(request.interval).await;
+            }
+            let messages = request.dump().await?;
+            let now = Instant::now();
+            let interval = last.map(|last: Instant| now - last);
+
+            let mut current = HashMap::new();
+            let samples = messages
+                .into_iter()
+                .map(|(class, message)| {
+                    let kind = message.kind().unwrap_or_default().to_string();
+                    let stats = TrafficStats::from_message(&message)
+                        .unwrap_or_default();
+                    let key = (
+                        class,
+                        message.header.handle.into(),
+                        message.header.parent.into(),
+                        kind.clone(),
+                    );
+                    let delta = match (previous.get(&key), interval) {
+                        (Some(previous), Some(interval)) => {
+                            TrafficStatsDelta::new(previous, &stats, interval)
+                        }
+                        _ => None,
+                    };
+                    current.insert(key, stats);
+                    TrafficStatsSample {
+                        class,
+                        kind,
+                        handle: message.header.handle,
+                        parent: message.header.parent,
+                        stats,
+                        delta,
+                    }
+                })
+                .collect();
+            Ok(Some((samples, (request, current, Some(now)))))
+        })
+    }
+
+    async fn dump(&self) -> Result<Vec<(bool, TcMessage)>, Error> {
+        let index = self.index;
+        // The kernel dumps the qdiscs of all the devices.
+        let mut messages: Vec<(bool, TcMessage)> =
+            QDiscGetRequest::new(self.handle.clone())
+                .execute()
+                .try_filter(|qdisc| future::ready(qdisc.header.index == index))
+                .map_ok(|qdisc| (false, qdisc))
+                .try_collect()
+                .await?;
+        let classes: Vec<TcMessage> =
+            TrafficClassGetRequest::new(self.handle.clone(), index)
+                .execute()
+                .try_collect()
+                .await?;
+        messages.extend(classes.into_iter().map(|class| (true, class)));
+        Ok(messages)
+    }
+}
@@ -0,0 +1,621 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use netlink_packet_core::{parse_i32, parse_u32, parse_u64, DecodeError};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::{
+    nla::{nested_options, nla_u32, nla_u64, parse_nlas},
+    ratespec::{time_to_us, us_to_time},
+};
+
+const TCA_CAKE_BASE_RATE64: u16 = 2;
+const TCA_CAKE_DIFFSERV_MODE: u16 = 3;
+const TCA_CAKE_ATM: u16 = 4;
+const TCA_CAKE_FLOW_MODE: u16 = 5;
+const TCA_CAKE_OVERHEAD: u16 = 6;
+const TCA_CAKE_RTT: u16 = 7;
+const TCA_CAKE_TARGET: u16 = 8;
+const TCA_CAKE_AUTORATE: u16 = 9;
+const TCA_CAKE_MEMORY: u16 = 10;
+const TCA_CAKE_NAT: u16 = 11;
+const TCA_CAKE_RAW: u16 = 12;
+const TCA_CAKE_WASH: u16 = 13;
+const TCA_CAKE_MPU: u16 = 14;
+const TCA_CAKE_INGRESS: u16 = 15;
+const TCA_CAKE_ACK_FILTER: u16 = 16;
+const TCA_CAKE_SPLIT_GSO: u16 = 17;
+const TCA_CAKE_FWMARK: u16 = 18;
+
+// iproute2 derives the target from the RTT.
+const RTT_TO_TARGET: u32 = 20;
+
+/// Priority queue layout of the `cake` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum CakeDiffserv {
+    /// `diffserv3`
+    Diffserv3,
+    /// `diffserv4`
+    Diffserv4,
+    /// `diffserv8`
+    Diffserv8,
+    /// `besteffort`
+    BestEffort,
+    /// `precedence`
+    Precedence,
+    Other(u32),
+}
+
+impl From<u32> for CakeDiffserv {
+    fn from(d: u32) -> Self {
+        match d {
+            0 => Self::Diffserv3,
+            1 => Self::Diffserv4,
+            2 => Self::Diffserv8,
+            3 => Self::BestEffort,
+            4 => Self::Precedence,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<CakeDiffserv> for u32 {
+    fn from(v: CakeDiffserv) -> u32 {
+        match v {
+            CakeDiffserv::Diffserv3 => 0,
+            CakeDiffserv::Diffserv4 => 1,
+            CakeDiffserv::Diffserv8 => 2,
+            CakeDiffserv::BestEffort => 3,
+            CakeDiffserv::Precedence => 4,
+            CakeDiffserv::Other(d) => d,
+        }
+    }
+}
+
+/// Flow isolation of the `cake` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum CakeFlowMode {
+    /// `flowblind`
+    None,
+    /// `srchost`
+    SrcHost,
+    /// `dsthost`
+    DstHost,
+    /// `hosts`
+    Hosts,
+    /// `flows`
+    Flows,
+    /// `dual-srchost`
+    DualSrcHost,
+    /// `dual-dsthost`
+    DualDstHost,
+    /// `triple-isolate`
+    Triple,
+    Other(u32),
+}
+
+impl From<u32> for CakeFlowMode {
+    fn from(d: u32) -> Self {
+        match d {
+            0 => Self::None,
+            1 => Self::SrcHost,
+            2 => Self::DstHost,
+            3 => Self::Hosts,
+            4 => Self::Flows,
+            5 => Self::DualSrcHost,
+            6 => Self::DualDstHost,
+            7 => Self::Triple,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<CakeFlowMode> for u32 {
+    fn from(v: CakeFlowMode) -> u32 {
+        match v {
+            CakeFlowMode::None => 0,
+            CakeFlowMode::SrcHost => 1,
+            CakeFlowMode::DstHost => 2,
+            CakeFlowMode::Hosts => 3,
+            CakeFlowMode::Flows => 4,
+            CakeFlowMode::DualSrcHost => 5,
+            CakeFlowMode::DualDstHost => 6,
+            CakeFlowMode::Triple => 7,
+            CakeFlowMode::Other(d) => d,
+        }
+    }
+}
+
+/// TCP ACK filtering of the `cake` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum CakeAckFilter {
+    /// `no-ack-filter`
+    Disabled,
+    /// `ack-filter`
+    Enabled,
+    /// `ack-filter-aggressive`
+    Aggressive,
+    Other(u32),
+}
+
+impl From<u32> for CakeAckFilter {
+    fn from(d: u32) -> Self {
+        match d {
+            0 => Self::Disabled,
+            1 => Self::Enabled,
+            2 => Self::Aggressive,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<CakeAckFilter> for u32 {
+    fn from(v: CakeAckFilter) -> u32 {
+        match v {
+            CakeAckFilter::Disabled => 0,
+            CakeAckFilter::Enabled => 1,
+            CakeAckFilter::Aggressive => 2,
+            CakeAckFilter::Other(d) => d,
+        }
+    }
+}
+
+/// Link layer compensation of the `cake` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum CakeAtm {
+    /// `noatm`
+    None,
+    /// `atm`
+    Atm,
+    /// `ptm`
+    Ptm,
+    Other(u32),
+}
+
+impl From<u32> for CakeAtm {
+    fn from(d: u32) -> Self {
+        match d {
+            0 => Self::None,
+            1 => Self::Atm,
+            2 => Self::Ptm,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<CakeAtm> for u32 {
+    fn from(v: CakeAtm) -> u32 {
+        match v {
+            CakeAtm::None => 0,
+            CakeAtm::Atm => 1,
+            CakeAtm::Ptm => 2,
+            CakeAtm::Other(d) => d,
+        }
+    }
+}
+
+/// Options of the `cake` (Common Applications Kept Enhanced) qdisc.
+/// Equivalent to `tc qdisc add ... cake`.
+///
+/// Unset options are left to the kernel defaults. Rates are in bytes per
+/// second.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscCake {
+    /// Shaped rate, 0 for `unlimited`.
+    pub bandwidth: Option<u64>,
+    /// Adjust the rate from the measured ingress rate.
+    pub autorate: Option<bool>,
+    pub diffserv: Option<CakeDiffserv>,
+    pub flow_mode: Option<CakeFlowMode>,
+    /// Perform a NAT lookup to isolate hosts.
+    pub nat: Option<bool>,
+    /// Clear the DSCP of packets after classification.
+    pub wash: Option<bool>,
+    /// Shape the ingress of the link.
+    pub ingress: Option<bool>,
+    pub ack_filter: Option<CakeAckFilter>,
+    /// Split GSO super packets.
+    pub split_gso: Option<bool>,
+    /// Expected round trip time.
+    pub rtt: Option<Duration>,
+    /// Bytes added to the size of each packet.
+    pub overhead: Option<i32>,
+    /// Use the size of the packets as seen by the kernel, without
+    /// overhead compensation.
+    pub raw: bool,
+    /// Minimum size of packets.
+    pub mpu: Option<u32>,
+    pub atm: Option<CakeAtm>,
+    /// Memory limit of the queues, in bytes.
+    pub memory_limit: Option<u32>,
+    /// Mask applied to the firewall mark to select the tin.
+    pub fwmark: Option<u32>,
+    /// Target queue delay, only reported by the kernel.
+    pub target: Option<Duration>,
+}
+
+impl QDiscCake {
+    pub const KIND: &'static str = "cake";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `bandwidth RATE`.
+    pub fn bandwidth(mut self, rate: u64) -> Self {
+        self.bandwidth = Some(rate);
+        self.autorate = Some(false);
+        self
+    }
+
+    /// Equivalent to `unlimited`.
+    pub fn unlimited(self) -> Self {
+        self.bandwidth(0)
+    }
+
+    /// Equivalent to `autorate-ingress`.
+    pub fn autorate(mut self) -> Self {
+        self.autorate = Some(true);
+        self
+    }
+
+    pub fn diffserv(mut self, diffserv: CakeDiffserv) -> Self {
+        self.diffserv = Some(diffserv);
+        self
+    }
+
+    pub fn flow_mode(mut self, mode: CakeFlowMode) -> Self {
+        self.flow_mode = Some(mode);
+        self
+    }
+
+    /// Equivalent to `nat` when true and `nonat` when false.
+    pub fn nat(mut self, nat: bool) -> Self {
+        self.nat = Some(nat);
+        self
+    }
+
+    /// Equivalent to `wash` when true and `nowash` when false.
+    pub fn wash(mut self, wash: bool) -> Self {
+        self.wash = Some(wash);
+        self
+    }
+
+    /// Equivalent to `ingress` when true and `egress` when false.
+    pub fn ingress(mut self, ingress: bool) -> Self {
+        self.ingress = Some(ingress);
+        self
+    }
+
+    pub fn ack_filter(mut self, filter: CakeAckFilter) -> Self {
+        self.ack_filter = Some(filter);
+        self
+    }
+
+    /// Equivalent to `split-gso` when true and `no-split-gso` when false.
+    pub fn split_gso(mut self, split: bool) -> Self {
+        self.split_gso = Some(split);
+        self
+    }
+
+    /// Equivalent to `rtt TIME`.
+    pub fn rtt(mut self, rtt: Duration) -> Self {
+        self.rtt = Some(rtt);
+        self
+    }
+
+    /// Equivalent to `overhead BYTES`.
+    pub fn overhead(mut self, overhead: i32) -> Self {
+        self.overhead = Some(overhead);
+        self
+    }
+
+    /// Equivalent to `raw`, which also resets the overhead and the link
+    /// layer compensation.
+    pub fn raw(mut self) -> Self {
+        self.raw = true;
+        self.overhead = Some(0);
+        self.atm = Some(CakeAtm::None);
+        self
+    }
+
+    /// Equivalent to `mpu BYTES`.
+    pub fn mpu(mut self, mpu: u32) -> Self {
+        self.mpu = Some(mpu);
+        self
+    }
+
+    pub fn atm(mut self, atm: CakeAtm) -> Self {
+        self.atm = Some(atm);
+        self
+    }
+
+    /// Equivalent to `memlimit BYTES`.
+    pub fn memory_limit(mut self, bytes: u32) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Equivalent to `fwmark MASK`.
+    pub fn fwmark(mut self, mask: u32) -> Self {
+        self.fwmark = Some(mask);
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut nlas = Vec::new();
+        if let Some(rate) = self.bandwidth {
+            nlas.push(nla_u64(TCA_CAKE_BASE_RATE64, rate));
+        }
+        if let Some(v) = self.diffserv {
+            nlas.push(nla_u32(TCA_CAKE_DIFFSERV_MODE, v.into()));
+        }
+        if let Some(v) = self.atm {
+            nlas.push(nla_u32(TCA_CAKE_ATM, v.into()));
+        }
+        if let Some(v) = self.flow_mode {
+            nlas.push(nla_u32(TCA_CAKE_FLOW_MODE, v.into()));
+        }
+        if let Some(v) = self.overhead {
+            nlas.push(nla_u32(TCA_CAKE_OVERHEAD, v as u32));
+        }
+        if self.raw {
+            nlas.push(nla_u32(TCA_CAKE_RAW, 0));
+        }
+        if let Some(v) = self.mpu {
+            nlas.push(nla_u32(TCA_CAKE_MPU, v));
+        }
+        if let Some(rtt) = self.rtt {
+            let rtt = time_to_us(rtt);
+            nlas.push(nla_u32(TCA_CAKE_RTT, rtt));
+            nlas.push(nla_u32(TCA_CAKE_TARGET, (rtt / RTT_TO_TARGET).max(1)));
+        }
+        if let Some(v) = self.autorate {
+            nlas.push(nla_u32(TCA_CAKE_AUTORATE, v.into()));
+        }
+        if let Some(v) = self.memory_limit {
+            nlas.push(nla_u32(TCA_CAKE_MEMORY, v));
+        }
+        if let Some(v) = self.fwmark {
+            nlas.push(nla_u32(TCA_CAKE_FWMARK, v));
+        }
+        if let Some(v) = self.nat {
+            nlas.push(nla_u32(TCA_CAKE_NAT, v.into()));
+        }
+        if let Some(v) = self.wash {
+            nlas.push(nla_u32(TCA_CAKE_WASH, v.into()));
+        }
+        if let Some(v) = self.split_gso {
+            nlas.push(nla_u32(TCA_CAKE_SPLIT_GSO, v.into()));
+        }
+        if let Some(v) = self.ingress {
+            nlas.push(nla_u32(TCA_CAKE_INGRESS, v.into()));
+        }
+        if let Some(v) = self.ack_filter {
+            nlas.push(nla_u32(TCA_CAKE_ACK_FILTER, v.into()));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut cake = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_CAKE_BASE_RATE64 => {
+                    cake.bandwidth = Some(parse_u64(value)?)
+                }
+                TCA_CAKE_DIFFSERV_MODE => {
+                    cake.diffserv = Some(parse_u32(value)?.into())
+                }
+                TCA_CAKE_ATM => cake.atm = Some(parse_u32(value)?.into()),
+                TCA_CAKE_FLOW_MODE => {
+                    cake.flow_mode = Some(parse_u32(value)?.into())
+                }
+                TCA_CAKE_OVERHEAD => cake.overhead = Some(parse_i32(value)?),
+                TCA_CAKE_RTT => cake.rtt = Some(us_to_time(parse_u32(value)?)),
+                TCA_CAKE_TARGET => {
+                    cake.target = Some(us_to_time(parse_u32(value)?))
+                }
+                TCA_CAKE_AUTORATE => {
+                    cake.autorate = Some(parse_u32(value)? != 0)
+                }
+                TCA_CAKE_MEMORY => cake.memory_limit = Some(parse_u32(value)?),
+                TCA_CAKE_NAT => cake.nat = Some(parse_u32(value)? != 0),
+                TCA_CAKE_RAW => cake.raw = true,
+                TCA_CAKE_WASH => cake.wash = Some(parse_u32(value)? != 0),
+                TCA_CAKE_MPU => cake.mpu = Some(parse_u32(value)?),
+                TCA_CAKE_INGRESS => cake.ingress = Some(parse_u32(value)? != 0),
+                TCA_CAKE_ACK_FILTER => {
+                    cake.ack_filter = Some(parse_u32(value)?.into())
+                }
+                TCA_CAKE_SPLIT_GSO => {
+                    cake.split_gso = Some(parse_u32(value)? != 0)
+                }
+                TCA_CAKE_FWMARK => cake.fwmark = Some(parse_u32(value)?),
+                _ => (),
+            }
+        }
+        Ok(cake)
+    }
+}
+
+const TCA_CAKE_STATS_CAPACITY_ESTIMATE64: u16 = 2;
+const TCA_CAKE_STATS_MEMORY_LIMIT: u16 = 3;
+const TCA_CAKE_STATS_MEMORY_USED: u16 = 4;
+const TCA_CAKE_STATS_AVG_NETOFF: u16 = 5;
+const TCA_CAKE_STATS_MIN_NETLEN: u16 = 6;
+const TCA_CAKE_STATS_MAX_NETLEN: u16 = 7;
+const TCA_CAKE_STATS_MIN_ADJLEN: u16 = 8;
+const TCA_CAKE_STATS_MAX_ADJLEN: u16 = 9;
+const TCA_CAKE_STATS_TIN_STATS: u16 = 10;
+
+const TCA_CAKE_TIN_STATS_SENT_PACKETS: u16 = 2;
+const TCA_CAKE_TIN_STATS_SENT_BYTES64: u16 = 3;
+const TCA_CAKE_TIN_STATS_DROPPED_PACKETS: u16 = 4;
+const TCA_CAKE_TIN_STATS_DROPPED_BYTES64: u16 = 5;
+const TCA_CAKE_TIN_STATS_ACKS_DROPPED_PACKETS: u16 = 6;
+const TCA_CAKE_TIN_STATS_ECN_MARKED_PACKETS: u16 = 8;
+const TCA_CAKE_TIN_STATS_BACKLOG_PACKETS: u16 = 10;
+const TCA_CAKE_TIN_STATS_BACKLOG_BYTES: u16 = 11;
+const TCA_CAKE_TIN_STATS_THRESHOLD_RATE64: u16 = 12;
+const TCA_CAKE_TIN_STATS_TARGET_US: u16 = 13;
+const TCA_CAKE_TIN_STATS_INTERVAL_US: u16 = 14;
+const TCA_CAKE_TIN_STATS_PEAK_DELAY_US: u16 = 18;
+const TCA_CAKE_TIN_STATS_AVG_DELAY_US: u16 = 19;
+const TCA_CAKE_TIN_STATS_BASE_DELAY_US: u16 = 20;
+const TCA_CAKE_TIN_STATS_SPARSE_FLOWS: u16 = 21;
+const TCA_CAKE_TIN_STATS_BULK_FLOWS: u16 = 22;
+const TCA_CAKE_TIN_STATS_UNRESPONSIVE_FLOWS: u16 = 23;
+
+/// Statistics specific to the `cake` qdisc, see
+/// [crate::TcMessageExt::xstats].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct CakeXstats {
+    /// Estimated capacity of the link in bytes per second, with
+    /// `autorate-ingress`.
+    pub capacity_estimate: Option<u64>,
+    pub memory_limit: Option<u32>,
+    pub memory_used: Option<u32>,
+    pub avg_netoff: Option<u32>,
+    pub min_netlen: Option<u32>,
+    pub max_netlen: Option<u32>,
+    pub min_adjlen: Option<u32>,
+    pub max_adjlen: Option<u32>,
+    /// Statistics of each priority tin, see [CakeDiffserv].
+    pub tins: Vec<CakeTinStats>,
+}
+
+/// Statistics of a priority tin of the `cake` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct CakeTinStats {
+    pub sent_packets: u32,
+    pub sent_bytes: u64,
+    pub dropped_packets: u32,
+    pub dropped_bytes: u64,
+    pub acks_dropped_packets: u32,
+    pub ecn_marked_packets: u32,
+    pub backlog_packets: u32,
+    pub backlog_bytes: u32,
+    /// Threshold rate of the tin in bytes per second.
+    pub threshold_rate: u64,
+    pub target: Duration,
+    pub interval: Duration,
+    pub peak_delay: Duration,
+    pub avg_delay: Duration,
+    pub base_delay: Duration,
+    pub sparse_flows: u32,
+    pub bulk_flows: u32,
+    pub unresponsive_flows: u32,
+}
+
+impl CakeXstats {
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut xstats = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_CAKE_STATS_CAPACITY_ESTIMATE64 => {
+                    xstats.capacity_estimate = Some(parse_u64(value)?)
+                }
+                TCA_CAKE_STATS_MEMORY_LIMIT => {
+                    xstats.memory_limit = Some(parse_u32(value)?)
+                }
+                TCA_CAKE_STATS_MEMORY_USED => {
+                    xstats.memory_used = Some(parse_u32(value)?)
+                }
+                TCA_CAKE_STATS_AVG_NETOFF => {
+                    xstats.avg_netoff = Some(parse_u32(value)?)
+                }
+                TCA_CAKE_STATS_MIN_NETLEN => {
+                    xstats.min_netlen = Some(parse_u32(value)?)
+                }
+                TCA_CAKE_STATS_MAX_NETLEN => {
+                    xstats.max_netlen = Some(parse_u32(value)?)
+                }
+                TCA_CAKE_STATS_MIN_ADJLEN => {
+                    xstats.min_adjlen = Some(parse_u32(value)?)
+                }
+                TCA_CAKE_STATS_MAX_ADJLEN => {
+                    xstats.max_adjlen = Some(parse_u32(value)?)
+                }
+                // One nested attribute per tin, indexed from 1.
+                TCA_CAKE_STATS_TIN_STATS => {
+                    for (_, tin) in parse_nlas(value)? {
+                        xstats.tins.push(CakeTinStats::parse(tin)?);
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(xstats)
+    }
+}
+
+impl CakeTinStats {
+    fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut tin = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_CAKE_TIN_STATS_SENT_PACKETS => {
+                    tin.sent_packets = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_SENT_BYTES64 => {
+                    tin.sent_bytes = parse_u64(value)?
+                }
+                TCA_CAKE_TIN_STATS_DROPPED_PACKETS => {
+                    tin.dropped_packets = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_DROPPED_BYTES64 => {
+                    tin.dropped_bytes = parse_u64(value)?
+                }
+                TCA_CAKE_TIN_STATS_ACKS_DROPPED_PACKETS => {
+                    tin.acks_dropped_packets = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_ECN_MARKED_PACKETS => {
+                    tin.ecn_marked_packets = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_BACKLOG_PACKETS => {
+                    tin.backlog_packets = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_BACKLOG_BYTES => {
+                    tin.backlog_bytes = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_THRESHOLD_RATE64 => {
+                    tin.threshold_rate = parse_u64(value)?
+                }
+                TCA_CAKE_TIN_STATS_TARGET_US => {
+                    tin.target = us_to_time(parse_u32(value)?)
+                }
+                TCA_CAKE_TIN_STATS_INTERVAL_US => {
+                    tin.interval = us_to_time(parse_u32(value)?)
+                }
+                TCA_CAKE_TIN_STATS_PEAK_DELAY_US => {
+                    tin.peak_delay = us_to_time(parse_u32(value)?)
+                }
+                TCA_CAKE_TIN_STATS_AVG_DELAY_US => {
+                    tin.avg_delay = us_to_time(parse_u32(value)?)
+                }
+                TCA_CAKE_TIN_STATS_BASE_DELAY_US => {
+                    tin.base_delay = us_to_time(parse_u32(value)?)
+                }
+                TCA_CAKE_TIN_STATS_SPARSE_FLOWS => {
+                    tin.sparse_flows = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_BULK_FLOWS => {
+                    tin.bulk_flows = parse_u32(value)?
+                }
+                TCA_CAKE_TIN_STATS_UNRESPONSIVE_FLOWS => {
+                    tin.unresponsive_flows = parse_u32(value)?
+                }
+                _ => (),
+            }
+        }
+        Ok(tin)
+    }
+}
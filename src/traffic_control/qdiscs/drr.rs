@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, DecodeError};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::nla::{nested_options, nla_u32, parse_nlas};
+
+const TCA_DRR_QUANTUM: u16 = 1;
+
+/// The `drr` (Deficit Round Robin) qdisc, which has no options.
+/// Equivalent to `tc qdisc add ... drr`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscDrr;
+
+impl QDiscDrr {
+    pub const KIND: &'static str = "drr";
+}
+
+/// Options of a class of the `drr` qdisc.
+/// Equivalent to `tc class add ... drr`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TrafficClassDrr {
+    /// Bytes served before moving to the next class. Defaults to the MTU
+    /// of the device.
+    pub quantum: Option<u32>,
+}
+
+impl TrafficClassDrr {
+    pub const KIND: &'static str = "drr";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `quantum BYTES`.
+    pub fn quantum(mut self, quantum: u32) -> Self {
+        self.quantum = Some(quantum);
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let nlas: Vec<_> = self
+            .quantum
+            .iter()
+            .map(|quantum| nla_u32(TCA_DRR_QUANTUM, *quantum))
+            .collect();
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut drr = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            if kind == TCA_DRR_QUANTUM {
+                drr.quantum = Some(parse_u32(value)?);
+            }
+        }
+        Ok(drr)
+    }
+}
@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, parse_u8, DecodeError};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::nla::{
+    nested_options, nla_nested, nla_u32, nla_u8, parse_nlas,
+};
+
+const TCA_ETS_NBANDS: u16 = 1;
+const TCA_ETS_NSTRICT: u16 = 2;
+const TCA_ETS_QUANTA: u16 = 3;
+const TCA_ETS_QUANTA_BAND: u16 = 4;
+const TCA_ETS_PRIOMAP: u16 = 5;
+const TCA_ETS_PRIOMAP_BAND: u16 = 6;
+
+/// Options of the `ets` (Enhanced Transmission Selection) qdisc.
+/// Equivalent to `tc qdisc add ... ets`.
+///
+/// The first `strict` bands are served in strict priority order, the
+/// remaining ones share the bandwidth according to their quantum.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct QDiscEts {
+    /// Number of bands. When 0, `strict` plus the number of `quanta`.
+    pub bands: u8,
+    /// Number of strict priority bands.
+    pub strict: u8,
+    /// Quantum in bytes of each bandwidth-sharing band.
+    pub quanta: Vec<u32>,
+    /// Band (starting at 0) of each packet priority.
+    pub priomap: Vec<u8>,
+}
+
+impl QDiscEts {
+    pub const KIND: &'static str = "ets";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `bands NUMBER`.
+    pub fn bands(mut self, bands: u8) -> Self {
+        self.bands = bands;
+        self
+    }
+
+    /// Equivalent to `strict NUMBER`.
+    pub fn strict(mut self, strict: u8) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Equivalent to `quanta Q1 Q2 ...`.
+    pub fn quanta(mut self, quanta: Vec<u32>) -> Self {
+        self.quanta = quanta;
+        self
+    }
+
+    /// Equivalent to `priomap B1 B2 ...`.
+    pub fn priomap(mut self, priomap: Vec<u8>) -> Self {
+        self.priomap = priomap;
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let bands = if self.bands == 0 {
+            self.strict
+                .saturating_add(u8::try_from(self.quanta.len()).unwrap_or(0))
+        } else {
+            self.bands
+        };
+        let mut nlas = vec![nla_u8(TCA_ETS_NBANDS, bands)];
+        if self.strict != 0 {
+            nlas.push(nla_u8(TCA_ETS_NSTRICT, self.strict));
+        }
+        if !self.quanta.is_empty() {
+            let quanta: Vec<_> = self
+                .quanta
+                .iter()
+                .map(|quantum| nla_u32(TCA_ETS_QUANTA_BAND, *quantum))
+                .collect();
+            nlas.push(nla_nested(TCA_ETS_QUANTA, &quanta));
+        }
+        if !self.priomap.is_empty() {
+            let priomap: Vec<_> = self
+                .priomap
+                .iter()
+                .map(|band| nla_u8(TCA_ETS_PRIOMAP_BAND, *band))
+                .collect();
+            nlas.push(nla_nested(TCA_ETS_PRIOMAP, &priomap));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut ets = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_ETS_NBANDS => ets.bands = parse_u8(value)?,
+                TCA_ETS_NSTRICT => ets.strict = parse_u8(value)?,
+                TCA_ETS_QUANTA => {
+                    for (kind, value) in parse_nlas(value)? {
+                        if kind == TCA_ETS_QUANTA_BAND {
+                            ets.quanta.push(parse_u32(value)?);
+                        }
+                    }
+                }
+                TCA_ETS_PRIOMAP => {
+                    for (kind, value) in parse_nlas(value)? {
+                        if kind == TCA_ETS_PRIOMAP_BAND {
+                            ets.priomap.push(parse_u8(value)?);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(ets)
+    }
+}
+
+/// Options of a class (band) of the `ets` qdisc.
+/// Equivalent to `tc class change ... ets`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TrafficClassEts {
+    /// Quantum in bytes, `None` for strict priority bands.
+    pub quantum: Option<u32>,
+}
+
+impl TrafficClassEts {
+    pub const KIND: &'static str = "ets";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `quantum BYTES`.
+    pub fn quantum(mut self, quantum: u32) -> Self {
+        self.quantum = Some(quantum);
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let nlas: Vec<_> = self
+            .quantum
+            .iter()
+            .map(|quantum| nla_u32(TCA_ETS_QUANTA_BAND, *quantum))
+            .collect();
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut ets = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            if kind == TCA_ETS_QUANTA_BAND {
+                ets.quantum = Some(parse_u32(value)?);
+            }
+        }
+        Ok(ets)
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, DecodeError};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::nla::raw_options;
+
+/// Options of the `pfifo` qdisc.
+/// Equivalent to `tc qdisc add ... pfifo`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscPfifo {
+    /// Maximum queue size in packets. When unset, the kernel uses the
+    /// `txqueuelen` of the interface.
+    pub limit: Option<u32>,
+}
+
+impl QDiscPfifo {
+    pub const KIND: &'static str = "pfifo";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `limit PACKETS`.
+    pub fn limit(mut self, packets: u32) -> Self {
+        self.limit = Some(packets);
+        self
+    }
+
+    pub(crate) fn options(&self) -> Option<TcAttribute> {
+        self.limit
+            .map(|limit| raw_options(limit.to_ne_bytes().to_vec()))
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            limit: (!payload.is_empty())
+                .then(|| parse_u32(payload))
+                .transpose()?,
+        })
+    }
+}
+
+/// Options of the `bfifo` qdisc.
+/// Equivalent to `tc qdisc add ... bfifo`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscBfifo {
+    /// Maximum queue size in bytes. When unset, the kernel uses the
+    /// `txqueuelen` times the MTU of the interface.
+    pub limit: Option<u32>,
+}
+
+impl QDiscBfifo {
+    pub const KIND: &'static str = "bfifo";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `limit BYTES`.
+    pub fn limit(mut self, bytes: u32) -> Self {
+        self.limit = Some(bytes);
+        self
+    }
+
+    pub(crate) fn options(&self) -> Option<TcAttribute> {
+        self.limit
+            .map(|limit| raw_options(limit.to_ne_bytes().to_vec()))
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            limit: (!payload.is_empty())
+                .then(|| parse_u32(payload))
+                .transpose()?,
+        })
+    }
+}
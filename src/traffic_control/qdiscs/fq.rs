@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use netlink_packet_core::{parse_u32, parse_u8, DecodeError, DefaultNla};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::{
+    nla::{nested_options, nla_u32, nla_u8, parse_nlas, read_u32, read_u64},
+    ratespec::{time_to_us, us_to_time},
+};
+
+const TCA_FQ_PLIMIT: u16 = 1;
+const TCA_FQ_FLOW_PLIMIT: u16 = 2;
+const TCA_FQ_QUANTUM: u16 = 3;
+const TCA_FQ_INITIAL_QUANTUM: u16 = 4;
+const TCA_FQ_RATE_ENABLE: u16 = 5;
+const TCA_FQ_FLOW_MAX_RATE: u16 = 7;
+const TCA_FQ_BUCKETS_LOG: u16 = 8;
+const TCA_FQ_FLOW_REFILL_DELAY: u16 = 9;
+const TCA_FQ_ORPHAN_MASK: u16 = 10;
+const TCA_FQ_LOW_RATE_THRESHOLD: u16 = 11;
+const TCA_FQ_CE_THRESHOLD: u16 = 12;
+const TCA_FQ_TIMER_SLACK: u16 = 13;
+const TCA_FQ_HORIZON: u16 = 14;
+const TCA_FQ_HORIZON_DROP: u16 = 15;
+
+/// Options of the `fq` (Fair Queue) qdisc.
+/// Equivalent to `tc qdisc add ... fq`.
+///
+/// Unset options are left to the kernel defaults. Rates are in bytes per
+/// second.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscFq {
+    /// Hard limit of the queue size, in packets.
+    pub limit: Option<u32>,
+    /// Hard limit of the queue size of a flow, in packets.
+    pub flow_limit: Option<u32>,
+    /// Credit of a flow per round.
+    pub quantum: Option<u32>,
+    /// Credit of a new flow.
+    pub initial_quantum: Option<u32>,
+    /// Pace flows according to the rate requested by the sockets.
+    pub pacing: Option<bool>,
+    /// Maximum rate of a flow.
+    pub maxrate: Option<u32>,
+    /// Log2 of the number of buckets of the flow hash table.
+    pub buckets_log: Option<u32>,
+    /// Delay before a flow whose credit is exhausted is served again.
+    pub refill_delay: Option<Duration>,
+    /// Mask applied to the hash of packets without a socket.
+    pub orphan_mask: Option<u32>,
+    /// Rate under which flows are served in a dedicated band.
+    pub low_rate_threshold: Option<u32>,
+    /// Delay above which packets are marked with ECN CE.
+    pub ce_threshold: Option<Duration>,
+    /// Slack of the pacing timer.
+    pub timer_slack: Option<Duration>,
+    /// Packets with a time to send further in the future are dropped or
+    /// capped.
+    pub horizon: Option<Duration>,
+    /// Drop packets beyond the horizon instead of capping their time.
+    pub horizon_drop: Option<bool>,
+}
+
+impl QDiscFq {
+    pub const KIND: &'static str = "fq";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `limit PACKETS`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Equivalent to `flow_limit PACKETS`.
+    pub fn flow_limit(mut self, limit: u32) -> Self {
+        self.flow_limit = Some(limit);
+        self
+    }
+
+    /// Equivalent to `quantum BYTES`.
+    pub fn quantum(mut self, quantum: u32) -> Self {
+        self.quantum = Some(quantum);
+        self
+    }
+
+    /// Equivalent to `initial_quantum BYTES`.
+    pub fn initial_quantum(mut self, quantum: u32) -> Self {
+        self.initial_quantum = Some(quantum);
+        self
+    }
+
+    /// Equivalent to `pacing` when true and `nopacing` when false.
+    pub fn pacing(mut self, pacing: bool) -> Self {
+        self.pacing = Some(pacing);
+        self
+    }
+
+    /// Equivalent to `maxrate RATE`.
+    pub fn maxrate(mut self, rate: u32) -> Self {
+        self.maxrate = Some(rate);
+        self
+    }
+
+    /// Equivalent to `buckets NUMBER`, with the base 2 logarithm of the
+    /// number of buckets.
+    pub fn buckets_log(mut self, log: u32) -> Self {
+        self.buckets_log = Some(log);
+        self
+    }
+
+    /// Equivalent to `refill_delay TIME`.
+    pub fn refill_delay(mut self, delay: Duration) -> Self {
+        self.refill_delay = Some(delay);
+        self
+    }
+
+    /// Equivalent to `orphan_mask MASK`.
+    pub fn orphan_mask(mut self, mask: u32) -> Self {
+        self.orphan_mask = Some(mask);
+        self
+    }
+
+    /// Equivalent to `low_rate_threshold RATE`.
+    pub fn low_rate_threshold(mut self, rate: u32) -> Self {
+        self.low_rate_threshold = Some(rate);
+        self
+    }
+
+    /// Equivalent to `ce_threshold TIME`.
+    pub fn ce_threshold(mut self, threshold: Duration) -> Self {
+        self.ce_threshold = Some(threshold);
+        self
+    }
+
+    /// Equivalent to `timer_slack TIME`.
+    pub fn timer_slack(mut self, slack: Duration) -> Self {
+        self.timer_slack = Some(slack);
+        self
+    }
+
+    /// Equivalent to `horizon TIME`.
+    pub fn horizon(mut self, horizon: Duration) -> Self {
+        self.horizon = Some(horizon);
+        self
+    }
+
+    /// Equivalent to `horizon_drop` when true and `horizon_cap` when false.
+    pub fn horizon_drop(mut self, drop: bool) -> Self {
+        self.horizon_drop = Some(drop);
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut nlas: Vec<DefaultNla> = Vec::new();
+        let mut push = |kind, value: Option<u32>| {
+            if let Some(value) = value {
+                nlas.push(nla_u32(kind, value));
+            }
+        };
+        push(TCA_FQ_PLIMIT, self.limit);
+        push(TCA_FQ_FLOW_PLIMIT, self.flow_limit);
+        push(TCA_FQ_QUANTUM, self.quantum);
+        push(TCA_FQ_INITIAL_QUANTUM, self.initial_quantum);
+        push(TCA_FQ_RATE_ENABLE, self.pacing.map(u32::from));
+        push(TCA_FQ_FLOW_MAX_RATE, self.maxrate);
+        push(TCA_FQ_BUCKETS_LOG, self.buckets_log);
+        push(TCA_FQ_FLOW_REFILL_DELAY, self.refill_delay.map(time_to_us));
+        push(TCA_FQ_ORPHAN_MASK, self.orphan_mask);
+        push(TCA_FQ_LOW_RATE_THRESHOLD, self.low_rate_threshold);
+        push(TCA_FQ_CE_THRESHOLD, self.ce_threshold.map(time_to_us));
+        push(TCA_FQ_TIMER_SLACK, self.timer_slack.map(time_to_ns));
+        push(TCA_FQ_HORIZON, self.horizon.map(time_to_us));
+        if let Some(drop) = self.horizon_drop {
+            nlas.push(nla_u8(TCA_FQ_HORIZON_DROP, drop.into()));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut fq = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_FQ_PLIMIT => fq.limit = Some(parse_u32(value)?),
+                TCA_FQ_FLOW_PLIMIT => fq.flow_limit = Some(parse_u32(value)?),
+                TCA_FQ_QUANTUM => fq.quantum = Some(parse_u32(value)?),
+                TCA_FQ_INITIAL_QUANTUM => {
+                    fq.initial_quantum = Some(parse_u32(value)?)
+                }
+                TCA_FQ_RATE_ENABLE => fq.pacing = Some(parse_u32(value)? != 0),
+                TCA_FQ_FLOW_MAX_RATE => fq.maxrate = Some(parse_u32(value)?),
+                TCA_FQ_BUCKETS_LOG => fq.buckets_log = Some(parse_u32(value)?),
+                TCA_FQ_FLOW_REFILL_DELAY => {
+                    fq.refill_delay = Some(us_to_time(parse_u32(value)?))
+                }
+                TCA_FQ_ORPHAN_MASK => fq.orphan_mask = Some(parse_u32(value)?),
+                TCA_FQ_LOW_RATE_THRESHOLD => {
+                    fq.low_rate_threshold = Some(parse_u32(value)?)
+                }
+                TCA_FQ_CE_THRESHOLD => {
+                    fq.ce_threshold = Some(us_to_time(parse_u32(value)?))
+                }
+                TCA_FQ_TIMER_SLACK => {
+                    fq.timer_slack =
+                        Some(Duration::from_nanos(parse_u32(value)?.into()))
+                }
+                TCA_FQ_HORIZON => {
+                    fq.horizon = Some(us_to_time(parse_u32(value)?))
+                }
+                TCA_FQ_HORIZON_DROP => {
+                    fq.horizon_drop = Some(parse_u8(value)? != 0)
+                }
+                _ => (),
+            }
+        }
+        Ok(fq)
+    }
+}
+
+fn time_to_ns(duration: Duration) -> u32 {
+    u32::try_from(duration.as_nanos()).unwrap_or(u32::MAX)
+}
+
+// Size of `struct tc_fq_qd_stats` up to `unthrottle_latency_ns`, the fields
+// after it were added by later kernels.
+const TC_FQ_QD_STATS_MIN_LEN: usize = 80;
+
+/// Statistics specific to the `fq` qdisc, see
+/// [crate::TcMessageExt::xstats].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct FqXstats {
+    /// Flows garbage collected.
+    pub gc_flows: u64,
+    /// Packets sent in the high priority band.
+    pub highprio_packets: u64,
+    /// Number of times flows were throttled by pacing.
+    pub throttled: u64,
+    /// Packets dropped because of the `flow_limit`.
+    pub flows_plimit: u64,
+    /// Packets dropped because they were larger than the quantum.
+    pub pkts_too_long: u64,
+    /// Packets dropped because of memory allocation failures.
+    pub allocation_errors: u64,
+    /// Current number of flows.
+    pub flows: u32,
+    /// Current number of flows without packets.
+    pub inactive_flows: u32,
+    /// Current number of throttled flows.
+    pub throttled_flows: u32,
+    /// Latency of the pacing timer.
+    pub unthrottle_latency: Duration,
+    /// Packets marked with ECN CE, 0 on kernels older than 4.20.
+    pub ce_mark: u64,
+    /// Packets dropped beyond the horizon, 0 on kernels older than 5.7.
+    pub horizon_drops: u64,
+    /// Packets capped to the horizon, 0 on kernels older than 5.7.
+    pub horizon_caps: u64,
+}
+
+impl FqXstats {
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        if payload.len() < TC_FQ_QD_STATS_MIN_LEN {
+            return Err(DecodeError::buffer_too_small(
+                payload.len(),
+                TC_FQ_QD_STATS_MIN_LEN,
+            ));
+        }
+        let optional = |offset| read_u64(payload, offset).unwrap_or_default();
+        Ok(Self {
+            gc_flows: read_u64(payload, 0)?,
+            highprio_packets: read_u64(payload, 8)?,
+            throttled: read_u64(payload, 24)?,
+            flows_plimit: read_u64(payload, 32)?,
+            pkts_too_long: read_u64(payload, 40)?,
+            allocation_errors: read_u64(payload, 48)?,
+            flows: read_u32(payload, 64)?,
+            inactive_flows: read_u32(payload, 68)?,
+            throttled_flows: read_u32(payload, 72)?,
+            unthrottle_latency: Duration::from_nanos(u64::from(read_u32(
+                payload, 76,
+            )?)),
+            ce_mark: optional(80),
+            horizon_drops: optional(88),
+            horizon_caps: optional(96),
+        })
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use netlink_packet_core::{DecodeError, NlasIterator, Parseable};
+use netlink_packet_route::tc::{TcAttribute, TcOption, TcQdiscFqCodelOption};
+
+use crate::traffic_control::ratespec::{time_to_us, us_to_time};
+
+/// Options of the `fq_codel` (Fair Queuing Controlled Delay) qdisc.
+/// Equivalent to `tc qdisc add ... fq_codel`.
+///
+/// Unset options are left to the kernel defaults.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscFqCodel {
+    /// Hard limit of the queue size, in packets.
+    pub limit: Option<u32>,
+    /// Number of flows in which packets are classified.
+    pub flows: Option<u32>,
+    /// Acceptable minimum standing queue delay.
+    pub target: Option<Duration>,
+    /// Width of the moving window used to compute the minimum delay.
+    pub interval: Option<Duration>,
+    /// Bytes dequeued from a flow before serving the next one.
+    pub quantum: Option<u32>,
+    /// Mark packets with ECN instead of dropping them.
+    pub ecn: Option<bool>,
+    /// Sojourn time above which packets are marked with ECN CE.
+    pub ce_threshold: Option<Duration>,
+    /// Hard limit of the memory used by the queue, in bytes.
+    pub memory_limit: Option<u32>,
+    /// Maximum number of packets dropped at once when the limit is hit.
+    pub drop_batch_size: Option<u32>,
+}
+
+impl QDiscFqCodel {
+    pub const KIND: &'static str = "fq_codel";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `limit PACKETS`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Equivalent to `flows NUMBER`.
+    pub fn flows(mut self, flows: u32) -> Self {
+        self.flows = Some(flows);
+        self
+    }
+
+    /// Equivalent to `target TIME`.
+    pub fn target(mut self, target: Duration) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Equivalent to `interval TIME`.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Equivalent to `quantum BYTES`.
+    pub fn quantum(mut self, quantum: u32) -> Self {
+        self.quantum = Some(quantum);
+        self
+    }
+
+    /// Equivalent to `ecn` when true and `noecn` when false.
+    pub fn ecn(mut self, ecn: bool) -> Self {
+        self.ecn = Some(ecn);
+        self
+    }
+
+    /// Equivalent to `ce_threshold TIME`.
+    pub fn ce_threshold(mut self, threshold: Duration) -> Self {
+        self.ce_threshold = Some(threshold);
+        self
+    }
+
+    /// Equivalent to `memory_limit BYTES`.
+    pub fn memory_limit(mut self, bytes: u32) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Equivalent to `drop_batch PACKETS`.
+    pub fn drop_batch_size(mut self, packets: u32) -> Self {
+        self.drop_batch_size = Some(packets);
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut options = Vec::new();
+        if let Some(limit) = self.limit {
+            options.push(TcQdiscFqCodelOption::Limit(limit));
+        }
+        if let Some(flows) = self.flows {
+            options.push(TcQdiscFqCodelOption::Flows(flows));
+        }
+        if let Some(quantum) = self.quantum {
+            options.push(TcQdiscFqCodelOption::Quantum(quantum));
+        }
+        if let Some(interval) = self.interval {
+            options.push(TcQdiscFqCodelOption::Interval(time_to_us(interval)));
+        }
+        if let Some(target) = self.target {
+            options.push(TcQdiscFqCodelOption::Target(time_to_us(target)));
+        }
+        if let Some(ecn) = self.ecn {
+            options.push(TcQdiscFqCodelOption::Ecn(ecn.into()));
+        }
+        if let Some(threshold) = self.ce_threshold {
+            options
+                .push(TcQdiscFqCodelOption::CeThreshold(time_to_us(threshold)));
+        }
+        if let Some(bytes) = self.memory_limit {
+            options.push(TcQdiscFqCodelOption::MemoryLimit(bytes));
+        }
+        if let Some(packets) = self.drop_batch_size {
+            options.push(TcQdiscFqCodelOption::DropBatchSize(packets));
+        }
+        TcAttribute::Options(
+            options.into_iter().map(TcOption::FqCodel).collect(),
+        )
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut fq_codel = Self::default();
+        for nla in NlasIterator::new(payload) {
+            match TcQdiscFqCodelOption::parse(&nla?)? {
+                TcQdiscFqCodelOption::Limit(v) => fq_codel.limit = Some(v),
+                TcQdiscFqCodelOption::Flows(v) => fq_codel.flows = Some(v),
+                TcQdiscFqCodelOption::Target(v) => {
+                    fq_codel.target = Some(us_to_time(v))
+                }
+                TcQdiscFqCodelOption::Interval(v) => {
+                    fq_codel.interval = Some(us_to_time(v))
+                }
+                TcQdiscFqCodelOption::Quantum(v) => fq_codel.quantum = Some(v),
+                TcQdiscFqCodelOption::Ecn(v) => fq_codel.ecn = Some(v != 0),
+                TcQdiscFqCodelOption::CeThreshold(v) => {
+                    fq_codel.ce_threshold = Some(us_to_time(v))
+                }
+                TcQdiscFqCodelOption::DropBatchSize(v) => {
+                    fq_codel.drop_batch_size = Some(v)
+                }
+                TcQdiscFqCodelOption::MemoryLimit(v) => {
+                    fq_codel.memory_limit = Some(v)
+                }
+                _ => (),
+            }
+        }
+        Ok(fq_codel)
+    }
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, DefaultNla};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::nla::{
+    nested_options, parse_nlas, raw_options, read_u16, read_u32,
+};
+
+const TCA_HFSC_RSC: u16 = 1;
+const TCA_HFSC_FSC: u16 = 2;
+const TCA_HFSC_USC: u16 = 3;
+
+const TC_SERVICE_CURVE_LEN: usize = 12;
+
+/// Options of the `hfsc` (Hierarchical Fair Service Curve) qdisc.
+/// Equivalent to `tc qdisc add ... hfsc`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscHfsc {
+    /// Minor number of the class receiving unclassified traffic.
+    pub default_class: u16,
+}
+
+impl QDiscHfsc {
+    pub const KIND: &'static str = "hfsc";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `default ID`.
+    pub fn default_class(mut self, minor: u16) -> Self {
+        self.default_class = minor;
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        raw_options(self.default_class.to_ne_bytes().to_vec())
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            default_class: read_u16(payload, 0)?,
+        })
+    }
+}
+
+/// A two-piece linear service curve of the `hfsc` qdisc: a slope of `m1`
+/// bytes per second during the first `d` microseconds, then `m2` bytes per
+/// second.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct HfscServiceCurve {
+    pub m1: u32,
+    pub d: u32,
+    pub m2: u32,
+}
+
+impl HfscServiceCurve {
+    /// Equivalent to `m1 BPS d USEC m2 BPS`.
+    pub fn new(m1: u32, d: u32, m2: u32) -> Self {
+        Self { m1, d, m2 }
+    }
+
+    /// Linear curve, equivalent to `rate BPS`.
+    pub fn rate(rate: u32) -> Self {
+        Self {
+            m1: 0,
+            d: 0,
+            m2: rate,
+        }
+    }
+
+    fn to_nla(self, kind: u16) -> DefaultNla {
+        let mut curve = Vec::with_capacity(TC_SERVICE_CURVE_LEN);
+        for value in [self.m1, self.d, self.m2] {
+            curve.extend_from_slice(&value.to_ne_bytes());
+        }
+        DefaultNla::new(kind, curve)
+    }
+
+    fn parse(value: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            m1: read_u32(value, 0)?,
+            d: read_u32(value, 4)?,
+            m2: read_u32(value, 8)?,
+        })
+    }
+}
+
+/// Options of a class of the `hfsc` qdisc.
+/// Equivalent to `tc class add ... hfsc`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TrafficClassHfsc {
+    /// Real-time curve, guaranteed to the class.
+    pub realtime: Option<HfscServiceCurve>,
+    /// Link-sharing curve, used to share the excess bandwidth.
+    pub link_share: Option<HfscServiceCurve>,
+    /// Upper limit curve, which can never be exceeded.
+    pub upper_limit: Option<HfscServiceCurve>,
+}
+
+impl TrafficClassHfsc {
+    pub const KIND: &'static str = "hfsc";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `rt CURVE`.
+    pub fn realtime(mut self, curve: HfscServiceCurve) -> Self {
+        self.realtime = Some(curve);
+        self
+    }
+
+    /// Equivalent to `ls CURVE`.
+    pub fn link_share(mut self, curve: HfscServiceCurve) -> Self {
+        self.link_share = Some(curve);
+        self
+    }
+
+    /// Equivalent to `ul CURVE`.
+    pub fn upper_limit(mut self, curve: HfscServiceCurve) -> Self {
+        self.upper_limit = Some(curve);
+        self
+    }
+
+    /// Set both the real-time and link-sharing curves.
+    /// Equivalent to `sc CURVE`.
+    pub fn service_curve(self, curve: HfscServiceCurve) -> Self {
+        self.realtime(curve).link_share(curve)
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let nlas: Vec<_> = [
+            (TCA_HFSC_RSC, self.realtime),
+            (TCA_HFSC_FSC, self.link_share),
+            (TCA_HFSC_USC, self.upper_limit),
+        ]
+        .into_iter()
+        .filter_map(|(kind, curve)| curve.map(|curve| curve.to_nla(kind)))
+        .collect();
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut hfsc = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_HFSC_RSC => {
+                    hfsc.realtime = Some(HfscServiceCurve::parse(value)?)
+                }
+                TCA_HFSC_FSC => {
+                    hfsc.link_share = Some(HfscServiceCurve::parse(value)?)
+                }
+                TCA_HFSC_USC => {
+                    hfsc.upper_limit = Some(HfscServiceCurve::parse(value)?)
+                }
+                _ => (),
+            }
+        }
+        Ok(hfsc)
+    }
+}
@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u32, parse_u64, DecodeError, DefaultNla};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::{
+    nla::{nested_options, nla_flag, nla_u32, nla_u64, parse_nlas, read_u32},
+    ratespec::{
+        emit_ratespec, needs_rate64, parse_ratespec, ticks_to_size, xmit_ticks,
+        RATESPEC_LEN,
+    },
+};
+
+const TCA_HTB_PARMS: u16 = 1;
+const TCA_HTB_INIT: u16 = 2;
+const TCA_HTB_DIRECT_QLEN: u16 = 5;
+const TCA_HTB_RATE64: u16 = 6;
+const TCA_HTB_CEIL64: u16 = 7;
+const TCA_HTB_OFFLOAD: u16 = 9;
+
+const TC_HTB_PROTOVER: u32 = 3;
+const TC_HTB_GLOB_LEN: usize = 20;
+const TC_HTB_OPT_LEN: usize = 2 * RATESPEC_LEN + 20;
+
+const DEFAULT_R2Q: u32 = 10;
+const DEFAULT_BURST: u32 = 1600;
+
+/// Options of the `htb` (Hierarchy Token Bucket) qdisc.
+/// Equivalent to `tc qdisc add ... htb`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct QDiscHtb {
+    /// Divisor used to compute the quantum of classes from their rate.
+    pub r2q: u32,
+    /// Minor number of the class receiving unclassified traffic. When 0,
+    /// unclassified traffic is sent directly at full speed.
+    pub default_class: u32,
+    /// Length of the queue of directly sent packets.
+    pub direct_qlen: Option<u32>,
+    /// Offload to hardware.
+    pub offload: bool,
+    /// Number of packets sent directly, only reported by the kernel.
+    pub direct_packets: u32,
+}
+
+impl Default for QDiscHtb {
+    fn default() -> Self {
+        Self {
+            r2q: DEFAULT_R2Q,
+            default_class: 0,
+            direct_qlen: None,
+            offload: false,
+            direct_packets: 0,
+        }
+    }
+}
+
+impl QDiscHtb {
+    pub const KIND: &'static str = "htb";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `r2q NUMBER`. Default: 10.
+    pub fn r2q(mut self, r2q: u32) -> Self {
+        self.r2q = r2q;
+        self
+    }
+
+    /// Equivalent to `default ID`.
+    pub fn default_class(mut self, minor: u32) -> Self {
+        self.default_class = minor;
+        self
+    }
+
+    /// Equivalent to `direct_qlen P`.
+    pub fn direct_qlen(mut self, packets: u32) -> Self {
+        self.direct_qlen = Some(packets);
+        self
+    }
+
+    /// Equivalent to `offload`.
+    pub fn offload(mut self) -> Self {
+        self.offload = true;
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut init = vec![0u8; TC_HTB_GLOB_LEN];
+        init[0..4].copy_from_slice(&TC_HTB_PROTOVER.to_ne_bytes());
+        init[4..8].copy_from_slice(&self.r2q.to_ne_bytes());
+        init[8..12].copy_from_slice(&self.default_class.to_ne_bytes());
+        let mut nlas = vec![DefaultNla::new(TCA_HTB_INIT, init)];
+        if let Some(qlen) = self.direct_qlen {
+            nlas.push(nla_u32(TCA_HTB_DIRECT_QLEN, qlen));
+        }
+        if self.offload {
+            nlas.push(nla_flag(TCA_HTB_OFFLOAD));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut htb = Self::default();
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_HTB_INIT => {
+                    htb.r2q = read_u32(value, 4)?;
+                    htb.default_class = read_u32(value, 8)?;
+                    htb.direct_packets = read_u32(value, 16)?;
+                }
+                TCA_HTB_DIRECT_QLEN => {
+                    htb.direct_qlen = Some(parse_u32(value)?)
+                }
+                TCA_HTB_OFFLOAD => htb.offload = true,
+                _ => (),
+            }
+        }
+        Ok(htb)
+    }
+}
+
+/// Options of a class of the `htb` qdisc.
+/// Equivalent to `tc class add ... htb`.
+///
+/// Rates are in bytes per second and bursts in bytes. Use
+/// [crate::TcRate] and [crate::TcSize] to convert them from the
+/// `tc` notation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TrafficClassHtb {
+    /// Guaranteed rate.
+    pub rate: u64,
+    /// Maximum rate when borrowing from the parent. Defaults to `rate`.
+    pub ceil: u64,
+    /// Bytes which can be sent at ceil speed before serving other classes.
+    pub burst: u32,
+    /// Bytes which can be sent at wire speed before serving other classes.
+    pub cburst: u32,
+    /// Bytes served before moving to the next class. When 0 the kernel
+    /// computes it from the rate and the `r2q` of the qdisc.
+    pub quantum: u32,
+    /// Priority, lower values are served first.
+    pub prio: u32,
+    /// Level in the hierarchy, only reported by the kernel.
+    pub level: u32,
+}
+
+impl TrafficClassHtb {
+    pub const KIND: &'static str = "htb";
+
+    /// Equivalent to `rate RATE`.
+    pub fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            ..Default::default()
+        }
+    }
+
+    /// Equivalent to `ceil RATE`.
+    pub fn ceil(mut self, ceil: u64) -> Self {
+        self.ceil = ceil;
+        self
+    }
+
+    /// Equivalent to `burst BYTES`. Default: 1600 bytes.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    /// Equivalent to `cburst BYTES`. Default: 1600 bytes.
+    pub fn cburst(mut self, cburst: u32) -> Self {
+        self.cburst = cburst;
+        self
+    }
+
+    /// Equivalent to `quantum BYTES`.
+    pub fn quantum(mut self, quantum: u32) -> Self {
+        self.quantum = quantum;
+        self
+    }
+
+    /// Equivalent to `prio PRIORITY`.
+    pub fn prio(mut self, prio: u32) -> Self {
+        self.prio = prio;
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let rate = self.rate;
+        let ceil = if self.ceil == 0 { rate } else { self.ceil };
+        let burst = if self.burst == 0 {
+            DEFAULT_BURST
+        } else {
+            self.burst
+        };
+        let cburst = if self.cburst == 0 {
+            DEFAULT_BURST
+        } else {
+            self.cburst
+        };
+
+        let mut parms = vec![0u8; TC_HTB_OPT_LEN];
+        emit_ratespec(&mut parms[0..RATESPEC_LEN], rate);
+        emit_ratespec(&mut parms[RATESPEC_LEN..2 * RATESPEC_LEN], ceil);
+        let mut offset = 2 * RATESPEC_LEN;
+        for value in [
+            xmit_ticks(rate, burst),
+            xmit_ticks(ceil, cburst),
+            self.quantum,
+            0, // level
+            self.prio,
+        ] {
+            parms[offset..offset + 4].copy_from_slice(&value.to_ne_bytes());
+            offset += 4;
+        }
+
+        let mut nlas = vec![DefaultNla::new(TCA_HTB_PARMS, parms)];
+        if needs_rate64(rate) {
+            nlas.push(nla_u64(TCA_HTB_RATE64, rate));
+        }
+        if needs_rate64(ceil) {
+            nlas.push(nla_u64(TCA_HTB_CEIL64, ceil));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut htb = Self::default();
+        let mut buffer = 0;
+        let mut cbuffer = 0;
+        let mut rate64 = None;
+        let mut ceil64 = None;
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_HTB_PARMS => {
+                    htb.rate = parse_ratespec(value)?;
+                    htb.ceil = parse_ratespec(
+                        value.get(RATESPEC_LEN..).unwrap_or_default(),
+                    )?;
+                    let offset = 2 * RATESPEC_LEN;
+                    buffer = read_u32(value, offset)?;
+                    cbuffer = read_u32(value, offset + 4)?;
+                    htb.quantum = read_u32(value, offset + 8)?;
+                    htb.level = read_u32(value, offset + 12)?;
+                    htb.prio = read_u32(value, offset + 16)?;
+                }
+                TCA_HTB_RATE64 => rate64 = Some(parse_u64(value)?),
+                TCA_HTB_CEIL64 => ceil64 = Some(parse_u64(value)?),
+                _ => (),
+            }
+        }
+        if let Some(rate) = rate64 {
+            htb.rate = rate;
+        }
+        if let Some(ceil) = ceil64 {
+            htb.ceil = ceil;
+        }
+        htb.burst = ticks_to_size(htb.rate, buffer);
+        htb.cburst = ticks_to_size(htb.ceil, cbuffer);
+        Ok(htb)
+    }
+}
+
+/// Statistics specific to a class of the `htb` qdisc, see
+/// [crate::TcMessageExt::xstats].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct HtbXstats {
+    /// Packets sent using the class own rate.
+    pub lends: u32,
+    /// Packets sent by borrowing from the parent.
+    pub borrows: u32,
+    /// Packets larger than the MTU of the class.
+    pub giants: u32,
+    /// Remaining tokens of the rate bucket, in scheduler ticks.
+    pub tokens: i32,
+    /// Remaining tokens of the ceil bucket, in scheduler ticks.
+    pub ctokens: i32,
+}
+
+impl HtbXstats {
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            lends: read_u32(payload, 0)?,
+            borrows: read_u32(payload, 4)?,
+            giants: read_u32(payload, 8)?,
+            tokens: read_u32(payload, 12)? as i32,
+            ctokens: read_u32(payload, 16)? as i32,
+        })
+    }
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MIT
+
+mod cake;
+mod drr;
+mod ets;
+mod fifo;
+mod fq;
+mod fq_codel;
+mod hfsc;
+mod htb;
+mod mq;
+mod mqprio;
+mod netem;
+mod prio;
+mod sfq;
+mod taprio;
+mod tbf;
+#[cfg(test)]
+mod test;
+
+use netlink_packet_core::DecodeError;
+
+pub use self::{
+    cake::{
+        CakeAckFilter, CakeAtm, CakeDiffserv, CakeFlowMode, CakeTinStats,
+        CakeXstats, QDiscCake,
+    },
+    drr::{QDiscDrr, TrafficClassDrr},
+    ets::{QDiscEts, TrafficClassEts},
+    fifo::{QDiscBfifo, QDiscPfifo},
+    fq::{FqXstats, QDiscFq},
+    fq_codel::QDiscFqCodel,
+    hfsc::{HfscServiceCurve, QDiscHfsc, TrafficClassHfsc},
+    htb::{HtbXstats, QDiscHtb, TrafficClassHtb},
+    mq::QDiscMq,
+    mqprio::{MqprioMode, MqprioShaper, QDiscMqprio},
+    netem::{NetemLoss, NetemRate, NetemSlot, QDiscNetem},
+    prio::QDiscPrio,
+    sfq::QDiscSfq,
+    taprio::{
+        QDiscTaprio, TaprioFramePreemption, TaprioGateCommand, TaprioSchedEntry,
+    },
+    tbf::QDiscTbf,
+};
+
+/// Decoded options of a qdisc, see [crate::TcMessageExt::qdisc_options].
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum QDiscOptions {
+    Htb(QDiscHtb),
+    Prio(QDiscPrio),
+    Drr(QDiscDrr),
+    Ets(QDiscEts),
+    Hfsc(QDiscHfsc),
+    Tbf(QDiscTbf),
+    FqCodel(QDiscFqCodel),
+    Fq(QDiscFq),
+    Cake(QDiscCake),
+    Sfq(QDiscSfq),
+    Pfifo(QDiscPfifo),
+    Bfifo(QDiscBfifo),
+    Netem(QDiscNetem),
+    Mq(QDiscMq),
+    Mqprio(QDiscMqprio),
+    Taprio(QDiscTaprio),
+    /// Qdisc kind without typed options.
+    Other(String),
+}
+
+impl QDiscOptions {
+    pub(crate) fn parse(
+        kind: &str,
+        payload: &[u8],
+    ) -> Result<Self, DecodeError> {
+        Ok(match kind {
+            QDiscHtb::KIND => Self::Htb(QDiscHtb::parse(payload)?),
+            QDiscPrio::KIND => Self::Prio(QDiscPrio::parse(payload)?),
+            QDiscDrr::KIND => Self::Drr(QDiscDrr),
+            QDiscEts::KIND => Self::Ets(QDiscEts::parse(payload)?),
+            QDiscHfsc::KIND => Self::Hfsc(QDiscHfsc::parse(payload)?),
+            QDiscTbf::KIND => Self::Tbf(QDiscTbf::parse(payload)?),
+            QDiscFqCodel::KIND => Self::FqCodel(QDiscFqCodel::parse(payload)?),
+            QDiscFq::KIND => Self::Fq(QDiscFq::parse(payload)?),
+            QDiscCake::KIND => Self::Cake(QDiscCake::parse(payload)?),
+            QDiscSfq::KIND => Self::Sfq(QDiscSfq::parse(payload)?),
+            QDiscPfifo::KIND => Self::Pfifo(QDiscPfifo::parse(payload)?),
+            QDiscBfifo::KIND => Self::Bfifo(QDiscBfifo::parse(payload)?),
+            QDiscNetem::KIND => Self::Netem(QDiscNetem::parse(payload)?),
+            QDiscMq::KIND => Self::Mq(QDiscMq),
+            QDiscMqprio::KIND => Self::Mqprio(QDiscMqprio::parse(payload)?),
+            QDiscTaprio::KIND => Self::Taprio(QDiscTaprio::parse(payload)?),
+            _ => Self::Other(kind.to_string()),
+        })
+    }
+}
+
+/// Decoded options of a traffic class, see
+/// [crate::TcMessageExt::class_options].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TrafficClassOptions {
+    Htb(TrafficClassHtb),
+    Drr(TrafficClassDrr),
+    Ets(TrafficClassEts),
+    Hfsc(TrafficClassHfsc),
+    /// Class kind without typed options.
+    Other(String),
+}
+
+impl TrafficClassOptions {
+    pub(crate) fn parse(
+        kind: &str,
+        payload: &[u8],
+    ) -> Result<Self, DecodeError> {
+        Ok(match kind {
+            TrafficClassHtb::KIND => {
+                Self::Htb(TrafficClassHtb::parse(payload)?)
+            }
+            TrafficClassDrr::KIND => {
+                Self::Drr(TrafficClassDrr::parse(payload)?)
+            }
+            TrafficClassEts::KIND => {
+                Self::Ets(TrafficClassEts::parse(payload)?)
+            }
+            TrafficClassHfsc::KIND => {
+                Self::Hfsc(TrafficClassHfsc::parse(payload)?)
+            }
+            _ => Self::Other(kind.to_string()),
+        })
+    }
+}
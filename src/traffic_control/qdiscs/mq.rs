@@ -0,0 +1,11 @@
+// SPDX-License-Identifier: MIT
+
+/// The `mq` (multiqueue) qdisc, which has no options. It creates one class
+/// per hardware transmit queue.
+/// Equivalent to `tc qdisc add ... mq`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscMq;
+
+impl QDiscMq {
+    pub const KIND: &'static str = "mq";
+}
@@ -0,0 +1,300 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{parse_u16, parse_u64, DecodeError, DefaultNla};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::nla::{
+    emit_nlas, nla_nested, nla_u16, nla_u64, parse_nlas, raw_options, read_u16,
+};
+
+const TCA_MQPRIO_MODE: u16 = 1;
+const TCA_MQPRIO_SHAPER: u16 = 2;
+const TCA_MQPRIO_MIN_RATE64: u16 = 3;
+const TCA_MQPRIO_MAX_RATE64: u16 = 4;
+
+const TC_QOPT_BITMASK: usize = 15;
+const TC_QOPT_MAX_QUEUE: usize = 16;
+// struct tc_mqprio_qopt
+pub(super) const TC_MQPRIO_QOPT_LEN: usize =
+    1 + TC_QOPT_BITMASK + 1 + 1 + 4 * TC_QOPT_MAX_QUEUE;
+const TC_MQPRIO_QOPT_ALIGNED_LEN: usize = (TC_MQPRIO_QOPT_LEN + 3) & !3;
+
+const DEFAULT_NUM_TC: u8 = 8;
+const DEFAULT_PRIOMAP: [u8; TC_QOPT_BITMASK + 1] =
+    [0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7];
+
+/// Who configures the traffic classes of the `mqprio` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum MqprioMode {
+    /// `dcb`
+    Dcb,
+    /// `channel`
+    Channel,
+    Other(u16),
+}
+
+impl From<u16> for MqprioMode {
+    fn from(d: u16) -> Self {
+        match d {
+            0 => Self::Dcb,
+            1 => Self::Channel,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<MqprioMode> for u16 {
+    fn from(v: MqprioMode) -> u16 {
+        match v {
+            MqprioMode::Dcb => 0,
+            MqprioMode::Channel => 1,
+            MqprioMode::Other(d) => d,
+        }
+    }
+}
+
+/// Rate limiting of the traffic classes of the `mqprio` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum MqprioShaper {
+    /// `dcb`
+    Dcb,
+    /// `bw_rlimit`, using the minimum and maximum rates.
+    BwRlimit,
+    Other(u16),
+}
+
+impl From<u16> for MqprioShaper {
+    fn from(d: u16) -> Self {
+        match d {
+            0 => Self::Dcb,
+            1 => Self::BwRlimit,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<MqprioShaper> for u16 {
+    fn from(v: MqprioShaper) -> u16 {
+        match v {
+            MqprioShaper::Dcb => 0,
+            MqprioShaper::BwRlimit => 1,
+            MqprioShaper::Other(d) => d,
+        }
+    }
+}
+
+/// Options of the `mqprio` (Multiqueue Priority) qdisc.
+/// Equivalent to `tc qdisc add ... mqprio`.
+///
+/// Rates are in bytes per second.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct QDiscMqprio {
+    /// Number of traffic classes.
+    pub num_tc: u8,
+    /// Traffic class of each of the 16 packet priorities.
+    pub priomap: [u8; TC_QOPT_BITMASK + 1],
+    /// `(count, offset)` range of transmit queues of each traffic class.
+    pub queues: Vec<(u16, u16)>,
+    /// Offload the configuration to hardware.
+    pub hw: bool,
+    pub mode: Option<MqprioMode>,
+    pub shaper: Option<MqprioShaper>,
+    /// Minimum rate of each traffic class.
+    pub min_rate: Vec<u64>,
+    /// Maximum rate of each traffic class.
+    pub max_rate: Vec<u64>,
+}
+
+impl Default for QDiscMqprio {
+    fn default() -> Self {
+        Self {
+            num_tc: DEFAULT_NUM_TC,
+            priomap: DEFAULT_PRIOMAP,
+            queues: Vec::new(),
+            hw: true,
+            mode: None,
+            shaper: None,
+            min_rate: Vec::new(),
+            max_rate: Vec::new(),
+        }
+    }
+}
+
+impl QDiscMqprio {
+    pub const KIND: &'static str = "mqprio";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `num_tc NUMBER`. Default: 8.
+    pub fn num_tc(mut self, num_tc: u8) -> Self {
+        self.num_tc = num_tc;
+        self
+    }
+
+    /// Equivalent to `map P0 P1 ... P15`.
+    pub fn priomap(mut self, priomap: [u8; TC_QOPT_BITMASK + 1]) -> Self {
+        self.priomap = priomap;
+        self
+    }
+
+    /// Equivalent to `queues count1@offset1 count2@offset2 ...`.
+    pub fn queues(mut self, queues: Vec<(u16, u16)>) -> Self {
+        self.queues = queues;
+        self
+    }
+
+    /// Equivalent to `hw 1` when true and `hw 0` when false. Default: true.
+    pub fn hw(mut self, hw: bool) -> Self {
+        self.hw = hw;
+        self
+    }
+
+    /// Equivalent to `mode MODE`.
+    pub fn mode(mut self, mode: MqprioMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Equivalent to `shaper SHAPER`.
+    pub fn shaper(mut self, shaper: MqprioShaper) -> Self {
+        self.shaper = Some(shaper);
+        self
+    }
+
+    /// Equivalent to `min_rate RATE1 RATE2 ...`.
+    pub fn min_rate(mut self, rates: Vec<u64>) -> Self {
+        self.min_rate = rates;
+        self
+    }
+
+    /// Equivalent to `max_rate RATE1 RATE2 ...`.
+    pub fn max_rate(mut self, rates: Vec<u64>) -> Self {
+        self.max_rate = rates;
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        // The attributes follow struct tc_mqprio_qopt.
+        let mut payload =
+            emit_mqprio_qopt(self.num_tc, &self.priomap, &self.queues, self.hw);
+        payload.resize(TC_MQPRIO_QOPT_ALIGNED_LEN, 0);
+
+        let mut nlas = Vec::new();
+        if let Some(mode) = self.mode {
+            nlas.push(nla_u16(TCA_MQPRIO_MODE, mode.into()));
+        }
+        if let Some(shaper) = self.shaper {
+            nlas.push(nla_u16(TCA_MQPRIO_SHAPER, shaper.into()));
+        }
+        if !self.min_rate.is_empty() {
+            nlas.push(nla_rates(TCA_MQPRIO_MIN_RATE64, &self.min_rate));
+        }
+        if !self.max_rate.is_empty() {
+            nlas.push(nla_rates(TCA_MQPRIO_MAX_RATE64, &self.max_rate));
+        }
+        payload.extend_from_slice(&emit_nlas(&nlas));
+        raw_options(payload)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let (num_tc, priomap, queues, hw) = parse_mqprio_qopt(payload)?;
+        let mut mqprio = Self {
+            num_tc,
+            priomap,
+            queues,
+            hw,
+            ..Default::default()
+        };
+        let nlas = payload
+            .get(TC_MQPRIO_QOPT_ALIGNED_LEN..)
+            .unwrap_or_default();
+        for (kind, value) in parse_nlas(nlas)? {
+            match kind {
+                TCA_MQPRIO_MODE => mqprio.mode = Some(parse_u16(value)?.into()),
+                TCA_MQPRIO_SHAPER => {
+                    mqprio.shaper = Some(parse_u16(value)?.into())
+                }
+                TCA_MQPRIO_MIN_RATE64 => {
+                    mqprio.min_rate = parse_rates(value, num_tc)?
+                }
+                TCA_MQPRIO_MAX_RATE64 => {
+                    mqprio.max_rate = parse_rates(value, num_tc)?
+                }
+                _ => (),
+            }
+        }
+        Ok(mqprio)
+    }
+}
+
+// iproute2 always sends one rate per possible traffic class.
+fn nla_rates(kind: u16, rates: &[u64]) -> DefaultNla {
+    let rates: Vec<_> = (0..TC_QOPT_MAX_QUEUE)
+        .map(|i| nla_u64(kind, rates.get(i).copied().unwrap_or_default()))
+        .collect();
+    nla_nested(kind, &rates)
+}
+
+fn parse_rates(payload: &[u8], num_tc: u8) -> Result<Vec<u64>, DecodeError> {
+    let mut rates = Vec::new();
+    for (_, value) in parse_nlas(payload)?.into_iter().take(num_tc.into()) {
+        rates.push(parse_u64(value)?);
+    }
+    Ok(rates)
+}
+
+/// Emit a `struct tc_mqprio_qopt`, shared with the `taprio` qdisc.
+pub(super) fn emit_mqprio_qopt(
+    num_tc: u8,
+    priomap: &[u8; TC_QOPT_BITMASK + 1],
+    queues: &[(u16, u16)],
+    hw: bool,
+) -> Vec<u8> {
+    let mut qopt = vec![0u8; TC_MQPRIO_QOPT_LEN];
+    qopt[0] = num_tc;
+    qopt[1..TC_QOPT_BITMASK + 2].copy_from_slice(priomap);
+    qopt[TC_QOPT_BITMASK + 2] = hw.into();
+    let counts = TC_QOPT_BITMASK + 3;
+    let offsets = counts + 2 * TC_QOPT_MAX_QUEUE;
+    for (i, (count, offset)) in
+        queues.iter().take(TC_QOPT_MAX_QUEUE).enumerate()
+    {
+        qopt[counts + 2 * i..counts + 2 * i + 2]
+            .copy_from_slice(&count.to_ne_bytes());
+        qopt[offsets + 2 * i..offsets + 2 * i + 2]
+            .copy_from_slice(&offset.to_ne_bytes());
+    }
+    qopt
+}
+
+/// Parse a `struct tc_mqprio_qopt` into `(num_tc, priomap, queues, hw)`.
+#[allow(clippy::type_complexity)]
+pub(super) fn parse_mqprio_qopt(
+    payload: &[u8],
+) -> Result<(u8, [u8; TC_QOPT_BITMASK + 1], Vec<(u16, u16)>, bool), DecodeError>
+{
+    if payload.len() < TC_MQPRIO_QOPT_LEN {
+        return Err(DecodeError::buffer_too_small(
+            payload.len(),
+            TC_MQPRIO_QOPT_LEN,
+        ));
+    }
+    let num_tc = payload[0];
+    let mut priomap = [0u8; TC_QOPT_BITMASK + 1];
+    priomap.copy_from_slice(&payload[1..TC_QOPT_BITMASK + 2]);
+    let hw = payload[TC_QOPT_BITMASK + 2] != 0;
+    let counts = TC_QOPT_BITMASK + 3;
+    let offsets = counts + 2 * TC_QOPT_MAX_QUEUE;
+    let mut queues = Vec::new();
+    for i in 0..usize::from(num_tc).min(TC_QOPT_MAX_QUEUE) {
+        queues.push((
+            read_u16(payload, counts + 2 * i)?,
+            read_u16(payload, offsets + 2 * i)?,
+        ));
+    }
+    Ok((num_tc, priomap, queues, hw))
+}
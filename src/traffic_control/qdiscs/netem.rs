@@ -0,0 +1,558 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use netlink_packet_core::{
+    parse_i64, parse_u32, parse_u64, DecodeError, DefaultNla,
+};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::{
+    nla::{
+        emit_nlas, nla_nested, nla_u32, nla_u64, parse_nlas, raw_options,
+        read_u32,
+    },
+    ratespec::{ticks_to_time, time_to_ticks},
+};
+
+const TCA_NETEM_CORR: u16 = 1;
+const TCA_NETEM_REORDER: u16 = 3;
+const TCA_NETEM_CORRUPT: u16 = 4;
+const TCA_NETEM_LOSS: u16 = 5;
+const TCA_NETEM_RATE: u16 = 6;
+const TCA_NETEM_ECN: u16 = 7;
+const TCA_NETEM_RATE64: u16 = 8;
+const TCA_NETEM_LATENCY64: u16 = 10;
+const TCA_NETEM_JITTER64: u16 = 11;
+const TCA_NETEM_SLOT: u16 = 12;
+const TCA_NETEM_PRNG_SEED: u16 = 14;
+
+const NETEM_LOSS_GI: u16 = 1;
+const NETEM_LOSS_GE: u16 = 2;
+
+const TC_NETEM_QOPT_LEN: usize = 24;
+const TC_NETEM_RATE_LEN: usize = 16;
+const TC_NETEM_SLOT_LEN: usize = 40;
+
+const DEFAULT_LIMIT: u32 = 1000;
+
+/// Packet loss model of the `netem` qdisc. Probabilities are percentages.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
+pub enum NetemLoss {
+    /// Equivalent to `loss random PERCENT [CORRELATION]`.
+    Random { percent: f64, correlation: f64 },
+    /// 4-state Markov model, equivalent to
+    /// `loss state P13 [P31 [P32 [P23 [P14]]]]`.
+    State {
+        p13: f64,
+        p31: f64,
+        p32: f64,
+        p23: f64,
+        p14: f64,
+    },
+    /// Gilbert-Elliot model, equivalent to
+    /// `loss gemodel P [R [1-H [1-K]]]`.
+    GilbertElliott {
+        p: f64,
+        r: f64,
+        one_minus_h: f64,
+        one_minus_k: f64,
+    },
+}
+
+impl NetemLoss {
+    /// Independent loss of `percent` of the packets.
+    pub fn random(percent: f64) -> Self {
+        Self::Random {
+            percent,
+            correlation: 0.0,
+        }
+    }
+}
+
+/// Rate limit of the `netem` qdisc.
+/// Equivalent to `rate RATE [PACKETOVERHEAD [CELLSIZE [CELLOVERHEAD]]]`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct NetemRate {
+    /// Rate in bytes per second.
+    pub rate: u64,
+    /// Bytes added to (or removed from when negative) each packet.
+    pub packet_overhead: i32,
+    /// Size of the link layer cells, 0 when not cell based.
+    pub cell_size: u32,
+    /// Bytes added to (or removed from when negative) each cell.
+    pub cell_overhead: i32,
+}
+
+impl NetemRate {
+    pub fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            ..Default::default()
+        }
+    }
+}
+
+/// Bursts of transmission of the `netem` qdisc.
+/// Equivalent to `slot MIN_DELAY [MAX_DELAY] [packets N] [bytes N]`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct NetemSlot {
+    /// Minimum delay between two slots.
+    pub min_delay: Duration,
+    /// Maximum delay between two slots, the delay being uniformly
+    /// distributed between `min_delay` and `max_delay`.
+    pub max_delay: Duration,
+    /// Maximum number of packets sent in a slot.
+    pub packets: Option<u32>,
+    /// Maximum number of bytes sent in a slot.
+    pub bytes: Option<u32>,
+}
+
+impl NetemSlot {
+    pub fn new(min_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            min_delay,
+            max_delay,
+            ..Default::default()
+        }
+    }
+
+    pub fn packets(mut self, packets: u32) -> Self {
+        self.packets = Some(packets);
+        self
+    }
+
+    pub fn bytes(mut self, bytes: u32) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+}
+
+/// Options of the `netem` (Network Emulator) qdisc.
+/// Equivalent to `tc qdisc add ... netem`.
+///
+/// Probabilities and correlations are percentages.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct QDiscNetem {
+    /// Maximum number of queued packets.
+    pub limit: u32,
+    pub delay: Duration,
+    pub jitter: Duration,
+    pub delay_correlation: f64,
+    pub loss: Option<NetemLoss>,
+    pub duplicate: f64,
+    pub duplicate_correlation: f64,
+    /// Percentage of packets sent immediately instead of being delayed.
+    pub reorder: f64,
+    pub reorder_correlation: f64,
+    /// Only reorder one packet out of `gap`.
+    pub gap: u32,
+    pub corrupt: f64,
+    pub corrupt_correlation: f64,
+    /// Mark packets with ECN instead of dropping them.
+    pub ecn: bool,
+    pub rate: Option<NetemRate>,
+    pub slot: Option<NetemSlot>,
+    /// Seed of the pseudo random generator.
+    pub seed: Option<u64>,
+}
+
+impl Default for QDiscNetem {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            delay: Duration::ZERO,
+            jitter: Duration::ZERO,
+            delay_correlation: 0.0,
+            loss: None,
+            duplicate: 0.0,
+            duplicate_correlation: 0.0,
+            reorder: 0.0,
+            reorder_correlation: 0.0,
+            gap: 0,
+            corrupt: 0.0,
+            corrupt_correlation: 0.0,
+            ecn: false,
+            rate: None,
+            slot: None,
+            seed: None,
+        }
+    }
+}
+
+impl QDiscNetem {
+    pub const KIND: &'static str = "netem";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `limit PACKETS`. Default: 1000.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Equivalent to `delay TIME [JITTER [CORRELATION]]`.
+    pub fn delay(
+        mut self,
+        delay: Duration,
+        jitter: Duration,
+        correlation: f64,
+    ) -> Self {
+        self.delay = delay;
+        self.jitter = jitter;
+        self.delay_correlation = correlation;
+        self
+    }
+
+    /// Equivalent to `loss ...`.
+    pub fn loss(mut self, loss: NetemLoss) -> Self {
+        self.loss = Some(loss);
+        self
+    }
+
+    /// Equivalent to `duplicate PERCENT [CORRELATION]`.
+    pub fn duplicate(mut self, percent: f64, correlation: f64) -> Self {
+        self.duplicate = percent;
+        self.duplicate_correlation = correlation;
+        self
+    }
+
+    /// Equivalent to `reorder PERCENT [CORRELATION]`.
+    pub fn reorder(mut self, percent: f64, correlation: f64) -> Self {
+        self.reorder = percent;
+        self.reorder_correlation = correlation;
+        self
+    }
+
+    /// Equivalent to `gap DISTANCE`.
+    pub fn gap(mut self, gap: u32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Equivalent to `corrupt PERCENT [CORRELATION]`.
+    pub fn corrupt(mut self, percent: f64, correlation: f64) -> Self {
+        self.corrupt = percent;
+        self.corrupt_correlation = correlation;
+        self
+    }
+
+    /// Equivalent to `ecn`.
+    pub fn ecn(mut self) -> Self {
+        self.ecn = true;
+        self
+    }
+
+    /// Equivalent to `rate ...`.
+    pub fn rate(mut self, rate: NetemRate) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Equivalent to `slot ...`.
+    pub fn slot(mut self, slot: NetemSlot) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    /// Equivalent to `seed VALUE`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let (loss, loss_correlation) = match self.loss {
+            Some(NetemLoss::Random {
+                percent,
+                correlation,
+            }) => (percent, correlation),
+            _ => (0.0, 0.0),
+        };
+
+        let mut qopt = vec![0u8; TC_NETEM_QOPT_LEN];
+        emit_u32s(
+            &mut qopt,
+            &[
+                time_to_ticks(self.delay),
+                self.limit,
+                probability(loss),
+                self.gap,
+                probability(self.duplicate),
+                time_to_ticks(self.jitter),
+            ],
+        );
+
+        let mut nlas = Vec::new();
+        if ticks_overflow(self.delay) {
+            nlas.push(nla_u64(TCA_NETEM_LATENCY64, time_to_ns(self.delay)));
+        }
+        if ticks_overflow(self.jitter) {
+            nlas.push(nla_u64(TCA_NETEM_JITTER64, time_to_ns(self.jitter)));
+        }
+        if self.delay_correlation != 0.0
+            || loss_correlation != 0.0
+            || self.duplicate_correlation != 0.0
+        {
+            nlas.push(nla_probabilities(
+                TCA_NETEM_CORR,
+                &[
+                    self.delay_correlation,
+                    loss_correlation,
+                    self.duplicate_correlation,
+                ],
+            ));
+        }
+        if self.reorder != 0.0 {
+            nlas.push(nla_probabilities(
+                TCA_NETEM_REORDER,
+                &[self.reorder, self.reorder_correlation],
+            ));
+        }
+        if self.ecn {
+            nlas.push(nla_u32(TCA_NETEM_ECN, 1));
+        }
+        if self.corrupt != 0.0 {
+            nlas.push(nla_probabilities(
+                TCA_NETEM_CORRUPT,
+                &[self.corrupt, self.corrupt_correlation],
+            ));
+        }
+        if let Some(slot) = self.slot {
+            let mut value = vec![0u8; TC_NETEM_SLOT_LEN];
+            value[0..8]
+                .copy_from_slice(&time_to_ns(slot.min_delay).to_ne_bytes());
+            value[8..16]
+                .copy_from_slice(&time_to_ns(slot.max_delay).to_ne_bytes());
+            emit_u32s(
+                &mut value[16..24],
+                &[slot.packets.unwrap_or(0), slot.bytes.unwrap_or(0)],
+            );
+            nlas.push(DefaultNla::new(TCA_NETEM_SLOT, value));
+        }
+        match self.loss {
+            Some(NetemLoss::State {
+                p13,
+                p31,
+                p32,
+                p23,
+                p14,
+            }) => nlas.push(nla_nested(
+                TCA_NETEM_LOSS,
+                &[nla_probabilities(NETEM_LOSS_GI, &[p13, p31, p32, p14, p23])],
+            )),
+            Some(NetemLoss::GilbertElliott {
+                p,
+                r,
+                one_minus_h,
+                one_minus_k,
+            }) => {
+                let mut value = vec![0u8; 16];
+                emit_u32s(
+                    &mut value,
+                    &[
+                        probability(p),
+                        probability(r),
+                        u32::MAX - probability(one_minus_h),
+                        probability(one_minus_k),
+                    ],
+                );
+                nlas.push(nla_nested(
+                    TCA_NETEM_LOSS,
+                    &[DefaultNla::new(NETEM_LOSS_GE, value)],
+                ))
+            }
+            _ => (),
+        }
+        if let Some(rate) = self.rate {
+            if rate.rate >= u64::from(u32::MAX) {
+                nlas.push(nla_u64(TCA_NETEM_RATE64, rate.rate));
+            }
+            let mut value = vec![0u8; TC_NETEM_RATE_LEN];
+            emit_u32s(
+                &mut value,
+                &[
+                    u32::try_from(rate.rate).unwrap_or(u32::MAX),
+                    rate.packet_overhead as u32,
+                    rate.cell_size,
+                    rate.cell_overhead as u32,
+                ],
+            );
+            nlas.push(DefaultNla::new(TCA_NETEM_RATE, value));
+        }
+        if let Some(seed) = self.seed {
+            nlas.push(nla_u64(TCA_NETEM_PRNG_SEED, seed));
+        }
+
+        // The attributes directly follow struct tc_netem_qopt.
+        qopt.extend_from_slice(&emit_nlas(&nlas));
+        raw_options(qopt)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut netem = Self {
+            delay: ticks_to_time(read_u32(payload, 0)?),
+            limit: read_u32(payload, 4)?,
+            gap: read_u32(payload, 12)?,
+            duplicate: percent(read_u32(payload, 16)?),
+            jitter: ticks_to_time(read_u32(payload, 20)?),
+            ..Default::default()
+        };
+        let loss = percent(read_u32(payload, 8)?);
+        let mut loss_correlation = 0.0;
+        let mut rate64 = None;
+        let nlas = payload.get(TC_NETEM_QOPT_LEN..).unwrap_or_default();
+        for (kind, value) in parse_nlas(nlas)? {
+            match kind {
+                TCA_NETEM_CORR => {
+                    netem.delay_correlation = percent(read_u32(value, 0)?);
+                    loss_correlation = percent(read_u32(value, 4)?);
+                    netem.duplicate_correlation = percent(read_u32(value, 8)?);
+                }
+                TCA_NETEM_REORDER => {
+                    netem.reorder = percent(read_u32(value, 0)?);
+                    netem.reorder_correlation = percent(read_u32(value, 4)?);
+                }
+                TCA_NETEM_CORRUPT => {
+                    netem.corrupt = percent(read_u32(value, 0)?);
+                    netem.corrupt_correlation = percent(read_u32(value, 4)?);
+                }
+                TCA_NETEM_LOSS => netem.loss = parse_loss_model(value)?,
+                TCA_NETEM_RATE => {
+                    netem.rate = Some(NetemRate {
+                        rate: read_u32(value, 0)?.into(),
+                        packet_overhead: read_u32(value, 4)? as i32,
+                        cell_size: read_u32(value, 8)?,
+                        cell_overhead: read_u32(value, 12)? as i32,
+                    })
+                }
+                TCA_NETEM_RATE64 => rate64 = Some(parse_u64(value)?),
+                TCA_NETEM_ECN => netem.ecn = parse_u32(value)? != 0,
+                TCA_NETEM_LATENCY64 => {
+                    netem.delay = ns_to_time(parse_i64(value)?)
+                }
+                TCA_NETEM_JITTER64 => {
+                    netem.jitter = ns_to_time(parse_i64(value)?)
+                }
+                TCA_NETEM_SLOT => {
+                    let min_delay = value
+                        .get(0..8)
+                        .map(parse_i64)
+                        .transpose()?
+                        .unwrap_or_default();
+                    let max_delay = value
+                        .get(8..16)
+                        .map(parse_i64)
+                        .transpose()?
+                        .unwrap_or_default();
+                    let packets = read_u32(value, 16)?;
+                    let bytes = read_u32(value, 20)?;
+                    netem.slot = Some(NetemSlot {
+                        min_delay: ns_to_time(min_delay),
+                        max_delay: ns_to_time(max_delay),
+                        packets: slot_limit(packets),
+                        bytes: slot_limit(bytes),
+                    });
+                }
+                TCA_NETEM_PRNG_SEED => netem.seed = Some(parse_u64(value)?),
+                _ => (),
+            }
+        }
+        if let (Some(rate), Some(rate64)) = (netem.rate.as_mut(), rate64) {
+            rate.rate = rate64;
+        }
+        // The kernel always reports the rate, 0 meaning unlimited.
+        if netem.rate.map(|r| r.rate) == Some(0) {
+            netem.rate = None;
+        }
+        if netem.loss.is_none() && loss != 0.0 {
+            netem.loss = Some(NetemLoss::Random {
+                percent: loss,
+                correlation: loss_correlation,
+            });
+        }
+        Ok(netem)
+    }
+}
+
+fn parse_loss_model(payload: &[u8]) -> Result<Option<NetemLoss>, DecodeError> {
+    for (kind, value) in parse_nlas(payload)? {
+        let p = |i: usize| read_u32(value, i * 4).map(percent);
+        match kind {
+            NETEM_LOSS_GI => {
+                return Ok(Some(NetemLoss::State {
+                    p13: p(0)?,
+                    p31: p(1)?,
+                    p32: p(2)?,
+                    p14: p(3)?,
+                    p23: p(4)?,
+                }))
+            }
+            NETEM_LOSS_GE => {
+                return Ok(Some(NetemLoss::GilbertElliott {
+                    p: p(0)?,
+                    r: p(1)?,
+                    one_minus_h: percent(u32::MAX - read_u32(value, 8)?),
+                    one_minus_k: p(3)?,
+                }))
+            }
+            _ => (),
+        }
+    }
+    // The legacy random loss is reported in struct tc_netem_qopt.
+    Ok(None)
+}
+
+// The kernel reports an unlimited slot as `INT_MAX`.
+fn slot_limit(limit: u32) -> Option<u32> {
+    if limit == 0 || limit == i32::MAX as u32 {
+        None
+    } else {
+        Some(limit)
+    }
+}
+
+/// Percentage scaled to a `u32` probability, rounded half to even like
+/// iproute2 does.
+fn probability(percent: f64) -> u32 {
+    let scaled =
+        (percent / 100.0 * f64::from(u32::MAX)).clamp(0.0, f64::from(u32::MAX));
+    let mut rounded = scaled.round();
+    if (rounded - scaled).abs() == 0.5 && rounded % 2.0 != 0.0 {
+        rounded -= 1.0;
+    }
+    rounded as u32
+}
+
+fn percent(probability: u32) -> f64 {
+    f64::from(probability) / f64::from(u32::MAX) * 100.0
+}
+
+fn nla_probabilities(kind: u16, percents: &[f64]) -> DefaultNla {
+    let mut value = vec![0u8; percents.len() * 4];
+    let probabilities: Vec<u32> =
+        percents.iter().copied().map(probability).collect();
+    emit_u32s(&mut value, &probabilities);
+    DefaultNla::new(kind, value)
+}
+
+fn emit_u32s(buf: &mut [u8], values: &[u32]) {
+    for (i, value) in values.iter().enumerate() {
+        buf[i * 4..i * 4 + 4].copy_from_slice(&value.to_ne_bytes());
+    }
+}
+
+fn ticks_overflow(duration: Duration) -> bool {
+    time_to_ticks(duration) == u32::MAX
+}
+
+fn time_to_ns(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(i64::MAX as u64)
+}
+
+fn ns_to_time(ns: i64) -> Duration {
+    Duration::from_nanos(ns.max(0) as u64)
+}
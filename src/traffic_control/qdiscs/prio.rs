@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::DecodeError;
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::nla::{raw_options, read_u32};
+
+const TC_PRIO_MAX: usize = 15;
+const TC_PRIO_QOPT_LEN: usize = 4 + TC_PRIO_MAX + 1;
+
+const DEFAULT_BANDS: u32 = 3;
+const DEFAULT_PRIOMAP: [u8; TC_PRIO_MAX + 1] =
+    [1, 2, 2, 2, 1, 2, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1];
+
+/// Options of the `prio` qdisc.
+/// Equivalent to `tc qdisc add ... prio`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct QDiscPrio {
+    /// Number of bands, each band being a class.
+    pub bands: u32,
+    /// Band (starting at 0) of each of the 16 packet priorities.
+    pub priomap: [u8; TC_PRIO_MAX + 1],
+}
+
+impl Default for QDiscPrio {
+    fn default() -> Self {
+        Self {
+            bands: DEFAULT_BANDS,
+            priomap: DEFAULT_PRIOMAP,
+        }
+    }
+}
+
+impl QDiscPrio {
+    pub const KIND: &'static str = "prio";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `bands NUMBER`. Default: 3.
+    pub fn bands(mut self, bands: u32) -> Self {
+        self.bands = bands;
+        self
+    }
+
+    /// Equivalent to `priomap P1 P2 ...`.
+    /// Default: `1 2 2 2 1 2 0 0 1 1 1 1 1 1 1 1`.
+    pub fn priomap(mut self, priomap: [u8; TC_PRIO_MAX + 1]) -> Self {
+        self.priomap = priomap;
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut qopt = vec![0u8; TC_PRIO_QOPT_LEN];
+        qopt[0..4].copy_from_slice(&self.bands.to_ne_bytes());
+        qopt[4..].copy_from_slice(&self.priomap);
+        raw_options(qopt)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let bands = read_u32(payload, 0)?;
+        let mut priomap = [0u8; TC_PRIO_MAX + 1];
+        priomap.copy_from_slice(payload.get(4..TC_PRIO_QOPT_LEN).ok_or_else(
+            || DecodeError::buffer_too_small(payload.len(), TC_PRIO_QOPT_LEN),
+        )?);
+        Ok(Self { bands, priomap })
+    }
+}
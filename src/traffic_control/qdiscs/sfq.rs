@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use netlink_packet_core::DecodeError;
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::nla::{raw_options, read_u32};
+
+// struct tc_sfq_qopt_v1, with the RED parameters and statistics left to 0.
+const TC_SFQ_QOPT_V1_LEN: usize = 72;
+
+/// Options of the `sfq` (Stochastic Fairness Queueing) qdisc.
+/// Equivalent to `tc qdisc add ... sfq`.
+///
+/// Options left to 0 use the kernel defaults.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscSfq {
+    /// Bytes dequeued from a flow before serving the next one.
+    pub quantum: u32,
+    /// Interval between changes of the hash function, disabled when 0.
+    pub perturb: Duration,
+    /// Hard limit of the queue size, in packets.
+    pub limit: u32,
+    /// Size of the flow hash table.
+    pub divisor: u32,
+    /// Maximum number of flows.
+    pub flows: u32,
+    /// Maximum number of packets queued per flow.
+    pub depth: u32,
+    /// Drop packets from the head of the queue instead of the tail.
+    pub headdrop: bool,
+}
+
+impl QDiscSfq {
+    pub const KIND: &'static str = "sfq";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent to `quantum BYTES`.
+    pub fn quantum(mut self, quantum: u32) -> Self {
+        self.quantum = quantum;
+        self
+    }
+
+    /// Equivalent to `perturb SECONDS`, truncated to seconds.
+    pub fn perturb(mut self, perturb: Duration) -> Self {
+        self.perturb = perturb;
+        self
+    }
+
+    /// Equivalent to `limit PACKETS`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Equivalent to `divisor NUMBER`.
+    pub fn divisor(mut self, divisor: u32) -> Self {
+        self.divisor = divisor;
+        self
+    }
+
+    /// Equivalent to `flows NUMBER`.
+    pub fn flows(mut self, flows: u32) -> Self {
+        self.flows = flows;
+        self
+    }
+
+    /// Equivalent to `depth PACKETS`.
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Equivalent to `headdrop`.
+    pub fn headdrop(mut self) -> Self {
+        self.headdrop = true;
+        self
+    }
+
+    pub(crate) fn options(&self) -> Option<TcAttribute> {
+        if *self == Self::default() {
+            return None;
+        }
+        let perturb =
+            u32::try_from(self.perturb.as_secs()).unwrap_or(i32::MAX as u32);
+        let mut qopt = vec![0u8; TC_SFQ_QOPT_V1_LEN];
+        for (i, value) in [
+            self.quantum,
+            perturb,
+            self.limit,
+            self.divisor,
+            self.flows,
+            self.depth,
+            self.headdrop.into(),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            qopt[i * 4..i * 4 + 4].copy_from_slice(&value.to_ne_bytes());
+        }
+        Some(raw_options(qopt))
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut sfq = Self {
+            quantum: read_u32(payload, 0)?,
+            perturb: Duration::from_secs(read_u32(payload, 4)?.into()),
+            limit: read_u32(payload, 8)?,
+            divisor: read_u32(payload, 12)?,
+            flows: read_u32(payload, 16)?,
+            ..Default::default()
+        };
+        // Older kernels only report struct tc_sfq_qopt.
+        if payload.len() >= TC_SFQ_QOPT_V1_LEN {
+            sfq.depth = read_u32(payload, 20)?;
+            sfq.headdrop = read_u32(payload, 24)? != 0;
+        }
+        Ok(sfq)
+    }
+}
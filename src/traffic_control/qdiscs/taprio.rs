@@ -0,0 +1,446 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use netlink_packet_core::{
+    parse_i32, parse_i64, parse_u32, parse_u8, DecodeError, DefaultNla,
+};
+use netlink_packet_route::tc::TcAttribute;
+
+use super::mqprio::{emit_mqprio_qopt, parse_mqprio_qopt};
+use crate::traffic_control::nla::{
+    emit_nlas, nested_options, nla_nested, nla_u32, nla_u64, nla_u8, parse_nlas,
+};
+
+const TCA_TAPRIO_ATTR_PRIOMAP: u16 = 1;
+const TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST: u16 = 2;
+const TCA_TAPRIO_ATTR_SCHED_BASE_TIME: u16 = 3;
+const TCA_TAPRIO_ATTR_SCHED_CLOCKID: u16 = 5;
+const TCA_TAPRIO_ATTR_ADMIN_SCHED: u16 = 7;
+const TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME: u16 = 8;
+const TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME_EXTENSION: u16 = 9;
+const TCA_TAPRIO_ATTR_FLAGS: u16 = 10;
+const TCA_TAPRIO_ATTR_TXTIME_DELAY: u16 = 11;
+const TCA_TAPRIO_ATTR_TC_ENTRY: u16 = 12;
+
+const TCA_TAPRIO_SCHED_ENTRY: u16 = 1;
+const TCA_TAPRIO_SCHED_ENTRY_CMD: u16 = 2;
+const TCA_TAPRIO_SCHED_ENTRY_GATE_MASK: u16 = 3;
+const TCA_TAPRIO_SCHED_ENTRY_INTERVAL: u16 = 4;
+
+const TCA_TAPRIO_TC_ENTRY_INDEX: u16 = 1;
+const TCA_TAPRIO_TC_ENTRY_MAX_SDU: u16 = 2;
+const TCA_TAPRIO_TC_ENTRY_FP: u16 = 3;
+
+const TCA_TAPRIO_ATTR_FLAG_TXTIME_ASSIST: u32 = 1 << 0;
+const TCA_TAPRIO_ATTR_FLAG_FULL_OFFLOAD: u32 = 1 << 1;
+
+/// Gate operation of a `taprio` schedule entry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TaprioGateCommand {
+    /// `S`
+    SetGates,
+    /// `H`
+    SetAndHold,
+    /// `R`
+    SetAndRelease,
+    Other(u8),
+}
+
+impl From<u8> for TaprioGateCommand {
+    fn from(d: u8) -> Self {
+        match d {
+            0 => Self::SetGates,
+            1 => Self::SetAndHold,
+            2 => Self::SetAndRelease,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TaprioGateCommand> for u8 {
+    fn from(v: TaprioGateCommand) -> u8 {
+        match v {
+            TaprioGateCommand::SetGates => 0,
+            TaprioGateCommand::SetAndHold => 1,
+            TaprioGateCommand::SetAndRelease => 2,
+            TaprioGateCommand::Other(d) => d,
+        }
+    }
+}
+
+/// Entry of the gate control list of the `taprio` qdisc.
+/// Equivalent to `sched-entry CMD GATEMASK INTERVAL`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TaprioSchedEntry {
+    pub command: TaprioGateCommand,
+    /// Traffic classes whose gate is open.
+    pub gate_mask: u32,
+    /// Duration of the entry, with nanosecond resolution.
+    pub interval: Duration,
+}
+
+impl TaprioSchedEntry {
+    /// `S GATEMASK INTERVAL` entry.
+    pub fn new(gate_mask: u32, interval: Duration) -> Self {
+        Self {
+            command: TaprioGateCommand::SetGates,
+            gate_mask,
+            interval,
+        }
+    }
+
+    pub fn command(mut self, command: TaprioGateCommand) -> Self {
+        self.command = command;
+        self
+    }
+}
+
+/// Frame preemption status of a traffic class of the `taprio` qdisc.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TaprioFramePreemption {
+    /// `E`
+    Express,
+    /// `P`
+    Preemptible,
+    Other(u32),
+}
+
+impl From<u32> for TaprioFramePreemption {
+    fn from(d: u32) -> Self {
+        match d {
+            1 => Self::Express,
+            2 => Self::Preemptible,
+            _ => Self::Other(d),
+        }
+    }
+}
+
+impl From<TaprioFramePreemption> for u32 {
+    fn from(v: TaprioFramePreemption) -> u32 {
+        match v {
+            TaprioFramePreemption::Express => 1,
+            TaprioFramePreemption::Preemptible => 2,
+            TaprioFramePreemption::Other(d) => d,
+        }
+    }
+}
+
+/// Options of the `taprio` (Time Aware Priority Shaper) qdisc.
+/// Equivalent to `tc qdisc add ... taprio`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct QDiscTaprio {
+    /// Number of traffic classes.
+    pub num_tc: u8,
+    /// Traffic class of each of the 16 packet priorities.
+    pub priomap: [u8; 16],
+    /// `(count, offset)` range of transmit queues of each traffic class.
+    pub queues: Vec<(u16, u16)>,
+    /// Start of the schedule, in nanoseconds of `clockid`.
+    pub base_time: Option<i64>,
+    /// Gate control list, repeated every cycle.
+    pub entries: Vec<TaprioSchedEntry>,
+    /// Duration of a cycle. Defaults to the sum of the entry intervals.
+    pub cycle_time: Option<Duration>,
+    /// Time the last entry of a cycle can be extended by when the
+    /// schedule changes.
+    pub cycle_time_extension: Option<Duration>,
+    /// Clock of the schedule, like [Self::CLOCK_TAI]. Not used in full
+    /// offload mode.
+    pub clockid: Option<i32>,
+    /// Let the `etf` qdisc of each queue send packets at their transmit
+    /// time.
+    pub txtime_assist: bool,
+    /// Offload the schedule to hardware.
+    pub full_offload: bool,
+    /// Time the network stack needs to send a packet in txtime-assist mode.
+    pub txtime_delay: Option<Duration>,
+    /// Maximum frame size of each traffic class, 0 meaning no limit.
+    pub max_sdu: Vec<u32>,
+    /// Frame preemption status of each traffic class.
+    pub fp: Vec<TaprioFramePreemption>,
+}
+
+impl QDiscTaprio {
+    pub const KIND: &'static str = "taprio";
+
+    /// `CLOCK_TAI` clock id.
+    pub const CLOCK_TAI: i32 = 11;
+
+    /// Equivalent to `num_tc NUMBER map P0 P1 ... P15 queues count@offset
+    /// ...`.
+    pub fn new(num_tc: u8, priomap: [u8; 16], queues: Vec<(u16, u16)>) -> Self {
+        Self {
+            num_tc,
+            priomap,
+            queues,
+            ..Default::default()
+        }
+    }
+
+    /// Equivalent to `base-time NANOSECONDS`.
+    pub fn base_time(mut self, base_time: i64) -> Self {
+        self.base_time = Some(base_time);
+        self
+    }
+
+    /// Equivalent to `sched-entry CMD GATEMASK INTERVAL`, appended to the
+    /// gate control list.
+    pub fn sched_entry(mut self, entry: TaprioSchedEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Equivalent to `cycle-time TIME`.
+    pub fn cycle_time(mut self, cycle_time: Duration) -> Self {
+        self.cycle_time = Some(cycle_time);
+        self
+    }
+
+    /// Equivalent to `cycle-time-extension TIME`.
+    pub fn cycle_time_extension(mut self, extension: Duration) -> Self {
+        self.cycle_time_extension = Some(extension);
+        self
+    }
+
+    /// Equivalent to `clockid CLOCKID`.
+    pub fn clockid(mut self, clockid: i32) -> Self {
+        self.clockid = Some(clockid);
+        self
+    }
+
+    /// Equivalent to `flags 0x1`.
+    pub fn txtime_assist(mut self) -> Self {
+        self.txtime_assist = true;
+        self
+    }
+
+    /// Equivalent to `flags 0x2`.
+    pub fn full_offload(mut self) -> Self {
+        self.full_offload = true;
+        self
+    }
+
+    /// Equivalent to `txtime-delay TIME`.
+    pub fn txtime_delay(mut self, delay: Duration) -> Self {
+        self.txtime_delay = Some(delay);
+        self
+    }
+
+    /// Equivalent to `max-sdu SDU1 SDU2 ...`.
+    pub fn max_sdu(mut self, max_sdu: Vec<u32>) -> Self {
+        self.max_sdu = max_sdu;
+        self
+    }
+
+    /// Equivalent to `fp FP1 FP2 ...`.
+    pub fn fp(mut self, fp: Vec<TaprioFramePreemption>) -> Self {
+        self.fp = fp;
+        self
+    }
+
+    fn flags(&self) -> u32 {
+        let mut flags = 0;
+        if self.txtime_assist {
+            flags |= TCA_TAPRIO_ATTR_FLAG_TXTIME_ASSIST;
+        }
+        if self.full_offload {
+            flags |= TCA_TAPRIO_ATTR_FLAG_FULL_OFFLOAD;
+        }
+        flags
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mut nlas = Vec::new();
+        if let Some(clockid) = self.clockid {
+            nlas.push(nla_u32(TCA_TAPRIO_ATTR_SCHED_CLOCKID, clockid as u32));
+        }
+        if self.flags() != 0 {
+            nlas.push(nla_u32(TCA_TAPRIO_ATTR_FLAGS, self.flags()));
+        }
+        nlas.push(DefaultNla::new(
+            TCA_TAPRIO_ATTR_PRIOMAP,
+            emit_mqprio_qopt(self.num_tc, &self.priomap, &self.queues, false),
+        ));
+        if let Some(delay) = self.txtime_delay {
+            nlas.push(nla_u32(
+                TCA_TAPRIO_ATTR_TXTIME_DELAY,
+                time_to_ns(delay) as u32,
+            ));
+        }
+        if let Some(base_time) = self.base_time {
+            nlas.push(nla_u64(
+                TCA_TAPRIO_ATTR_SCHED_BASE_TIME,
+                base_time as u64,
+            ));
+        }
+        if let Some(cycle_time) = self.cycle_time {
+            nlas.push(nla_u64(
+                TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME,
+                time_to_ns(cycle_time),
+            ));
+        }
+        if let Some(extension) = self.cycle_time_extension {
+            nlas.push(nla_u64(
+                TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME_EXTENSION,
+                time_to_ns(extension),
+            ));
+        }
+        let tcs = self.max_sdu.len().max(self.fp.len());
+        for index in 0..tcs {
+            let mut entry =
+                vec![nla_u32(TCA_TAPRIO_TC_ENTRY_INDEX, index as u32)];
+            if let Some(max_sdu) = self.max_sdu.get(index) {
+                entry.push(nla_u32(TCA_TAPRIO_TC_ENTRY_MAX_SDU, *max_sdu));
+            }
+            if let Some(fp) = self.fp.get(index) {
+                entry.push(nla_u32(TCA_TAPRIO_TC_ENTRY_FP, (*fp).into()));
+            }
+            nlas.push(nla_nested(TCA_TAPRIO_ATTR_TC_ENTRY, &entry));
+        }
+        if !self.entries.is_empty() {
+            let entries: Vec<_> = self
+                .entries
+                .iter()
+                .map(|entry| {
+                    // iproute2 does not flag the entries as nested.
+                    DefaultNla::new(
+                        TCA_TAPRIO_SCHED_ENTRY,
+                        emit_nlas(&[
+                            nla_u8(
+                                TCA_TAPRIO_SCHED_ENTRY_CMD,
+                                entry.command.into(),
+                            ),
+                            nla_u32(
+                                TCA_TAPRIO_SCHED_ENTRY_GATE_MASK,
+                                entry.gate_mask,
+                            ),
+                            nla_u32(
+                                TCA_TAPRIO_SCHED_ENTRY_INTERVAL,
+                                time_to_ns(entry.interval) as u32,
+                            ),
+                        ]),
+                    )
+                })
+                .collect();
+            nlas.push(nla_nested(TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST, &entries));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut taprio = Self::default();
+        let mut admin = None;
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_TAPRIO_ATTR_PRIOMAP => {
+                    let (num_tc, priomap, queues, _) =
+                        parse_mqprio_qopt(value)?;
+                    taprio.num_tc = num_tc;
+                    taprio.priomap = priomap;
+                    taprio.queues = queues;
+                }
+                TCA_TAPRIO_ATTR_SCHED_CLOCKID => {
+                    taprio.clockid = Some(parse_i32(value)?)
+                }
+                TCA_TAPRIO_ATTR_FLAGS => {
+                    let flags = parse_u32(value)?;
+                    taprio.txtime_assist =
+                        flags & TCA_TAPRIO_ATTR_FLAG_TXTIME_ASSIST != 0;
+                    taprio.full_offload =
+                        flags & TCA_TAPRIO_ATTR_FLAG_FULL_OFFLOAD != 0;
+                }
+                TCA_TAPRIO_ATTR_TXTIME_DELAY => {
+                    taprio.txtime_delay =
+                        Some(Duration::from_nanos(parse_u32(value)?.into()))
+                }
+                TCA_TAPRIO_ATTR_TC_ENTRY => {
+                    let mut max_sdu = None;
+                    let mut fp: Option<TaprioFramePreemption> = None;
+                    for (kind, value) in parse_nlas(value)? {
+                        match kind {
+                            TCA_TAPRIO_TC_ENTRY_MAX_SDU => {
+                                max_sdu = Some(parse_u32(value)?)
+                            }
+                            TCA_TAPRIO_TC_ENTRY_FP => {
+                                fp = Some(parse_u32(value)?.into())
+                            }
+                            _ => (),
+                        }
+                    }
+                    taprio.max_sdu.extend(max_sdu);
+                    taprio.fp.extend(fp);
+                }
+                TCA_TAPRIO_ATTR_ADMIN_SCHED => admin = Some(value),
+                _ => (),
+            }
+        }
+        // The kernel reports the operational schedule at the top level and
+        // the pending one in TCA_TAPRIO_ATTR_ADMIN_SCHED.
+        parse_schedule(&mut taprio, payload)?;
+        if taprio.entries.is_empty() {
+            if let Some(admin) = admin {
+                parse_schedule(&mut taprio, admin)?;
+            }
+        }
+        Ok(taprio)
+    }
+}
+
+fn parse_schedule(
+    taprio: &mut QDiscTaprio,
+    payload: &[u8],
+) -> Result<(), DecodeError> {
+    for (kind, value) in parse_nlas(payload)? {
+        match kind {
+            TCA_TAPRIO_ATTR_SCHED_BASE_TIME => {
+                taprio.base_time = Some(parse_i64(value)?)
+            }
+            TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME => {
+                taprio.cycle_time = Some(ns_to_time(parse_i64(value)?))
+            }
+            TCA_TAPRIO_ATTR_SCHED_CYCLE_TIME_EXTENSION => {
+                taprio.cycle_time_extension =
+                    Some(ns_to_time(parse_i64(value)?))
+            }
+            TCA_TAPRIO_ATTR_SCHED_ENTRY_LIST => {
+                for (kind, value) in parse_nlas(value)? {
+                    if kind == TCA_TAPRIO_SCHED_ENTRY {
+                        taprio.entries.push(parse_sched_entry(value)?);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+fn parse_sched_entry(payload: &[u8]) -> Result<TaprioSchedEntry, DecodeError> {
+    let mut entry = TaprioSchedEntry::new(0, Duration::ZERO);
+    for (kind, value) in parse_nlas(payload)? {
+        match kind {
+            TCA_TAPRIO_SCHED_ENTRY_CMD => {
+                entry.command = parse_u8(value)?.into()
+            }
+            TCA_TAPRIO_SCHED_ENTRY_GATE_MASK => {
+                entry.gate_mask = parse_u32(value)?
+            }
+            TCA_TAPRIO_SCHED_ENTRY_INTERVAL => {
+                entry.interval = Duration::from_nanos(parse_u32(value)?.into())
+            }
+            _ => (),
+        }
+    }
+    Ok(entry)
+}
+
+fn time_to_ns(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(i64::MAX as u64)
+}
+
+fn ns_to_time(ns: i64) -> Duration {
+    Duration::from_nanos(ns.max(0) as u64)
+}
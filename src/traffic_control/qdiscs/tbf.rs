@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use netlink_packet_core::{parse_u32, parse_u64, DecodeError, DefaultNla};
+use netlink_packet_route::tc::TcAttribute;
+
+use crate::traffic_control::{
+    nla::{nested_options, nla_u32, nla_u64, parse_nlas, read_u32},
+    ratespec::{
+        emit_ratespec, needs_rate64, parse_ratespec, ticks_to_size, xmit_ticks,
+        RATESPEC_LEN,
+    },
+};
+
+const TCA_TBF_PARMS: u16 = 1;
+const TCA_TBF_RATE64: u16 = 4;
+const TCA_TBF_PRATE64: u16 = 5;
+const TCA_TBF_BURST: u16 = 6;
+const TCA_TBF_PBURST: u16 = 7;
+
+const TC_TBF_QOPT_LEN: usize = 2 * RATESPEC_LEN + 12;
+
+const DEFAULT_MTU: u32 = 2047;
+
+/// Options of the `tbf` (Token Bucket Filter) qdisc.
+/// Equivalent to `tc qdisc add ... tbf`.
+///
+/// Rates are in bytes per second, sizes in bytes. Use [crate::TcRate]
+/// and [crate::TcSize] to convert them from the `tc` notation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct QDiscTbf {
+    pub rate: u64,
+    /// Size of the bucket.
+    pub burst: u32,
+    /// Bytes which can be queued waiting for tokens.
+    pub limit: u32,
+    /// Maximum time a packet can wait for tokens, used to compute `limit`
+    /// when set. Never reported by the kernel.
+    pub latency: Option<Duration>,
+    /// Maximum depletion rate of the bucket, 0 when unlimited.
+    pub peakrate: u64,
+    /// Size of the peakrate bucket.
+    pub mtu: u32,
+}
+
+impl QDiscTbf {
+    pub const KIND: &'static str = "tbf";
+
+    /// Equivalent to `rate RATE burst BYTES`. Either [Self::limit] or
+    /// [Self::latency] has to be set too.
+    pub fn new(rate: u64, burst: u32) -> Self {
+        Self {
+            rate,
+            burst,
+            ..Default::default()
+        }
+    }
+
+    /// Equivalent to `limit BYTES`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Equivalent to `latency TIME`.
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Equivalent to `peakrate RATE`.
+    pub fn peakrate(mut self, peakrate: u64) -> Self {
+        self.peakrate = peakrate;
+        self
+    }
+
+    /// Equivalent to `mtu BYTES`. Default: 2047 bytes when `peakrate` is
+    /// set.
+    pub fn mtu(mut self, mtu: u32) -> Self {
+        self.mtu = mtu;
+        self
+    }
+
+    pub(crate) fn options(&self) -> TcAttribute {
+        let mtu = if self.peakrate != 0 && self.mtu == 0 {
+            DEFAULT_MTU
+        } else {
+            self.mtu
+        };
+        let limit = match self.latency {
+            Some(latency) => {
+                let limit =
+                    bytes_in(self.rate, latency) + u64::from(self.burst);
+                let limit = if self.peakrate != 0 {
+                    limit.min(bytes_in(self.peakrate, latency) + u64::from(mtu))
+                } else {
+                    limit
+                };
+                u32::try_from(limit).unwrap_or(u32::MAX)
+            }
+            None => self.limit,
+        };
+
+        let mut qopt = vec![0u8; TC_TBF_QOPT_LEN];
+        emit_ratespec(&mut qopt[0..RATESPEC_LEN], self.rate);
+        if self.peakrate != 0 {
+            emit_ratespec(
+                &mut qopt[RATESPEC_LEN..2 * RATESPEC_LEN],
+                self.peakrate,
+            );
+        }
+        let offset = 2 * RATESPEC_LEN;
+        qopt[offset..offset + 4].copy_from_slice(&limit.to_ne_bytes());
+        qopt[offset + 4..offset + 8]
+            .copy_from_slice(&xmit_ticks(self.rate, self.burst).to_ne_bytes());
+        qopt[offset + 8..offset + 12]
+            .copy_from_slice(&xmit_ticks(self.peakrate, mtu).to_ne_bytes());
+
+        let mut nlas = vec![
+            DefaultNla::new(TCA_TBF_PARMS, qopt),
+            nla_u32(TCA_TBF_BURST, self.burst),
+        ];
+        if needs_rate64(self.rate) {
+            nlas.push(nla_u64(TCA_TBF_RATE64, self.rate));
+        }
+        if self.peakrate != 0 {
+            if needs_rate64(self.peakrate) {
+                nlas.push(nla_u64(TCA_TBF_PRATE64, self.peakrate));
+            }
+            nlas.push(nla_u32(TCA_TBF_PBURST, mtu));
+        }
+        nested_options(&nlas)
+    }
+
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let mut tbf = Self::default();
+        let mut buffer = 0;
+        let mut mtu = 0;
+        let mut burst = None;
+        let mut pburst = None;
+        for (kind, value) in parse_nlas(payload)? {
+            match kind {
+                TCA_TBF_PARMS => {
+                    tbf.rate = parse_ratespec(value)?;
+                    tbf.peakrate = parse_ratespec(
+                        value.get(RATESPEC_LEN..).unwrap_or_default(),
+                    )?;
+                    let offset = 2 * RATESPEC_LEN;
+                    tbf.limit = read_u32(value, offset)?;
+                    buffer = read_u32(value, offset + 4)?;
+                    mtu = read_u32(value, offset + 8)?;
+                }
+                TCA_TBF_RATE64 => tbf.rate = parse_u64(value)?,
+                TCA_TBF_PRATE64 => tbf.peakrate = parse_u64(value)?,
+                TCA_TBF_BURST => burst = Some(parse_u32(value)?),
+                TCA_TBF_PBURST => pburst = Some(parse_u32(value)?),
+                _ => (),
+            }
+        }
+        tbf.burst = burst.unwrap_or_else(|| ticks_to_size(tbf.rate, buffer));
+        tbf.mtu = pburst.unwrap_or_else(|| ticks_to_size(tbf.peakrate, mtu));
+        Ok(tbf)
+    }
+}
+
+// Bytes sent at `rate` bytes per second during `duration`.
+fn bytes_in(rate: u64, duration: Duration) -> u64 {
+    u64::try_from(u128::from(rate) * duration.as_micros() / 1_000_000)
+        .unwrap_or(u64::MAX)
+}
@@ -0,0 +1,650 @@
+// SPDX-License-Identifier: MIT
+
+// Expected payloads are the TCA_OPTIONS sent by iproute2 for the equivalent
+// `tc` command.
+
+use std::time::Duration;
+
+use netlink_packet_route::tc::{TcAttribute, TcMessage};
+
+use super::*;
+use netlink_packet_core::DefaultNla;
+
+use crate::traffic_control::nla::{self, options_payload};
+
+fn payload(kind: &str, options: TcAttribute) -> Vec<u8> {
+    let mut message = TcMessage::default();
+    message.attributes.push(TcAttribute::Kind(kind.to_string()));
+    message.attributes.push(options);
+    options_payload(&message).unwrap()
+}
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_htb_qdisc() {
+    // tc qdisc add dev eth0 root handle 1: htb default 10
+    let expected = "18000200030000000a000000100000000000000000000000";
+    let htb = QDiscHtb::new().default_class(0x10);
+    assert_eq!(payload(QDiscHtb::KIND, htb.options()), hex(expected));
+    assert_eq!(QDiscHtb::parse(&hex(expected)).unwrap(), htb);
+
+    // tc qdisc add dev eth0 root handle 1: htb r2q 5 direct_qlen 100 offload
+    let expected = "1800020003000000050000000000000000000000000000000800\
+                    05006400000004000900";
+    let htb = QDiscHtb::new().r2q(5).direct_qlen(100).offload();
+    assert_eq!(payload(QDiscHtb::KIND, htb.options()), hex(expected));
+    assert_eq!(QDiscHtb::parse(&hex(expected)).unwrap(), htb);
+}
+
+#[test]
+fn test_htb_class() {
+    // tc class add dev eth0 parent 1: classid 1:10 htb rate 1mbit
+    // (without the rate tables)
+    let sent = "3000010003010000ffff000048e8010003010000ffff000048e80100\
+                400d0300400d0300000000000000000000000000";
+    let class = TrafficClassHtb::new(125_000);
+    let decoded = TrafficClassHtb::parse(&hex(sent)).unwrap();
+    assert_eq!(
+        decoded,
+        TrafficClassHtb {
+            ceil: 125_000,
+            burst: 1600,
+            cburst: 1600,
+            ..class
+        }
+    );
+    assert_eq!(
+        TrafficClassHtb::parse(&payload(
+            TrafficClassHtb::KIND,
+            class.options()
+        ))
+        .unwrap(),
+        decoded
+    );
+
+    // Rates which do not fit in the 32 bits ratespec
+    let class = TrafficClassHtb::new(10_000_000_000)
+        .ceil(20_000_000_000)
+        .burst(64000)
+        .cburst(128000)
+        .prio(2)
+        .quantum(1500);
+    assert_eq!(
+        TrafficClassHtb::parse(&payload(
+            TrafficClassHtb::KIND,
+            class.options()
+        ))
+        .unwrap(),
+        class
+    );
+}
+
+#[test]
+fn test_prio_qdisc() {
+    // tc qdisc add dev eth0 root handle 1: prio bands 4 \
+    //     priomap 1 2 3 0 1 2 3 0 1 2 3 0 1 2 3 0
+    let expected = "0400000001020300010203000102030001020300";
+    let prio = QDiscPrio::new()
+        .bands(4)
+        .priomap([1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3, 0]);
+    assert_eq!(payload(QDiscPrio::KIND, prio.options()), hex(expected));
+    assert_eq!(QDiscPrio::parse(&hex(expected)).unwrap(), prio);
+}
+
+#[test]
+fn test_ets_qdisc() {
+    // tc qdisc add dev eth0 root handle 1: ets bands 4 strict 1 \
+    //     quanta 1000 2000 3000 priomap 0 1 2 3
+    let expected = "050001000400000005000200010000001c00038008000400e8030000\
+                    08000400d007000008000400b80b0000240005800500060000000000\
+                    050006000100000005000600020000000500060003000000";
+    let ets = QDiscEts::new()
+        .bands(4)
+        .strict(1)
+        .quanta(vec![1000, 2000, 3000])
+        .priomap(vec![0, 1, 2, 3]);
+    assert_eq!(payload(QDiscEts::KIND, ets.options()), hex(expected));
+    assert_eq!(QDiscEts::parse(&hex(expected)).unwrap(), ets);
+
+    // tc class change dev eth0 classid 1:2 ets quantum 1500
+    let expected = "08000400dc050000";
+    let class = TrafficClassEts::new().quantum(1500);
+    assert_eq!(
+        payload(TrafficClassEts::KIND, class.options()),
+        hex(expected)
+    );
+}
+
+#[test]
+fn test_drr_class() {
+    // tc class add dev eth0 parent 1: classid 1:10 drr quantum 1500
+    let expected = "08000100dc050000";
+    let class = TrafficClassDrr::new().quantum(1500);
+    assert_eq!(
+        payload(TrafficClassDrr::KIND, class.options()),
+        hex(expected)
+    );
+    assert_eq!(TrafficClassDrr::parse(&hex(expected)).unwrap(), class);
+}
+
+#[test]
+fn test_hfsc() {
+    // tc qdisc add dev eth0 root handle 1: hfsc default 11
+    let hfsc = QDiscHfsc::new().default_class(0x11);
+    assert_eq!(payload(QDiscHfsc::KIND, hfsc.options()), hex("1100"));
+
+    // tc class add dev eth0 parent 1: classid 1:10 hfsc \
+    //     sc rate 1mbit ul rate 2mbit
+    let expected = "10000100000000000000000048e80100100002000000000000000000\
+                    48e8010010000300000000000000000090d00300";
+    let class = TrafficClassHfsc::new()
+        .service_curve(HfscServiceCurve::rate(125_000))
+        .upper_limit(HfscServiceCurve::rate(250_000));
+    assert_eq!(
+        payload(TrafficClassHfsc::KIND, class.options()),
+        hex(expected)
+    );
+    assert_eq!(TrafficClassHfsc::parse(&hex(expected)).unwrap(), class);
+}
+
+#[test]
+fn test_tbf_qdisc() {
+    // tc qdisc add dev eth0 root tbf rate 1mbit burst 10kb latency 50ms
+    // (without the rate table)
+    let expected = "2800010000010000ffff000048e80100000000000000000000000000\
+                    6a400000008813000000000008000600\
+                    00280000";
+    let tbf = QDiscTbf::new(125_000, 10240).latency(Duration::from_millis(50));
+    assert_eq!(payload(QDiscTbf::KIND, tbf.options()), hex(expected));
+    assert_eq!(
+        QDiscTbf::parse(&hex(expected)).unwrap(),
+        QDiscTbf {
+            limit: 16490,
+            latency: None,
+            ..tbf
+        }
+    );
+
+    // tc qdisc add dev eth0 root tbf rate 1mbit burst 10kb limit 20000 \
+    //     peakrate 2mbit mtu 1600
+    let expected = "2800010000010000ffff000048e8010000010000ffff000090d00300\
+                    204e000000881300a086010008000600\
+                    002800000800070040060000";
+    let tbf = QDiscTbf::new(125_000, 10240)
+        .limit(20000)
+        .peakrate(250_000)
+        .mtu(1600);
+    assert_eq!(payload(QDiscTbf::KIND, tbf.options()), hex(expected));
+    assert_eq!(QDiscTbf::parse(&hex(expected)).unwrap(), tbf);
+}
+
+#[test]
+fn test_fq_codel_qdisc() {
+    // tc qdisc add dev eth0 root fq_codel limit 2000 target 10ms \
+    //     interval 200ms ecn
+    let expected = "08000200d007000008000300400d0300080001001027000008000400\
+                    01000000";
+    let fq_codel = QDiscFqCodel::new()
+        .limit(2000)
+        .target(Duration::from_millis(10))
+        .interval(Duration::from_millis(200))
+        .ecn(true);
+    assert_eq!(
+        payload(QDiscFqCodel::KIND, fq_codel.options()),
+        hex(expected)
+    );
+    assert_eq!(QDiscFqCodel::parse(&hex(expected)).unwrap(), fq_codel);
+}
+
+#[test]
+fn test_fq_qdisc() {
+    // tc qdisc add dev eth0 root fq limit 5000 flow_limit 50 maxrate 10mbit \
+    //     nopacing horizon 5s horizon_drop
+    let expected = "080001008813000008000200320000000800050000000000\
+                    08000700d012130008000e00404b4c0005000f0001000000";
+    let fq = QDiscFq::new()
+        .limit(5000)
+        .flow_limit(50)
+        .maxrate(1_250_000)
+        .pacing(false)
+        .horizon(Duration::from_secs(5))
+        .horizon_drop(true);
+    assert_eq!(payload(QDiscFq::KIND, fq.options()), hex(expected));
+    assert_eq!(QDiscFq::parse(&hex(expected)).unwrap(), fq);
+}
+
+#[test]
+fn test_cake_qdisc() {
+    // tc qdisc add dev eth0 root cake bandwidth 100mbit diffserv4 \
+    //     dual-srchost nat ack-filter rtt 50ms overhead 18 mpu 64
+    let expected = "0c00020020bcbe0000000000080003000100000008000500050000\
+                    00080006001200000008000e00400000000800070050c300000800\
+                    0800c4090000080009000000000008000b00010000000800100001\
+                    000000";
+    let cake = QDiscCake::new()
+        .bandwidth(12_500_000)
+        .diffserv(CakeDiffserv::Diffserv4)
+        .flow_mode(CakeFlowMode::DualSrcHost)
+        .nat(true)
+        .ack_filter(CakeAckFilter::Enabled)
+        .rtt(Duration::from_millis(50))
+        .overhead(18)
+        .mpu(64);
+    assert_eq!(payload(QDiscCake::KIND, cake.options()), hex(expected));
+    assert_eq!(
+        QDiscCake::parse(&hex(expected)).unwrap(),
+        QDiscCake {
+            target: Some(Duration::from_micros(2500)),
+            ..cake
+        }
+    );
+
+    // tc qdisc add dev eth0 root cake autorate-ingress atm raw \
+    //     memlimit 1000000 fwmark 0xff wash ingress split-gso
+    let expected = "0800040000000000080006000000000008000c0000000000080009\
+                    000100000008000a0040420f0008001200ff00000008000d000100\
+                    0000080011000100000008000f0001000000";
+    let cake = QDiscCake::new()
+        .autorate()
+        .atm(CakeAtm::Atm)
+        .raw()
+        .memory_limit(1_000_000)
+        .fwmark(0xff)
+        .wash(true)
+        .ingress(true)
+        .split_gso(true);
+    assert_eq!(payload(QDiscCake::KIND, cake.options()), hex(expected));
+}
+
+#[test]
+fn test_sfq_qdisc() {
+    // tc qdisc add dev eth0 root sfq perturb 10 limit 100 depth 20 headdrop
+    let expected = format!(
+        "000000000a00000064000000000000000000000014000000010000{}",
+        "0".repeat(90)
+    );
+    let sfq = QDiscSfq::new()
+        .perturb(Duration::from_secs(10))
+        .limit(100)
+        .depth(20)
+        .headdrop();
+    assert_eq!(
+        payload(QDiscSfq::KIND, sfq.options().unwrap()),
+        hex(&expected)
+    );
+    assert_eq!(QDiscSfq::parse(&hex(&expected)).unwrap(), sfq);
+    assert_eq!(QDiscSfq::new().options(), None);
+}
+
+#[test]
+fn test_fifo_qdisc() {
+    // tc qdisc add dev eth0 root pfifo limit 100
+    let pfifo = QDiscPfifo::new().limit(100);
+    assert_eq!(
+        payload(QDiscPfifo::KIND, pfifo.options().unwrap()),
+        hex("64000000")
+    );
+    assert_eq!(QDiscPfifo::parse(&hex("64000000")).unwrap(), pfifo);
+
+    // tc qdisc add dev eth0 root bfifo
+    assert_eq!(QDiscBfifo::new().options(), None);
+}
+
+#[test]
+fn test_netem_qdisc() {
+    // tc qdisc add dev eth0 root netem delay 100ms 10ms 25% loss 1% 30% \
+    //     duplicate 2% reorder 5% 50% gap 3 corrupt 0.5%
+    let expected = "84d71700e8030000295c8f020300000052b81e055a62020010000100\
+                    00000040cccccc4c000000000c000300cdcccc0c000000800c000400\
+                    14ae470100000000";
+    let netem = QDiscNetem::new()
+        .delay(Duration::from_millis(100), Duration::from_millis(10), 25.0)
+        .loss(NetemLoss::Random {
+            percent: 1.0,
+            correlation: 30.0,
+        })
+        .duplicate(2.0, 0.0)
+        .reorder(5.0, 50.0)
+        .gap(3)
+        .corrupt(0.5, 0.0);
+    assert_eq!(payload(QDiscNetem::KIND, netem.options()), hex(expected));
+    let decoded = QDiscNetem::parse(&hex(expected)).unwrap();
+    assert_eq!(decoded.delay, netem.delay);
+    assert_eq!(decoded.jitter, netem.jitter);
+    assert_eq!(decoded.gap, 3);
+    assert_eq!(payload(QDiscNetem::KIND, decoded.options()), hex(expected));
+
+    // tc qdisc add dev eth0 root netem rate 1mbit 20 100 -5
+    let expected = "00000000e80300000000000000000000000000000000000014000600\
+                    48e801001400000064000000fbffffff";
+    let netem = QDiscNetem::new().rate(NetemRate {
+        rate: 125_000,
+        packet_overhead: 20,
+        cell_size: 100,
+        cell_overhead: -5,
+    });
+    assert_eq!(payload(QDiscNetem::KIND, netem.options()), hex(expected));
+    assert_eq!(QDiscNetem::parse(&hex(expected)).unwrap(), netem);
+
+    // tc qdisc add dev eth0 root netem rate 100gbit
+    let expected = "00000000e803000000000000000000000000000000000000\
+                    0c00080000dd0ee90200000014000600ffffffff0000000000000000\
+                    00000000";
+    let netem = QDiscNetem::new().rate(NetemRate::new(12_500_000_000));
+    assert_eq!(payload(QDiscNetem::KIND, netem.options()), hex(expected));
+    assert_eq!(QDiscNetem::parse(&hex(expected)).unwrap(), netem);
+
+    // tc qdisc add dev eth0 root netem slot 10ms 20ms packets 10 bytes 2000
+    let expected = "00000000e8030000000000000000000000000000000000002c000c00\
+                    8096980000000000002d3101000000000a000000d0070000000000\
+                    00000000000000000000000000";
+    let netem = QDiscNetem::new().slot(
+        NetemSlot::new(Duration::from_millis(10), Duration::from_millis(20))
+            .packets(10)
+            .bytes(2000),
+    );
+    assert_eq!(payload(QDiscNetem::KIND, netem.options()), hex(expected));
+    assert_eq!(QDiscNetem::parse(&hex(expected)).unwrap(), netem);
+
+    // tc qdisc add dev eth0 root netem loss 1% ecn
+    let expected = "00000000e8030000295c8f020000000000000000000000000800070001\
+                    000000";
+    let netem = QDiscNetem::new().loss(NetemLoss::random(1.0)).ecn();
+    assert_eq!(payload(QDiscNetem::KIND, netem.options()), hex(expected));
+
+    // tc qdisc add dev eth0 root netem loss state 1% 2% 3% 4% 5%
+    let expected = "00000000e8030000000000000000000000000000000000001c000580\
+                    18000100295c8f0252b81e057b14ae07cdcccc0ca4703d0a";
+    let netem = QDiscNetem::new().loss(NetemLoss::State {
+        p13: 1.0,
+        p31: 2.0,
+        p32: 3.0,
+        p23: 4.0,
+        p14: 5.0,
+    });
+    assert_eq!(payload(QDiscNetem::KIND, netem.options()), hex(expected));
+
+    // tc qdisc add dev eth0 root netem loss gemodel 1% 10% 70% 0.1%
+    let expected = "00000000e8030000000000000000000000000000000000001800058014\
+                    000200295c8f029a999919cdcccc4c37894100";
+    let netem = QDiscNetem::new().loss(NetemLoss::GilbertElliott {
+        p: 1.0,
+        r: 10.0,
+        one_minus_h: 70.0,
+        one_minus_k: 0.1,
+    });
+    assert_eq!(payload(QDiscNetem::KIND, netem.options()), hex(expected));
+    let decoded = QDiscNetem::parse(&hex(expected)).unwrap();
+    assert_eq!(payload(QDiscNetem::KIND, decoded.options()), hex(expected));
+}
+
+#[test]
+fn test_mqprio_qdisc() {
+    // tc qdisc add dev eth0 root mqprio num_tc 3 \
+    //     map 2 2 1 0 2 2 2 2 2 2 2 2 2 2 2 2 queues 1@0 1@1 2@2 hw 0
+    let expected = format!(
+        "030202010002020202020202020202020200{}{}{}{}0000",
+        "010001000200",
+        "0000".repeat(13),
+        "000001000200",
+        "0000".repeat(13),
+    );
+    let mqprio = QDiscMqprio::new()
+        .num_tc(3)
+        .priomap([2, 2, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2])
+        .queues(vec![(1, 0), (1, 1), (2, 2)])
+        .hw(false);
+    assert_eq!(payload(QDiscMqprio::KIND, mqprio.options()), hex(&expected));
+    assert_eq!(QDiscMqprio::parse(&hex(&expected)).unwrap(), mqprio);
+
+    // tc qdisc add dev eth0 root mqprio num_tc 2 map 0 1 queues 1@0 1@1 \
+    //     hw 1 mode channel shaper bw_rlimit min_rate 1Mbit 2Mbit \
+    //     max_rate 10Mbit 20Mbit
+    let expected = format!(
+        "02000100000000000000000000000000000101000100{}0000{}0000\
+         06000100010000000600020001000000\
+         c40003800c00030048e80100000000000c00030090d0030000000000{}\
+         c40004800c000400d0121300000000000c000400a025260000000000{}",
+        "0000".repeat(14),
+        "0100".to_string() + &"0000".repeat(14),
+        "0c0003000000000000000000".repeat(14),
+        "0c0004000000000000000000".repeat(14),
+    );
+    let mqprio = QDiscMqprio::new()
+        .num_tc(2)
+        .priomap([0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        .queues(vec![(1, 0), (1, 1)])
+        .mode(MqprioMode::Channel)
+        .shaper(MqprioShaper::BwRlimit)
+        .min_rate(vec![125_000, 250_000])
+        .max_rate(vec![1_250_000, 2_500_000]);
+    assert_eq!(payload(QDiscMqprio::KIND, mqprio.options()), hex(&expected));
+    assert_eq!(QDiscMqprio::parse(&hex(&expected)).unwrap(), mqprio);
+}
+
+#[test]
+fn test_taprio_qdisc() {
+    // tc qdisc add dev eth0 parent root handle 100 taprio num_tc 3 \
+    //     map 2 2 1 0 2 2 2 2 2 2 2 2 2 2 2 2 queues 1@0 1@1 2@2 \
+    //     base-time 1528743495910289987 sched-entry S 01 300000 \
+    //     sched-entry S 02 300000 sched-entry S 04 400000 flags 0x1 \
+    //     txtime-delay 200000 clockid CLOCK_TAI
+    let expected = format!(
+        "080005000b00000008000a0001000000\
+         56000100030202010002020202020202020202020200010001000200{}\
+         000001000200{}0000\
+         08000b00400d03000c00030043f26b221c303715\
+         580002801c0001000500020000000000080003000100000008000400e0930400\
+         1c0001000500020000000000080003000200000008000400e0930400\
+         1c0001000500020000000000080003000400000008000400801a0600",
+        "0000".repeat(13),
+        "0000".repeat(13),
+    );
+    let taprio = QDiscTaprio::new(
+        3,
+        [2, 2, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+        vec![(1, 0), (1, 1), (2, 2)],
+    )
+    .base_time(1528743495910289987)
+    .sched_entry(TaprioSchedEntry::new(0x1, Duration::from_micros(300)))
+    .sched_entry(TaprioSchedEntry::new(0x2, Duration::from_micros(300)))
+    .sched_entry(TaprioSchedEntry::new(0x4, Duration::from_micros(400)))
+    .txtime_assist()
+    .txtime_delay(Duration::from_micros(200))
+    .clockid(QDiscTaprio::CLOCK_TAI);
+    assert_eq!(payload(QDiscTaprio::KIND, taprio.options()), hex(&expected));
+    assert_eq!(QDiscTaprio::parse(&hex(&expected)).unwrap(), taprio);
+
+    // tc qdisc add dev eth0 parent root handle 100 taprio num_tc 2 \
+    //     map 0 1 queues 1@0 1@1 cycle-time 1000000 \
+    //     cycle-time-extension 1000 sched-entry S 3 500000 \
+    //     sched-entry S 1 500000 clockid CLOCK_TAI
+    let expected = format!(
+        "080005000b000000\
+         5600010002000100000000000000000000000000000001000100{}\
+         00000100{}0000\
+         0c00080040420f00000000000c000900e803000000000000\
+         3c0002801c000100050002000000000008000300030000000800040020a10700\
+         1c000100050002000000000008000300010000000800040020a10700",
+        "0000".repeat(14),
+        "0000".repeat(14),
+    );
+    let taprio = QDiscTaprio::new(
+        2,
+        [0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        vec![(1, 0), (1, 1)],
+    )
+    .cycle_time(Duration::from_millis(1))
+    .cycle_time_extension(Duration::from_micros(1))
+    .sched_entry(TaprioSchedEntry::new(0x3, Duration::from_micros(500)))
+    .sched_entry(TaprioSchedEntry::new(0x1, Duration::from_micros(500)))
+    .clockid(QDiscTaprio::CLOCK_TAI);
+    assert_eq!(payload(QDiscTaprio::KIND, taprio.options()), hex(&expected));
+    assert_eq!(QDiscTaprio::parse(&hex(&expected)).unwrap(), taprio);
+
+    // Per traffic class entries, with the kernel reporting the pending
+    // schedule in TCA_TAPRIO_ATTR_ADMIN_SCHED.
+    let taprio = QDiscTaprio::new(2, [0; 16], vec![(1, 0), (1, 1)])
+        .max_sdu(vec![0, 1500])
+        .fp(vec![
+            TaprioFramePreemption::Express,
+            TaprioFramePreemption::Preemptible,
+        ]);
+    let options = payload(QDiscTaprio::KIND, taprio.options());
+    let admin = nla::emit_nlas(&[nla::nla_nested(
+        7,
+        &[nla::nla_nested(
+            2,
+            &[DefaultNla::new(1, nla::emit_nlas(&[nla::nla_u32(4, 1000)]))],
+        )],
+    )]);
+    let decoded =
+        QDiscTaprio::parse(&[options.as_slice(), &admin].concat()).unwrap();
+    assert_eq!(
+        decoded,
+        taprio.sched_entry(TaprioSchedEntry::new(0, Duration::from_micros(1)))
+    );
+}
+
+#[test]
+fn test_stats() {
+    use netlink_packet_route::tc::{
+        TcStats, TcStats2, TcStatsBasic, TcStatsQueue, TcXstats,
+    };
+
+    use crate::{TcMessageExt, TrafficStats, TrafficXstats};
+
+    let mut basic = TcStatsBasic::default();
+    basic.bytes = 1_500_000;
+    basic.packets = 1000;
+    let mut queue = TcStatsQueue::default();
+    queue.qlen = 3;
+    queue.backlog = 4500;
+    queue.drops = 7;
+    queue.requeues = 2;
+    queue.overlimits = 11;
+    // TCA_STATS_RATE_EST64: 125000 bytes/s, 100 packets/s
+    let rate_est64 =
+        DefaultNla::new(5, hex("48e80100000000006400000000000000"));
+    // tc_htb_xstats: lends 10, borrows 20, giants 0, tokens -1, ctokens 5
+    let htb = hex("0a0000001400000000000000ffffffff05000000");
+
+    let mut message = TcMessage::default();
+    message
+        .attributes
+        .push(TcAttribute::Kind(TrafficClassHtb::KIND.to_string()));
+    message.attributes.push(TcAttribute::Stats2(vec![
+        TcStats2::Basic(basic),
+        TcStats2::Queue(queue),
+        TcStats2::Other(rate_est64),
+        TcStats2::App(TcXstats::Other(htb)),
+    ]));
+    let stats = TrafficStats {
+        bytes: 1_500_000,
+        packets: 1000,
+        qlen: 3,
+        backlog: 4500,
+        drops: 7,
+        requeues: 2,
+        overlimits: 11,
+        rate: 125_000,
+        rate_pps: 100,
+        ..Default::default()
+    };
+    assert_eq!(message.stats(), Some(stats));
+    let htb = HtbXstats {
+        lends: 10,
+        borrows: 20,
+        giants: 0,
+        tokens: -1,
+        ctokens: 5,
+    };
+    assert_eq!(message.xstats().unwrap(), Some(TrafficXstats::Htb(htb)));
+
+    // Kernels without TCA_STATS2 only report TCA_STATS.
+    let mut compat = TcStats::default();
+    compat.bytes = 1_500_000;
+    compat.packets = 1000;
+    compat.drops = 7;
+    compat.overlimits = 11;
+    compat.qlen = 3;
+    compat.backlog = 4500;
+    let mut message = TcMessage::default();
+    message.attributes.push(TcAttribute::Stats(compat));
+    assert_eq!(
+        message.stats(),
+        Some(TrafficStats {
+            requeues: 0,
+            rate: 0,
+            rate_pps: 0,
+            ..stats
+        })
+    );
+    assert_eq!(message.xstats().unwrap(), None);
+    assert_eq!(TcMessage::default().stats(), None);
+}
+
+#[test]
+fn test_fq_xstats() {
+    // struct tc_fq_qd_stats of a 4.19 kernel, without ce_mark and horizon
+    let mut payload = vec![0u8; 80];
+    payload[0] = 3; // gc_flows
+    payload[24] = 5; // throttled
+    payload[64] = 12; // flows
+    payload[68] = 10; // inactive_flows
+    payload[76..80].copy_from_slice(&1000u32.to_ne_bytes());
+    let mut xstats = FqXstats {
+        gc_flows: 3,
+        throttled: 5,
+        flows: 12,
+        inactive_flows: 10,
+        unthrottle_latency: Duration::from_micros(1),
+        ..Default::default()
+    };
+    assert_eq!(FqXstats::parse(&payload).unwrap(), xstats);
+
+    payload.resize(104, 0);
+    payload[80] = 9; // ce_mark
+    xstats.ce_mark = 9;
+    assert_eq!(FqXstats::parse(&payload).unwrap(), xstats);
+
+    assert!(FqXstats::parse(&payload[..64]).is_err());
+}
+
+#[test]
+fn test_cake_xstats() {
+    let tin = |sent: u32, delay_us: u32| {
+        nla::nla_nested(
+            1,
+            &[
+                nla::nla_u32(2, sent),
+                nla::nla_u64(3, u64::from(sent) * 1500),
+                nla::nla_u32(4, 1),
+                nla::nla_u32(19, delay_us),
+                nla::nla_u32(22, 2),
+            ],
+        )
+    };
+    let payload = nla::emit_nlas(&[
+        nla::nla_u64(2, 12_500_000),
+        nla::nla_u32(3, 4_000_000),
+        nla::nla_u32(4, 1024),
+        nla::nla_nested(10, &[tin(10, 100), tin(20, 200)]),
+    ]);
+    let xstats = CakeXstats::parse(&payload).unwrap();
+    assert_eq!(xstats.capacity_estimate, Some(12_500_000));
+    assert_eq!(xstats.memory_limit, Some(4_000_000));
+    assert_eq!(xstats.memory_used, Some(1024));
+    assert_eq!(xstats.tins.len(), 2);
+    assert_eq!(xstats.tins[1].sent_packets, 20);
+    assert_eq!(xstats.tins[1].sent_bytes, 30_000);
+    assert_eq!(xstats.tins[1].dropped_packets, 1);
+    assert_eq!(xstats.tins[1].avg_delay, Duration::from_micros(200));
+    assert_eq!(xstats.tins[1].bulk_flows, 2);
+}
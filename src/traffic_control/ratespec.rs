@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MIT
+
+// `struct tc_ratespec` and the psched time units used by the shaping qdiscs.
+
+use std::time::Duration;
+
+use netlink_packet_core::DecodeError;
+
+use super::nla::read_u32;
+
+pub(crate) const RATESPEC_LEN: usize = 12;
+
+const TC_LINKLAYER_ETHERNET: u8 = 1;
+
+// PSCHED_SHIFT: one psched tick is 64 nanoseconds.
+const PSCHED_TICK_NS: u128 = 64;
+const NSEC_PER_SEC: u128 = 1_000_000_000;
+
+/// Rates above `u32::MAX` bytes per second are carried by a separate 64 bits
+/// attribute, with the ratespec rate saturated.
+pub(crate) fn needs_rate64(rate: u64) -> bool {
+    rate >= u64::from(u32::MAX)
+}
+
+pub(crate) fn emit_ratespec(buf: &mut [u8], rate: u64) {
+    // No rate table is sent, so tell the kernel which link layer to
+    // account for instead of letting it guess from the table.
+    buf[0] = 0; // cell_log
+    buf[1] = TC_LINKLAYER_ETHERNET;
+    buf[2..4].copy_from_slice(&0u16.to_ne_bytes()); // overhead
+    buf[4..6].copy_from_slice(&(-1i16).to_ne_bytes()); // cell_align
+    buf[6..8].copy_from_slice(&0u16.to_ne_bytes()); // mpu
+    buf[8..12].copy_from_slice(
+        &u32::try_from(rate).unwrap_or(u32::MAX).to_ne_bytes(),
+    );
+}
+
+/// Return the 32 bits rate of a `tc_ratespec`.
+pub(crate) fn parse_ratespec(buf: &[u8]) -> Result<u64, DecodeError> {
+    read_u32(buf, 8).map(u64::from)
+}
+
+/// Time needed to send `size` bytes at `rate` bytes per second, in psched
+/// ticks.
+pub(crate) fn xmit_ticks(rate: u64, size: u32) -> u32 {
+    if rate == 0 {
+        return 0;
+    }
+    let ticks =
+        u128::from(size) * NSEC_PER_SEC / u128::from(rate) / PSCHED_TICK_NS;
+    u32::try_from(ticks).unwrap_or(u32::MAX)
+}
+
+/// Number of bytes sent in `ticks` psched ticks at `rate` bytes per second.
+pub(crate) fn ticks_to_size(rate: u64, ticks: u32) -> u32 {
+    let size =
+        u128::from(ticks) * PSCHED_TICK_NS * u128::from(rate) / NSEC_PER_SEC;
+    u32::try_from(size).unwrap_or(u32::MAX)
+}
+
+/// `duration` in psched ticks.
+pub(crate) fn time_to_ticks(duration: Duration) -> u32 {
+    u32::try_from(duration.as_nanos() / PSCHED_TICK_NS).unwrap_or(u32::MAX)
+}
+
+pub(crate) fn ticks_to_time(ticks: u32) -> Duration {
+    Duration::from_nanos(u64::from(ticks) * PSCHED_TICK_NS as u64)
+}
+
+/// `duration` in microseconds, the unit of most qdisc time parameters.
+pub(crate) fn time_to_us(duration: Duration) -> u32 {
+    u32::try_from(duration.as_micros()).unwrap_or(u32::MAX)
+}
+
+pub(crate) fn us_to_time(us: u32) -> Duration {
+    Duration::from_micros(u64::from(us))
+}
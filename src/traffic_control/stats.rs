@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{DecodeError, Emitable, Nla};
+use netlink_packet_route::tc::{
+    TcAttribute, TcFqCodelXstats, TcMessage, TcStats2, TcXstats,
+};
+
+use super::{
+    nla::{message_kind, nla_value, read_u32, read_u64},
+    CakeXstats, FqXstats, HtbXstats, QDiscCake, QDiscFq, TrafficClassHtb,
+};
+
+const TCA_STATS_RATE_EST: u16 = 2;
+const TCA_STATS_RATE_EST64: u16 = 5;
+const TCA_STATS_PKT64: u16 = 8;
+
+/// Generic statistics of a qdisc, class or action, see
+/// [crate::TcMessageExt::stats].
+///
+/// Equivalent to the `Sent ... backlog ...` lines of `tc -s`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TrafficStats {
+    /// Bytes sent.
+    pub bytes: u64,
+    /// Packets sent.
+    pub packets: u64,
+    /// Packets dropped.
+    pub drops: u32,
+    /// Number of times the qdisc was over its limit, e.g. throttled by a
+    /// shaper.
+    pub overlimits: u32,
+    /// Packets requeued, e.g. because the driver queue was full.
+    pub requeues: u32,
+    /// Bytes currently queued.
+    pub backlog: u32,
+    /// Packets currently queued.
+    pub qlen: u32,
+    /// Rate estimated by the kernel in bytes per second, 0 unless a rate
+    /// estimator is attached (`tc qdisc add ... est INTERVAL TIME`).
+    pub rate: u64,
+    /// Rate estimated by the kernel in packets per second.
+    pub rate_pps: u64,
+    /// Bytes handled by hardware, when offloaded.
+    pub hw_bytes: u64,
+    /// Packets handled by hardware, when offloaded.
+    pub hw_packets: u64,
+}
+
+impl TrafficStats {
+    /// Parse `TCA_STATS2`, falling back to the legacy `TCA_STATS` of
+    /// `message`.
+    pub(crate) fn from_message(message: &TcMessage) -> Option<Self> {
+        let stats2 = message.attributes.iter().find_map(|attr| match attr {
+            TcAttribute::Stats2(stats2) => Some(Self::from_stats2(stats2)),
+            _ => None,
+        });
+        stats2.or_else(|| {
+            message.attributes.iter().find_map(|attr| match attr {
+                TcAttribute::Stats(s) => Some(Self {
+                    bytes: s.bytes,
+                    packets: u64::from(s.packets),
+                    drops: s.drops,
+                    overlimits: s.overlimits,
+                    backlog: s.backlog,
+                    qlen: s.qlen,
+                    rate: u64::from(s.bps),
+                    rate_pps: u64::from(s.pps),
+                    ..Default::default()
+                }),
+                _ => None,
+            })
+        })
+    }
+
+    pub(crate) fn from_stats2(stats2: &[TcStats2]) -> Self {
+        let mut stats = Self::default();
+        let mut rate64 = false;
+        for attr in stats2 {
+            match attr {
+                TcStats2::Basic(basic) => {
+                    stats.bytes = basic.bytes;
+                    // TCA_STATS_PKT64 wins when the counter overflowed.
+                    stats.packets = stats.packets.max(basic.packets.into());
+                }
+                TcStats2::BasicHw(basic) => {
+                    stats.hw_bytes = basic.bytes;
+                    stats.hw_packets = basic.packets.into();
+                }
+                TcStats2::Queue(queue) => {
+                    stats.qlen = queue.qlen;
+                    stats.backlog = queue.backlog;
+                    stats.drops = queue.drops;
+                    stats.requeues = queue.requeues;
+                    stats.overlimits = queue.overlimits;
+                }
+                TcStats2::Other(nla) => {
+                    let value = nla_value(nla);
+                    match nla.kind() {
+                        TCA_STATS_PKT64 => {
+                            if let Ok(packets) = read_u64(&value, 0) {
+                                stats.packets = packets;
+                            }
+                        }
+                        TCA_STATS_RATE_EST64 => {
+                            if let (Ok(bps), Ok(pps)) =
+                                (read_u64(&value, 0), read_u64(&value, 8))
+                            {
+                                stats.rate = bps;
+                                stats.rate_pps = pps;
+                                rate64 = true;
+                            }
+                        }
+                        TCA_STATS_RATE_EST if !rate64 => {
+                            if let (Ok(bps), Ok(pps)) =
+                                (read_u32(&value, 0), read_u32(&value, 4))
+                            {
+                                stats.rate = bps.into();
+                                stats.rate_pps = pps.into();
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+        }
+        stats
+    }
+}
+
+/// Statistics specific to the kind of a qdisc or class, see
+/// [crate::TcMessageExt::xstats].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum TrafficXstats {
+    FqCodel(TcFqCodelXstats),
+    Fq(FqXstats),
+    Cake(CakeXstats),
+    Htb(HtbXstats),
+    /// Raw statistics of a kind without typed statistics.
+    Other(Vec<u8>),
+}
+
+impl TrafficXstats {
+    /// Parse `TCA_STATS_APP` of `TCA_STATS2`, falling back to the legacy
+    /// `TCA_XSTATS` of `message`.
+    pub(crate) fn from_message(
+        message: &TcMessage,
+    ) -> Result<Option<Self>, DecodeError> {
+        let app = message.attributes.iter().find_map(|attr| match attr {
+            TcAttribute::Stats2(stats2) => {
+                stats2.iter().find_map(|stats| match stats {
+                    TcStats2::App(xstats) => Some(xstats),
+                    _ => None,
+                })
+            }
+            _ => None,
+        });
+        let xstats = app.or_else(|| {
+            message.attributes.iter().find_map(|attr| match attr {
+                TcAttribute::Xstats(xstats) => Some(xstats),
+                _ => None,
+            })
+        });
+        let Some(xstats) = xstats else {
+            return Ok(None);
+        };
+        let payload = match xstats {
+            TcXstats::FqCodel(xstats) => {
+                return Ok(Some(Self::FqCodel(xstats.clone())))
+            }
+            TcXstats::Other(payload) => payload.clone(),
+            xstats => {
+                let mut payload = vec![0u8; xstats.buffer_len()];
+                xstats.emit(&mut payload);
+                payload
+            }
+        };
+        Self::parse(message_kind(message).unwrap_or_default(), payload)
+            .map(Some)
+    }
+
+    fn parse(kind: &str, payload: Vec<u8>) -> Result<Self, DecodeError> {
+        Ok(match kind {
+            QDiscFq::KIND => Self::Fq(FqXstats::parse(&payload)?),
+            QDiscCake::KIND => Self::Cake(CakeXstats::parse(&payload)?),
+            TrafficClassHtb::KIND => Self::Htb(HtbXstats::parse(&payload)?),
+            _ => Self::Other(payload),
+        })
+    }
+}
@@ -295,3 +295,154 @@ fn test_get_traffic_classes_filters_and_chains() {
         assert_eq!(chains[0].attributes[0], TcAttribute::Chain(0),);
     }
 }
+
+#[test]
+fn test_tc_tree_assemble() {
+    use super::{TrafficTree, TrafficTreeNode};
+
+    let message = |kind: &str, handle: u32, parent: u32| {
+        let mut message = TcMessage::with_index(2);
+        message.header.handle = handle.into();
+        message.header.parent = parent.into();
+        message.attributes.push(TcAttribute::Kind(kind.to_string()));
+        message
+    };
+    let node = |class: bool, message: TcMessage| TrafficTreeNode {
+        class,
+        message,
+        filters: Vec::new(),
+        chains: Vec::new(),
+        children: Vec::new(),
+    };
+    let mut filter = message("u32", 0x8000_0800, 0x1_0000);
+    // pref 1 protocol ip
+    filter.header.info = (1 << 16) | u32::from(0x0800u16.to_be());
+    filter.attributes.push(TcAttribute::Chain(0));
+    let mut htb = node(false, message("htb", 0x1_0000, u32::MAX));
+    htb.filters.push(filter);
+    htb.chains.push(0);
+
+    // The kernel dumps the qdiscs before the classes.
+    let tree = TrafficTree::assemble(
+        2,
+        vec![
+            htb,
+            node(false, message("fq_codel", 0x10_0000, 0x1_0010)),
+            node(false, message("clsact", 0xffff_0000, 0xffff_fff1)),
+            node(true, message("htb", 0x1_0001, u32::MAX)),
+            node(true, message("htb", 0x1_0010, 0x1_0001)),
+        ],
+    );
+    assert_eq!(tree.qdiscs.len(), 2);
+    assert_eq!(tree.qdiscs[1].message.header.handle, 0xffff_0000.into());
+    let class = &tree.qdiscs[0].children[0].children[0];
+    assert_eq!(class.message.header.handle, 0x1_0010.into());
+    assert_eq!(class.children[0].message.header.handle, 0x10_0000.into());
+    assert_eq!(
+        tree.to_string(),
+        "qdisc htb 1: root\n  chain 0\n  \
+         filter parent 1: protocol ip pref 1 u32 chain 0 \
+         handle 0x80000800\n  \
+         class htb 1:1 root\n    \
+         class htb 1:10 parent 1:1\n      \
+         qdisc fq_codel 10: parent 1:10\n\
+         qdisc clsact ffff: parent ffff:fff1\n"
+    );
+}
+
+#[test]
+fn test_tc_notation() {
+    use std::time::Duration;
+
+    use netlink_packet_route::tc::TcHandle;
+
+    use crate::{TcClassId, TcRate, TcSize, TcTime};
+
+    let classid = |s: &str| s.parse::<TcClassId>().map(TcHandle::from);
+    for (s, handle) in [
+        ("1:10", 0x1_0010),
+        ("ffff:", 0xffff_0000),
+        (":a", 0xa),
+        ("root", u32::MAX),
+        ("none", 0),
+    ] {
+        assert_eq!(classid(s).unwrap(), handle.into());
+        assert_eq!(TcClassId(handle.into()).to_string(), s);
+    }
+    assert_eq!(classid("ingress").unwrap(), TcHandle::INGRESS);
+    assert_eq!(classid("clsact").unwrap(), TcHandle::CLSACT);
+    assert_eq!(classid("1").unwrap(), 0x1_0000.into());
+    for s in ["", ":", "1:2:3", "10000:", "+1:", "1:-1", "eth0"] {
+        assert!(classid(s).is_err(), "{s}");
+    }
+
+    let rate = |s: &str| s.parse::<TcRate>().map(u64::from);
+    assert_eq!(rate("10mbit").unwrap(), 1_250_000);
+    assert_eq!(rate("1gibit").unwrap(), 134_217_728);
+    assert_eq!(rate("100kbps").unwrap(), 100_000);
+    assert_eq!(rate("1.5Mbit").unwrap(), 187_500);
+    assert_eq!(rate("8000").unwrap(), 1000);
+    assert!(rate("10mbits").is_err());
+    assert!(rate("mbit").is_err());
+    assert_eq!(TcRate(1_250_000).to_string(), "10Mbit");
+    assert_eq!(TcRate(125_000_000).to_string(), "1Gbit");
+    assert_eq!(TcRate(1_250_001).to_string(), "10000008bit");
+
+    let size = |s: &str| s.parse::<TcSize>().map(u32::from);
+    assert_eq!(size("1500b").unwrap(), 1500);
+    assert_eq!(size("64kb").unwrap(), 65_536);
+    assert_eq!(size("1M").unwrap(), 1_048_576);
+    assert_eq!(size("32kbit").unwrap(), 4096);
+    assert_eq!(size("1600").unwrap(), 1600);
+    assert!(size("8gb").is_err());
+    assert_eq!(TcSize(1500).to_string(), "1500b");
+    assert_eq!(TcSize(65_536).to_string(), "64Kb");
+    assert_eq!(TcSize(1_048_576).to_string(), "1Mb");
+
+    let time = |s: &str| s.parse::<TcTime>().map(Duration::from);
+    assert_eq!(time("10ms").unwrap(), Duration::from_millis(10));
+    assert_eq!(time("1.5s").unwrap(), Duration::from_millis(1500));
+    assert_eq!(time("500").unwrap(), Duration::from_micros(500));
+    assert!(time("10min").is_err());
+    assert_eq!(TcTime(Duration::from_millis(10)).to_string(), "10ms");
+    assert_eq!(TcTime(Duration::from_millis(1500)).to_string(), "1.5s");
+    assert_eq!(TcTime(Duration::from_micros(2500)).to_string(), "2.5ms");
+    assert_eq!(TcTime(Duration::from_micros(500)).to_string(), "500us");
+}
+
+#[test]
+fn test_tc_notation_builders() {
+    use netlink_packet_route::tc::TcHandle;
+
+    use crate::new_connection;
+
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+
+        // tc qdisc add dev eth0 parent 1:10 handle 10: ...
+        let mut request = handle
+            .qdisc()
+            .add(1)
+            .parent_str("1:10")
+            .unwrap()
+            .handle_str("10:")
+            .unwrap();
+        let header = &request.message_mut().header;
+        assert_eq!(header.parent, 0x1_0010.into());
+        assert_eq!(header.handle, 0x10_0000.into());
+        assert!(handle.qdisc().add(1).handle_str("eth0").is_err());
+
+        // tc class add dev eth0 parent root classid 1:1 ...
+        let mut request = handle
+            .traffic_class(1)
+            .add()
+            .parent_str("root")
+            .unwrap()
+            .classid_str("1:1")
+            .unwrap();
+        let header = &request.message_mut().header;
+        assert_eq!(header.parent, TcHandle::ROOT);
+        assert_eq!(header.handle, 0x1_0001.into());
+        assert!(handle.traffic_class(1).add().parent_str("1:2:3").is_err());
+    });
+}
@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use futures_util::{future, stream::TryStreamExt};
+use netlink_packet_route::tc::{TcAttribute, TcHandle, TcMessage};
+
+use super::{
+    QDiscGetRequest, TcClassId, TrafficChainGetRequest, TrafficClassGetRequest,
+    TrafficFilterGetRequest, TrafficStats,
+};
+use crate::{Error, Handle, TcMessageExt};
+
+/// Qdiscs, classes, filters and chains of a device, joined by parent and
+/// handle. See [crate::Handle::tc_tree].
+///
+/// The [fmt::Display] implementation renders the tree like `tc -s`.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct TrafficTree {
+    /// Interface index of the device.
+    pub index: i32,
+    /// Root qdisc, followed by the ingress or clsact qdisc if any.
+    pub qdiscs: Vec<TrafficTreeNode>,
+}
+
+/// Qdisc or class of a [TrafficTree].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct TrafficTreeNode {
+    /// Whether the node is a class, or a qdisc.
+    pub class: bool,
+    pub message: TcMessage,
+    /// Filters attached to the qdisc or class, of all the chains.
+    pub filters: Vec<TcMessage>,
+    /// Chains of the filter block of the qdisc or class.
+    pub chains: Vec<u32>,
+    /// Classes of a qdisc, child classes of a class, and qdiscs attached to
+    /// a class.
+    pub children: Vec<TrafficTreeNode>,
+}
+
+impl TrafficTreeNode {
+    fn new(class: bool, message: TcMessage) -> Self {
+        Self {
+            class,
+            message,
+            filters: Vec::new(),
+            chains: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    // Parents to use to dump the filters and chains of the node.
+    fn filter_parents(&self) -> Vec<u32> {
+        let handle = self.message.header.handle;
+        match (self.class, self.message.kind()) {
+            (false, Some("ingress")) => vec![ingress_parent()],
+            (false, Some("clsact")) => vec![ingress_parent(), egress_parent()],
+            // The children of multiqueue qdiscs have no handle.
+            _ if handle == TcHandle::UNSPEC => Vec::new(),
+            _ => vec![handle.into()],
+        }
+    }
+}
+
+fn ingress_parent() -> u32 {
+    TcHandle {
+        major: 0xffff,
+        minor: TcHandle::MIN_INGRESS,
+    }
+    .into()
+}
+
+fn egress_parent() -> u32 {
+    TcHandle {
+        major: 0xffff,
+        minor: TcHandle::MIN_EGRESS,
+    }
+    .into()
+}
+
+impl TrafficTree {
+    /// Nest the qdiscs and classes of a device.
+    pub(crate) fn assemble(index: i32, nodes: Vec<TrafficTreeNode>) -> Self {
+        let parents: Vec<Option<usize>> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| parent_of(&nodes, i, node))
+            .collect();
+        let mut nodes: Vec<Option<TrafficTreeNode>> =
+            nodes.into_iter().map(Some).collect();
+        let qdiscs = (0..nodes.len())
+            .filter(|i| parents[*i].is_none())
+            .filter_map(|i| take_node(&mut nodes, &parents, i))
+            .collect();
+        Self { index, qdiscs }
+    }
+}
+
+fn parent_of(
+    nodes: &[TrafficTreeNode],
+    index: usize,
+    node: &TrafficTreeNode,
+) -> Option<usize> {
+    let header = &node.message.header;
+    if !node.class
+        && (header.parent == TcHandle::ROOT
+            || header.parent == TcHandle::INGRESS)
+    {
+        return None;
+    }
+    // Nested under a class first, then under the qdisc owning the class or
+    // the qdisc itself.
+    let major = if node.class {
+        header.handle.major
+    } else {
+        header.parent.major
+    };
+    nodes
+        .iter()
+        .position(|other| {
+            other.class && other.message.header.handle == header.parent
+        })
+        .or_else(|| {
+            nodes.iter().position(|other| {
+                !other.class && other.message.header.handle.major == major
+            })
+        })
+        .filter(|parent| *parent != index)
+}
+
+fn take_node(
+    nodes: &mut [Option<TrafficTreeNode>],
+    parents: &[Option<usize>],
+    index: usize,
+) -> Option<TrafficTreeNode> {
+    let mut node = nodes[index].take()?;
+    for child in 0..parents.len() {
+        if parents[child] == Some(index) {
+            node.children.extend(take_node(nodes, parents, child));
+        }
+    }
+    Some(node)
+}
+
+impl fmt::Display for TrafficTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for qdisc in &self.qdiscs {
+            fmt_node(f, qdisc, 0)?;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_node(
+    f: &mut fmt::Formatter<'_>,
+    node: &TrafficTreeNode,
+    depth: usize,
+) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    let header = &node.message.header;
+    // Like `tc`, the handle of a qdisc is only its major number.
+    let handle = if node.class {
+        TcClassId(header.handle).to_string()
+    } else {
+        format!("{:x}:", header.handle.major)
+    };
+    write!(
+        f,
+        "{indent}{} {} {handle} ",
+        if node.class { "class" } else { "qdisc" },
+        node.message.kind().unwrap_or_default(),
+    )?;
+    if header.parent == TcHandle::ROOT {
+        writeln!(f, "root")?;
+    } else {
+        writeln!(f, "parent {}", TcClassId(header.parent))?;
+    }
+    if let Some(stats) = node.message.stats() {
+        fmt_stats(f, &indent, &stats)?;
+    }
+    for chain in &node.chains {
+        writeln!(f, "{indent}  chain {chain}")?;
+    }
+    for filter in &node.filters {
+        fmt_filter(f, &indent, filter)?;
+    }
+    for child in &node.children {
+        fmt_node(f, child, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn fmt_stats(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    stats: &TrafficStats,
+) -> fmt::Result {
+    writeln!(
+        f,
+        "{indent} Sent {} bytes {} pkt (dropped {}, overlimits {} requeues {})",
+        stats.bytes,
+        stats.packets,
+        stats.drops,
+        stats.overlimits,
+        stats.requeues,
+    )?;
+    if stats.rate != 0 || stats.rate_pps != 0 {
+        writeln!(
+            f,
+            "{indent} rate {}bit {}pps",
+            stats.rate * 8,
+            stats.rate_pps
+        )?;
+    }
+    writeln!(
+        f,
+        "{indent} backlog {}b {}p requeues {}",
+        stats.backlog, stats.qlen, stats.requeues
+    )
+}
+
+fn fmt_filter(
+    f: &mut fmt::Formatter<'_>,
+    indent: &str,
+    filter: &TcMessage,
+) -> fmt::Result {
+    let info = filter.header.info;
+    let chain = filter
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            TcAttribute::Chain(chain) => Some(*chain),
+            _ => None,
+        })
+        .unwrap_or_default();
+    write!(
+        f,
+        "{indent}  filter parent {} protocol {} pref {} {} chain {chain}",
+        TcClassId(filter.header.parent),
+        fmt_protocol(u16::from_be(info as u16)),
+        info >> 16,
+        filter.kind().unwrap_or_default(),
+    )?;
+    let handle = u32::from(filter.header.handle);
+    if handle != 0 {
+        write!(f, " handle {handle:#x}")?;
+    }
+    writeln!(f)
+}
+
+// See llproto_names at iproute2/lib/ll_proto.c.
+fn fmt_protocol(protocol: u16) -> String {
+    match protocol {
+        0x0003 => "all".to_string(),
+        0x0800 => "ip".to_string(),
+        0x0806 => "arp".to_string(),
+        0x86dd => "ipv6".to_string(),
+        0x8100 => "802.1Q".to_string(),
+        0x88a8 => "802.1ad".to_string(),
+        0x8847 => "mpls_uc".to_string(),
+        _ => format!("{protocol:#06x}"),
+    }
+}
+
+/// Fetch the qdiscs, classes, filters and chains of a device and assemble
+/// them into a [TrafficTree].
+#[derive(Debug, Clone)]
+pub struct TrafficTreeGetRequest {
+    handle: Handle,
+    index: i32,
+}
+
+impl TrafficTreeGetRequest {
+    pub(crate) fn new(handle: Handle, index: i32) -> Self {
+        TrafficTreeGetRequest { handle, index }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<TrafficTree, Error> {
+        let TrafficTreeGetRequest { handle, index } = self;
+
+        // The kernel dumps the qdiscs of all the devices.
+        let mut nodes: Vec<TrafficTreeNode> =
+            QDiscGetRequest::new(handle.clone())
+                .execute()
+                .try_filter(|qdisc| future::ready(qdisc.header.index == index))
+                .map_ok(|qdisc| TrafficTreeNode::new(false, qdisc))
+                .try_collect()
+                .await?;
+        let classes: Vec<TcMessage> =
+            TrafficClassGetRequest::new(handle.clone(), index)
+                .execute()
+                .try_collect()
+                .await?;
+        nodes.extend(
+            classes
+                .into_iter()
+                .map(|class| TrafficTreeNode::new(true, class)),
+        );
+
+        for node in nodes.iter_mut() {
+            for parent in node.filter_parents() {
+                let filters: Vec<TcMessage> =
+                    TrafficFilterGetRequest::new(handle.clone(), index)
+                        .parent(parent)
+                        .execute()
+                        .try_collect()
+                        .await?;
+                node.filters.extend(filters);
+                let chains: Vec<TcMessage> =
+                    TrafficChainGetRequest::new(handle.clone(), index)
+                        .parent(parent)
+                        .execute()
+                        .try_collect()
+                        .await?;
+                node.chains.extend(chains.iter().filter_map(|chain| {
+                    chain.attributes.iter().find_map(|attr| match attr {
+                        TcAttribute::Chain(chain) => Some(*chain),
+                        _ => None,
+                    })
+                }));
+            }
+        }
+        Ok(TrafficTree::assemble(index, nodes))
+    }
+}
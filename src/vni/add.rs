@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+
+use super::message::{VniSetEntry, RTM_NEWTUNNEL};
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle,
+};
+
+/// Add VNIs to a VXLAN device with VNI filtering.
+/// Equivalent to `bridge vni add`.
+pub struct VniAddRequest {
+    handle: Handle,
+    entry: VniSetEntry,
+}
+
+impl VniAddRequest {
+    pub(crate) fn new(handle: Handle, index: u32, vni: u32) -> Self {
+        VniAddRequest {
+            handle,
+            entry: VniSetEntry::new(index, vni),
+        }
+    }
+
+    /// Add all the VNIs from `vni` to `vni_end`.
+    /// Equivalent to `vni VNI-VNI_END`.
+    pub fn range(mut self, vni_end: u32) -> Self {
+        self.entry.vni_end = Some(vni_end);
+        self
+    }
+
+    /// Multicast group, or unicast remote, of the VNIs. A multicast group
+    /// requires the VXLAN device to have a lower device.
+    /// Equivalent to `group GROUP` and `remote REMOTE`.
+    pub fn group(mut self, group: IpAddr) -> Self {
+        self.entry.group = Some(group);
+        self
+    }
+
+    pub(crate) fn raw_message(&self) -> RawRouteMessage {
+        self.entry.build(RTM_NEWTUNNEL)
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message());
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+
+use super::message::{VniSetEntry, RTM_DELTUNNEL};
+use crate::{
+    raw::{self, RawRouteMessage},
+    Error, Handle,
+};
+
+/// Delete VNIs of a VXLAN device with VNI filtering.
+/// Equivalent to `bridge vni delete`.
+pub struct VniDelRequest {
+    handle: Handle,
+    entry: VniSetEntry,
+}
+
+impl VniDelRequest {
+    pub(crate) fn new(handle: Handle, index: u32, vni: u32) -> Self {
+        VniDelRequest {
+            handle,
+            entry: VniSetEntry::new(index, vni),
+        }
+    }
+
+    /// Delete all the VNIs from `vni` to `vni_end`.
+    /// Equivalent to `vni VNI-VNI_END`.
+    pub fn range(mut self, vni_end: u32) -> Self {
+        self.entry.vni_end = Some(vni_end);
+        self
+    }
+
+    pub(crate) fn raw_message(&self) -> RawRouteMessage {
+        self.entry.build(RTM_DELTUNNEL)
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let mut req = NetlinkMessage::from(self.raw_message());
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        raw::request(&self.handle, req).await?;
+        Ok(())
+    }
+}
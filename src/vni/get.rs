@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::{self, Stream, TryStreamExt};
+use netlink_packet_core::{NetlinkMessage, NLM_F_DUMP, NLM_F_REQUEST};
+
+use super::message::{dump_request, parse_vnis, RTM_NEWTUNNEL};
+use crate::{raw, Error, Handle, VniEntry};
+
+/// List the VNIs of the VXLAN devices with VNI filtering.
+/// Equivalent to `bridge vni show`.
+pub struct VniGetRequest {
+    handle: Handle,
+    index: u32,
+    stats: bool,
+}
+
+impl VniGetRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        VniGetRequest {
+            handle,
+            index: 0,
+            stats: false,
+        }
+    }
+
+    /// Only list the VNIs of this VXLAN device (equivalent to
+    /// `dev DEVICE`).
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Include the traffic counters of the VNIs, each VNI then being
+    /// listed on its own (equivalent to `bridge -s vni show`).
+    pub fn stats(mut self) -> Self {
+        self.stats = true;
+        self
+    }
+
+    /// Execute the request.
+    pub fn execute(self) -> impl Stream<Item = Result<VniEntry, Error>> {
+        let VniGetRequest {
+            handle,
+            index,
+            stats,
+        } = self;
+
+        let mut req = NetlinkMessage::from(dump_request(index, stats));
+        req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+
+        stream::once(async move {
+            let mut vnis = Vec::new();
+            for message in raw::request(&handle, req).await? {
+                if message.message_type == RTM_NEWTUNNEL {
+                    vnis.extend(
+                        parse_vnis(&message.payload).map_err(|e| {
+                            Error::InvalidMessage(e.to_string())
+                        })?,
+                    );
+                }
+            }
+            Ok(stream::iter(vnis.into_iter().map(Ok)))
+        })
+        .try_flatten()
+    }
+}
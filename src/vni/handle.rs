@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{Handle, VniAddRequest, VniDelRequest, VniGetRequest, VniMonitor};
+
+/// Handle of the VNI filter of the `external` VXLAN devices created with
+/// `vnifilter`, see [crate::LinkVxlan], which carry several VNIs on a
+/// single device.
+///
+/// The `RTM_NEWTUNNEL` messages have no variant in
+/// [RouteNetlinkMessage](netlink_packet_route::RouteNetlinkMessage), so the
+/// requests are sent on their own netlink connection, opened in the network
+/// namespace of the [Handle].
+#[derive(Debug, Clone)]
+pub struct VniHandle(Handle);
+
+impl VniHandle {
+    pub fn new(handle: Handle) -> Self {
+        VniHandle(handle)
+    }
+
+    /// List the VNIs of the VXLAN devices (equivalent to `bridge vni show`)
+    pub fn get(&self) -> VniGetRequest {
+        VniGetRequest::new(self.0.clone())
+    }
+
+    /// Add the VNI `vni` to the VXLAN device of index `index`, or change
+    /// its group when it exists (equivalent to `bridge vni add`)
+    pub fn add(&self, index: u32, vni: u32) -> VniAddRequest {
+        VniAddRequest::new(self.0.clone(), index, vni)
+    }
+
+    /// Delete the VNI `vni` of the VXLAN device of index `index`
+    /// (equivalent to `bridge vni delete`)
+    pub fn del(&self, index: u32, vni: u32) -> VniDelRequest {
+        VniDelRequest::new(self.0.clone(), index, vni)
+    }
+
+    /// Receive the changes of the VNIs (equivalent to
+    /// `bridge monitor vni`)
+    pub fn monitor(&self) -> VniMonitor {
+        VniMonitor::new(self.0.clone())
+    }
+}
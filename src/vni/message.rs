@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_core::{
+    parse_ip, parse_u32, parse_u64, DecodeError, DefaultNla,
+};
+use netlink_packet_route::AddressFamily;
+
+use crate::{
+    nla::{emit_nlas, nla_nested, nla_u32, parse_nlas, read_u32},
+    raw::RawRouteMessage,
+};
+
+pub(crate) const RTM_NEWTUNNEL: u16 = 120;
+pub(crate) const RTM_DELTUNNEL: u16 = 121;
+pub(crate) const RTM_GETTUNNEL: u16 = 122;
+
+const TUNNEL_MSG_FLAG_STATS: u8 = 0x01;
+
+const VXLAN_VNIFILTER_ENTRY: u16 = 1;
+
+const VXLAN_VNIFILTER_ENTRY_START: u16 = 1;
+const VXLAN_VNIFILTER_ENTRY_END: u16 = 2;
+const VXLAN_VNIFILTER_ENTRY_GROUP: u16 = 3;
+const VXLAN_VNIFILTER_ENTRY_GROUP6: u16 = 4;
+const VXLAN_VNIFILTER_ENTRY_STATS: u16 = 5;
+
+const VNIFILTER_ENTRY_STATS_RX_BYTES: u16 = 1;
+const VNIFILTER_ENTRY_STATS_RX_PKTS: u16 = 2;
+const VNIFILTER_ENTRY_STATS_RX_DROPS: u16 = 3;
+const VNIFILTER_ENTRY_STATS_RX_ERRORS: u16 = 4;
+const VNIFILTER_ENTRY_STATS_TX_BYTES: u16 = 5;
+const VNIFILTER_ENTRY_STATS_TX_PKTS: u16 = 6;
+const VNIFILTER_ENTRY_STATS_TX_DROPS: u16 = 7;
+const VNIFILTER_ENTRY_STATS_TX_ERRORS: u16 = 8;
+
+// struct tunnel_msg
+const TUNNEL_MSG_LEN: usize = 8;
+
+/// Traffic counters of a VNI.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct VniStats {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_drops: u64,
+    pub rx_errors: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_drops: u64,
+    pub tx_errors: u64,
+}
+
+/// VNI, or range of VNIs sharing the same group, of a VXLAN device with
+/// VNI filtering.
+/// Equivalent to a line of `bridge vni show`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct VniEntry {
+    /// Interface index of the VXLAN device.
+    pub index: u32,
+    pub vni: u32,
+    /// Last VNI of the range starting at [Self::vni], if any.
+    pub vni_end: Option<u32>,
+    /// Multicast group, or unicast remote, of the VNI.
+    pub group: Option<IpAddr>,
+    /// Only dumped with [crate::VniGetRequest::stats].
+    pub stats: Option<VniStats>,
+}
+
+/// Change of the VNIs of VXLAN devices, received from
+/// [crate::VniHandle::monitor].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum VniEvent {
+    New(VniEntry),
+    Del(VniEntry),
+}
+
+impl VniEvent {
+    pub(crate) fn parse(
+        message: &RawRouteMessage,
+    ) -> Result<Vec<Self>, DecodeError> {
+        let event = match message.message_type {
+            RTM_NEWTUNNEL => Self::New,
+            RTM_DELTUNNEL => Self::Del,
+            _ => return Ok(Vec::new()),
+        };
+        Ok(parse_vnis(&message.payload)?
+            .into_iter()
+            .map(event)
+            .collect())
+    }
+}
+
+/// Parse the VNIs of a `RTM_NEWTUNNEL` or `RTM_DELTUNNEL` message.
+pub(crate) fn parse_vnis(payload: &[u8]) -> Result<Vec<VniEntry>, DecodeError> {
+    let index = read_u32(payload, 4)?;
+    let nlas = payload.get(TUNNEL_MSG_LEN..).unwrap_or_default();
+    let mut entries = Vec::new();
+    for (kind, value) in parse_nlas(nlas)? {
+        if kind == VXLAN_VNIFILTER_ENTRY {
+            entries.push(parse_entry(index, value)?);
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_entry(index: u32, payload: &[u8]) -> Result<VniEntry, DecodeError> {
+    let mut entry = VniEntry {
+        index,
+        vni: 0,
+        vni_end: None,
+        group: None,
+        stats: None,
+    };
+    for (kind, value) in parse_nlas(payload)? {
+        match kind {
+            VXLAN_VNIFILTER_ENTRY_START => entry.vni = parse_u32(value)?,
+            VXLAN_VNIFILTER_ENTRY_END => {
+                entry.vni_end = Some(parse_u32(value)?)
+            }
+            VXLAN_VNIFILTER_ENTRY_GROUP | VXLAN_VNIFILTER_ENTRY_GROUP6 => {
+                entry.group = Some(parse_ip(value)?)
+            }
+            VXLAN_VNIFILTER_ENTRY_STATS => {
+                let mut stats = VniStats::default();
+                for (kind, value) in parse_nlas(value)? {
+                    let counter = match kind {
+                        VNIFILTER_ENTRY_STATS_RX_BYTES => &mut stats.rx_bytes,
+                        VNIFILTER_ENTRY_STATS_RX_PKTS => &mut stats.rx_packets,
+                        VNIFILTER_ENTRY_STATS_RX_DROPS => &mut stats.rx_drops,
+                        VNIFILTER_ENTRY_STATS_RX_ERRORS => &mut stats.rx_errors,
+                        VNIFILTER_ENTRY_STATS_TX_BYTES => &mut stats.tx_bytes,
+                        VNIFILTER_ENTRY_STATS_TX_PKTS => &mut stats.tx_packets,
+                        VNIFILTER_ENTRY_STATS_TX_DROPS => &mut stats.tx_drops,
+                        VNIFILTER_ENTRY_STATS_TX_ERRORS => &mut stats.tx_errors,
+                        _ => continue,
+                    };
+                    *counter = parse_u64(value)?;
+                }
+                entry.stats = Some(stats);
+            }
+            _ => (),
+        }
+    }
+    Ok(entry)
+}
+
+/// `struct tunnel_msg` of the device `index`.
+fn tunnel_msg(index: u32, flags: u8) -> Vec<u8> {
+    let mut header = vec![0u8; TUNNEL_MSG_LEN];
+    header[0] = AddressFamily::Bridge.into();
+    header[1] = flags;
+    header[4..].copy_from_slice(&index.to_ne_bytes());
+    header
+}
+
+/// `RTM_GETTUNNEL` dump request of the device `index`, all of them for 0.
+pub(crate) fn dump_request(index: u32, stats: bool) -> RawRouteMessage {
+    let flags = if stats { TUNNEL_MSG_FLAG_STATS } else { 0 };
+    RawRouteMessage::new(RTM_GETTUNNEL, tunnel_msg(index, flags))
+}
+
+/// VNI, or range of VNIs, in a `RTM_NEWTUNNEL` or `RTM_DELTUNNEL` request.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct VniSetEntry {
+    pub(crate) index: u32,
+    pub(crate) vni: u32,
+    pub(crate) vni_end: Option<u32>,
+    pub(crate) group: Option<IpAddr>,
+}
+
+impl VniSetEntry {
+    pub(crate) fn new(index: u32, vni: u32) -> Self {
+        VniSetEntry {
+            index,
+            vni,
+            vni_end: None,
+            group: None,
+        }
+    }
+
+    pub(crate) fn build(&self, message_type: u16) -> RawRouteMessage {
+        let mut nlas = vec![nla_u32(VXLAN_VNIFILTER_ENTRY_START, self.vni)];
+        if let Some(vni_end) = self.vni_end {
+            nlas.push(nla_u32(VXLAN_VNIFILTER_ENTRY_END, vni_end));
+        }
+        match self.group {
+            Some(IpAddr::V4(group)) => nlas.push(DefaultNla::new(
+                VXLAN_VNIFILTER_ENTRY_GROUP,
+                group.octets().to_vec(),
+            )),
+            Some(IpAddr::V6(group)) => nlas.push(DefaultNla::new(
+                VXLAN_VNIFILTER_ENTRY_GROUP6,
+                group.octets().to_vec(),
+            )),
+            None => (),
+        }
+        let mut payload = tunnel_msg(self.index, 0);
+        payload.extend(emit_nlas(&[nla_nested(VXLAN_VNIFILTER_ENTRY, &nlas)]));
+        RawRouteMessage::new(message_type, payload)
+    }
+}
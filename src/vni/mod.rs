@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+
+mod add;
+mod del;
+mod get;
+mod handle;
+mod message;
+mod monitor;
+
+#[cfg(test)]
+mod test;
+
+pub use self::{
+    add::VniAddRequest,
+    del::VniDelRequest,
+    get::VniGetRequest,
+    handle::VniHandle,
+    message::{VniEntry, VniEvent, VniStats},
+    monitor::VniMonitor,
+};
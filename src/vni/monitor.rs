@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+
+use futures_util::stream::{self, Stream, StreamExt};
+
+use crate::{raw, Error, Handle, MulticastGroup, VniEvent};
+
+/// Receive the changes of the VNIs of VXLAN devices, which the connections
+/// of this crate cannot decode.
+/// Equivalent to `bridge monitor vni`.
+pub struct VniMonitor {
+    handle: Handle,
+}
+
+impl VniMonitor {
+    pub(crate) fn new(handle: Handle) -> Self {
+        VniMonitor { handle }
+    }
+
+    /// Execute the request, subscribing to [MulticastGroup::Tunnel] on a
+    /// dedicated socket: the changes made after this call are received.
+    ///
+    /// Must be called from the context of the async runtime.
+    pub fn execute(
+        self,
+    ) -> Result<impl Stream<Item = Result<VniEvent, Error>>, Error> {
+        let messages = raw::subscribe(&self.handle, &[MulticastGroup::Tunnel])?;
+        Ok(messages.flat_map(|message| {
+            stream::iter(match VniEvent::parse(&message) {
+                Ok(events) => events.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(Error::InvalidMessage(e.to_string()))],
+            })
+        }))
+    }
+}
@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+use futures_util::stream::{StreamExt, TryStreamExt};
+use tokio::runtime::Runtime;
+
+use super::message::{dump_request, parse_vnis};
+use crate::{
+    new_connection,
+    test_netns::{get_link, setup_veth},
+    Error, Handle, LinkVxlan, VniEntry, VniEvent, VniStats,
+};
+
+const TEST_NS: &str = "netlink_test_vni_ns";
+const TEST_VXLAN: &str = "test_vxlan";
+
+#[test]
+fn vni_add_del_request() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (_, handle, _) = new_connection().unwrap();
+        // bridge vni add dev vxlan0 vni 100-110 group 239.1.1.1
+        let message = handle
+            .vni()
+            .add(4, 100)
+            .range(110)
+            .group(Ipv4Addr::new(239, 1, 1, 1).into())
+            .raw_message();
+        assert_eq!(message.message_type, 120);
+        assert_eq!(
+            message.payload,
+            vec![
+                7, 0, 0, 0, 4, 0, 0, 0, // tunnel_msg
+                28, 0, 1, 0x80, // VXLAN_VNIFILTER_ENTRY
+                8, 0, 1, 0, 100, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_START
+                8, 0, 2, 0, 110, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_END
+                8, 0, 3, 0, 239, 1, 1, 1, // VXLAN_VNIFILTER_ENTRY_GROUP
+            ]
+        );
+
+        // bridge vni add dev vxlan0 vni 200 remote 2001:db8::1
+        let message = handle
+            .vni()
+            .add(4, 200)
+            .group("2001:db8::1".parse().unwrap())
+            .raw_message();
+        assert_eq!(
+            message.payload[8..],
+            [
+                32, 0, 1, 0x80, // VXLAN_VNIFILTER_ENTRY
+                8, 0, 1, 0, 200, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_START
+                20, 0, 4, 0, // VXLAN_VNIFILTER_ENTRY_GROUP6
+                0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+            ]
+        );
+
+        // bridge vni delete dev vxlan0 vni 100-110
+        let message = handle.vni().del(4, 100).range(110).raw_message();
+        assert_eq!(message.message_type, 121);
+        assert_eq!(
+            message.payload[8..],
+            [
+                20, 0, 1, 0x80, // VXLAN_VNIFILTER_ENTRY
+                8, 0, 1, 0, 100, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_START
+                8, 0, 2, 0, 110, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_END
+            ]
+        );
+    });
+}
+
+#[test]
+fn vni_dump_request() {
+    // bridge -s vni show dev vxlan0
+    let message = dump_request(4, true);
+    assert_eq!(message.message_type, 122);
+    assert_eq!(message.payload, vec![7, 1, 0, 0, 4, 0, 0, 0]);
+}
+
+#[test]
+fn vni_parse() {
+    #[rustfmt::skip]
+    let payload = vec![
+        7, 0, 0, 0, 4, 0, 0, 0, // tunnel_msg
+        28, 0, 1, 0x80, // VXLAN_VNIFILTER_ENTRY
+        8, 0, 1, 0, 100, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_START
+        8, 0, 2, 0, 110, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_END
+        8, 0, 3, 0, 239, 1, 1, 1, // VXLAN_VNIFILTER_ENTRY_GROUP
+        44, 0, 1, 0x80, // VXLAN_VNIFILTER_ENTRY
+        8, 0, 1, 0, 200, 0, 0, 0, // VXLAN_VNIFILTER_ENTRY_START
+        32, 0, 5, 0x80, // VXLAN_VNIFILTER_ENTRY_STATS
+        12, 0, 1, 0, 100, 0, 0, 0, 0, 0, 0, 0, // RX_BYTES
+        4, 0, 9, 0, // PAD
+        12, 0, 6, 0, 2, 0, 0, 0, 0, 0, 0, 0, // TX_PKTS
+    ];
+    assert_eq!(
+        parse_vnis(&payload).unwrap(),
+        vec![
+            VniEntry {
+                index: 4,
+                vni: 100,
+                vni_end: Some(110),
+                group: Some(Ipv4Addr::new(239, 1, 1, 1).into()),
+                stats: None,
+            },
+            VniEntry {
+                index: 4,
+                vni: 200,
+                vni_end: None,
+                group: None,
+                stats: Some(VniStats {
+                    rx_bytes: 100,
+                    tx_packets: 2,
+                    ..Default::default()
+                }),
+            },
+        ]
+    );
+}
+
+async fn vni_entries(
+    handle: &Handle,
+    index: u32,
+    stats: bool,
+) -> Vec<VniEntry> {
+    let request = handle.vni().get().index(index);
+    let request = if stats { request.stats() } else { request };
+    request.execute().try_collect().await.unwrap()
+}
+
+async fn test_async_vni() {
+    let (handle, link, _, _netns) = setup_veth(TEST_NS).await;
+    handle
+        .link()
+        .add(
+            LinkVxlan::new(TEST_VXLAN, 0)
+                .dev(link.header.index)
+                .port(4789)
+                .collect_metadata(true)
+                .vnifilter(true)
+                .build(),
+        )
+        .execute()
+        .await
+        .unwrap();
+    let vxlan = get_link(&handle, TEST_VXLAN).await.header.index;
+    let mut events = handle.vni().monitor().execute().unwrap();
+
+    let group: IpAddr = Ipv4Addr::new(239, 1, 1, 1).into();
+    let remote: IpAddr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1).into();
+    handle
+        .vni()
+        .add(vxlan, 100)
+        .range(110)
+        .group(group)
+        .execute()
+        .await
+        .unwrap();
+    handle
+        .vni()
+        .add(vxlan, 200)
+        .group(remote)
+        .execute()
+        .await
+        .unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(1), events.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        event,
+        VniEvent::New(VniEntry { index, vni: 100, .. }) if index == vxlan
+    ));
+
+    assert_eq!(
+        vni_entries(&handle, vxlan, false).await,
+        vec![
+            VniEntry {
+                index: vxlan,
+                vni: 100,
+                vni_end: Some(110),
+                group: Some(group),
+                stats: None,
+            },
+            VniEntry {
+                index: vxlan,
+                vni: 200,
+                vni_end: None,
+                group: Some(remote),
+                stats: None,
+            },
+        ]
+    );
+    // The VNIs are listed one by one with their counters.
+    let entries = vni_entries(&handle, vxlan, true).await;
+    assert_eq!(entries.len(), 12);
+    assert!(entries
+        .iter()
+        .all(|entry| entry.vni_end.is_none() && entry.stats.is_some()));
+
+    handle
+        .vni()
+        .del(vxlan, 100)
+        .range(110)
+        .execute()
+        .await
+        .unwrap();
+    let result = handle.vni().del(vxlan, 100).execute().await;
+    assert!(matches!(result, Err(Error::NetlinkError(_))));
+    let entries = vni_entries(&handle, vxlan, false).await;
+    assert_eq!(
+        entries.iter().map(|entry| entry.vni).collect::<Vec<_>>(),
+        vec![200]
+    );
+}
+
+#[test]
+fn test_vni() {
+    Runtime::new().unwrap().block_on(test_async_vni());
+}